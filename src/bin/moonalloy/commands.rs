@@ -0,0 +1,390 @@
+//! Batch commands - Non-interactive jobs run from the command line
+//!
+//! Every command reads its input files, computes a table of results and writes it to standard
+//! output as CSV or JSON. Failures are reported with an exit status that tells usage errors,
+//! unreadable input and numerical failures apart.
+
+use crate::data::{read_matrix, read_table, read_vector};
+
+use moonalloy::linalg::array::Array;
+use moonalloy::linalg::matrix::Matrix;
+use moonalloy::linalg::methods::gauss_elimination;
+use moonalloy::machine_learning::linear_regression::SimpleLinearRegression;
+use moonalloy::machine_learning::model::{ModelError, Predictor};
+
+/// The names of the batch commands.
+pub const COMMANDS: [&str; 3] = ["solve", "describe", "fit"];
+
+/// The ways a command can fail.
+#[derive(Debug, PartialEq)]
+pub enum Failure {
+    /// The command line is invalid.
+    Usage(String),
+    /// An input file cannot be read or parsed.
+    Input(String),
+    /// The computation has no solution for the given input.
+    Numerical(String),
+}
+
+impl Failure {
+    /// Returns the exit status of the failure.
+    pub fn status(&self) -> i32 {
+        match self {
+            Failure::Numerical(_) => 1,
+            Failure::Usage(_) => 2,
+            Failure::Input(_) => 3,
+        }
+    }
+
+    /// Returns the message of the failure.
+    pub fn message(&self) -> &str {
+        match self {
+            Failure::Usage(msg) | Failure::Input(msg) | Failure::Numerical(msg) => msg,
+        }
+    }
+}
+
+/// The output formats.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Csv,
+    Json,
+}
+
+/// A cell of a result table.
+#[derive(Debug, PartialEq)]
+enum Cell {
+    Text(String),
+    Number(f64),
+}
+
+/// The result of a command, written as one record per row.
+#[derive(Debug, PartialEq)]
+struct Report {
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<Cell>>,
+}
+
+fn csv_text(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn json_text(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl Report {
+    fn render(&self, format: Format) -> String {
+        match format {
+            Format::Csv => {
+                let mut out = self.columns.join(",");
+                out.push('\n');
+                for row in &self.rows {
+                    let cells: Vec<String> = row
+                        .iter()
+                        .map(|cell| match cell {
+                            Cell::Text(text) => csv_text(text),
+                            Cell::Number(val) => val.to_string(),
+                        })
+                        .collect();
+                    out.push_str(&cells.join(","));
+                    out.push('\n');
+                }
+                out
+            }
+            Format::Json => {
+                // JSON has no representation of NaN or the infinities, so they become null.
+                let records: Vec<String> = self
+                    .rows
+                    .iter()
+                    .map(|row| {
+                        let fields: Vec<String> = self
+                            .columns
+                            .iter()
+                            .zip(row)
+                            .map(|(column, cell)| {
+                                let val = match cell {
+                                    Cell::Text(text) => json_text(text),
+                                    Cell::Number(val) if val.is_finite() => val.to_string(),
+                                    Cell::Number(_) => "null".to_string(),
+                                };
+                                format!("{}: {}", json_text(column), val)
+                            })
+                            .collect();
+                        format!("{{{}}}", fields.join(", "))
+                    })
+                    .collect();
+                format!("[{}]\n", records.join(",\n "))
+            }
+        }
+    }
+}
+
+/// Solves the linear system in the files `a` and `b`.
+fn solve(a: &str, b: &str) -> Result<Report, Failure> {
+    let a = read_matrix(a).map_err(Failure::Input)?;
+    let b = read_vector(b).map_err(Failure::Input)?;
+
+    let rows = a.len();
+    if a.iter().any(|row| row.len() != rows) || b.len() != rows {
+        return Err(Failure::Numerical(format!(
+            "solve needs a square matrix and a matching number of constants, found a {}x{} matrix and {} constants",
+            rows,
+            a.first().map_or(0, |row| row.len()),
+            b.len()
+        )));
+    }
+    if rows == 0 {
+        return Err(Failure::Numerical("the system is empty".to_string()));
+    }
+
+    let a = Matrix::from_rows(a.into_iter().map(Array::from_vec).collect());
+    let x = gauss_elimination(a, Array::from_vec(b));
+    if x.iter().any(|val| !val.is_finite()) {
+        return Err(Failure::Numerical("the matrix is singular".to_string()));
+    }
+
+    Ok(Report {
+        columns: vec!["row", "x"],
+        rows: x
+            .iter()
+            .enumerate()
+            .map(|(i, val)| vec![Cell::Number((i + 1) as f64), Cell::Number(*val)])
+            .collect(),
+    })
+}
+
+/// Summarises every column of a CSV file, ignoring missing values.
+fn describe(path: &str) -> Result<Report, Failure> {
+    let table = read_table(path).map_err(Failure::Input)?;
+
+    let rows = table
+        .names
+        .iter()
+        .zip(&table.columns)
+        .map(|(name, column)| {
+            let values = Array::from_vec(column.iter().copied().filter(|v| !v.is_nan()).collect());
+            let summary = values.describe();
+
+            vec![
+                Cell::Text(name.clone()),
+                Cell::Number(summary.count as f64),
+                Cell::Number((column.len() - summary.count) as f64),
+                Cell::Number(summary.mean),
+                Cell::Number(summary.std),
+                Cell::Number(summary.min),
+                Cell::Number(summary.q1),
+                Cell::Number(summary.median),
+                Cell::Number(summary.q3),
+                Cell::Number(summary.max),
+            ]
+        })
+        .collect();
+
+    Ok(Report {
+        columns: vec![
+            "column", "count", "missing", "mean", "std", "min", "q1", "median", "q3", "max",
+        ],
+        rows,
+    })
+}
+
+/// Fits a simple linear regression of the column `y` on the column `x`.
+fn fit_linear(path: &str, x: &str, y: &str) -> Result<Report, Failure> {
+    let table = read_table(path).map_err(Failure::Input)?;
+    // The column names come from the command line.
+    let xs = table
+        .column(x)
+        .map_err(|err| Failure::Usage(format!("{}: {}", path, err)))?;
+    let ys = table
+        .column(y)
+        .map_err(|err| Failure::Usage(format!("{}: {}", path, err)))?;
+
+    // Rows missing either value are left out of the fit.
+    let (xs, ys): (Vec<f64>, Vec<f64>) = xs
+        .iter()
+        .zip(ys)
+        .filter(|(x, y)| !x.is_nan() && !y.is_nan())
+        .unzip();
+
+    let n = xs.len();
+    if n < 2 || xs.iter().all(|x| *x == xs[0]) {
+        return Err(Failure::Numerical(
+            "a linear fit needs at least two distinct values of x".to_string(),
+        ));
+    }
+
+    let xs = Matrix::from_rows(xs.iter().map(|&x| Array::from(&[x])).collect());
+    let ys = Array::from_vec(ys);
+    let mut model = SimpleLinearRegression::new();
+    let numerical = |err: ModelError| Failure::Numerical(err.to_string());
+    model.fit(&xs, &ys).map_err(numerical)?;
+    let r2 = model.score(&xs, &ys).map_err(numerical)?;
+
+    Ok(Report {
+        columns: vec!["slope", "intercept", "r2", "n"],
+        rows: vec![vec![
+            Cell::Number(model.get_slope()),
+            Cell::Number(model.get_feature()),
+            Cell::Number(r2),
+            Cell::Number(n as f64),
+        ]],
+    })
+}
+
+/// Runs the command `name` with the arguments following it and returns its output.
+pub fn run(name: &str, args: &[String]) -> Result<String, Failure> {
+    let mut format = Format::Csv;
+    let mut options: Vec<(&str, &str)> = Vec::new();
+    let mut positional: Vec<&str> = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if let Some(option) = arg.strip_prefix("--") {
+            let val = args
+                .next()
+                .ok_or_else(|| Failure::Usage(format!("--{} needs a value", option)))?;
+
+            match option {
+                "format" => {
+                    format = match val.as_str() {
+                        "csv" => Format::Csv,
+                        "json" => Format::Json,
+                        _ => return Err(Failure::Usage(format!("unknown format `{}`", val))),
+                    }
+                }
+                _ => options.push((option, val)),
+            }
+        } else {
+            positional.push(arg);
+        }
+    }
+
+    let option = |key: &str| {
+        options
+            .iter()
+            .find(|(option, _)| *option == key)
+            .map(|(_, val)| *val)
+            .ok_or_else(|| Failure::Usage(format!("{} needs the option --{}", name, key)))
+    };
+    let allow = |keys: &[&str]| match options.iter().find(|(option, _)| !keys.contains(option)) {
+        Some((option, _)) => Err(Failure::Usage(format!(
+            "{} has no option --{}",
+            name, option
+        ))),
+        None => Ok(()),
+    };
+
+    let report = match (name, positional.as_slice()) {
+        ("solve", [a, b]) => {
+            allow(&[])?;
+            solve(a, b)?
+        }
+        ("describe", [path]) => {
+            allow(&[])?;
+            describe(path)?
+        }
+        ("fit", ["linear", path]) => {
+            allow(&["x", "y"])?;
+            fit_linear(path, option("x")?, option("y")?)?
+        }
+        ("fit", [model, _]) => {
+            return Err(Failure::Usage(format!("unknown model `{}`", model)));
+        }
+        _ => return Err(Failure::Usage(format!("invalid arguments for {}", name))),
+    };
+
+    Ok(report.render(format))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::env;
+    use std::fs;
+
+    fn file(name: &str, contents: &str) -> String {
+        let path = env::temp_dir().join(format!("moonalloy-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_solve() {
+        let a = file(
+            "a.mtx",
+            "%%MatrixMarket matrix coordinate real general\n2 2 3\n1 1 2\n1 2 1\n2 2 4\n",
+        );
+        let b = file("b.csv", "5\n8\n");
+
+        assert_eq!(
+            Ok("row,x\n1,1.5\n2,2\n".to_string()),
+            run("solve", &args(&[&a, &b]))
+        );
+    }
+
+    #[test]
+    fn test_solve_singular() {
+        let a = file("singular.csv", "1,2\n2,4\n");
+        let b = file("singular-b.csv", "1,1\n");
+
+        assert_eq!(1, run("solve", &args(&[&a, &b])).unwrap_err().status());
+    }
+
+    #[test]
+    fn test_describe_json() {
+        let path = file("describe.csv", "a,b\n1,4\n2,\n3,5\n4,6\n");
+        let output = run("describe", &args(&[&path, "--format", "json"])).unwrap();
+
+        assert!(
+            output.starts_with("[{\"column\": \"a\", \"count\": 4, \"missing\": 0, \"mean\": 2.5,")
+        );
+        assert!(output.contains("{\"column\": \"b\", \"count\": 3, \"missing\": 1, \"mean\": 5, \"std\": 1, \"min\": 4, \"q1\": 4.5, \"median\": 5, \"q3\": 5.5, \"max\": 6}"));
+    }
+
+    #[test]
+    fn test_fit_linear() {
+        let path = file("fit.csv", "x,y\n0,1\n1,3\n2,5\n");
+
+        assert_eq!(
+            Ok("slope,intercept,r2,n\n2,1,1,3\n".to_string()),
+            run("fit", &args(&["linear", &path, "--x", "x", "--y", "y"]))
+        );
+    }
+
+    #[test]
+    fn test_failures() {
+        let missing = run("describe", &args(&["/nonexistent/data.csv"])).unwrap_err();
+        assert_eq!(3, missing.status());
+
+        let usage = run("fit", &args(&["linear", "data.csv", "--x", "x"])).unwrap_err();
+        assert_eq!(2, usage.status());
+
+        let format = run("describe", &args(&["data.csv", "--format", "xml"])).unwrap_err();
+        assert_eq!(2, format.status());
+
+        let path = file("columns.csv", "x,y\n0,1\n1,3\n");
+        let column = run("fit", &args(&["linear", &path, "--x", "x", "--y", "z"])).unwrap_err();
+        assert_eq!(2, column.status());
+    }
+}
@@ -0,0 +1,304 @@
+//! Data files - Readers for CSV and Matrix Market files
+//!
+//! CSV files may start with a header of column names. Empty fields are read as missing values
+//! (NaN). Matrix Market files can use either the coordinate or the array format with real,
+//! integer or pattern entries, and general, symmetric or skew-symmetric storage.
+
+use std::fs;
+use std::path::Path;
+
+/// A table of numeric columns.
+#[derive(Debug, PartialEq)]
+pub struct Table {
+    pub names: Vec<String>,
+    pub columns: Vec<Vec<f64>>,
+}
+
+impl Table {
+    /// Returns the rows of the table.
+    pub fn rows(&self) -> Vec<Vec<f64>> {
+        let len = self.columns.first().map_or(0, |column| column.len());
+        (0..len)
+            .map(|i| self.columns.iter().map(|column| column[i]).collect())
+            .collect()
+    }
+
+    /// Finds a column by its name or by its 1-based position.
+    pub fn column(&self, key: &str) -> Result<&[f64], String> {
+        if let Some(i) = self.names.iter().position(|name| name == key) {
+            return Ok(&self.columns[i]);
+        }
+
+        match key.parse::<usize>() {
+            Ok(i) if i >= 1 && i <= self.columns.len() => Ok(&self.columns[i - 1]),
+            _ => Err(format!("no column named `{}`", key)),
+        }
+    }
+}
+
+fn read(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("cannot read {}: {}", path, err))
+}
+
+fn field(raw: &str) -> &str {
+    let raw = raw.trim();
+    raw.strip_prefix('"')
+        .and_then(|raw| raw.strip_suffix('"'))
+        .unwrap_or(raw)
+}
+
+fn parse_field(raw: &str) -> Option<f64> {
+    let raw = field(raw);
+    if raw.is_empty() || raw.eq_ignore_ascii_case("na") || raw.eq_ignore_ascii_case("nan") {
+        return Some(f64::NAN);
+    }
+    raw.parse().ok()
+}
+
+/// Parses the contents of a CSV file.
+pub fn parse_csv(contents: &str) -> Result<Table, String> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let (first_number, first) = lines.next().ok_or("the file is empty")?;
+    let first_fields: Vec<&str> = first.split(',').collect();
+    let header = first_fields.iter().any(|raw| parse_field(raw).is_none());
+
+    let names: Vec<String> = if header {
+        first_fields
+            .iter()
+            .map(|raw| field(raw).to_string())
+            .collect()
+    } else {
+        (1..=first_fields.len()).map(|i| i.to_string()).collect()
+    };
+    let mut columns = vec![Vec::new(); names.len()];
+
+    let data = if header {
+        None
+    } else {
+        Some((first_number, first))
+    };
+
+    for (number, line) in data.into_iter().chain(lines) {
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() != names.len() {
+            return Err(format!(
+                "line {}: expected {} fields, found {}",
+                number + 1,
+                names.len(),
+                fields.len()
+            ));
+        }
+
+        for (column, raw) in columns.iter_mut().zip(fields) {
+            let val = parse_field(raw)
+                .ok_or_else(|| format!("line {}: invalid number `{}`", number + 1, field(raw)))?;
+            column.push(val);
+        }
+    }
+
+    Ok(Table { names, columns })
+}
+
+/// Parses the contents of a Matrix Market file into rows.
+pub fn parse_matrix_market(contents: &str) -> Result<Vec<Vec<f64>>, String> {
+    let mut lines = contents.lines();
+
+    let header = lines.next().ok_or("the file is empty")?.to_lowercase();
+    let words: Vec<&str> = header.split_whitespace().collect();
+    let (format, field, symmetry) = match words.as_slice() {
+        ["%%matrixmarket", "matrix", format, field, symmetry] => (*format, *field, *symmetry),
+        _ => return Err("invalid Matrix Market header".to_string()),
+    };
+
+    if !matches!(field, "real" | "integer" | "pattern") {
+        return Err(format!("unsupported Matrix Market field `{}`", field));
+    }
+    if !matches!(symmetry, "general" | "symmetric" | "skew-symmetric") {
+        return Err(format!("unsupported Matrix Market symmetry `{}`", symmetry));
+    }
+
+    let mut entries = lines
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('%'));
+
+    let sizes = entries
+        .next()
+        .ok_or("missing Matrix Market size line")?
+        .split_whitespace()
+        .map(|word| {
+            word.parse::<usize>()
+                .map_err(|_| "invalid size line".to_string())
+        })
+        .collect::<Result<Vec<usize>, String>>()?;
+
+    let mut rows;
+    match (format, sizes.as_slice()) {
+        ("coordinate", [m, n, nnz]) => {
+            rows = vec![vec![0.0; *n]; *m];
+            let mut count = 0;
+
+            for entry in entries {
+                let words: Vec<&str> = entry.split_whitespace().collect();
+                let (i, j, val) = match (field, words.as_slice()) {
+                    ("pattern", [i, j]) => (*i, *j, 1.0),
+                    (_, [i, j, val]) => (
+                        *i,
+                        *j,
+                        val.parse::<f64>()
+                            .map_err(|_| format!("invalid entry `{}`", entry))?,
+                    ),
+                    _ => return Err(format!("invalid entry `{}`", entry)),
+                };
+                let (i, j) = match (i.parse::<usize>(), j.parse::<usize>()) {
+                    (Ok(i), Ok(j)) if i >= 1 && i <= *m && j >= 1 && j <= *n => (i - 1, j - 1),
+                    _ => return Err(format!("entry `{}` is out of bounds", entry)),
+                };
+
+                rows[i][j] = val;
+                match symmetry {
+                    "symmetric" if i != j => rows[j][i] = val,
+                    "skew-symmetric" if i != j => rows[j][i] = -val,
+                    _ => {}
+                }
+                count += 1;
+            }
+
+            if count != *nnz {
+                return Err(format!("expected {} entries, found {}", nnz, count));
+            }
+        }
+        ("array", [m, n]) => {
+            if field == "pattern" {
+                return Err("the array format cannot store a pattern".to_string());
+            }
+            rows = vec![vec![0.0; *n]; *m];
+
+            // The array format stores the columns one after another, and only the lower
+            // triangle of symmetric matrices.
+            let mut positions = (0..*n).flat_map(|j| {
+                let first = match symmetry {
+                    "general" => 0,
+                    "symmetric" => j,
+                    _ => j + 1,
+                };
+                (first..*m).map(move |i| (i, j))
+            });
+
+            for entry in entries {
+                let val = entry
+                    .parse::<f64>()
+                    .map_err(|_| format!("invalid entry `{}`", entry))?;
+                let (i, j) = positions.next().ok_or("too many entries")?;
+
+                rows[i][j] = val;
+                match symmetry {
+                    "symmetric" if i != j => rows[j][i] = val,
+                    "skew-symmetric" => rows[j][i] = -val,
+                    _ => {}
+                }
+            }
+
+            if positions.next().is_some() {
+                return Err("too few entries".to_string());
+            }
+        }
+        _ => return Err("invalid Matrix Market size line".to_string()),
+    }
+
+    Ok(rows)
+}
+
+/// Reads a matrix from a Matrix Market (`.mtx`) or a CSV file.
+pub fn read_matrix(path: &str) -> Result<Vec<Vec<f64>>, String> {
+    let contents = read(path)?;
+    let is_market = Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("mtx"));
+
+    let rows = if is_market {
+        parse_matrix_market(&contents)
+    } else {
+        parse_csv(&contents).map(|table| table.rows())
+    };
+
+    rows.map_err(|err| format!("{}: {}", path, err))
+}
+
+/// Reads a vector from a Matrix Market or CSV file with a single row or column.
+pub fn read_vector(path: &str) -> Result<Vec<f64>, String> {
+    let rows = read_matrix(path)?;
+
+    match rows.first().map(|row| row.len()) {
+        Some(1) => Ok(rows.into_iter().map(|row| row[0]).collect()),
+        Some(_) if rows.len() == 1 => Ok(rows.into_iter().next().unwrap()),
+        _ => Err(format!("{}: expected a single row or column", path)),
+    }
+}
+
+/// Reads a table from a CSV file.
+pub fn read_table(path: &str) -> Result<Table, String> {
+    parse_csv(&read(path)?).map_err(|err| format!("{}: {}", path, err))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_csv_with_header() {
+        let table = parse_csv("a, \"b\"\n1, 2\n3,\n").unwrap();
+
+        assert_eq!(vec!["a".to_string(), "b".to_string()], table.names);
+        assert_eq!(vec![1.0, 3.0], table.columns[0]);
+        assert!(table.columns[1][1].is_nan());
+        assert_eq!(&[1.0, 3.0], table.column("1").unwrap());
+        assert!(table.column("c").is_err());
+    }
+
+    #[test]
+    fn test_csv_without_header() {
+        let table = parse_csv("1,2\n3,4\n").unwrap();
+
+        assert_eq!(vec![vec![1.0, 2.0], vec![3.0, 4.0]], table.rows());
+    }
+
+    #[test]
+    fn test_csv_ragged_rows() {
+        assert!(parse_csv("1,2\n3\n").is_err());
+    }
+
+    #[test]
+    fn test_matrix_market_coordinate_symmetric() {
+        let contents = "%%MatrixMarket matrix coordinate real symmetric\n\
+                        % a comment\n\
+                        2 2 2\n\
+                        1 1 4\n\
+                        2 1 1\n";
+
+        assert_eq!(
+            vec![vec![4.0, 1.0], vec![1.0, 0.0]],
+            parse_matrix_market(contents).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_matrix_market_array() {
+        let contents = "%%MatrixMarket matrix array real general\n2 2\n1\n3\n2\n4\n";
+
+        assert_eq!(
+            vec![vec![1.0, 2.0], vec![3.0, 4.0]],
+            parse_matrix_market(contents).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_matrix_market_wrong_entry_count() {
+        let contents = "%%MatrixMarket matrix coordinate real general\n2 2 2\n1 1 1\n";
+
+        assert!(parse_matrix_market(contents).is_err());
+    }
+}
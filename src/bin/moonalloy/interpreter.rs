@@ -0,0 +1,855 @@
+//! Interpreter - Evaluation of the moonalloy expression language
+//!
+//! Expressions evaluate to scalars, Arrays or matrices. Arithmetic is delegated to `Array` and
+//! `Matrix`, and functions such as `solve` and `mean` are implemented with `linalg::methods`
+//! and the `Array` reductions.
+
+use crate::parser::{parse_line, BinaryOp, Expr, Statement};
+use crate::workspace;
+
+use moonalloy::linalg::array::Array;
+use moonalloy::linalg::matrix::Matrix;
+use moonalloy::linalg::methods::gauss_elimination;
+use moonalloy::statistics::functions;
+
+use std::collections::BTreeMap;
+
+/// The largest number of elements of a value created by a range or by functions such as
+/// `zeros`, so a typo like `1:1e12` is reported instead of exhausting the memory.
+const MAX_ELEMENTS: f64 = 1e8;
+
+/// The value of an expression.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Scalar(f64),
+    Array(Array),
+    Matrix(Matrix),
+}
+
+impl Value {
+    fn kind(&self) -> &'static str {
+        match self {
+            Value::Scalar(_) => "scalar",
+            Value::Array(_) => "array",
+            Value::Matrix(_) => "matrix",
+        }
+    }
+
+    fn as_scalar(&self, what: &str) -> Result<f64, String> {
+        match self {
+            Value::Scalar(val) => Ok(*val),
+            value => Err(format!("{} must be a scalar, found {}", what, value.kind())),
+        }
+    }
+
+    /// Returns the value as a vector. Matrices with a single row or column are accepted.
+    fn as_vector(&self, what: &str) -> Result<Vec<f64>, String> {
+        match self {
+            Value::Array(arr) => Ok(arr.to_vec()),
+            Value::Matrix(mat) => match mat.dimensions() {
+                (1, _) => Ok(mat[0].to_vec()),
+                (_, 1) => Ok(mat.iter().map(|row| row[0]).collect()),
+                (rows, cols) => Err(format!(
+                    "{} must be a vector, found a {}x{} matrix",
+                    what, rows, cols
+                )),
+            },
+            Value::Scalar(val) => Ok(vec![*val]),
+        }
+    }
+
+    fn as_index(&self, what: &str, len: usize) -> Result<usize, String> {
+        let index = self.as_scalar(what)?;
+
+        if index.fract() != 0.0 || index < 1.0 || index > len as f64 {
+            return Err(format!(
+                "{} {} is out of bounds for length {}",
+                what, index, len
+            ));
+        }
+
+        Ok(index as usize - 1)
+    }
+}
+
+/// Creates a Value from a vector of elements.
+pub fn array(values: Vec<f64>) -> Value {
+    Value::Array(Array::from_vec(values))
+}
+
+/// Creates a Value from a vector of rows.
+pub fn matrix(rows: Vec<Vec<f64>>) -> Value {
+    Value::Matrix(Matrix::from_rows(
+        rows.into_iter().map(Array::from_vec).collect(),
+    ))
+}
+
+fn rows_of(mat: &Matrix) -> Vec<Vec<f64>> {
+    mat.iter().map(|row| row.to_vec()).collect()
+}
+
+/// Applies a function to every element of a value.
+fn map(value: &Value, f: impl Fn(f64) -> f64) -> Value {
+    match value {
+        Value::Scalar(val) => Value::Scalar(f(*val)),
+        Value::Array(arr) => array(arr.iter().map(|&x| f(x)).collect()),
+        Value::Matrix(mat) => matrix(
+            mat.iter()
+                .map(|row| row.iter().map(|&x| f(x)).collect())
+                .collect(),
+        ),
+    }
+}
+
+/// Combines two values element by element. Scalars are combined with every element.
+fn zip(op: &str, lhs: &Value, rhs: &Value, f: impl Fn(f64, f64) -> f64) -> Result<Value, String> {
+    match (lhs, rhs) {
+        (Value::Scalar(a), b) => Ok(map(b, |x| f(*a, x))),
+        (a, Value::Scalar(b)) => Ok(map(a, |x| f(x, *b))),
+        (Value::Array(a), Value::Array(b)) => {
+            if a.len() != b.len() {
+                return Err(format!(
+                    "cannot {} arrays of lengths {} and {}",
+                    op,
+                    a.len(),
+                    b.len()
+                ));
+            }
+            Ok(array(a.iter().zip(b.iter()).map(|(&x, &y)| f(x, y)).collect()))
+        }
+        (Value::Matrix(a), Value::Matrix(b)) => {
+            if a.dimensions() != b.dimensions() {
+                let (r1, c1) = a.dimensions();
+                let (r2, c2) = b.dimensions();
+                return Err(format!(
+                    "cannot {} matrices of dimensions {}x{} and {}x{}",
+                    op, r1, c1, r2, c2
+                ));
+            }
+            Ok(matrix(
+                a.iter()
+                    .zip(b.iter())
+                    .map(|(x, y)| x.iter().zip(y.iter()).map(|(&x, &y)| f(x, y)).collect())
+                    .collect(),
+            ))
+        }
+        (a, b) => Err(format!("cannot {} {} and {}", op, a.kind(), b.kind())),
+    }
+}
+
+/// Matrix multiplication, where Arrays act as column vectors on the right and row vectors on
+/// the left.
+fn multiply(lhs: &Value, rhs: &Value) -> Result<Value, String> {
+    match (lhs, rhs) {
+        (Value::Scalar(_), _) | (_, Value::Scalar(_)) => zip("multiply", lhs, rhs, |x, y| x * y),
+        (Value::Matrix(a), Value::Matrix(b)) => {
+            let (r1, c1) = a.dimensions();
+            let (r2, c2) = b.dimensions();
+            if c1 != r2 {
+                return Err(format!(
+                    "cannot multiply a {}x{} matrix with a {}x{} matrix",
+                    r1, c1, r2, c2
+                ));
+            }
+            Ok(Value::Matrix(a.mult(b)))
+        }
+        (Value::Matrix(a), Value::Array(x)) => {
+            let (rows, cols) = a.dimensions();
+            if cols != x.len() {
+                return Err(format!(
+                    "cannot multiply a {}x{} matrix with an array of length {}",
+                    rows,
+                    cols,
+                    x.len()
+                ));
+            }
+            Ok(array(a.iter().map(|row| row.dotp(x)).collect()))
+        }
+        (Value::Array(x), Value::Matrix(a)) => {
+            let (rows, cols) = a.dimensions();
+            if rows != x.len() {
+                return Err(format!(
+                    "cannot multiply an array of length {} with a {}x{} matrix",
+                    x.len(),
+                    rows,
+                    cols
+                ));
+            }
+            Ok(array(
+                (0..cols)
+                    .map(|j| (0..rows).map(|i| x[i] * a[i][j]).sum())
+                    .collect(),
+            ))
+        }
+        (Value::Array(_), Value::Array(_)) => Err(
+            "cannot multiply two arrays, use `.*` for element-wise multiplication or `dot`"
+                .to_string(),
+        ),
+    }
+}
+
+fn identity(n: usize) -> Value {
+    matrix(
+        (0..n)
+            .map(|i| {
+                let mut row = vec![0.0; n];
+                row[i] = 1.0;
+                row
+            })
+            .collect(),
+    )
+}
+
+fn power(base: &Value, exponent: &Value) -> Result<Value, String> {
+    match (base, exponent) {
+        (Value::Scalar(b), Value::Scalar(e)) => Ok(Value::Scalar(b.powf(*e))),
+        (Value::Matrix(a), Value::Scalar(e)) => {
+            let (rows, cols) = a.dimensions();
+            if rows != cols || e.fract() != 0.0 || *e < 0.0 {
+                return Err(
+                    "matrix powers need a square matrix and a non-negative integer exponent"
+                        .to_string(),
+                );
+            }
+
+            let mut result = identity(rows);
+            for _ in 0..(*e as usize) {
+                result = multiply(&result, base)?;
+            }
+            Ok(result)
+        }
+        _ => Err("use `.^` for element-wise powers".to_string()),
+    }
+}
+
+fn transpose(value: &Value) -> Value {
+    match value {
+        Value::Matrix(mat) => Value::Matrix(mat.transpose()),
+        value => value.clone(),
+    }
+}
+
+fn range(start: f64, step: f64, end: f64) -> Result<Value, String> {
+    if step == 0.0 || !step.is_finite() || !start.is_finite() || !end.is_finite() {
+        return Err("invalid range".to_string());
+    }
+
+    let count = ((end - start) / step + 1e-10).floor() + 1.0;
+    if count <= 0.0 {
+        return Ok(array(Vec::new()));
+    }
+    if count > MAX_ELEMENTS {
+        return Err("range too large".to_string());
+    }
+
+    Ok(array(
+        (0..count as usize)
+            .map(|i| start + step * i as f64)
+            .collect(),
+    ))
+}
+
+fn check_args(name: &str, args: &[Value], min: usize, max: usize) -> Result<(), String> {
+    if args.len() < min || args.len() > max {
+        let expected = if min == max {
+            format!("{}", min)
+        } else {
+            format!("{} to {}", min, max)
+        };
+        return Err(format!(
+            "{} expects {} arguments, found {}",
+            name,
+            expected,
+            args.len()
+        ));
+    }
+
+    Ok(())
+}
+
+fn dimension(value: &Value, what: &str) -> Result<usize, String> {
+    let n = value.as_scalar(what)?;
+    if n.fract() != 0.0 || n < 0.0 {
+        return Err(format!("{} must be a non-negative integer", what));
+    }
+    Ok(n as usize)
+}
+
+/// Returns an error if a `rows`x`cols` value would have too many elements.
+fn check_size(rows: usize, cols: usize) -> Result<(), String> {
+    if rows as f64 * cols as f64 > MAX_ELEMENTS {
+        return Err(format!("a {}x{} value has too many elements", rows, cols));
+    }
+    Ok(())
+}
+
+/// Creates an Array of length `n` or a `rows`x`cols` matrix filled with `val`.
+fn filled(name: &str, args: &[Value], val: f64) -> Result<Value, String> {
+    check_args(name, args, 1, 2)?;
+
+    let rows = dimension(&args[0], "the size")?;
+    if args.len() == 1 {
+        check_size(1, rows)?;
+        return Ok(array(vec![val; rows]));
+    }
+
+    let cols = dimension(&args[1], "the size")?;
+    check_size(rows, cols)?;
+    Ok(matrix(vec![vec![val; cols]; rows]))
+}
+
+/// Applies a reduction to an Array, or to every column of a matrix.
+fn reduce(value: &Value, f: impl Fn(&Array) -> f64) -> Value {
+    match value {
+        Value::Scalar(val) => Value::Scalar(f(&Array::from_vec(vec![*val]))),
+        Value::Array(arr) => Value::Scalar(f(arr)),
+        Value::Matrix(mat) => {
+            let columns = mat.transpose();
+            array(columns.iter().map(f).collect())
+        }
+    }
+}
+
+fn integer(value: &Value, what: &str, max: usize) -> Result<usize, String> {
+    let n = dimension(value, what)?;
+    if n > max {
+        return Err(format!("{} must be at most {}", what, max));
+    }
+    Ok(n)
+}
+
+/// Calls a built-in function.
+fn call(name: &str, args: &[Value]) -> Result<Value, String> {
+    match name {
+        "zeros" => filled(name, args, 0.0),
+        "ones" => filled(name, args, 1.0),
+        "eye" => {
+            check_args(name, args, 1, 1)?;
+            let n = dimension(&args[0], "the size")?;
+            check_size(n, n)?;
+            Ok(identity(n))
+        }
+        "pi" => {
+            check_args(name, args, 0, 0)?;
+            Ok(Value::Scalar(std::f64::consts::PI))
+        }
+        "size" => {
+            check_args(name, args, 1, 1)?;
+            Ok(match &args[0] {
+                Value::Scalar(_) => array(vec![1.0, 1.0]),
+                Value::Array(arr) => array(vec![1.0, arr.len() as f64]),
+                Value::Matrix(mat) => {
+                    let (rows, cols) = mat.dimensions();
+                    array(vec![rows as f64, cols as f64])
+                }
+            })
+        }
+        "length" => {
+            check_args(name, args, 1, 1)?;
+            Ok(Value::Scalar(match &args[0] {
+                Value::Scalar(_) => 1.0,
+                Value::Array(arr) => arr.len() as f64,
+                Value::Matrix(mat) => {
+                    let (rows, cols) = mat.dimensions();
+                    rows.max(cols) as f64
+                }
+            }))
+        }
+        "sum" => {
+            check_args(name, args, 1, 1)?;
+            Ok(reduce(&args[0], Array::sum))
+        }
+        "mean" => {
+            check_args(name, args, 1, 1)?;
+            Ok(reduce(&args[0], Array::average))
+        }
+        "norm" => {
+            check_args(name, args, 1, 1)?;
+            Ok(match &args[0] {
+                Value::Matrix(mat) => Value::Scalar(
+                    mat.iter()
+                        .map(|row| row.dotp(row))
+                        .sum::<f64>()
+                        .sqrt(),
+                ),
+                value => reduce(value, Array::norm),
+            })
+        }
+        "dot" => {
+            check_args(name, args, 2, 2)?;
+            let x = args[0].as_vector("the first argument of dot")?;
+            let y = args[1].as_vector("the second argument of dot")?;
+            if x.len() != y.len() {
+                return Err(format!(
+                    "cannot take the dot product of arrays of lengths {} and {}",
+                    x.len(),
+                    y.len()
+                ));
+            }
+            Ok(Value::Scalar(
+                Array::from_vec(x).dotp(&Array::from_vec(y)),
+            ))
+        }
+        "transpose" => {
+            check_args(name, args, 1, 1)?;
+            Ok(transpose(&args[0]))
+        }
+        "solve" => {
+            check_args(name, args, 2, 2)?;
+            let a = match &args[0] {
+                Value::Matrix(mat) => mat.clone(),
+                value => {
+                    return Err(format!(
+                        "the coefficients of solve must be a matrix, found {}",
+                        value.kind()
+                    ))
+                }
+            };
+            let b = args[1].as_vector("the constants of solve")?;
+            let (rows, cols) = a.dimensions();
+            if rows != cols || rows != b.len() {
+                return Err(format!(
+                    "solve needs a square matrix and a matching number of constants, found a {}x{} matrix and {} constants",
+                    rows,
+                    cols,
+                    b.len()
+                ));
+            }
+
+            let x = gauss_elimination(a, Array::from_vec(b));
+            if x.iter().any(|val| !val.is_finite()) {
+                return Err("the matrix is singular".to_string());
+            }
+            Ok(Value::Array(x))
+        }
+        "sqrt" | "abs" | "exp" | "log" | "sin" | "cos" | "tan" | "floor" | "ceil" | "round"
+        | "gamma" | "lgamma" | "erf" | "erfc" | "erfinv" => {
+            check_args(name, args, 1, 1)?;
+            let f = match name {
+                "sqrt" => f64::sqrt,
+                "abs" => f64::abs,
+                "exp" => f64::exp,
+                "log" => f64::ln,
+                "sin" => f64::sin,
+                "cos" => f64::cos,
+                "tan" => f64::tan,
+                "floor" => f64::floor,
+                "ceil" => f64::ceil,
+                "round" => f64::round,
+                "gamma" => functions::gamma,
+                "lgamma" => functions::lgamma,
+                "erf" => functions::erf,
+                "erfc" => functions::erfc,
+                _ => functions::erfinv,
+            };
+            Ok(map(&args[0], f))
+        }
+        "factorial" => {
+            check_args(name, args, 1, 1)?;
+            let n = integer(&args[0], "n", 20)?;
+            Ok(Value::Scalar(functions::factorial(n) as f64))
+        }
+        "binomial" => {
+            check_args(name, args, 2, 2)?;
+            let n = integer(&args[0], "n", 20)?;
+            let k = integer(&args[1], "k", n)?;
+            Ok(Value::Scalar(functions::binomial_coefficient(n, k) as f64))
+        }
+        "dirac" => {
+            check_args(name, args, 1, 1)?;
+            let x = args[0].as_scalar("x")?;
+            Ok(Value::Scalar(if x == 0.0 { 1.0 } else { 0.0 }))
+        }
+        name => Err(format!("unknown function or variable `{}`", name)),
+    }
+}
+
+/// The names of the built-in functions, used by `help`.
+pub const FUNCTIONS: &[&str] = &[
+    "zeros(n)", "zeros(r, c)", "ones(n)", "ones(r, c)", "eye(n)", "size(x)", "length(x)",
+    "sum(x)", "mean(x)", "norm(x)", "dot(x, y)", "transpose(A)", "solve(A, b)", "sqrt(x)",
+    "abs(x)", "exp(x)", "log(x)", "sin(x)", "cos(x)", "tan(x)", "floor(x)", "ceil(x)",
+    "round(x)", "gamma(x)", "lgamma(x)", "erf(x)", "erfc(x)", "erfinv(x)", "factorial(n)",
+    "binomial(n, k)", "dirac(x)", "pi",
+];
+
+/// Indexes into a variable with 1-based indices.
+fn index(value: &Value, args: &[Value]) -> Result<Value, String> {
+    match (value, args) {
+        (Value::Scalar(val), [i]) => {
+            i.as_index("index", 1)?;
+            Ok(Value::Scalar(*val))
+        }
+        (Value::Array(arr), [i]) => Ok(Value::Scalar(arr[i.as_index("index", arr.len())?])),
+        (Value::Matrix(mat), [i]) => {
+            let row = i.as_index("row", mat.dimensions().0)?;
+            Ok(array(mat[row].to_vec()))
+        }
+        (Value::Matrix(mat), [i, j]) => {
+            let (rows, cols) = mat.dimensions();
+            Ok(Value::Scalar(
+                mat[i.as_index("row", rows)?][j.as_index("column", cols)?],
+            ))
+        }
+        _ => Err(format!(
+            "cannot index a {} with {} indices",
+            value.kind(),
+            args.len()
+        )),
+    }
+}
+
+/// A collection of named variables and the state of an interpreter session.
+#[derive(Default)]
+pub struct Workspace {
+    variables: BTreeMap<String, Value>,
+}
+
+impl Workspace {
+    pub fn new() -> Self {
+        Workspace::default()
+    }
+
+    pub fn set(&mut self, name: &str, value: Value) {
+        self.variables.insert(name.to_string(), value);
+    }
+
+    /// Evaluates an expression against the variables of the workspace.
+    pub fn evaluate(&self, expr: &Expr) -> Result<Value, String> {
+        match expr {
+            Expr::Number(n) => Ok(Value::Scalar(*n)),
+            Expr::Variable(name) => match self.variables.get(name) {
+                Some(value) => Ok(value.clone()),
+                None => call(name, &[]).map_err(|_| format!("undefined variable `{}`", name)),
+            },
+            Expr::Matrix(rows) => self.matrix_literal(rows),
+            Expr::Neg(expr) => Ok(map(&self.evaluate(expr)?, |x| -x)),
+            Expr::Transpose(expr) => Ok(transpose(&self.evaluate(expr)?)),
+            Expr::Range(start, step, end) => {
+                let start = self.evaluate(start)?.as_scalar("the start of a range")?;
+                let step = match step {
+                    Some(step) => self.evaluate(step)?.as_scalar("the step of a range")?,
+                    None => 1.0,
+                };
+                let end = self.evaluate(end)?.as_scalar("the end of a range")?;
+                range(start, step, end)
+            }
+            Expr::Binary(op, lhs, rhs) => {
+                let lhs = self.evaluate(lhs)?;
+                let rhs = self.evaluate(rhs)?;
+                match op {
+                    BinaryOp::Add => zip("add", &lhs, &rhs, |x, y| x + y),
+                    BinaryOp::Sub => zip("subtract", &lhs, &rhs, |x, y| x - y),
+                    BinaryOp::Mul => multiply(&lhs, &rhs),
+                    BinaryOp::Div => match rhs {
+                        Value::Scalar(_) => zip("divide", &lhs, &rhs, |x, y| x / y),
+                        _ => Err("use `./` for element-wise division".to_string()),
+                    },
+                    BinaryOp::Pow => power(&lhs, &rhs),
+                    BinaryOp::ElemMul => zip("multiply", &lhs, &rhs, |x, y| x * y),
+                    BinaryOp::ElemDiv => zip("divide", &lhs, &rhs, |x, y| x / y),
+                    BinaryOp::ElemPow => zip("raise", &lhs, &rhs, f64::powf),
+                }
+            }
+            Expr::Call(name, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.evaluate(arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match self.variables.get(name) {
+                    Some(value) => index(value, &args),
+                    None => call(name, &args),
+                }
+            }
+        }
+    }
+
+    /// Evaluates a matrix literal. A single row becomes an Array, while several rows become a
+    /// matrix. Nested Arrays and matrices are concatenated.
+    fn matrix_literal(&self, rows: &[Vec<Expr>]) -> Result<Value, String> {
+        let mut result: Vec<Vec<f64>> = Vec::new();
+
+        for row in rows {
+            let mut block: Vec<Vec<f64>> = Vec::new();
+
+            for element in row {
+                let element = match self.evaluate(element)? {
+                    Value::Scalar(val) => vec![vec![val]],
+                    Value::Array(arr) => vec![arr.to_vec()],
+                    Value::Matrix(mat) => rows_of(&mat),
+                };
+
+                if block.is_empty() {
+                    block = element;
+                } else if block.len() == element.len() {
+                    for (row, extension) in block.iter_mut().zip(element) {
+                        row.extend(extension);
+                    }
+                } else {
+                    return Err("inconsistent number of rows in matrix literal".to_string());
+                }
+            }
+
+            result.extend(block);
+        }
+
+        if let Some(first) = result.first() {
+            if result.iter().any(|row| row.len() != first.len()) {
+                return Err("inconsistent number of columns in matrix literal".to_string());
+            }
+        }
+
+        match result.len() {
+            0 => Ok(array(Vec::new())),
+            1 => Ok(array(result.remove(0))),
+            _ => Ok(matrix(result)),
+        }
+    }
+
+    /// Executes a line of input and returns the output to print.
+    ///
+    /// Besides statements, a line can contain one of the commands:
+    /// * `load <file>` - load the variables of a workspace file.
+    /// * `save <file> [names...]` - save all or some variables to a workspace file.
+    /// * `who` - list the variables in the workspace.
+    /// * `clear [names...]` - remove all or some variables from the workspace.
+    pub fn execute(&mut self, source: &str) -> Result<String, String> {
+        if let Some(output) = self.command(source)? {
+            return Ok(output);
+        }
+
+        let mut output = String::new();
+
+        for line in parse_line(source)? {
+            let (name, value) = match line.statement {
+                Statement::Assign(name, expr) => (name, self.evaluate(&expr)?),
+                Statement::Expr(expr) => ("ans".to_string(), self.evaluate(&expr)?),
+            };
+
+            if line.print {
+                output.push_str(&format_value(&name, &value));
+            }
+            self.set(&name, value);
+        }
+
+        Ok(output)
+    }
+
+    fn command(&mut self, source: &str) -> Result<Option<String>, String> {
+        let mut words = source.split_whitespace();
+        let command = words.next().unwrap_or("");
+        let args: Vec<&str> = words.collect();
+
+        // `who = 3` or `clear(x)` are statements, not commands.
+        if args.first().is_some_and(|arg| arg.starts_with('=')) {
+            return Ok(None);
+        }
+
+        match command {
+            "load" => {
+                let path = single_path(command, &args)?;
+                let variables = workspace::load(path)?;
+                let names: Vec<String> = variables.iter().map(|(name, _)| name.clone()).collect();
+                for (name, value) in variables {
+                    self.set(&name, value);
+                }
+                Ok(Some(format!("loaded {}\n", names.join(", "))))
+            }
+            "save" => {
+                let (path, names) = match args.split_first() {
+                    Some((path, names)) => (*path, names),
+                    None => return Err("usage: save <file> [names...]".to_string()),
+                };
+
+                let variables = if names.is_empty() {
+                    self.variables.iter().collect::<Vec<_>>()
+                } else {
+                    names
+                        .iter()
+                        .map(|name| {
+                            self.variables
+                                .get_key_value(*name)
+                                .ok_or_else(|| format!("undefined variable `{}`", name))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?
+                };
+
+                workspace::save(path, &variables)?;
+                Ok(Some(format!("saved {} variables to {}\n", variables.len(), path)))
+            }
+            "who" if args.is_empty() => Ok(Some(
+                self.variables
+                    .iter()
+                    .map(|(name, value)| format!("  {:<12} {}\n", name, describe(value)))
+                    .collect(),
+            )),
+            "clear" => {
+                if args.is_empty() {
+                    self.variables.clear();
+                } else {
+                    for name in args {
+                        self.variables.remove(name);
+                    }
+                }
+                Ok(Some(String::new()))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+fn single_path<'a>(command: &str, args: &[&'a str]) -> Result<&'a str, String> {
+    match args {
+        [path] => Ok(path),
+        _ => Err(format!("usage: {} <file>", command)),
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Scalar(_) => "scalar".to_string(),
+        Value::Array(arr) => format!("array of length {}", arr.len()),
+        Value::Matrix(mat) => {
+            let (rows, cols) = mat.dimensions();
+            format!("{}x{} matrix", rows, cols)
+        }
+    }
+}
+
+/// Formats a number for display with up to four decimals.
+pub fn format_number(val: f64) -> String {
+    if val.is_nan() {
+        return "NaN".to_string();
+    }
+    if val.is_infinite() {
+        return if val > 0.0 { "Inf" } else { "-Inf" }.to_string();
+    }
+    if val == 0.0 {
+        return "0".to_string();
+    }
+    if val.fract() == 0.0 && val.abs() < 1e15 {
+        return format!("{}", val);
+    }
+    if val.abs() >= 1e5 || val.abs() < 1e-4 {
+        return format!("{:.4e}", val);
+    }
+
+    let formatted = format!("{:.4}", val);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn format_rows(rows: &[Vec<f64>]) -> String {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(|&val| format_number(val)).collect())
+        .collect();
+    let width = cells
+        .iter()
+        .flatten()
+        .map(|cell| cell.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut output = String::new();
+    for row in cells {
+        for cell in row {
+            output.push_str(&format!("  {:>width$}", cell, width = width));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Formats a named value for display.
+pub fn format_value(name: &str, value: &Value) -> String {
+    match value {
+        Value::Scalar(val) => format!("{} = {}\n", name, format_number(*val)),
+        Value::Array(arr) if arr.is_empty() => format!("{} = []\n", name),
+        Value::Array(arr) => format!("{} =\n{}", name, format_rows(&[arr.to_vec()])),
+        Value::Matrix(mat) => format!("{} =\n{}", name, format_rows(&rows_of(mat))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn eval(workspace: &mut Workspace, source: &str) -> Value {
+        workspace.execute(source).unwrap();
+        workspace.variables["ans"].clone()
+    }
+
+    fn assert_vector(expected: &[f64], value: &Value) {
+        let actual = value.as_vector("value").unwrap();
+        assert_eq!(expected.len(), actual.len());
+        for (e, a) in expected.iter().zip(actual.iter()) {
+            assert!((e - a).abs() < 1e-12, "expected {:?}, found {:?}", expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_matrix_product_with_transpose() {
+        let mut ws = Workspace::new();
+        ws.execute("A = [1 2; 3 4];").unwrap();
+
+        match eval(&mut ws, "A * A'") {
+            Value::Matrix(mat) => assert_eq!(
+                vec![vec![5.0, 11.0], vec![11.0, 25.0]],
+                rows_of(&mat)
+            ),
+            value => panic!("expected a matrix, found {:?}", value),
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        let mut ws = Workspace::new();
+        ws.execute("A = [3 2; -6 6]; b = [7; 6];").unwrap();
+
+        assert_vector(&[1.0, 2.0], &eval(&mut ws, "solve(A, b)"));
+    }
+
+    #[test]
+    fn test_mean_and_indexing() {
+        let mut ws = Workspace::new();
+        ws.execute("x = 1:4").unwrap();
+
+        assert_eq!(2.5, eval(&mut ws, "mean(x)").as_scalar("mean").unwrap());
+        assert_eq!(3.0, eval(&mut ws, "x(3)").as_scalar("x").unwrap());
+        assert_vector(&[2.0, 3.0], &eval(&mut ws, "mean([1 2; 3 4])"));
+    }
+
+    #[test]
+    fn test_element_wise_operations() {
+        let mut ws = Workspace::new();
+
+        assert_vector(&[2.0, 4.0, 8.0], &eval(&mut ws, "2.^[1 2 3]"));
+        assert_vector(&[0.5, 1.0], &eval(&mut ws, "[1 2] / 2"));
+        assert_vector(&[3.0, 8.0], &eval(&mut ws, "[1 2] .* [3 4]"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut ws = Workspace::new();
+
+        assert!(ws.execute("[1 2] + [1 2 3]").is_err());
+        assert!(ws.execute("undefined_name").is_err());
+        assert!(ws.execute("solve([1 2; 3 4], [1 2 3])").is_err());
+        assert!(ws.execute("[1 2] * [3 4]").is_err());
+        assert_eq!(Err("range too large".to_string()), ws.execute("1:1e12"));
+        assert!(ws.execute("zeros(1e6, 1e6)").is_err());
+        assert!(ws.execute("eye(1e5)").is_err());
+    }
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!("x = 0.3333\n", format_value("x", &Value::Scalar(1.0 / 3.0)));
+        assert_eq!(
+            "A =\n   1  -2\n  30   4\n",
+            format_value("A", &matrix(vec![vec![1.0, -2.0], vec![30.0, 4.0]]))
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! moonalloy - An interactive calculator for Arrays and matrices
+//!
+//! Without arguments an interactive session is started. With a file argument the file is run
+//! as a script, stopping at the first error. The commands `solve`, `describe` and `fit` run a
+//! single batch job and write the results as CSV or JSON.
+
+mod commands;
+mod data;
+mod interpreter;
+mod parser;
+mod workspace;
+
+use commands::COMMANDS;
+use interpreter::{Workspace, FUNCTIONS};
+
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process;
+
+const USAGE: &str = "\
+usage: moonalloy [script]
+       moonalloy solve <A.mtx|A.csv> <b.csv> [--format csv|json]
+       moonalloy describe <data.csv> [--format csv|json]
+       moonalloy fit linear <data.csv> --x <column> --y <column> [--format csv|json]
+
+Starts an interactive session, or runs the statements of `script` one line at a time.
+
+The commands solve a linear system, summarise the columns of a CSV file, or fit a line
+through two of its columns, which are chosen by header name or 1-based position.
+
+Exit status:
+  0  success
+  1  the statements or the computation failed, e.g. a singular matrix
+  2  invalid command line
+  3  an input file cannot be read or parsed";
+
+const HELP: &str = "\
+Statements:
+  A = [1 2; 3 4]        assign a matrix, rows are separated by `;`
+  x = [1, 2]            a single row is an array
+  A * A'                matrix product and transpose
+  x .* x, x ./ 2, x.^2  element-wise operations
+  x = 0:0.5:2           ranges
+  A(2, 1), x(3)         1-based indexing
+  stmt;                 a trailing `;` suppresses the output
+Commands:
+  load <file>           load variables from a workspace file
+  save <file> [names]   save all or some variables to a workspace file
+  who                   list the variables
+  clear [names]         remove all or some variables
+  help                  show this help
+  exit, quit            leave the session";
+
+/// Runs an interactive session on standard input.
+fn repl() {
+    let mut workspace = Workspace::new();
+    let interactive = io::stdin().is_terminal();
+
+    if interactive {
+        println!(
+            "moonalloy {} - type `help` for help",
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        if interactive {
+            print!(">> ");
+            io::stdout().flush().unwrap();
+        }
+
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            Some(Err(err)) => {
+                eprintln!("error: {}", err);
+                process::exit(1);
+            }
+            None => break,
+        };
+
+        match line.trim() {
+            "exit" | "quit" => break,
+            "help" => {
+                println!("{}\nFunctions:\n  {}", HELP, FUNCTIONS.join(", "));
+                continue;
+            }
+            _ => {}
+        }
+
+        match workspace.execute(&line) {
+            Ok(output) => print!("{}", output),
+            Err(err) => eprintln!("error: {}", err),
+        }
+    }
+}
+
+/// Runs a script, exiting with a non-zero status on the first error.
+fn script(path: &str) {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("error: cannot read {}: {}", path, err);
+            process::exit(3);
+        }
+    };
+
+    let mut workspace = Workspace::new();
+
+    for (number, line) in source.lines().enumerate() {
+        if matches!(line.trim(), "exit" | "quit") {
+            break;
+        }
+
+        match workspace.execute(line) {
+            Ok(output) => print!("{}", output),
+            Err(err) => {
+                eprintln!("{}:{}: error: {}", path, number + 1, err);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.as_slice() {
+        [] => repl(),
+        [flag] if flag == "-h" || flag == "--help" => println!("{}", USAGE),
+        [command, rest @ ..] if COMMANDS.contains(&command.as_str()) => {
+            match commands::run(command, rest) {
+                Ok(output) => print!("{}", output),
+                Err(failure) => {
+                    eprintln!("error: {}", failure.message());
+                    if let commands::Failure::Usage(_) = failure {
+                        eprintln!("{}", USAGE);
+                    }
+                    process::exit(failure.status());
+                }
+            }
+        }
+        [path] => script(path),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,643 @@
+//! Parser - A tokenizer and recursive descent parser for the moonalloy expression language
+//!
+//! The language is a small, MATLAB-like language for working with scalars, Arrays and
+//! matrices:
+//!
+//! ```text
+//! A = [1 2; 3 4]      % matrix literal, rows are separated by `;`
+//! x = [1, 2]          % a single row is an Array
+//! A * A'              % matrix product and transpose
+//! solve(A, x);        % a trailing `;` suppresses the output
+//! y = 1:0.5:3         % ranges
+//! ```
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A binary operator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    ElemMul,
+    ElemDiv,
+    ElemPow,
+}
+
+/// An expression of the language.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Variable(String),
+    /// A matrix literal, stored as rows of elements.
+    Matrix(Vec<Vec<Expr>>),
+    Neg(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Transpose(Box<Expr>),
+    /// A range `start:step:end`.
+    Range(Box<Expr>, Option<Box<Expr>>, Box<Expr>),
+    /// A function call or an index into a variable, e.g. `mean(x)` or `x(2)`.
+    Call(String, Vec<Expr>),
+}
+
+/// A statement of the language.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    Assign(String, Expr),
+    Expr(Expr),
+}
+
+/// A parsed statement and whether its result should be printed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    pub statement: Statement,
+    pub print: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    DotStar,
+    DotSlash,
+    DotCaret,
+    Quote,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    Semicolon,
+    Colon,
+    Equals,
+}
+
+/// A token and whether it was preceded by whitespace.
+///
+/// Whitespace separates the elements of matrix literals, so `[1 -2]` has two elements while
+/// `[1 - 2]` has one.
+#[derive(Debug, Clone, PartialEq)]
+struct Spanned {
+    token: Token,
+    spaced: bool,
+}
+
+fn tokenize(source: &str) -> Result<Vec<Spanned>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut spaced = false;
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            spaced = true;
+            continue;
+        }
+
+        if c == '%' || c == '#' {
+            break;
+        }
+
+        let token = if c.is_ascii_digit()
+            || (c == '.' && chars.clone().nth(1).is_some_and(|d| d.is_ascii_digit()))
+        {
+            Token::Number(number(&mut chars)?)
+        } else if c.is_alphabetic() || c == '_' {
+            let mut ident = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' {
+                    ident.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            Token::Ident(ident)
+        } else {
+            chars.next();
+            match c {
+                '+' => Token::Plus,
+                '-' => Token::Minus,
+                '*' => Token::Star,
+                '/' => Token::Slash,
+                '^' => Token::Caret,
+                '\'' => Token::Quote,
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                '[' => Token::LBracket,
+                ']' => Token::RBracket,
+                ',' => Token::Comma,
+                ';' => Token::Semicolon,
+                ':' => Token::Colon,
+                '=' => Token::Equals,
+                '.' => match chars.next() {
+                    Some('*') => Token::DotStar,
+                    Some('/') => Token::DotSlash,
+                    Some('^') => Token::DotCaret,
+                    _ => return Err("expected `.*`, `./` or `.^` after `.`".to_string()),
+                },
+                c => return Err(format!("unexpected character `{}`", c)),
+            }
+        };
+
+        tokens.push(Spanned { token, spaced });
+        spaced = false;
+    }
+
+    Ok(tokens)
+}
+
+fn number(chars: &mut Peekable<Chars>) -> Result<f64, String> {
+    let mut literal = String::new();
+
+    while let Some(&c) = chars.peek() {
+        // `2.^x` is an element-wise power, not the number `2.`
+        let operator = c == '.'
+            && matches!(chars.clone().nth(1), Some('*') | Some('/') | Some('^'));
+
+        if c.is_ascii_digit() || (c == '.' && !operator) {
+            literal.push(c);
+            chars.next();
+        } else {
+            break;
+        }
+    }
+
+    if let Some(&c) = chars.peek() {
+        if c == 'e' || c == 'E' {
+            let mut lookahead = chars.clone();
+            lookahead.next();
+            let sign = lookahead.peek().is_some_and(|&c| c == '+' || c == '-');
+            if sign {
+                lookahead.next();
+            }
+
+            if lookahead.peek().is_some_and(|c| c.is_ascii_digit()) {
+                literal.push(c);
+                chars.next();
+                if sign {
+                    literal.push(chars.next().unwrap());
+                }
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_digit() {
+                        literal.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    literal
+        .parse()
+        .map_err(|_| format!("invalid number `{}`", literal))
+}
+
+/// Parses a line of source code into its statements.
+///
+/// Statements are separated by `,` or `;`, where `;` also suppresses the output of the
+/// statement before it.
+pub fn parse_line(source: &str) -> Result<Vec<Line>, String> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        in_matrix: vec![false],
+    };
+    let mut lines = Vec::new();
+
+    while parser.peek().is_some() {
+        let statement = parser.statement()?;
+        let print = match parser.peek() {
+            None => true,
+            Some(Token::Comma) => {
+                parser.pos += 1;
+                true
+            }
+            Some(Token::Semicolon) => {
+                parser.pos += 1;
+                false
+            }
+            Some(token) => return Err(format!("unexpected {}", describe(token))),
+        };
+
+        lines.push(Line { statement, print });
+    }
+
+    Ok(lines)
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Number(n) => format!("number `{}`", n),
+        Token::Ident(name) => format!("identifier `{}`", name),
+        token => {
+            let symbol = match token {
+                Token::Plus => "+",
+                Token::Minus => "-",
+                Token::Star => "*",
+                Token::Slash => "/",
+                Token::Caret => "^",
+                Token::DotStar => ".*",
+                Token::DotSlash => "./",
+                Token::DotCaret => ".^",
+                Token::Quote => "'",
+                Token::LParen => "(",
+                Token::RParen => ")",
+                Token::LBracket => "[",
+                Token::RBracket => "]",
+                Token::Comma => ",",
+                Token::Semicolon => ";",
+                Token::Colon => ":",
+                Token::Equals => "=",
+                Token::Number(_) | Token::Ident(_) => unreachable!(),
+            };
+            format!("`{}`", symbol)
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    pos: usize,
+    /// Whether the innermost bracket is a matrix literal. Parentheses push `false`.
+    in_matrix: Vec<bool>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|t| &t.token)
+    }
+
+    fn peek_spanned(&self) -> Option<&Spanned> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|t| t.token.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(ref token) if *token == expected => Ok(()),
+            Some(token) => Err(format!(
+                "expected {}, found {}",
+                describe(&expected),
+                describe(&token)
+            )),
+            None => Err(format!("expected {}, found end of line", describe(&expected))),
+        }
+    }
+
+    fn matrix_context(&self) -> bool {
+        *self.in_matrix.last().unwrap()
+    }
+
+    /// Returns true if the next token starts a new element of a matrix literal rather than
+    /// continuing the current one, e.g. the `-2` in `[1 -2]`.
+    fn at_element_boundary(&self) -> bool {
+        if !self.matrix_context() {
+            return false;
+        }
+
+        match self.peek_spanned() {
+            Some(Spanned {
+                token: Token::Plus,
+                spaced: true,
+            })
+            | Some(Spanned {
+                token: Token::Minus,
+                spaced: true,
+            }) => self.tokens.get(self.pos + 1).is_some_and(|t| !t.spaced),
+            _ => false,
+        }
+    }
+
+    fn statement(&mut self) -> Result<Statement, String> {
+        if let (Some(Token::Ident(name)), Some(Token::Equals)) = (
+            self.peek().cloned(),
+            self.tokens.get(self.pos + 1).map(|t| &t.token),
+        ) {
+            self.pos += 2;
+            return Ok(Statement::Assign(name, self.expression()?));
+        }
+
+        Ok(Statement::Expr(self.expression()?))
+    }
+
+    fn expression(&mut self) -> Result<Expr, String> {
+        let start = self.additive()?;
+
+        if self.peek() != Some(&Token::Colon) || self.at_element_boundary() {
+            return Ok(start);
+        }
+
+        self.pos += 1;
+        let second = self.additive()?;
+
+        if self.peek() == Some(&Token::Colon) {
+            self.pos += 1;
+            let end = self.additive()?;
+            Ok(Expr::Range(
+                Box::new(start),
+                Some(Box::new(second)),
+                Box::new(end),
+            ))
+        } else {
+            Ok(Expr::Range(Box::new(start), None, Box::new(second)))
+        }
+    }
+
+    fn additive(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.multiplicative()?;
+
+        loop {
+            if self.at_element_boundary() {
+                return Ok(lhs);
+            }
+
+            let op = match self.peek() {
+                Some(Token::Plus) => BinaryOp::Add,
+                Some(Token::Minus) => BinaryOp::Sub,
+                _ => return Ok(lhs),
+            };
+            self.pos += 1;
+
+            let rhs = self.multiplicative()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+
+        loop {
+            let op = match self.peek() {
+                Some(Token::Star) => BinaryOp::Mul,
+                Some(Token::Slash) => BinaryOp::Div,
+                Some(Token::DotStar) => BinaryOp::ElemMul,
+                Some(Token::DotSlash) => BinaryOp::ElemDiv,
+                _ => return Ok(lhs),
+            };
+            self.pos += 1;
+
+            let rhs = self.unary()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some(Token::Minus) => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            }
+            Some(Token::Plus) => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Expr, String> {
+        let base = self.postfix()?;
+
+        let op = match self.peek() {
+            Some(Token::Caret) => BinaryOp::Pow,
+            Some(Token::DotCaret) => BinaryOp::ElemPow,
+            _ => return Ok(base),
+        };
+        self.pos += 1;
+
+        let exponent = self.unary()?;
+        Ok(Expr::Binary(op, Box::new(base), Box::new(exponent)))
+    }
+
+    fn postfix(&mut self) -> Result<Expr, String> {
+        let mut expr = self.primary()?;
+
+        while self.peek() == Some(&Token::Quote) {
+            self.pos += 1;
+            expr = Expr::Transpose(Box::new(expr));
+        }
+
+        Ok(expr)
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::Ident(name)) => {
+                let call = match self.peek_spanned() {
+                    Some(Spanned {
+                        token: Token::LParen,
+                        spaced,
+                    }) => !(*spaced && self.matrix_context()),
+                    _ => false,
+                };
+
+                if call {
+                    self.pos += 1;
+                    Ok(Expr::Call(name, self.arguments()?))
+                } else {
+                    Ok(Expr::Variable(name))
+                }
+            }
+            Some(Token::LParen) => {
+                self.in_matrix.push(false);
+                let expr = self.expression();
+                self.in_matrix.pop();
+                let expr = expr?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::LBracket) => {
+                self.in_matrix.push(true);
+                let rows = self.matrix_rows();
+                self.in_matrix.pop();
+                Ok(Expr::Matrix(rows?))
+            }
+            Some(token) => Err(format!("unexpected {}", describe(&token))),
+            None => Err("unexpected end of line".to_string()),
+        }
+    }
+
+    fn arguments(&mut self) -> Result<Vec<Expr>, String> {
+        self.in_matrix.push(false);
+        let args = self.argument_list();
+        self.in_matrix.pop();
+        args
+    }
+
+    fn argument_list(&mut self) -> Result<Vec<Expr>, String> {
+        let mut args = Vec::new();
+
+        if self.peek() == Some(&Token::RParen) {
+            self.pos += 1;
+            return Ok(args);
+        }
+
+        loop {
+            args.push(self.expression()?);
+
+            match self.next() {
+                Some(Token::Comma) => continue,
+                Some(Token::RParen) => return Ok(args),
+                Some(token) => return Err(format!("unexpected {}", describe(&token))),
+                None => return Err("missing `)`".to_string()),
+            }
+        }
+    }
+
+    fn matrix_rows(&mut self) -> Result<Vec<Vec<Expr>>, String> {
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+
+        loop {
+            match self.peek() {
+                Some(Token::RBracket) => {
+                    self.pos += 1;
+                    if !row.is_empty() || !rows.is_empty() {
+                        rows.push(row);
+                    }
+                    return Ok(rows);
+                }
+                Some(Token::Semicolon) => {
+                    self.pos += 1;
+                    rows.push(std::mem::take(&mut row));
+                }
+                Some(Token::Comma) => {
+                    self.pos += 1;
+                }
+                Some(_) => row.push(self.expression()?),
+                None => return Err("missing `]`".to_string()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse_expr(source: &str) -> Expr {
+        match parse_line(source).unwrap().remove(0).statement {
+            Statement::Expr(expr) => expr,
+            statement => panic!("expected an expression, found {:?}", statement),
+        }
+    }
+
+    fn num(n: f64) -> Expr {
+        Expr::Number(n)
+    }
+
+    #[test]
+    fn test_precedence() {
+        let expected = Expr::Binary(
+            BinaryOp::Add,
+            Box::new(num(1.0)),
+            Box::new(Expr::Binary(
+                BinaryOp::Mul,
+                Box::new(num(2.0)),
+                Box::new(num(3.0)),
+            )),
+        );
+
+        assert_eq!(expected, parse_expr("1 + 2 * 3"));
+    }
+
+    #[test]
+    fn test_unary_minus_binds_looser_than_power() {
+        let expected = Expr::Neg(Box::new(Expr::Binary(
+            BinaryOp::Pow,
+            Box::new(num(2.0)),
+            Box::new(num(2.0)),
+        )));
+
+        assert_eq!(expected, parse_expr("-2^2"));
+    }
+
+    #[test]
+    fn test_matrix_literal() {
+        let expected = Expr::Matrix(vec![vec![num(1.0), num(2.0)], vec![num(3.0), num(4.0)]]);
+
+        assert_eq!(expected, parse_expr("[1 2; 3 4]"));
+    }
+
+    #[test]
+    fn test_matrix_literal_whitespace_separates_signed_elements() {
+        let separate = Expr::Matrix(vec![vec![num(1.0), Expr::Neg(Box::new(num(2.0)))]]);
+        let subtraction = Expr::Matrix(vec![vec![Expr::Binary(
+            BinaryOp::Sub,
+            Box::new(num(1.0)),
+            Box::new(num(2.0)),
+        )]]);
+
+        assert_eq!(separate, parse_expr("[1 -2]"));
+        assert_eq!(subtraction, parse_expr("[1 - 2]"));
+    }
+
+    #[test]
+    fn test_transpose_and_call() {
+        let expected = Expr::Binary(
+            BinaryOp::Mul,
+            Box::new(Expr::Variable("A".to_string())),
+            Box::new(Expr::Transpose(Box::new(Expr::Variable("A".to_string())))),
+        );
+
+        assert_eq!(expected, parse_expr("A * A'"));
+        assert_eq!(
+            Expr::Call(
+                "solve".to_string(),
+                vec![Expr::Variable("A".to_string()), Expr::Variable("b".to_string())]
+            ),
+            parse_expr("solve(A, b)")
+        );
+    }
+
+    #[test]
+    fn test_assignment_and_suppression() {
+        let lines = parse_line("x = 1e-3; y = x").unwrap();
+
+        assert_eq!(
+            vec![
+                Line {
+                    statement: Statement::Assign("x".to_string(), num(1e-3)),
+                    print: false,
+                },
+                Line {
+                    statement: Statement::Assign(
+                        "y".to_string(),
+                        Expr::Variable("x".to_string())
+                    ),
+                    print: true,
+                },
+            ],
+            lines
+        );
+    }
+
+    #[test]
+    fn test_comments_are_ignored() {
+        assert!(parse_line("% nothing to see here").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unbalanced_brackets() {
+        assert!(parse_line("[1 2").is_err());
+        assert!(parse_line("(1 + 2").is_err());
+    }
+}
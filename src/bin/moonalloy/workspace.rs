@@ -0,0 +1,148 @@
+//! Workspace files - Saving and loading variables
+//!
+//! A workspace file is a plain text file with one variable per line:
+//!
+//! ```text
+//! scalar x 3.5
+//! array v 3 1 2 3
+//! matrix A 2 2 1 2 3 4
+//! ```
+//!
+//! Arrays store their length and matrices their dimensions before the elements. Numbers are
+//! written with enough digits to be read back exactly.
+
+use crate::interpreter::{array, matrix, Value};
+
+use std::fs;
+
+const HEADER: &str = "# moonalloy workspace";
+
+/// Saves variables to a workspace file.
+pub fn save(path: &str, variables: &[(&String, &Value)]) -> Result<(), String> {
+    let mut contents = String::from(HEADER);
+    contents.push('\n');
+
+    for (name, value) in variables {
+        let line = match value {
+            Value::Scalar(val) => format!("scalar {} {}", name, val),
+            Value::Array(arr) => format!("array {} {}{}", name, arr.len(), join(arr)),
+            Value::Matrix(mat) => {
+                let (rows, cols) = mat.dimensions();
+                let elements: String = mat.iter().map(|row| join(row)).collect();
+                format!("matrix {} {} {}{}", name, rows, cols, elements)
+            }
+        };
+        contents.push_str(&line);
+        contents.push('\n');
+    }
+
+    fs::write(path, contents).map_err(|err| format!("cannot write {}: {}", path, err))
+}
+
+fn join(values: &[f64]) -> String {
+    values.iter().map(|val| format!(" {}", val)).collect()
+}
+
+/// Loads the variables of a workspace file.
+pub fn load(path: &str) -> Result<Vec<(String, Value)>, String> {
+    let contents =
+        fs::read_to_string(path).map_err(|err| format!("cannot read {}: {}", path, err))?;
+
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| {
+            parse_variable(line).map_err(|err| format!("{}:{}: {}", path, number + 1, err))
+        })
+        .collect()
+}
+
+fn parse_variable(line: &str) -> Result<(String, Value), String> {
+    let mut words = line.split_whitespace();
+    let kind = words.next().ok_or("missing variable kind")?;
+    let name = words.next().ok_or("missing variable name")?.to_string();
+    let numbers = words
+        .map(|word| {
+            word.parse::<f64>()
+                .map_err(|_| format!("invalid number `{}`", word))
+        })
+        .collect::<Result<Vec<f64>, String>>()?;
+
+    let value = match (kind, numbers.as_slice()) {
+        ("scalar", [val]) => Value::Scalar(*val),
+        ("array", [len, elements @ ..]) if dimension(*len) == Some(elements.len()) => {
+            array(elements.to_vec())
+        }
+        ("matrix", [rows, cols, elements @ ..]) => match (dimension(*rows), dimension(*cols)) {
+            (Some(rows), Some(cols))
+                if cols >= 1 && rows.checked_mul(cols) == Some(elements.len()) =>
+            {
+                matrix(elements.chunks(cols).map(|row| row.to_vec()).collect())
+            }
+            _ => return Err(format!("invalid {} `{}`", kind, name)),
+        },
+        _ => return Err(format!("invalid {} `{}`", kind, name)),
+    };
+
+    Ok((name, value))
+}
+
+/// Returns a dimension read as a number, if it is a non-negative integer.
+fn dimension(x: f64) -> Option<usize> {
+    if x >= 0.0 && x.fract() == 0.0 && x <= usize::MAX as f64 {
+        Some(x as usize)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("moonalloy-{}.ws", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let x = Value::Scalar(0.1 + 0.2);
+        let v = array(vec![1.0, -2.5, 1e-300]);
+        let a = matrix(vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+        let (nx, nv, na) = ("x".to_string(), "v".to_string(), "A".to_string());
+
+        save(path, &[(&nx, &x), (&nv, &v), (&na, &a)]).unwrap();
+        let loaded = load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(3, loaded.len());
+        match &loaded[0] {
+            (name, Value::Scalar(val)) => {
+                assert_eq!("x", name);
+                assert_eq!(0.1 + 0.2, *val);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        match &loaded[1] {
+            (_, Value::Array(arr)) => assert_eq!(&[1.0, -2.5, 1e-300], arr.as_slice()),
+            other => panic!("unexpected {:?}", other),
+        }
+        match &loaded[2] {
+            (_, Value::Matrix(mat)) => {
+                assert_eq!((2, 2), mat.dimensions());
+                assert_eq!(3.0, mat[1][0]);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_load_rejects_malformed_lines() {
+        assert!(parse_variable("array v 3 1 2").is_err());
+        assert!(parse_variable("matrix A 2 2 1 2 3").is_err());
+        assert!(parse_variable("scalar x one").is_err());
+        assert!(parse_variable("array v 2.5 1 2").is_err());
+        assert!(parse_variable("matrix A 2 2.5 1 2 3 4").is_err());
+        assert!(parse_variable("matrix A -2 -2 1 2 3 4").is_err());
+    }
+}
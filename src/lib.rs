@@ -10,20 +10,26 @@
 //! ![moonalloy-luajit](https://git.hacktheoxidation.xyz/HackTheOxidation/moonalloy-luajit).
 
 pub mod linalg;
+#[cfg(feature = "lua")]
+pub mod lua;
 pub mod machine_learning;
+pub mod random;
 pub mod statistics;
 
 use crate::linalg::array::Array;
 use crate::linalg::matrix::Matrix;
 use crate::linalg::methods::gauss_elimination;
 
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 use std::ffi::CString;
 use std::os::raw::c_char;
 
 /// Moonalloy as a python module.
+#[cfg(feature = "python")]
 #[pymodule]
 fn moonalloy(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    machine_learning::python::register(m)?;
     Ok(())
 }
 
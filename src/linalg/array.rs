@@ -2,21 +2,36 @@
 //!
 //! This module contains structures and functions for manipulating vectors/arrays in Linear
 //! Algebra.
+//!
+//! # Thread safety
+//!
+//! An Array owns its elements: the buffer behind the raw pointer is allocated by the Array,
+//! is never shared with another Array and is freed when the Array is dropped. Cloning an Array
+//! copies the elements into a new buffer. The elements can therefore only be reached through
+//! the Array itself, so the borrow checker rules for `&Array` and `&mut Array` carry over to
+//! the elements, which makes Arrays `Send` and `Sync` just like `Vec<f64>`.
+
+use crate::linalg::summation::{self, Summation};
+use crate::random::{samplers, Rng};
 
-use std::alloc::{alloc, Layout};
 use std::fmt::*;
 use std::ops::{Add, Deref, DerefMut, Index, IndexMut, Mul, Neg, Sub};
 
 /// A representation of a mathematical array/vector
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug)]
 #[repr(C)]
 pub struct Array {
     /// Number of elements in the Array
     len: usize,
-    /// Elements of the Array, stored as a mutable pointer
+    /// Elements of the Array, stored as a mutable pointer to a buffer owned by the Array
     arr: *mut f64,
 }
 
+// SAFETY: An Array is the unique owner of its buffer (see the module documentation), so moving
+// it to another thread moves the buffer with it, and shared references only allow reads.
+unsafe impl Send for Array {}
+unsafe impl Sync for Array {}
+
 impl Array {
     /// Returns a new Array with no elements
     ///
@@ -28,58 +43,85 @@ impl Array {
     /// let array = Array::new();
     /// ```
     pub fn new() -> Array {
-        let arr_slice = unsafe {
-            let layout = Layout::new::<f64>();
-            let ptr = alloc(layout);
-            std::slice::from_raw_parts_mut(ptr as *mut f64, 0)
-        };
-
-        Array {
-            len: 0,
-            arr: arr_slice.as_mut_ptr(),
-        }
+        Array::from_vec(Vec::new())
     }
 
     /// Creates a new Array from a slice of elements
     ///
     /// # Arguments
     ///
-    /// * `slice` - A mutable slice of float values. This will become the internal values of the
-    /// Array.
+    /// * `slice` - A slice of float values. The values are copied into the Array.
     ///
     /// # Examples
     ///
     /// ```
     /// // Create a new Array containing the values 1.0, 2.0 and 3.0
     /// use moonalloy::linalg::array::Array;
-    /// let array = Array::from(&mut [1.0, 2.0, 3.0]);
+    /// let array = Array::from(&[1.0, 2.0, 3.0]);
     /// ```
-    pub fn from(slice: &mut [f64]) -> Array {
+    pub fn from(slice: &[f64]) -> Array {
+        Array::from_vec(slice.to_vec())
+    }
+
+    /// Creates a new Array that takes ownership of the elements of a vector
+    ///
+    /// # Arguments
+    ///
+    /// * `vec` - a vector of float values. This will become the internal values of the Array.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Create a new Array containing the values 1.0, 2.0 and 3.0
+    /// use moonalloy::linalg::array::Array;
+    /// let array = Array::from_vec(vec![1.0, 2.0, 3.0]);
+    ///
+    /// assert_eq!(3, array.len());
+    /// ```
+    pub fn from_vec(vec: Vec<f64>) -> Array {
+        let boxed = vec.into_boxed_slice();
         Array {
-            len: slice.len(),
-            arr: slice.as_mut_ptr(),
+            len: boxed.len(),
+            arr: Box::into_raw(boxed) as *mut f64,
         }
     }
 
     /// Calculate the sum of all the elements in the Array
     ///
+    /// The elements are added with Kahan-Neumaier compensated summation, so the result is
+    /// accurate even for many elements of different magnitudes.
+    ///
     /// # Examples
     ///
     /// ```
     /// // Create a new Array containing the values 1.0, 2.0 and 3.0
     /// use moonalloy::linalg::array::Array;
-    /// let array = Array::from(&mut [1.0, 2.0, 3.0]);
+    /// let array = Array::from(&[1.0, 2.0, 3.0]);
     ///
     /// assert_eq!(6.0, array.sum());
     /// ```
     pub fn sum(&self) -> f64 {
-        let mut s: f64 = 0.0;
-        let v = unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) };
+        summation::neumaier(self.iter().copied())
+    }
 
-        for i in 0..self.len() {
-            s += v[i];
-        }
-        s
+    /// Calculate the sum of all the elements in the Array with the given algorithm
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - the summation algorithm.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moonalloy::linalg::array::Array;
+    /// use moonalloy::linalg::summation::Summation;
+    /// let array = Array::from(&[1e100, 1.0, -1e100]);
+    ///
+    /// assert_eq!(0.0, array.sum_with(Summation::Naive));
+    /// assert_eq!(1.0, array.sum_with(Summation::Neumaier));
+    /// ```
+    pub fn sum_with(&self, method: Summation) -> f64 {
+        summation::sum(self, method)
     }
 
     /// Calculate the average of all the elements in the Array
@@ -89,40 +131,30 @@ impl Array {
     /// ```
     /// // Create a new Array containing the values 1.0, 2.0 and 3.0
     /// use moonalloy::linalg::array::Array;
-    /// let array = Array::from(&mut [1.0, 2.0, 3.0]);
+    /// let array = Array::from(&[1.0, 2.0, 3.0]);
     ///
     /// assert_eq!(2.0, array.average());
     /// ```
     pub fn average(&self) -> f64 {
-        let mut s: f64 = 0.0;
-        let v = unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) };
-
-        for i in 0..self.len() {
-            s += v[i];
-        }
-        s / self.len as f64
+        self.sum() / self.len as f64
     }
 
     /// Calculate the norm of the Array
     ///
+    /// The elements are scaled before they are squared, so the norm does not overflow or
+    /// underflow when it is representable.
+    ///
     /// # Examples
     ///
     /// ```
     /// // Create a new Array containing the values 3.0 and 4.0
     /// use moonalloy::linalg::array::Array;
-    /// let array = Array::from(&mut [3.0, 4.0]);
+    /// let array = Array::from(&[3.0, 4.0]);
     ///
     /// assert_eq!(5.0, array.norm());
     /// ```
     pub fn norm(&self) -> f64 {
-        let mut n: f64 = 0.0;
-        let v = unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) };
-
-        for i in 0..self.len() {
-            n += v[i] * v[i];
-        }
-
-        n.sqrt()
+        summation::norm(self)
     }
 
     /// Add a scalar value to every element in the Array
@@ -136,27 +168,12 @@ impl Array {
     /// ```
     /// // Create a new Array containing the values 1.0, 2.0 and 3.0
     /// use moonalloy::linalg::array::Array;
-    /// let array = Array::from(&mut [1.0, 2.0, 3.0]);
+    /// let array = Array::from(&[1.0, 2.0, 3.0]);
     ///
-    /// assert_eq!(Array::from(&mut [3.0, 4.0, 5.0]), array.scalar_add(2.0));
+    /// assert_eq!(Array::from(&[3.0, 4.0, 5.0]), array.scalar_add(2.0));
     /// ```
     pub fn scalar_add(&self, scalar: f64) -> Array {
-        let result = unsafe {
-            let layout = Layout::array::<f64>(self.len()).unwrap();
-            let ptr = alloc(layout) as *mut f64;
-            std::slice::from_raw_parts_mut(ptr, self.len())
-        };
-
-        let arr_slice = unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) };
-
-        for i in 0..self.len() {
-            result[i] = scalar + arr_slice[i];
-        }
-
-        Array {
-            arr: result.as_mut_ptr(),
-            len: self.len,
-        }
+        Array::from_vec(self.iter().map(|v| scalar + v).collect())
     }
 
     /// Subtract a scalar value from every element in the Array
@@ -170,27 +187,12 @@ impl Array {
     /// ```
     /// // Create a new Array containing the values 1.0, 2.0 and 3.0
     /// use moonalloy::linalg::array::Array;
-    /// let array = Array::from(&mut [1.0, 2.0, 3.0]);
+    /// let array = Array::from(&[1.0, 2.0, 3.0]);
     ///
-    /// assert_eq!(Array::from(&mut [-1.0, 0.0, 1.0]), array.scalar_sub(2.0));
+    /// assert_eq!(Array::from(&[-1.0, 0.0, 1.0]), array.scalar_sub(2.0));
     /// ```
     pub fn scalar_sub(&self, scalar: f64) -> Array {
-        let result = unsafe {
-            let layout = Layout::array::<f64>(self.len()).unwrap();
-            let ptr = alloc(layout) as *mut f64;
-            std::slice::from_raw_parts_mut(ptr, self.len())
-        };
-
-        let arr_slice = unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) };
-
-        for i in 0..self.len() {
-            result[i] = arr_slice[i] - scalar;
-        }
-
-        Array {
-            arr: result.as_mut_ptr(),
-            len: self.len,
-        }
+        Array::from_vec(self.iter().map(|v| v - scalar).collect())
     }
 
     /// Multiply every element in the Array with a scalar value
@@ -204,27 +206,12 @@ impl Array {
     /// ```
     /// // Create a new Array containing the values 1.0, 2.0 and 3.0
     /// use moonalloy::linalg::array::Array;
-    /// let array = Array::from(&mut [1.0, 2.0, 3.0]);
+    /// let array = Array::from(&[1.0, 2.0, 3.0]);
     ///
-    /// assert_eq!(Array::from(&mut [2.0, 4.0, 6.0]), array.scalar_mult(2.0));
+    /// assert_eq!(Array::from(&[2.0, 4.0, 6.0]), array.scalar_mult(2.0));
     /// ```
     pub fn scalar_mult(&self, scalar: f64) -> Array {
-        let result = unsafe {
-            let layout = Layout::array::<f64>(self.len()).unwrap();
-            let ptr = alloc(layout) as *mut f64;
-            std::slice::from_raw_parts_mut(ptr, self.len())
-        };
-
-        let arr_slice = unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) };
-
-        for i in 0..self.len() {
-            result[i] = scalar * arr_slice[i];
-        }
-
-        Array {
-            arr: result.as_mut_ptr(),
-            len: self.len,
-        }
+        Array::from_vec(self.iter().map(|v| scalar * v).collect())
     }
 
     /// Add two Arrays without modifying either Array.
@@ -238,34 +225,17 @@ impl Array {
     /// ```
     /// // Creates two new Arrays both containing the values 1.0, 2.0 and 3.0
     /// use moonalloy::linalg::array::Array;
-    /// let a = Array::from(&mut [1.0, 2.0, 3.0]);
-    /// let b = Array::from(&mut [1.0, 2.0, 3.0]);
+    /// let a = Array::from(&[1.0, 2.0, 3.0]);
+    /// let b = Array::from(&[1.0, 2.0, 3.0]);
     ///
-    /// assert_eq!(Array::from(&mut [2.0, 4.0, 6.0]), a.plus(&b));
+    /// assert_eq!(Array::from(&[2.0, 4.0, 6.0]), a.plus(&b));
     /// // You can use the `+`-operator as a shorthand for this
-    /// assert_eq!(Array::from(&mut [2.0, 4.0, 6.0]), a + b);
+    /// assert_eq!(Array::from(&[2.0, 4.0, 6.0]), a + b);
     /// ```
     pub fn plus(&self, other: &Array) -> Array {
         assert_eq!(self.len(), other.len(), "Lengths are different!");
 
-        let result = unsafe {
-            let layout = Layout::array::<f64>(self.len()).unwrap();
-            let ptr = alloc(layout) as *mut f64;
-            std::slice::from_raw_parts_mut(ptr, self.len())
-        };
-
-        let arr1 = unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) };
-
-        let arr2 = unsafe { std::slice::from_raw_parts_mut(other.arr, other.len()) };
-
-        for i in 0..self.len() {
-            result[i] = arr1[i] + arr2[i];
-        }
-
-        Array {
-            len: result.len(),
-            arr: result.as_mut_ptr(),
-        }
+        Array::from_vec(self.iter().zip(other.iter()).map(|(a, b)| a + b).collect())
     }
 
     /// Performs substraction on two Arrays without modifying either Array.
@@ -279,34 +249,17 @@ impl Array {
     /// ```
     /// // Creates two new Arrays both containing the values 1.0, 2.0 and 3.0
     /// use moonalloy::linalg::array::Array;
-    /// let a = Array::from(&mut [1.0, 2.0, 3.0]);
-    /// let b = Array::from(&mut [1.0, 2.0, 3.0]);
+    /// let a = Array::from(&[1.0, 2.0, 3.0]);
+    /// let b = Array::from(&[1.0, 2.0, 3.0]);
     ///
-    /// assert_eq!(Array::from(&mut [0.0, 0.0, 0.0]), a.minus(&b));
+    /// assert_eq!(Array::from(&[0.0, 0.0, 0.0]), a.minus(&b));
     /// // You can use the `-`-operator as a shorthand for this
-    /// assert_eq!(Array::from(&mut [0.0, 0.0, 0.0]), a - b);
+    /// assert_eq!(Array::from(&[0.0, 0.0, 0.0]), a - b);
     /// ```
     pub fn minus(&self, other: &Array) -> Array {
         assert_eq!(self.len(), other.len(), "Lengths are different!");
 
-        let result = unsafe {
-            let layout = Layout::array::<f64>(self.len()).unwrap();
-            let ptr = alloc(layout) as *mut f64;
-            std::slice::from_raw_parts_mut(ptr, self.len())
-        };
-
-        let arr1 = unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) };
-
-        let arr2 = unsafe { std::slice::from_raw_parts_mut(other.arr, other.len()) };
-
-        for i in 0..self.len() {
-            result[i] = arr1[i] - arr2[i];
-        }
-
-        Array {
-            len: result.len(),
-            arr: result.as_mut_ptr(),
-        }
+        Array::from_vec(self.iter().zip(other.iter()).map(|(a, b)| a - b).collect())
     }
 
     /// Performs multiplication on two Arrays without modifying either Array.
@@ -320,39 +273,25 @@ impl Array {
     /// ```
     /// // Creates two new Arrays both containing the values 1.0, 2.0 and 3.0
     /// use moonalloy::linalg::array::Array;
-    /// let a = Array::from(&mut [1.0, 2.0, 3.0]);
-    /// let b = Array::from(&mut [1.0, 2.0, 3.0]);
+    /// let a = Array::from(&[1.0, 2.0, 3.0]);
+    /// let b = Array::from(&[1.0, 2.0, 3.0]);
     ///
-    /// assert_eq!(Array::from(&mut [1.0, 4.0, 9.0]), a.mult(&b));
+    /// assert_eq!(Array::from(&[1.0, 4.0, 9.0]), a.mult(&b));
     /// // You can use the `*`-operator as a shorthand for this
-    /// assert_eq!(Array::from(&mut [1.0, 4.0, 9.0]), a * b);
+    /// assert_eq!(Array::from(&[1.0, 4.0, 9.0]), a * b);
     /// ```
     pub fn mult(&self, other: &Array) -> Array {
         assert_eq!(self.len(), other.len(), "Lengths are different!");
 
-        let result = unsafe {
-            let layout = Layout::array::<f64>(self.len()).unwrap();
-            let ptr = alloc(layout) as *mut f64;
-            std::slice::from_raw_parts_mut(ptr, self.len())
-        };
-
-        let arr1 = unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) };
-
-        let arr2 = unsafe { std::slice::from_raw_parts_mut(other.arr, other.len()) };
-
-        for i in 0..self.len() {
-            result[i] = arr1[i] * arr2[i];
-        }
-
-        Array {
-            len: result.len(),
-            arr: result.as_mut_ptr(),
-        }
+        Array::from_vec(self.iter().zip(other.iter()).map(|(a, b)| a * b).collect())
     }
 
     /// Calculates the dot product on two Arrays without modifying either Array.
     /// Returns a single floating-point value.
     ///
+    /// The products are added with compensated summation, as if in twice the working
+    /// precision.
+    ///
     /// # Arguments
     ///
     /// * `other` - the other Array calculate the dot product with
@@ -362,15 +301,14 @@ impl Array {
     /// ```
     /// // Creates two new Arrays both containing the values 1.0, 2.0 and 3.0
     /// use moonalloy::linalg::array::Array;
-    /// let a = Array::from(&mut [1.0, 2.0, 3.0]);
-    /// let b = Array::from(&mut [1.0, 2.0, 3.0]);
+    /// let a = Array::from(&[1.0, 2.0, 3.0]);
+    /// let b = Array::from(&[1.0, 2.0, 3.0]);
     ///
     /// assert_eq!(14.0, a.dotp(&b));
     /// ```
     pub fn dotp(&self, other: &Array) -> f64 {
-        let arr = self.mult(other);
-        let v = unsafe { std::slice::from_raw_parts_mut(arr.arr, arr.len()) };
-        v.iter().sum()
+        assert_eq!(self.len(), other.len(), "Lengths are different!");
+        summation::dot(self, other)
     }
 
     /// Concatenate with another Array. This will modify the original array.
@@ -384,39 +322,18 @@ impl Array {
     /// ```
     /// // Creates two new Arrays both containing the values 1.0, 2.0 and 3.0
     /// use moonalloy::linalg::array::Array;
-    /// let a = Array::from(&mut [1.0, 2.0, 3.0]);
-    /// let b = Array::from(&mut [4.0, 5.0]);
+    /// let a = Array::from(&[1.0, 2.0, 3.0]);
+    /// let b = Array::from(&[4.0, 5.0]);
     /// a.concat(&b);
     ///
-    /// assert_eq!(Array::from(&mut [1.0, 2.0, 3.0, 4.0, 5.0]), a.dotp(&b));
+    /// assert_eq!(Array::from(&[1.0, 2.0, 3.0, 4.0, 5.0]), a.dotp(&b));
     /// ```
     pub fn concat(&self, other: &Array) -> Array {
-        let len = self.len() + other.len();
-        let result = unsafe {
-            let layout = Layout::array::<f64>(len).unwrap();
-            let ptr = alloc(layout) as *mut f64;
-            std::slice::from_raw_parts_mut(ptr, len)
-        };
-
-        let arr1 = unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) };
+        let mut result = Vec::with_capacity(self.len() + other.len());
+        result.extend_from_slice(self);
+        result.extend_from_slice(other);
 
-        let arr2 = unsafe { std::slice::from_raw_parts_mut(other.arr, other.len()) };
-
-        let mut i = 0;
-        for elem in arr1.iter() {
-            result[i] = *elem;
-            i += 1;
-        }
-
-        for elem in arr2.iter() {
-            result[i] = *elem;
-            i += 1;
-        }
-
-        Array {
-            len: result.len(),
-            arr: result.as_mut_ptr(),
-        }
+        Array::from_vec(result)
     }
 
     /// Returns a string representation of the Array.
@@ -427,16 +344,14 @@ impl Array {
     /// ```
     /// // Creates two new Arrays both containing the values 1.0, 2.0 and 3.0
     /// use moonalloy::linalg::array::Array;
-    /// let a = Array::from(&mut [1.0, 2.0, 3.0]);
+    /// let a = Array::from(&[1.0, 2.0, 3.0]);
     ///
     /// println!("{}", a.to_string());
     /// // The to_string() is not necessary since Array implements the `Display` trait.
     /// println!("{}", a);
     /// ```
     pub fn to_string(&self) -> String {
-        let array_slice = unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) };
-
-        format!("Array: {:?}", array_slice)
+        format!("Array: {:?}", self.as_slice())
     }
 
     /// Returns a raw mutable pointer to the Array.
@@ -463,23 +378,10 @@ impl Array {
     /// use moonalloy::linalg::array::Array;
     /// let array = Array::of(2.0, 3);
     ///
-    /// assert_eq!(Array::from(&mut [2.0, 2.0, 2.0]), array);
+    /// assert_eq!(Array::from(&[2.0, 2.0, 2.0]), array);
     /// ```
     pub fn of(val: f64, len: usize) -> Array {
-        let arr_slice = unsafe {
-            let layout = Layout::array::<f64>(len).unwrap();
-            let ptr = alloc(layout);
-            std::slice::from_raw_parts_mut(ptr as *mut f64, len)
-        };
-
-        for i in 0..len {
-            arr_slice[i] = val;
-        }
-
-        Array {
-            arr: arr_slice.as_mut_ptr(),
-            len,
-        }
+        Array::from_vec(vec![val; len])
     }
 
     /// Creates a new Array of length `len` all where all elements are set to 0.0.
@@ -495,7 +397,7 @@ impl Array {
     /// use moonalloy::linalg::array::Array;
     /// let array = Array::zeros(3);
     ///
-    /// assert_eq!(Array::from(&mut [0.0, 0.0, 0.0]), array);
+    /// assert_eq!(Array::from(&[0.0, 0.0, 0.0]), array);
     /// ```
     pub fn zeros(len: usize) -> Array {
         Array::of(0.0, len)
@@ -514,12 +416,46 @@ impl Array {
     /// use moonalloy::linalg::array::Array;
     /// let array = Array::ones(3);
     ///
-    /// assert_eq!(Array::from(&mut [1.0, 1.0, 1.0]), array);
+    /// assert_eq!(Array::from(&[1.0, 1.0, 1.0]), array);
     /// ```
     pub fn ones(len: usize) -> Array {
         Array::of(1.0, len)
     }
 
+    /// Creates a new Array of length `len` with random elements, uniformly distributed in
+    /// [0, 1).
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - the number of elements in the new Array
+    /// * `rng` - the random number generator
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Create an Array with 3 random elements
+    /// use moonalloy::linalg::array::Array;
+    /// use moonalloy::random::pcg::Pcg64;
+    /// let mut rng = Pcg64::seed_from_u64(42);
+    /// let array = Array::random_uniform(3, &mut rng);
+    ///
+    /// assert!(array.iter().all(|x| (0.0..1.0).contains(x)));
+    /// ```
+    pub fn random_uniform(len: usize, rng: &mut dyn Rng) -> Array {
+        Array::from_vec((0..len).map(|_| rng.next_f64()).collect())
+    }
+
+    /// Creates a new Array of length `len` with random elements from the standard normal
+    /// distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - the number of elements in the new Array
+    /// * `rng` - the random number generator
+    pub fn random_normal(len: usize, rng: &mut dyn Rng) -> Array {
+        Array::from_vec((0..len).map(|_| samplers::standard_normal(rng)).collect())
+    }
+
     /// Returns the value at index: `index` in the Array.
     ///
     /// # Arguments
@@ -536,7 +472,7 @@ impl Array {
     /// ```
     /// // Create an Array with 3 elements
     /// use moonalloy::linalg::array::Array;
-    /// let array = Array::from(&mut [1.0, 2.0, 3.0]);
+    /// let array = Array::from(&[1.0, 2.0, 3.0]);
     ///
     /// assert_eq!(2.0, array.get(1));
     /// // The shorthand for this is the `[]`-operator
@@ -547,9 +483,7 @@ impl Array {
             index < self.len(),
             "ERROR - Array get: Index out of bounds."
         );
-        let slice = unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) };
-
-        slice[index]
+        self.as_slice()[index]
     }
 
     /// Mutates the value at index: `index` in the Array.
@@ -569,7 +503,7 @@ impl Array {
     /// ```
     /// // Create an Array with 3 elements
     /// use moonalloy::linalg::array::Array;
-    /// let array = Array::from(&mut [1.0, 2.0, 3.0]);
+    /// let array = Array::from(&[1.0, 2.0, 3.0]);
     ///
     /// array.set(5.0, 1);
     /// // use the `[]`-operator as a shorthand
@@ -581,9 +515,7 @@ impl Array {
             index < self.len(),
             "ERROR - Array get: Index out of bounds."
         );
-        let slice = unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) };
-
-        slice[index] = val;
+        self.deref_mut()[index] = val;
     }
 
     /// Returns a copy of a section of the Array
@@ -601,31 +533,21 @@ impl Array {
     ///
     /// ```
     /// use moonalloy::linalg::array::Array;
-    /// let array = Array::from(&mut [1.0, 2.0, 3.0, 4.0, 5.0]);
+    /// let array = Array::from(&[1.0, 2.0, 3.0, 4.0, 5.0]);
     ///
-    /// assert_eq(Array::from(&mut [2.0, 3.0]), array.splice(1, 3));
+    /// assert_eq(Array::from(&[2.0, 3.0]), array.splice(1, 3));
     /// ```
     pub fn splice(&self, first: usize, last: usize) -> Array {
         assert!(
             first < last,
             "ERROR - Array splice: first index must be before last index"
         );
-        let arr_slice = unsafe {
-            let layout = Layout::array::<f64>(last - first).unwrap();
-            let ptr = alloc(layout);
-            std::slice::from_raw_parts_mut(ptr as *mut f64, last - first)
-        };
-
-        for i in first..last {
-            arr_slice[i - first] = self.get(i);
-        }
-
-        Array::from(arr_slice)
+        Array::from(&self.as_slice()[first..last])
     }
 
     /// Returns the contents of the Array as a slice of floating-point values.
     pub fn as_slice(&self) -> &[f64] {
-        unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) }
+        self
     }
 
     /// Returns the number of elements in the Array
@@ -640,23 +562,27 @@ impl std::fmt::Display for Array {
     }
 }
 
-impl PartialEq for Array {
-    fn eq(&self, other: &Self) -> bool {
-        if self.len() != other.len() {
-            return false;
-        }
-
-        let slice1 = unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) };
-
-        let slice2 = unsafe { std::slice::from_raw_parts_mut(other.arr, other.len()) };
+impl Clone for Array {
+    fn clone(&self) -> Self {
+        Array::from(self)
+    }
+}
 
-        for i in 0..self.len() {
-            if slice1[i] != slice2[i] {
-                return false;
-            }
+impl Drop for Array {
+    fn drop(&mut self) {
+        // SAFETY: `arr` and `len` were taken from a boxed slice in `from_vec`, which is the only
+        // place where Arrays are created.
+        unsafe {
+            drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+                self.arr, self.len,
+            )))
         }
+    }
+}
 
-        true
+impl PartialEq for Array {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
     }
 }
 
@@ -679,8 +605,7 @@ impl Index<usize> for Array {
 
     fn index(&self, i: usize) -> &Self::Output {
         assert!(i < self.len(), "ERROR - Array: Index out of bounds.");
-        let slice = unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) };
-        &slice[i]
+        &self.as_slice()[i]
     }
 }
 
@@ -690,8 +615,7 @@ impl IndexMut<usize> for Array {
             index < self.len(),
             "ERROR - Array get: Index out of bounds."
         );
-        let slice = unsafe { std::slice::from_raw_parts_mut(self.arr, self.len()) };
-        &mut slice[index]
+        &mut self.deref_mut()[index]
     }
 }
 
@@ -734,14 +658,14 @@ mod test {
     #[test]
     fn test_new() {
         let n = Array::new();
-        let f = Array::from(&mut []);
+        let f = Array::from(&[]);
 
         assert_eq!(n, f);
     }
 
     #[test]
     fn test_index() {
-        let a = Array::from(&mut [1.0, 2.0, 3.0]);
+        let a = Array::from(&[1.0, 2.0, 3.0]);
 
         assert_eq!(2.0, a[1]);
     }
@@ -749,74 +673,74 @@ mod test {
     #[test]
     #[should_panic]
     fn test_index_out_of_bounds() {
-        let a = Array::from(&mut [1.0, 2.0, 3.0]);
+        let a = Array::from(&[1.0, 2.0, 3.0]);
 
-        a[3];
+        let _ = a[3];
     }
 
     #[test]
     fn test_sum() {
-        let a = Array::from(&mut [1.0, 2.0, 3.0]);
+        let a = Array::from(&[1.0, 2.0, 3.0]);
 
         assert_eq!(6.0, a.sum());
     }
 
     #[test]
     fn test_scalar_mult() {
-        let a = Array::from(&mut [1.0, 2.0, 3.0]);
-        let r = Array::from(&mut [2.0, 4.0, 6.0]);
+        let a = Array::from(&[1.0, 2.0, 3.0]);
+        let r = Array::from(&[2.0, 4.0, 6.0]);
 
         assert_eq!(r, a.scalar_mult(2.0))
     }
 
     #[test]
     fn test_neg() {
-        let a = Array::from(&mut [1.0, 2.0, 3.0]);
-        let r = Array::from(&mut [-1.0, -2.0, -3.0]);
+        let a = Array::from(&[1.0, 2.0, 3.0]);
+        let r = Array::from(&[-1.0, -2.0, -3.0]);
 
         assert_eq!(r, -a)
     }
 
     #[test]
     fn test_add() {
-        let a = Array::from(&mut [1.0, 2.0, 3.0]);
-        let b = Array::from(&mut [2.0, 3.0, 5.0]);
-        let r = Array::from(&mut [3.0, 5.0, 8.0]);
+        let a = Array::from(&[1.0, 2.0, 3.0]);
+        let b = Array::from(&[2.0, 3.0, 5.0]);
+        let r = Array::from(&[3.0, 5.0, 8.0]);
 
         assert_eq!(r, a + b);
     }
 
     #[test]
     fn test_sub() {
-        let a = Array::from(&mut [2.0, 3.0, 5.0]);
-        let b = Array::from(&mut [1.0, 2.0, 3.0]);
-        let r = Array::from(&mut [1.0, 1.0, 2.0]);
+        let a = Array::from(&[2.0, 3.0, 5.0]);
+        let b = Array::from(&[1.0, 2.0, 3.0]);
+        let r = Array::from(&[1.0, 1.0, 2.0]);
 
         assert_eq!(r, a - b);
     }
 
     #[test]
     fn test_mult() {
-        let a = Array::from(&mut [1.0, 2.0, 3.0]);
-        let b = Array::from(&mut [2.0, 3.0, 5.0]);
-        let r = Array::from(&mut [2.0, 6.0, 15.0]);
+        let a = Array::from(&[1.0, 2.0, 3.0]);
+        let b = Array::from(&[2.0, 3.0, 5.0]);
+        let r = Array::from(&[2.0, 6.0, 15.0]);
 
         assert_eq!(r, a * b);
     }
 
     #[test]
     fn test_dotp() {
-        let a = Array::from(&mut [1.0, 2.0, 3.0]);
-        let b = Array::from(&mut [2.0, 3.0, 5.0]);
+        let a = Array::from(&[1.0, 2.0, 3.0]);
+        let b = Array::from(&[2.0, 3.0, 5.0]);
 
         assert_eq!(23.0, a.dotp(&b));
     }
 
     #[test]
     fn test_concat() {
-        let a = Array::from(&mut [1.0, 2.0]);
-        let b = Array::from(&mut [3.0, 5.0]);
-        let r = Array::from(&mut [1.0, 2.0, 3.0, 5.0]);
+        let a = Array::from(&[1.0, 2.0]);
+        let b = Array::from(&[3.0, 5.0]);
+        let r = Array::from(&[1.0, 2.0, 3.0, 5.0]);
 
         assert_eq!(r, a.concat(&b));
     }
@@ -824,7 +748,7 @@ mod test {
     #[test]
     fn test_zeros() {
         let a = Array::zeros(3);
-        let r = Array::from(&mut [0.0, 0.0, 0.0]);
+        let r = Array::from(&[0.0, 0.0, 0.0]);
 
         assert_eq!(r, a);
     }
@@ -832,22 +756,34 @@ mod test {
     #[test]
     fn test_ones() {
         let a = Array::ones(3);
-        let r = Array::from(&mut [1.0, 1.0, 1.0]);
+        let r = Array::from(&[1.0, 1.0, 1.0]);
 
         assert_eq!(r, a);
     }
 
+    #[test]
+    fn test_random_uniform() {
+        use crate::random::pcg::Pcg64;
+
+        let a = Array::random_uniform(1000, &mut Pcg64::seed_from_u64(1));
+        let b = Array::random_uniform(1000, &mut Pcg64::seed_from_u64(1));
+
+        assert_eq!(a, b);
+        assert!(a.iter().all(|x| (0.0..1.0).contains(x)));
+        assert!((a.average() - 0.5).abs() < 0.05);
+    }
+
     #[test]
     fn test_get() {
-        let a = Array::from(&mut [1.0, 2.0, 3.0]);
+        let a = Array::from(&[1.0, 2.0, 3.0]);
 
         assert_eq!(2.0, a.get(1));
     }
 
     #[test]
     fn test_set() {
-        let mut a = Array::from(&mut [1.0, 2.0, 3.0]);
-        let r = Array::from(&mut [5.0, 2.0, 3.0]);
+        let mut a = Array::from(&[1.0, 2.0, 3.0]);
+        let r = Array::from(&[5.0, 2.0, 3.0]);
 
         a.set(5.0, 0);
 
@@ -856,7 +792,7 @@ mod test {
 
     #[test]
     fn test_iterator() {
-        let a = Array::from(&mut [1.0, 2.0, 3.0]);
+        let a = Array::from(&[1.0, 2.0, 3.0]);
         let mut it = a.iter();
 
         assert_eq!(*it.next().unwrap(), 1.0_f64);
@@ -866,10 +802,49 @@ mod test {
 
     #[test]
     fn test_splice() {
-        let expected = Array::from(&mut [2.0, 3.0]);
-        let a = Array::from(&mut [1.0, 2.0, 3.0, 4.0, 5.0]);
+        let expected = Array::from(&[2.0, 3.0]);
+        let a = Array::from(&[1.0, 2.0, 3.0, 4.0, 5.0]);
 
         let actual = a.splice(1, 3);
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_clone_is_independent() {
+        let a = Array::from(&[1.0, 2.0, 3.0]);
+        let mut b = a.clone();
+
+        b[0] = 5.0;
+
+        assert_eq!(1.0, a[0]);
+        assert_eq!(5.0, b[0]);
+    }
+
+    #[test]
+    fn test_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Array>();
+
+        // Move Arrays into worker threads and back.
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let a = Array::of(i as f64, 1000);
+                std::thread::spawn(move || a.scalar_mult(2.0))
+            })
+            .collect();
+
+        for (i, handle) in handles.into_iter().enumerate() {
+            assert_eq!(Array::of(2.0 * i as f64, 1000), handle.join().unwrap());
+        }
+
+        // Share one Array between threads for reading.
+        let shared = Array::ones(1000);
+        std::thread::scope(|scope| {
+            let sums: Vec<_> = (0..4).map(|_| scope.spawn(|| shared.sum())).collect();
+
+            for sum in sums {
+                assert_eq!(1000.0, sum.join().unwrap());
+            }
+        });
+    }
 }
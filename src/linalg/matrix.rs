@@ -2,32 +2,54 @@
 //!
 //! This module contains structures and functions for manipulating matrices in Linear Algebra.
 //! All of the basics of matrix arithmetic.
+//!
+//! # Thread safety
+//!
+//! A matrix owns its rows in the same way as an Array owns its elements: the buffer of rows is
+//! allocated by the matrix, never shared with another matrix and freed together with the rows
+//! when the matrix is dropped. Cloning a matrix copies all of its rows. This makes matrices
+//! `Send` and `Sync`, so they can be moved into worker threads or shared read-only between them.
 
+use crate::random::Rng;
 use crate::Array;
 
-use std::alloc::{alloc, Layout};
 use std::fmt::*;
-use std::ops::{Add, Deref, DerefMut, Index, IndexMut, Mul, Neg, Sub};
+use std::ops::{Add, Deref, Index, IndexMut, Mul, Neg, Sub};
 
 /// A representation of a mathematical matrix
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug)]
 #[repr(C)]
 pub struct Matrix {
     /// Number of rows in the matrix
     rows: usize,
     /// Number of columns in the matrix
     cols: usize,
-    /// Elements of the matrix as a raw pointer of Arrays
+    /// Elements of the matrix as a raw pointer to a buffer of Arrays owned by the matrix
     arrays: *mut Array,
 }
 
+// SAFETY: A matrix is the unique owner of its rows (see the module documentation), and Arrays
+// are `Send` and `Sync` themselves.
+unsafe impl Send for Matrix {}
+unsafe impl Sync for Matrix {}
+
 impl Matrix {
+    /// Returns a matrix that takes ownership of its rows, which must all have `cols` elements.
+    fn from_parts(rows: Vec<Array>, cols: usize) -> Matrix {
+        let boxed = rows.into_boxed_slice();
+        Matrix {
+            rows: boxed.len(),
+            cols,
+            arrays: Box::into_raw(boxed) as *mut Array,
+        }
+    }
+
     /// Checks that the slice of Arrays can be converted to a valid matrix.
     ///
     /// # Arguments
     ///
-    /// * `slice` - a mutable slice of Arrays
-    fn is_valid_slice(slice: &mut [Array]) -> bool {
+    /// * `slice` - a slice of Arrays
+    fn is_valid_slice(slice: &[Array]) -> bool {
         let len = slice[0].len();
         for i in 1..slice.len() {
             assert!(len == slice[i].len());
@@ -36,11 +58,11 @@ impl Matrix {
         true
     }
 
-    /// Returns a new matrix from a mutable slice of Arrays.
+    /// Returns a new matrix from a slice of Arrays.
     ///
     /// # Arguments
     ///
-    /// * `slice` - a mutable slice of Arrays
+    /// * `slice` - a slice of Arrays. The Arrays are copied into the matrix.
     ///
     /// # Panics
     ///
@@ -52,15 +74,44 @@ impl Matrix {
     /// ```
     /// // Create a 2x2 matrix
     /// use moonalloy::linalg::matrix::Matrix;
-    /// let mat = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0)]);
+    /// let mat = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0)]);
     /// ```
-    pub fn new(slice: &mut [Array]) -> Matrix {
+    pub fn new(slice: &[Array]) -> Matrix {
         assert!(Matrix::is_valid_slice(slice));
-        Matrix {
-            rows: slice.len(),
-            cols: slice[0].len(),
-            arrays: slice.as_mut_ptr(),
-        }
+        Matrix::from_parts(slice.to_vec(), slice[0].len())
+    }
+
+    /// Returns a new matrix that takes ownership of a vector of rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - a vector of Arrays, one for each row of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// All the rows must have the same length otherwise the code will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Create a 2x3 matrix
+    /// use moonalloy::linalg::array::Array;
+    /// use moonalloy::linalg::matrix::Matrix;
+    /// let mat = Matrix::from_rows(vec![
+    ///     Array::from_vec(vec![1.0, 2.0, 3.0]),
+    ///     Array::from_vec(vec![4.0, 5.0, 6.0]),
+    /// ]);
+    ///
+    /// assert_eq!((2, 3), mat.dimensions());
+    /// ```
+    pub fn from_rows(rows: Vec<Array>) -> Matrix {
+        let cols = rows.first().map_or(0, |row| row.len());
+        assert!(
+            rows.iter().all(|row| row.len() == cols),
+            "ERROR - Matrix from_rows: Rows differ in length."
+        );
+
+        Matrix::from_parts(rows, cols)
     }
 
     /// Swaps the ith and jth row in the matrix.
@@ -69,12 +120,7 @@ impl Matrix {
     /// * `j` - the jth row in the matrix
     pub fn swap_rows(&mut self, i: usize, j: usize) {
 	if i < self.rows && j < self.rows && i != j {
-	    let arrays = unsafe {
-		std::slice::from_raw_parts_mut(self.arrays, self.rows)
-	    };
-	    let temp = arrays[i];
-	    arrays[i] = arrays[j];
-	    arrays[j] = temp;
+	    self.rows_mut().swap(i, j);
 	}
     }
 
@@ -93,24 +139,10 @@ impl Matrix {
     /// use moonalloy::linalg::matrix::Matrix;
     /// let mat = Matrix::of(3.0, 2, 2);
     ///
-    /// assert_eq(Matrix::new(&mut [Array::from(&mut [3.0, 3.0]), Array::from(&mut [3.0, 3.0)]), mat);
+    /// assert_eq(Matrix::new(&[Array::from(&[3.0, 3.0]), Array::from(&[3.0, 3.0)]), mat);
     /// ```
     fn of(val: f64, rows: usize, cols: usize) -> Matrix {
-        let mat_slice = unsafe {
-            let layout = Layout::array::<Array>(rows).unwrap();
-            let ptr = alloc(layout);
-            std::slice::from_raw_parts_mut(ptr as *mut Array, rows)
-        };
-
-        for i in 0..rows {
-            mat_slice[i] = Array::of(val, cols);
-        }
-
-        Matrix {
-            rows,
-            cols,
-            arrays: mat_slice.as_mut_ptr(),
-        }
+        Matrix::from_parts((0..rows).map(|_| Array::of(val, cols)).collect(), cols)
     }
 
     /// Returns a new matrix where all the elements have the value of 0.0
@@ -127,7 +159,7 @@ impl Matrix {
     /// use moonalloy::linalg::matrix::Matrix;
     /// let mat = Matrix::zeros(2, 2);
     ///
-    /// assert_eq(Matrix::new(&mut [Array::from(&mut [0.0, 0.0]), Array::from(&mut [0.0, 0.0)]), mat);
+    /// assert_eq(Matrix::new(&[Array::from(&[0.0, 0.0]), Array::from(&[0.0, 0.0)]), mat);
     /// ```
     pub fn zeros(rows: usize, cols: usize) -> Matrix {
         Matrix::of(0.0, rows, cols)
@@ -147,12 +179,52 @@ impl Matrix {
     /// use moonalloy::linalg::matrix::Matrix;
     /// let mat = Matrix::ones(2, 2);
     ///
-    /// assert_eq(Matrix::new(&mut [Array::from(&mut [1.0, 1.0]), Array::from(&mut [1.0, 1.0)]), mat);
+    /// assert_eq(Matrix::new(&[Array::from(&[1.0, 1.0]), Array::from(&[1.0, 1.0)]), mat);
     /// ```
     pub fn ones(rows: usize, cols: usize) -> Matrix {
         Matrix::of(1.0, rows, cols)
     }
 
+    /// Returns a new matrix with random elements, uniformly distributed in [0, 1).
+    ///
+    /// The elements are drawn row by row.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - the number of rows in the new matrix.
+    /// * `cols` - the number of columns in the new matrix.
+    /// * `rng` - the random number generator.
+    pub fn random_uniform(rows: usize, cols: usize, rng: &mut dyn Rng) -> Matrix {
+        let arrays = (0..rows).map(|_| Array::random_uniform(cols, rng)).collect();
+        Matrix::from_parts(arrays, cols)
+    }
+
+    /// Returns a new matrix with random elements from the standard normal distribution.
+    ///
+    /// The elements are drawn row by row.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - the number of rows in the new matrix.
+    /// * `cols` - the number of columns in the new matrix.
+    /// * `rng` - the random number generator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Create a 2x3 matrix of standard normal samples
+    /// use moonalloy::linalg::matrix::Matrix;
+    /// use moonalloy::random::pcg::Pcg64;
+    /// let mut rng = Pcg64::seed_from_u64(42);
+    /// let mat = Matrix::random_normal(2, 3, &mut rng);
+    ///
+    /// assert_eq!((2, 3), mat.dimensions());
+    /// ```
+    pub fn random_normal(rows: usize, cols: usize, rng: &mut dyn Rng) -> Matrix {
+        let arrays = (0..rows).map(|_| Array::random_normal(cols, rng)).collect();
+        Matrix::from_parts(arrays, cols)
+    }
+
     /// Returns an `n`x`n` identity matrix.
     ///
     /// # Arguments
@@ -166,17 +238,13 @@ impl Matrix {
     /// use moonalloy::linalg::matrix::Matrix;
     /// let mat = Matrix::identity(2);
     ///
-    /// assert_eq(Matrix::new(&mut [Array::from(&mut [1.0, 0.0]), Array::from(&mut [0.0, 1.0)]), mat);
+    /// assert_eq(Matrix::new(&[Array::from(&[1.0, 0.0]), Array::from(&[0.0, 1.0)]), mat);
     /// ```
     pub fn identity(len: usize) -> Matrix {
-        let mat = Matrix::zeros(len, len);
-
-        let mat_slice = unsafe { std::slice::from_raw_parts_mut(mat.arrays, len) };
+        let mut mat = Matrix::zeros(len, len);
 
         for i in 0..len {
-            let slice = &mut mat_slice[i];
-
-            slice[i] = 1.0;
+            mat[(i, i)] = 1.0;
         }
 
         mat
@@ -184,11 +252,9 @@ impl Matrix {
 
     /// Returns a string representation of a matrix.
     pub fn to_string(&self) -> String {
-        let array_slice = unsafe { std::slice::from_raw_parts_mut(self.arrays, self.rows) };
-
         let mut result = String::from("Matrix: \n[");
 
-        for (i, arr) in array_slice.iter().enumerate() {
+        for (i, arr) in self.iter().enumerate() {
             let slice = arr.as_slice();
 
             result.push_str(format!("{:?}", slice).as_str());
@@ -217,12 +283,12 @@ impl Matrix {
     ///
     /// ```
     /// // Create to matrices `a` and `b` and add them.
-    /// let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0])]);
-    /// let b = Matrix::new(&mut [Array::from(&mut [2.0, 3.0]), Array::from(&mut [5.0, 8.0])]);
+    /// let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0])]);
+    /// let b = Matrix::new(&[Array::from(&[2.0, 3.0]), Array::from(&[5.0, 8.0])]);
     ///
-    /// assert_eq!(Matrix::new(&mut [Array::from(&mut [3.0, 5.0]), Array::from(&mut [8.0, 13.0])]), a.plus(&b));
+    /// assert_eq!(Matrix::new(&[Array::from(&[3.0, 5.0]), Array::from(&[8.0, 13.0])]), a.plus(&b));
     /// // Use the `+`-operator as a shorthand for this.
-    /// assert_eq!(Matrix::new(&mut [Array::from(&mut [3.0, 5.0]), Array::from(&mut [8.0, 13.0])]), a + b);
+    /// assert_eq!(Matrix::new(&[Array::from(&[3.0, 5.0]), Array::from(&[8.0, 13.0])]), a + b);
     /// ```
     pub fn plus(&self, other: &Matrix) -> Matrix {
         assert!(
@@ -234,25 +300,13 @@ impl Matrix {
             "ERROR - Matrix addition: Rows differ in dimensions."
         );
 
-        let result = unsafe {
-            let layout = Layout::array::<Array>(self.rows).unwrap();
-            let ptr = alloc(layout);
-            std::slice::from_raw_parts_mut(ptr as *mut Array, self.rows)
-        };
-
-        let mat_slice1 = unsafe { std::slice::from_raw_parts_mut(self.arrays, self.rows) };
-
-        let mat_slice2 = unsafe { std::slice::from_raw_parts_mut(other.arrays, other.rows) };
+        let result = self
+            .iter()
+            .zip(other.iter())
+            .map(|(a, b)| a.plus(b))
+            .collect();
 
-        for i in 0..self.rows {
-            result[i] = mat_slice1[i].plus(&mat_slice2[i]);
-        }
-
-        Matrix {
-            rows: self.rows,
-            cols: self.cols,
-            arrays: result.as_mut_ptr(),
-        }
+        Matrix::from_parts(result, self.cols)
     }
 
     /// Multiply every element in a matrix with a scalar value without modifying the original.
@@ -265,30 +319,16 @@ impl Matrix {
     ///
     /// ```
     /// // Create a matrix `a` and multiply all elements with -1.0.
-    /// let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0])]);
+    /// let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0])]);
     ///
-    /// assert_eq!(Matrix::new(&mut [Array::from(&mut [-1.0, -2.0]), Array::from(&mut [-3.0, -5.0])]), a.scalar(-1.0));
+    /// assert_eq!(Matrix::new(&[Array::from(&[-1.0, -2.0]), Array::from(&[-3.0, -5.0])]), a.scalar(-1.0));
     /// // Use the unary `-`-operator as a shorthand for multiplication with -1.0.
-    /// assert_eq!(Matrix::new(&mut [Array::from(&mut [-1.0, -2.0]), Array::from(&mut [-3.0, -5.0])]), -a);
+    /// assert_eq!(Matrix::new(&[Array::from(&[-1.0, -2.0]), Array::from(&[-3.0, -5.0])]), -a);
     /// ```
     pub fn scalar(&self, scal: f64) -> Matrix {
-        let result = unsafe {
-            let layout = Layout::array::<Array>(self.rows).unwrap();
-            let ptr = alloc(layout);
-            std::slice::from_raw_parts_mut(ptr as *mut Array, self.rows)
-        };
-
-        let slice = unsafe { std::slice::from_raw_parts_mut(self.arrays, self.rows) };
+        let result = self.iter().map(|row| row.scalar_mult(scal)).collect();
 
-        for i in 0..self.rows {
-            result[i] = slice[i].scalar_mult(scal);
-        }
-
-        Matrix {
-            rows: self.rows,
-            cols: self.cols,
-            arrays: result.as_mut_ptr(),
-        }
+        Matrix::from_parts(result, self.cols)
     }
 
     /// Subtracts two matrices without modifying the originals.
@@ -306,12 +346,12 @@ impl Matrix {
     ///
     /// ```
     /// // Create two matrices `a` and `b` and subtract them.
-    /// let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0])]);
-    /// let b = Matrix::new(&mut [Array::from(&mut [2.0, 3.0]), Array::from(&mut [5.0, 8.0])]);
+    /// let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0])]);
+    /// let b = Matrix::new(&[Array::from(&[2.0, 3.0]), Array::from(&[5.0, 8.0])]);
     ///
-    /// assert_eq!(Matrix::new(&mut [Array::from(&mut [-1.0, -1.0]), Array::from(&mut [-2.0, -3.0])]), a.minus(&b));
+    /// assert_eq!(Matrix::new(&[Array::from(&[-1.0, -1.0]), Array::from(&[-2.0, -3.0])]), a.minus(&b));
     /// // Use the `+`-operator as a shorthand for this.
-    /// assert_eq!(Matrix::new(&mut [Array::from(&mut [-1.0, -1.0]), Array::from(&mut [-2.0, -3.0])]), a - b);
+    /// assert_eq!(Matrix::new(&[Array::from(&[-1.0, -1.0]), Array::from(&[-2.0, -3.0])]), a - b);
     /// ```
     pub fn minus(&self, other: &Matrix) -> Matrix {
         assert!(
@@ -323,25 +363,13 @@ impl Matrix {
             "ERROR - Matrix subtraction: Rows differ in dimensions."
         );
 
-        let result = unsafe {
-            let layout = Layout::array::<Array>(self.rows).unwrap();
-            let ptr = alloc(layout);
-            std::slice::from_raw_parts_mut(ptr as *mut Array, self.rows)
-        };
-
-        let mat_slice1 = unsafe { std::slice::from_raw_parts_mut(self.arrays, self.rows) };
-
-        let mat_slice2 = unsafe { std::slice::from_raw_parts_mut(other.arrays, other.rows) };
+        let result = self
+            .iter()
+            .zip(other.iter())
+            .map(|(a, b)| a.minus(b))
+            .collect();
 
-        for i in 0..self.rows {
-            result[i] = mat_slice1[i].minus(&mat_slice2[i]);
-        }
-
-        Matrix {
-            rows: self.rows,
-            cols: self.cols,
-            arrays: result.as_mut_ptr(),
-        }
+        Matrix::from_parts(result, self.cols)
     }
 
     /// Multiplies two matrices element by element without modifying the originals.
@@ -359,12 +387,12 @@ impl Matrix {
     ///
     /// ```
     /// // Create two matrices `a` and `b` and perform element-wise multiplication.
-    /// let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0])]);
-    /// let b = Matrix::new(&mut [Array::from(&mut [2.0, 3.0]), Array::from(&mut [5.0, 8.0])]);
+    /// let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0])]);
+    /// let b = Matrix::new(&[Array::from(&[2.0, 3.0]), Array::from(&[5.0, 8.0])]);
     ///
-    /// assert_eq!(Matrix::new(&mut [Array::from(&mut [2.0, 6.0]), Array::from(&mut [15.0, 40.0])]), a.elem_mult(&b));
+    /// assert_eq!(Matrix::new(&[Array::from(&[2.0, 6.0]), Array::from(&[15.0, 40.0])]), a.elem_mult(&b));
     /// // Use the `*`-operator as a shorthand for this.
-    /// assert_eq!(Matrix::new(&mut [Array::from(&mut [2.0, 6.0]), Array::from(&mut [15.0, 40.0])]), a * b);
+    /// assert_eq!(Matrix::new(&[Array::from(&[2.0, 6.0]), Array::from(&[15.0, 40.0])]), a * b);
     /// ```
     pub fn elem_mult(&self, other: &Matrix) -> Matrix {
         assert!(
@@ -376,25 +404,13 @@ impl Matrix {
             "ERROR - Matrix element-wise multiplication: Rows differ in dimensions."
         );
 
-        let result = unsafe {
-            let layout = Layout::array::<Array>(self.rows).unwrap();
-            let ptr = alloc(layout);
-            std::slice::from_raw_parts_mut(ptr as *mut Array, self.rows)
-        };
-
-        let mat_slice1 = unsafe { std::slice::from_raw_parts_mut(self.arrays, self.rows) };
+        let result = self
+            .iter()
+            .zip(other.iter())
+            .map(|(a, b)| a.mult(b))
+            .collect();
 
-        let mat_slice2 = unsafe { std::slice::from_raw_parts_mut(other.arrays, other.rows) };
-
-        for i in 0..self.rows {
-            result[i] = mat_slice1[i].mult(&mat_slice2[i]);
-        }
-
-        Matrix {
-            rows: self.rows,
-            cols: self.cols,
-            arrays: result.as_mut_ptr(),
-        }
+        Matrix::from_parts(result, self.cols)
     }
 
     /// Returns a transpose of a matrix without modifying the original.
@@ -404,32 +420,16 @@ impl Matrix {
     ///
     /// ```
     /// // Create a matrix `a` and transpose it.
-    /// let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0])]);
+    /// let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0])]);
     ///
-    /// assert_eq!(Matrix::new(&mut [Array::from(&mut [1.0, 3.0]), Array::from(&mut [2.0, 5.0])]), a.transpose());
+    /// assert_eq!(Matrix::new(&[Array::from(&[1.0, 3.0]), Array::from(&[2.0, 5.0])]), a.transpose());
     /// ```
     pub fn transpose(&self) -> Matrix {
-        let result = unsafe {
-            let layout = Layout::array::<Array>(self.cols).unwrap();
-            let ptr = alloc(layout);
-            std::slice::from_raw_parts_mut(ptr as *mut Array, self.cols)
-        };
-
-        let arr_slice = unsafe { std::slice::from_raw_parts_mut(self.arrays, self.rows) };
+        let result = (0..self.cols)
+            .map(|i| Array::from_vec(self.iter().map(|row| row.get(i)).collect()))
+            .collect();
 
-        for i in 0..self.cols {
-            result[i] = Array::zeros(self.rows);
-
-            for j in 0..self.rows {
-                result[i].set(arr_slice[j].get(i), j);
-            }
-        }
-
-        Matrix {
-            rows: self.cols,
-            cols: self.rows,
-            arrays: result.as_mut_ptr(),
-        }
+        Matrix::from_parts(result, self.rows)
     }
 
     /// Perform matrix multiplication on two matrices
@@ -441,49 +441,34 @@ impl Matrix {
     /// # Panics
     ///
     /// For matrix multiplication of two matrices, A and B,
-    /// A must have the dimensions `n`x`m` and B must have the dimensions `r`x`n`
-    /// in order for the multiplication to be valid.
+    /// A must have the dimensions `n`x`m` and B must have the dimensions `m`x`r`
+    /// in order for the multiplication to be valid. The result has the dimensions `n`x`r`.
     ///
     /// # Examples
     ///
     /// ```
     /// // Create two matrices `a` and `b` and multiply them.
-    /// let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0])]);
-    /// let b = Matrix::new(&mut [Array::from(&mut [2.0, 3.0]), Array::from(&mut [5.0, 8.0])]);
+    /// use moonalloy::linalg::array::Array;
+    /// use moonalloy::linalg::matrix::Matrix;
+    /// let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0])]);
+    /// let b = Matrix::new(&[Array::from(&[2.0, 3.0]), Array::from(&[5.0, 8.0])]);
     ///
-    /// assert_eq!(Matrix::new(&mut [Array::from(&mut [10.0, 19.0]), Array::from(&mut [21.0, 50.0])]), a.mult(&b));
+    /// assert_eq!(Matrix::new(&[Array::from(&[12.0, 19.0]), Array::from(&[31.0, 49.0])]), a.mult(&b));
     /// ```
     pub fn mult(&self, other: &Matrix) -> Matrix {
         assert!(
-            self.rows == other.cols,
+            self.cols == other.rows,
             "ERROR - Matrix multiplication: Invalid dimensions."
         );
 
-        let result = unsafe {
-            let layout = Layout::array::<Array>(self.cols).unwrap();
-            let ptr = alloc(layout);
-            std::slice::from_raw_parts_mut(ptr as *mut Array, self.cols)
-        };
-
-        let mat_t = self.transpose();
+        let other_t = other.transpose();
 
-        let mat_slice1 = unsafe { std::slice::from_raw_parts_mut(mat_t.arrays, mat_t.rows) };
+        let result = self
+            .iter()
+            .map(|row| Array::from_vec(other_t.iter().map(|col| row.dotp(col)).collect()))
+            .collect();
 
-        let mat_slice2 = unsafe { std::slice::from_raw_parts_mut(other.arrays, other.rows) };
-
-        for i in 0..self.cols {
-            result[i] = Array::zeros(other.rows);
-
-            for j in 0..other.rows {
-                result[i].set(mat_slice1[j].dotp(&mat_slice2[i]), j);
-            }
-        }
-
-        Matrix {
-            rows: other.rows,
-            cols: self.cols,
-            arrays: result.as_mut_ptr(),
-        }
+        Matrix::from_parts(result, other.cols)
     }
 
     /// Returns the element at the index (i,j)
@@ -502,16 +487,31 @@ impl Matrix {
     ///
     /// ```
     /// // Create a 2x2 matrix and get the value at (1,0)
-    /// let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0])]);
+    /// let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0])]);
     ///
     /// assert_eq!(3.0, a.get(1, 0));
     /// // Use the `[]`-operator twice as a shorthand for indexing.
     /// assert_eq!(3.0, a[1][0]);
     /// ```
     pub fn get(&self, i: usize, j: usize) -> f64 {
-        let slice = unsafe { std::slice::from_raw_parts_mut(self.arrays, self.rows) };
+        self[i].get(j)
+    }
 
-        slice[i].get(j)
+    /// Returns a copy of the column at index `j` as an Array.
+    ///
+    /// # Arguments
+    ///
+    /// * `j` - the index of the column.
+    ///
+    /// # Panics
+    ///
+    /// The index must be smaller than the number of columns, otherwise the code will panic.
+    pub fn column(&self, j: usize) -> Array {
+        assert!(
+            j < self.cols,
+            "ERROR - matrix column: Index out of bounds."
+        );
+        Array::from_vec(self.iter().map(|row| row[j]).collect())
     }
 
     /// Returns a subsection of a row in the matrix as an Array without modifying the matrix itself.
@@ -531,26 +531,16 @@ impl Matrix {
     ///
     /// ```
     /// // Create a 2x2 matrix and get a copy of the second row with `splice()`
-    /// let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0])]);
+    /// let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0])]);
     ///
-    /// assert_eq!(Array::from(&mut [3.0, 5.0]), a.splice(1, 0, 2));
+    /// assert_eq!(Array::from(&[3.0, 5.0]), a.splice(1, 0, 2));
     /// ```
     pub fn splice(&self, row: usize, first: usize, last: usize) -> Array {
         assert!(
             first < last,
             "ERROR - matrix splice: first index must be smaller than last index."
         );
-        let slice = unsafe {
-            let layout = Layout::array::<f64>(last - first).unwrap();
-            let ptr = alloc(layout);
-            std::slice::from_raw_parts_mut(ptr as *mut f64, last - first)
-        };
-
-        for col in first..last {
-            slice[(col - first) as usize] = self.get(row, col);
-        }
-
-        Array::from(slice)
+        self[row].splice(first, last)
     }
 
     /// Changes the element at the index (i,j).
@@ -570,17 +560,32 @@ impl Matrix {
     ///
     /// ```
     /// // Create a 2x2 matrix and set the value at (1,0) to 8.0
-    /// let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0])]);
+    /// let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0])]);
     ///
     /// a.set(8.0, 1, 0);
-    /// // use the `[]`-operator twice as a shorthand for indexing
-    /// // a[1][0] = 8.0;
+    /// // use the `[]`-operator with both indexes as a shorthand
+    /// // a[(1, 0)] = 8.0;
     /// assert_eq!(8.0, a.get(1, 0));
     /// ```
     pub fn set(&mut self, val: f64, i: usize, j: usize) {
-        let slice = unsafe { std::slice::from_raw_parts_mut(self.arrays, self.rows) };
+        self[(i, j)] = val;
+    }
 
-        slice[i].set(val, j);
+    /// Returns the elements of the ith row for changing them in place.
+    ///
+    /// The rows can only be changed element by element, so they all keep the same length.
+    ///
+    /// # Panics
+    ///
+    /// If the index goes beyond the number of rows the code will panic.
+    pub fn row_mut(&mut self, i: usize) -> &mut [f64] {
+        assert!(i < self.rows, "ERROR - Matrix: Index out of bounds.");
+        &mut self.rows_mut()[i]
+    }
+
+    /// Returns the rows of the matrix for reordering them.
+    fn rows_mut(&mut self) -> &mut [Array] {
+        unsafe { std::slice::from_raw_parts_mut(self.arrays, self.rows) }
     }
 
     /// Constructs an Augmented matrix for a matrix and an array.
@@ -594,9 +599,9 @@ impl Matrix {
 	let mut result = Matrix::zeros(self.rows, self.cols + 1);
 	for i in 0..self.rows {
 	    for j in 0..self.cols {
-		result[i][j] = self[i][j];
+		result[(i, j)] = self[i][j];
 	    }
-	    result[i][self.cols] = b[i];
+	    result[(i, self.cols)] = b[i];
 	}
 
 	result
@@ -630,27 +635,28 @@ impl Display for Matrix {
     }
 }
 
-impl PartialEq for Matrix {
-    fn eq(&self, other: &Self) -> bool {
-        if self.rows != other.rows {
-            return false;
-        }
-
-        if self.cols != other.cols {
-            return false;
-        }
-
-        let slice1 = unsafe { std::slice::from_raw_parts_mut(self.arrays, self.rows) };
-
-        let slice2 = unsafe { std::slice::from_raw_parts_mut(other.arrays, other.rows) };
+impl Clone for Matrix {
+    fn clone(&self) -> Self {
+        Matrix::from_parts(self.to_vec(), self.cols)
+    }
+}
 
-        for i in 0..self.rows {
-            if slice1[i] != slice2[i] {
-                return false;
-            }
+impl Drop for Matrix {
+    fn drop(&mut self) {
+        // SAFETY: `arrays` and `rows` were taken from a boxed slice in `from_parts`, which is
+        // the only place where matrices are created. Dropping the box drops the rows as well.
+        unsafe {
+            drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+                self.arrays,
+                self.rows,
+            )))
         }
+    }
+}
 
-        true
+impl PartialEq for Matrix {
+    fn eq(&self, other: &Self) -> bool {
+        self.rows == other.rows && self.cols == other.cols && self.deref() == other.deref()
     }
 }
 
@@ -661,27 +667,31 @@ impl Deref for Matrix {
     }
 }
 
-impl DerefMut for Matrix {
-    fn deref_mut(&mut self) -> &mut [Array] {
-        unsafe { std::slice::from_raw_parts_mut(self.arrays, self.rows) }
-    }
-}
-
 impl Index<usize> for Matrix {
     type Output = Array;
 
     fn index(&self, i: usize) -> &Self::Output {
         assert!(i < self.rows, "ERROR - Matrix: Index out of bounds.");
-        let slice = unsafe { std::slice::from_raw_parts_mut(self.arrays, self.rows) };
-        &slice[i]
+        &self.deref()[i]
+    }
+}
+
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = f64;
+
+    fn index(&self, (i, j): (usize, usize)) -> &Self::Output {
+        assert!(
+            i < self.rows && j < self.cols,
+            "ERROR - Matrix: Index out of bounds."
+        );
+        &self.deref()[i][j]
     }
 }
 
-impl IndexMut<usize> for Matrix {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        assert!(index < self.rows, "ERROR - Matrix: Index out of bounds.");
-        let slice = unsafe { std::slice::from_raw_parts_mut(self.arrays, self.rows) };
-        &mut slice[index]
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut Self::Output {
+        &mut self.row_mut(i)[j]
     }
 }
 
@@ -722,7 +732,7 @@ mod test {
 
     #[test]
     fn test_index() {
-        let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0])]);
+        let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0])]);
 
         assert_eq!(3.0, a[1][0]);
     }
@@ -730,23 +740,23 @@ mod test {
     #[test]
     #[should_panic]
     fn test_index_out_of_bounds_rows() {
-        let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0])]);
+        let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0])]);
 
-        a[2][1];
+        let _ = a[2][1];
     }
 
     #[test]
     #[should_panic]
     fn test_index_out_of_bounds_columns() {
-        let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0])]);
+        let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0])]);
 
-        a[1][2];
+        let _ = a[1][2];
     }
 
     #[test]
     fn test_zeros() {
         let z = Matrix::zeros(2, 2);
-        let r = Matrix::new(&mut [Array::from(&mut [0.0, 0.0]), Array::from(&mut [0.0, 0.0])]);
+        let r = Matrix::new(&[Array::from(&[0.0, 0.0]), Array::from(&[0.0, 0.0])]);
 
         assert_eq!(r, z);
     }
@@ -754,49 +764,61 @@ mod test {
     #[test]
     fn test_ones() {
         let o = Matrix::ones(2, 2);
-        let r = Matrix::new(&mut [Array::from(&mut [1.0, 1.0]), Array::from(&mut [1.0, 1.0])]);
+        let r = Matrix::new(&[Array::from(&[1.0, 1.0]), Array::from(&[1.0, 1.0])]);
 
         assert_eq!(r, o);
     }
 
+    #[test]
+    fn test_random_is_reproducible() {
+        use crate::random::xoshiro::Xoshiro256PlusPlus;
+
+        let a = Matrix::random_normal(3, 2, &mut Xoshiro256PlusPlus::seed_from_u64(1));
+        let b = Matrix::random_normal(3, 2, &mut Xoshiro256PlusPlus::seed_from_u64(1));
+
+        assert_eq!((3, 2), a.dimensions());
+        assert_eq!(a, b);
+        assert_ne!(a, Matrix::random_uniform(3, 2, &mut Xoshiro256PlusPlus::seed_from_u64(1)));
+    }
+
     #[test]
     fn test_identity() {
         let i = Matrix::identity(2);
-        let r = Matrix::new(&mut [Array::from(&mut [1.0, 0.0]), Array::from(&mut [0.0, 1.0])]);
+        let r = Matrix::new(&[Array::from(&[1.0, 0.0]), Array::from(&[0.0, 1.0])]);
 
         assert_eq!(r, i);
     }
 
     #[test]
     fn test_add() {
-        let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0])]);
-        let b = Matrix::new(&mut [Array::from(&mut [2.0, 3.0]), Array::from(&mut [5.0, 8.0])]);
-        let r = Matrix::new(&mut [Array::from(&mut [3.0, 5.0]), Array::from(&mut [8.0, 13.0])]);
+        let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0])]);
+        let b = Matrix::new(&[Array::from(&[2.0, 3.0]), Array::from(&[5.0, 8.0])]);
+        let r = Matrix::new(&[Array::from(&[3.0, 5.0]), Array::from(&[8.0, 13.0])]);
         assert_eq!(r, a + b);
     }
 
     #[test]
     fn test_sub() {
-        let a = Matrix::new(&mut [Array::from(&mut [3.0, 5.0]), Array::from(&mut [8.0, 13.0])]);
-        let b = Matrix::new(&mut [Array::from(&mut [2.0, 3.0]), Array::from(&mut [5.0, 8.0])]);
-        let r = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0])]);
+        let a = Matrix::new(&[Array::from(&[3.0, 5.0]), Array::from(&[8.0, 13.0])]);
+        let b = Matrix::new(&[Array::from(&[2.0, 3.0]), Array::from(&[5.0, 8.0])]);
+        let r = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0])]);
         assert_eq!(r, a - b);
     }
 
     #[test]
     fn test_scalar() {
-        let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0])]);
-        let r = Matrix::new(&mut [Array::from(&mut [2.0, 4.0]), Array::from(&mut [6.0, 10.0])]);
+        let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0])]);
+        let r = Matrix::new(&[Array::from(&[2.0, 4.0]), Array::from(&[6.0, 10.0])]);
 
         assert_eq!(r, a.scalar(2.0));
     }
 
     #[test]
     fn test_neg() {
-        let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 5.0])]);
-        let r = Matrix::new(&mut [
-            Array::from(&mut [-1.0, -2.0]),
-            Array::from(&mut [-3.0, -5.0]),
+        let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 5.0])]);
+        let r = Matrix::new(&[
+            Array::from(&[-1.0, -2.0]),
+            Array::from(&[-3.0, -5.0]),
         ]);
 
         assert_eq!(r, -a);
@@ -804,42 +826,63 @@ mod test {
 
     #[test]
     fn test_elem_mult() {
-        let a = Matrix::new(&mut [Array::from(&mut [3.0, 5.0]), Array::from(&mut [8.0, 13.0])]);
-        let b = Matrix::new(&mut [Array::from(&mut [2.0, 3.0]), Array::from(&mut [5.0, 8.0])]);
-        let r = Matrix::new(&mut [
-            Array::from(&mut [6.0, 15.0]),
-            Array::from(&mut [40.0, 104.0]),
+        let a = Matrix::new(&[Array::from(&[3.0, 5.0]), Array::from(&[8.0, 13.0])]);
+        let b = Matrix::new(&[Array::from(&[2.0, 3.0]), Array::from(&[5.0, 8.0])]);
+        let r = Matrix::new(&[
+            Array::from(&[6.0, 15.0]),
+            Array::from(&[40.0, 104.0]),
         ]);
         assert_eq!(r, a * b);
     }
 
     #[test]
     fn test_mult() {
-        let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 4.0])]);
-        let r = Matrix::new(&mut [
-            Array::from(&mut [7.0, 10.0]),
-            Array::from(&mut [15.0, 22.0]),
+        let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 4.0])]);
+        let r = Matrix::new(&[
+            Array::from(&[7.0, 10.0]),
+            Array::from(&[15.0, 22.0]),
         ]);
         assert_eq!(r, a.mult(&a));
     }
 
+    #[test]
+    fn test_mult_non_square() {
+        let a = Matrix::new(&[
+            Array::from(&[1.0, 2.0, 3.0]),
+            Array::from(&[4.0, 5.0, 6.0]),
+        ]);
+        let b = Matrix::new(&[
+            Array::from(&[1.0, 0.0]),
+            Array::from(&[0.0, 1.0]),
+            Array::from(&[1.0, 1.0]),
+        ]);
+        let r = Matrix::new(&[Array::from(&[4.0, 5.0]), Array::from(&[10.0, 11.0])]);
+        assert_eq!(r, a.mult(&b));
+    }
+
     #[test]
     fn test_transpose() {
-        let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 4.0])]);
-        let r = Matrix::new(&mut [Array::from(&mut [1.0, 3.0]), Array::from(&mut [2.0, 4.0])]);
+        let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 4.0])]);
+        let r = Matrix::new(&[Array::from(&[1.0, 3.0]), Array::from(&[2.0, 4.0])]);
         assert_eq!(r, a.transpose());
     }
 
+    #[test]
+    fn test_column() {
+        let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 4.0])]);
+        assert_eq!(Array::from(&[2.0, 4.0]), a.column(1));
+    }
+
     #[test]
     fn test_get() {
-        let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 4.0])]);
+        let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 4.0])]);
         assert_eq!(3.0, a.get(1, 0));
     }
 
     #[test]
     fn test_set() {
-        let mut a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 4.0])]);
-        let r = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 8.0])]);
+        let mut a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 4.0])]);
+        let r = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 8.0])]);
 
         a.set(8.0, 1, 1);
 
@@ -848,9 +891,9 @@ mod test {
 
     #[test]
     fn test_iterator() {
-        let a = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 4.0])]);
-        let first = Array::from(&mut [1.0, 2.0]);
-        let second = Array::from(&mut [3.0, 4.0]);
+        let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 4.0])]);
+        let first = Array::from(&[1.0, 2.0]);
+        let second = Array::from(&[3.0, 4.0]);
 
         let mut it = a.iter();
 
@@ -860,10 +903,63 @@ mod test {
 
     #[test]
     fn test_swap() {
-	let mut actual = Matrix::new(&mut [Array::from(&mut [1.0, 2.0]), Array::from(&mut [3.0, 4.0])]);
-	let expected = Matrix::new(&mut [Array::from(&mut [3.0, 4.0]), Array::from(&mut [1.0, 2.0])]);
+	let mut actual = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, 4.0])]);
+	let expected = Matrix::new(&[Array::from(&[3.0, 4.0]), Array::from(&[1.0, 2.0])]);
 
-	actual.swap(0, 1);
+	actual.swap_rows(0, 1);
 	assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_element_writes() {
+        let mut a = Matrix::zeros(2, 3);
+        a[(1, 2)] = 4.0;
+        a.row_mut(0).copy_from_slice(&[1.0, 2.0, 3.0]);
+
+        assert_eq!(
+            Matrix::new(&[Array::from(&[1.0, 2.0, 3.0]), Array::from(&[0.0, 0.0, 4.0])]),
+            a
+        );
+        assert_eq!(4.0, a[(1, 2)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_element_out_of_bounds() {
+        let mut a = Matrix::zeros(2, 3);
+        a[(0, 3)] = 1.0;
+    }
+
+    #[test]
+    fn test_clone_is_independent() {
+        let a = Matrix::identity(2);
+        let mut b = a.clone();
+
+        b[(0, 1)] = 5.0;
+
+        assert_eq!(Matrix::identity(2), a);
+        assert_eq!(5.0, b[0][1]);
+    }
+
+    #[test]
+    fn test_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Matrix>();
+
+        // Share one matrix between threads for reading.
+        let a = Matrix::ones(50, 50);
+        let products: Vec<Matrix> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4).map(|_| scope.spawn(|| a.mult(&a))).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        for product in products {
+            assert_eq!(Matrix::ones(50, 50).scalar(50.0), product);
+        }
+
+        // Move a matrix into a worker thread and back.
+        let b = Matrix::identity(3);
+        let handle = std::thread::spawn(move || b.transpose());
+        assert_eq!(Matrix::identity(3), handle.join().unwrap());
+    }
 }
@@ -2,6 +2,7 @@
 
 use crate::linalg::array::Array;
 use crate::linalg::matrix::Matrix;
+use crate::linalg::summation;
 use std::ops::Range;
 
 /// Returns the solution of a linear system of equations in the form: Ax = b.
@@ -16,16 +17,15 @@ use std::ops::Range;
 ///
 /// ```
 /// // Create a n*n-dimensional coefficient matrix `a` and a vector of constants `b`
-/// let a = Matrix::new(&mut [Array::from(&mut [3.0, 2.0]), Array::from(&mut [-6.0, 6.0])]);
-/// let b = Array::from(&mut [7.0, 6.0]);
+/// let a = Matrix::new(&[Array::from(&[3.0, 2.0]), Array::from(&[-6.0, 6.0])]);
+/// let b = Array::from(&[7.0, 6.0]);
 ///
 /// // Solve the system with Gauss-Elimination
-/// assert_eq!(Array::from(&mut [-1.0, 2.0]), gauss_elimination(a, b));
+/// assert_eq!(Array::from(&[-1.0, 2.0]), gauss_elimination(a, b));
 /// ```
 pub fn gauss_elimination(a: Matrix, b: Array) -> Array {
     let augmented = a.augment(b);
     let reduced_row = row_echelon_form(augmented);
-    println!("reduced_row = {}", reduced_row);
     back_substitution(reduced_row)
 }
 
@@ -48,7 +48,7 @@ fn row_echelon_form(augmented: Matrix) -> Matrix {
     };
 
     while h < m && k < n {
-	let i_max = argmax(h..m, a, k, &abs);
+	let i_max = argmax(h..m, &a, k, &abs);
 
 	if a[i_max][k] == 0.0 {
 	    k += 1;
@@ -56,10 +56,10 @@ fn row_echelon_form(augmented: Matrix) -> Matrix {
 	    a.swap_rows(h, i_max);
 	    for i in (h + 1)..m {
 		let f = a[i][k] / a[h][k];
-		a[i][k] = 0.0;
+		a[(i, k)] = 0.0;
 		
 		for j in (k + 1)..n {
-		    a[i][j] = a[i][j] - a[h][j] * f;
+		    a[(i, j)] = a[i][j] - a[h][j] * f;
 		}
 	    }
 	    h += 1;
@@ -76,20 +76,208 @@ fn back_substitution(reduced: Matrix) -> Array {
     let mut x = Array::zeros(rows);
     let n = rows - 1;
     let k = cols - 1;
-    let y = |index| reduced[index][k];
+    let y = |index: usize| reduced[index][k];
 
     x[n] = y(n) / reduced[n][n]; 
     
-    for i in 0..n {
+    for i in (0..n).rev() {
 	let mut kernel = 0.0;
-	for j in i..=n {
+	for j in (i + 1)..=n {
 	    kernel += reduced[i][j] * x[j];
 	}
 	x[i] = (y(i) - kernel) / reduced[i][i];
     }
 
-    println!("x = {}", x);
-    
+    x
+}
+
+/// Returns the least-squares solution of an overdetermined system of equations Ax = b, the x
+/// that minimizes the norm of Ax - b.
+/// It uses a QR decomposition of `a` by Householder reflections, which avoids the loss of
+/// accuracy of solving the normal equations.
+///
+/// # Arguments
+///
+/// * `a` - a m*n-dimensional matrix with m >= n and full column rank.
+/// * `b` - a vector containing the m right-hand sides.
+///
+/// # Panics
+///
+/// `a` must have at least as many rows as columns, as many rows as `b` has elements, and
+/// linearly independent columns, otherwise the code will panic.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::linalg::methods::least_squares;
+///
+/// // Fit the line y = c0 + c1 t through (0, 1), (1, 3) and (2, 5).
+/// let a = Matrix::new(&[
+///     Array::from(&[1.0, 0.0]),
+///     Array::from(&[1.0, 1.0]),
+///     Array::from(&[1.0, 2.0]),
+/// ]);
+/// let b = Array::from(&[1.0, 3.0, 5.0]);
+/// let x = least_squares(&a, &b);
+///
+/// assert!((x[0] - 1.0).abs() < 1e-12 && (x[1] - 2.0).abs() < 1e-12);
+/// ```
+pub fn least_squares(a: &Matrix, b: &Array) -> Array {
+    let (m, n) = a.dimensions();
+    assert!(
+        m >= n && n > 0,
+        "ERROR - least_squares: The matrix must have at least as many rows as columns."
+    );
+    assert_eq!(
+        m,
+        b.len(),
+        "ERROR - least_squares: The matrix and the vector must have the same number of rows."
+    );
+
+    householder_least_squares(a, b).expect(
+        "ERROR - least_squares: The columns of the matrix must be linearly independent.",
+    )
+}
+
+/// Returns the least-squares solution of Ax = b by Householder QR, or `None` if the columns of
+/// `a` are linearly dependent. The dimensions must have been checked.
+pub(crate) fn householder_least_squares(a: &Matrix, b: &Array) -> Option<Array> {
+    let (m, n) = a.dimensions();
+
+    // The columns of `a`, which are overwritten by R above the diagonal.
+    let mut cols: Vec<Vec<f64>> = (0..n).map(|j| a.column(j).to_vec()).collect();
+    let mut rhs = b.to_vec();
+
+    for k in 0..n {
+        let norm = summation::norm(&cols[k][k..]);
+        if norm == 0.0 {
+            continue;
+        }
+
+        // The Householder vector v that reflects the column onto -sign(a_kk) |a_k| e_k.
+        let alpha = if cols[k][k] > 0.0 { -norm } else { norm };
+        let mut v = cols[k][k..].to_vec();
+        v[0] -= alpha;
+        let v_norm2 = summation::dot(&v, &v);
+
+        let reflect = |x: &mut [f64]| {
+            let s = 2.0 * summation::dot(&v, x) / v_norm2;
+            for (xi, vi) in x.iter_mut().zip(&v) {
+                *xi -= s * vi;
+            }
+        };
+        for col in cols.iter_mut().skip(k) {
+            reflect(&mut col[k..]);
+        }
+        reflect(&mut rhs[k..]);
+    }
+
+    let largest = (0..n).fold(0.0, |acc: f64, k| acc.max(cols[k][k].abs()));
+    let tolerance = largest * m as f64 * f64::EPSILON;
+    if (0..n).any(|k| cols[k][k].abs() <= tolerance) {
+        return None;
+    }
+
+    let mut x = Array::zeros(n);
+    for i in (0..n).rev() {
+        let mut kernel = 0.0;
+        for j in (i + 1)..n {
+            kernel += cols[j][i] * x[j];
+        }
+        x[i] = (rhs[i] - kernel) / cols[i][i];
+    }
+
+    Some(x)
+}
+
+/// Returns the Cholesky factor of a symmetric positive definite matrix: the lower triangular
+/// matrix L with a positive diagonal such that A = L L^T.
+///
+/// Only the lower triangle of `a` is read.
+///
+/// # Arguments
+///
+/// * `a` - a symmetric positive definite n*n-dimensional matrix.
+///
+/// # Panics
+///
+/// `a` must be square and positive definite, otherwise the code will panic.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::linalg::methods::{cholesky, cholesky_solve};
+///
+/// let a = Matrix::new(&[Array::from(&[4.0, 2.0]), Array::from(&[2.0, 5.0])]);
+/// let l = cholesky(&a);
+///
+/// assert_eq!(Matrix::new(&[Array::from(&[2.0, 0.0]), Array::from(&[1.0, 2.0])]), l);
+/// assert_eq!(Array::from(&[1.0, 1.0]), cholesky_solve(&l, &Array::from(&[6.0, 7.0])));
+/// ```
+pub fn cholesky(a: &Matrix) -> Matrix {
+    let (m, n) = a.dimensions();
+    assert_eq!(m, n, "ERROR - cholesky: The matrix must be square.");
+
+    cholesky_decomposition(a)
+        .expect("ERROR - cholesky: The matrix must be positive definite.")
+}
+
+/// Returns the Cholesky factor of a square matrix, or `None` if it is not positive definite.
+pub(crate) fn cholesky_decomposition(a: &Matrix) -> Option<Matrix> {
+    let (n, _) = a.dimensions();
+    let mut l = Matrix::zeros(n, n);
+
+    for j in 0..n {
+        let row = l[j].as_slice()[..j].to_vec();
+        // A pivot that is only rounding error means the matrix is singular.
+        let pivot = a[j][j] - summation::dot(&row, &row);
+        if pivot <= a[j][j].abs() * n as f64 * f64::EPSILON || pivot.is_nan() {
+            return None;
+        }
+        let pivot = pivot.sqrt();
+        l[(j, j)] = pivot;
+
+        for i in (j + 1)..n {
+            let kernel = summation::dot(&l[i].as_slice()[..j], &row);
+            l[(i, j)] = (a[i][j] - kernel) / pivot;
+        }
+    }
+
+    Some(l)
+}
+
+/// Returns the solution of Ax = b given the Cholesky factor L of A, by forward substitution
+/// with L and back substitution with L^T.
+///
+/// # Panics
+///
+/// `l` must be a square lower triangular matrix with as many rows as `b` has elements,
+/// otherwise the code will panic.
+pub fn cholesky_solve(l: &Matrix, b: &Array) -> Array {
+    let (m, n) = l.dimensions();
+    assert!(
+        m == n && n == b.len(),
+        "ERROR - cholesky_solve: The factor must be square with as many rows as the vector."
+    );
+
+    let mut y = Array::zeros(n);
+    for i in 0..n {
+        y[i] = (b[i] - summation::dot(&l[i].as_slice()[..i], &y.as_slice()[..i])) / l[i][i];
+    }
+
+    let mut x = Array::zeros(n);
+    for i in (0..n).rev() {
+        let mut kernel = 0.0;
+        for j in (i + 1)..n {
+            kernel += l[j][i] * x[j];
+        }
+        x[i] = (y[i] - kernel) / l[i][i];
+    }
+
     x
 }
 
@@ -97,7 +285,7 @@ fn back_substitution(reduced: Matrix) -> Array {
 ///
 /// Based on the mathematical description of backwards substitution:
 /// https://algowiki-project.org/en/Backward_substitution
-pub fn argmax(range: Range<usize>, a: Matrix, k: usize, f: &dyn Fn(f64) -> f64) -> usize {
+pub fn argmax(range: Range<usize>, a: &Matrix, k: usize, f: &dyn Fn(f64) -> f64) -> usize {
     let mut max_arg = range.start;
     let mut max_out = f(a[max_arg][k]);
 
@@ -117,11 +305,11 @@ mod test {
 
     #[test]
     fn test_row_echelon_form() {
-	let a = Matrix::new(&mut [Array::from(&mut [3.0, 2.0]), Array::from(&mut [-6.0, 6.0])]);
-        let b = Array::from(&mut [7.0, 6.0]);
+	let a = Matrix::new(&[Array::from(&[3.0, 2.0]), Array::from(&[-6.0, 6.0])]);
+        let b = Array::from(&[7.0, 6.0]);
 	let augmented = a.augment(b);
 	
-	let expected = Matrix::new(&mut [Array::from(&mut [-6.0, 6.0, 6.0]), Array::from(&mut [0.0, 5.0, 10.0])]);
+	let expected = Matrix::new(&[Array::from(&[-6.0, 6.0, 6.0]), Array::from(&[0.0, 5.0, 10.0])]);
 
 	let actual = row_echelon_form(augmented);
 	assert_eq!(expected, actual);
@@ -129,9 +317,9 @@ mod test {
 
     #[test]
     fn test_backsubstitution() {
-	let augmented = Matrix::new(&mut [Array::from(&mut [-6.0, 6.0, 6.0]), Array::from(&mut [0.0, 5.0, 10.0])]);
+	let augmented = Matrix::new(&[Array::from(&[-6.0, 6.0, 6.0]), Array::from(&[0.0, 5.0, 10.0])]);
 
-	let expected = Array::from(&mut [1.0, 2.0]);
+	let expected = Array::from(&[1.0, 2.0]);
 
 	let actual = back_substitution(augmented);
 	assert_eq!(expected, actual);
@@ -139,12 +327,97 @@ mod test {
 
     #[test]
     fn test_gauss_elimination() {
-        let a = Matrix::new(&mut [Array::from(&mut [3.0, 2.0]), Array::from(&mut [-6.0, 6.0])]);
-        let b = Array::from(&mut [7.0, 6.0]);
+        let a = Matrix::new(&[Array::from(&[3.0, 2.0]), Array::from(&[-6.0, 6.0])]);
+        let b = Array::from(&[7.0, 6.0]);
 
-	let expected = Array::from(&mut [1.0, 2.0]);
+	let expected = Array::from(&[1.0, 2.0]);
 	
         let actual = gauss_elimination(a, b);
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_gauss_elimination_3x3() {
+        let a = Matrix::new(&[
+            Array::from(&[2.0, 1.0, -1.0]),
+            Array::from(&[-3.0, -1.0, 2.0]),
+            Array::from(&[-2.0, 1.0, 2.0]),
+        ]);
+        let b = Array::from(&[8.0, -11.0, -3.0]);
+
+        let expected = [2.0, 3.0, -1.0];
+
+        let actual = gauss_elimination(a, b);
+        for i in 0..3 {
+            assert!((expected[i] - actual[i]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_least_squares() {
+        // The data lies exactly on the plane 1 + 2 s - 3 t.
+        let a = Matrix::new(&[
+            Array::from(&[1.0, 0.0, 0.0]),
+            Array::from(&[1.0, 1.0, 0.0]),
+            Array::from(&[1.0, 0.0, 1.0]),
+            Array::from(&[1.0, 2.0, 1.0]),
+            Array::from(&[1.0, -1.0, 3.0]),
+        ]);
+        let b = Array::from(&[1.0, 3.0, -2.0, 2.0, -10.0]);
+        let x = least_squares(&a, &b);
+        let expected = [1.0, 2.0, -3.0];
+        for i in 0..3 {
+            assert!((expected[i] - x[i]).abs() < 1e-12);
+        }
+
+        // The mean minimizes the squared distances to the values.
+        let ones = Matrix::new(&[
+            Array::from(&[1.0]),
+            Array::from(&[1.0]),
+            Array::from(&[1.0]),
+        ]);
+        let x = least_squares(&ones, &Array::from(&[1.0, 2.0, 6.0]));
+        assert!((x[0] - 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_least_squares_rank_deficient() {
+        let a = Matrix::new(&[
+            Array::from(&[1.0, 2.0]),
+            Array::from(&[2.0, 4.0]),
+            Array::from(&[3.0, 6.0]),
+        ]);
+        least_squares(&a, &Array::from(&[1.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    fn test_cholesky() {
+        let a = Matrix::new(&[
+            Array::from(&[4.0, 12.0, -16.0]),
+            Array::from(&[12.0, 37.0, -43.0]),
+            Array::from(&[-16.0, -43.0, 98.0]),
+        ]);
+        let l = cholesky(&a);
+        let expected = Matrix::new(&[
+            Array::from(&[2.0, 0.0, 0.0]),
+            Array::from(&[6.0, 1.0, 0.0]),
+            Array::from(&[-8.0, 5.0, 3.0]),
+        ]);
+        assert_eq!(expected, l);
+        assert_eq!(a, l.mult(&l.transpose()));
+
+        let x = cholesky_solve(&l, &Array::from(&[-4.0, -9.5, 33.0]));
+        let expected = [1.0, 0.0, 0.5];
+        for i in 0..3 {
+            assert!((expected[i] - x[i]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_cholesky_indefinite() {
+        let a = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[2.0, 1.0])]);
+        cholesky(&a);
+    }
 }
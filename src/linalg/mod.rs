@@ -5,3 +5,4 @@
 pub mod array;
 pub mod matrix;
 pub mod methods;
+pub mod summation;
@@ -0,0 +1,208 @@
+//! Summation - Accurate sums, dot products and norms
+//!
+//! Adding floating-point values one after the other loses the low-order digits of every
+//! addend that is much smaller than the running sum, so the error of a naive sum grows with
+//! the number of values and with their range of magnitudes. This module contains summation
+//! algorithms that keep the error small:
+//!
+//! * Kahan-Neumaier summation carries the rounding error of every addition in a second
+//!   variable. Its error does not grow with the number of values.
+//! * Pairwise summation adds the two halves of the values recursively. Its error grows with the
+//!   logarithm of the number of values, and it is nearly as fast as the naive sum.
+//!
+//! Array reductions use Kahan-Neumaier summation by default.
+
+/// The algorithm used to add up values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Summation {
+    /// Add the values one after the other.
+    Naive,
+    /// Kahan-Neumaier compensated summation. This is the default.
+    #[default]
+    Neumaier,
+    /// Pairwise (cascade) summation.
+    Pairwise,
+}
+
+/// The number of values below which pairwise summation adds naively.
+const PAIRWISE_BLOCK: usize = 128;
+
+/// Returns the sum `a + b` and its rounding error, such that `a + b = sum + error` exactly.
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let b_virtual = sum - a;
+    let error = (a - (sum - b_virtual)) + (b - b_virtual);
+    (sum, error)
+}
+
+/// Returns the sum of the values with the given algorithm.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::summation::{sum, Summation};
+///
+/// let values = [1e100, 1.0, -1e100];
+///
+/// assert_eq!(0.0, sum(&values, Summation::Naive));
+/// assert_eq!(1.0, sum(&values, Summation::Neumaier));
+/// ```
+pub fn sum(values: &[f64], method: Summation) -> f64 {
+    match method {
+        Summation::Naive => values.iter().sum(),
+        Summation::Neumaier => neumaier(values.iter().copied()),
+        Summation::Pairwise => pairwise(values),
+    }
+}
+
+/// Returns the sum of the values with Kahan-Neumaier compensated summation.
+///
+/// Unlike Kahan's original algorithm, Neumaier's variant also compensates when an addend is
+/// larger than the running sum. Infinite and NaN values give the same result as a naive sum.
+pub fn neumaier<I: IntoIterator<Item = f64>>(values: I) -> f64 {
+    let mut sum = 0.0;
+    let mut compensation = 0.0;
+
+    for v in values {
+        let (s, error) = two_sum(sum, v);
+        sum = s;
+        compensation += error;
+    }
+
+    if sum.is_finite() {
+        sum + compensation
+    } else {
+        sum
+    }
+}
+
+/// Returns the sum of the values with pairwise summation.
+pub fn pairwise(values: &[f64]) -> f64 {
+    if values.len() <= PAIRWISE_BLOCK {
+        values.iter().sum()
+    } else {
+        let (left, right) = values.split_at(values.len() / 2);
+        pairwise(left) + pairwise(right)
+    }
+}
+
+/// Returns the dot product of two slices with compensated summation.
+///
+/// This is the algorithm Dot2 by Ogita, Rump and Oishi: the rounding errors of both the
+/// products and the additions are accumulated separately, so the result is as accurate as if
+/// it was computed in twice the working precision.
+///
+/// # Panics
+///
+/// The slices must have the same length, otherwise the code will panic.
+pub fn dot(xs: &[f64], ys: &[f64]) -> f64 {
+    assert_eq!(
+        xs.len(),
+        ys.len(),
+        "ERROR - dot: The slices must have the same length."
+    );
+
+    let mut sum = 0.0;
+    let mut compensation = 0.0;
+
+    for (x, y) in xs.iter().zip(ys) {
+        let product = x * y;
+        let product_error = x.mul_add(*y, -product);
+        let (s, error) = two_sum(sum, product);
+        sum = s;
+        compensation += error + product_error;
+    }
+
+    if sum.is_finite() {
+        sum + compensation
+    } else {
+        sum
+    }
+}
+
+/// Returns the Euclidean norm of the values without overflow or underflow.
+///
+/// The values are scaled by the largest magnitude before they are squared, so the norm is
+/// finite whenever it is representable, and the squares are added with compensated summation.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::summation::norm;
+///
+/// // Squaring the values would overflow and underflow.
+/// assert!((norm(&[3e200, 4e200]) / 5e200 - 1.0).abs() < 1e-15);
+/// assert!((norm(&[3e-200, 4e-200]) / 5e-200 - 1.0).abs() < 1e-15);
+/// ```
+pub fn norm(values: &[f64]) -> f64 {
+    if values.iter().any(|v| v.is_nan()) {
+        return f64::NAN;
+    }
+
+    let scale = values.iter().fold(0.0, |acc: f64, v| acc.max(v.abs()));
+    if scale == 0.0 || scale.is_infinite() {
+        return scale;
+    }
+
+    scale * neumaier(values.iter().map(|v| (v / scale) * (v / scale))).sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Values of many magnitudes whose exact sum is close to 1.
+    fn ill_conditioned() -> Vec<f64> {
+        let mut values = Vec::new();
+        for i in 0..1000 {
+            let big = 10f64.powi(i % 8);
+            values.push(big);
+            values.push(1e-3);
+            values.push(-big);
+        }
+        values
+    }
+
+    #[test]
+    fn test_sums() {
+        let values = ill_conditioned();
+
+        assert!((sum(&values, Summation::Neumaier) - 1.0).abs() < 1e-15);
+        assert!((sum(&values, Summation::Naive) - 1.0).abs() > 1e-10);
+
+        let tenths = vec![0.1; 1_000_000];
+        let exact = 100_000.0;
+        let naive = (sum(&tenths, Summation::Naive) - exact).abs();
+        let pairwise = (sum(&tenths, Summation::Pairwise) - exact).abs();
+        assert!(pairwise < 1e-9 && pairwise < naive);
+        assert!((sum(&tenths, Summation::Neumaier) - exact).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_special_values() {
+        assert_eq!(f64::INFINITY, neumaier(vec![1.0, f64::INFINITY, 2.0]));
+        assert!(neumaier(vec![f64::INFINITY, f64::NEG_INFINITY]).is_nan());
+        assert!(neumaier(vec![1.0, f64::NAN]).is_nan());
+        assert_eq!(0.0, pairwise(&[]));
+    }
+
+    #[test]
+    fn test_dot() {
+        let xs = [1e100, 1.0, -1e100, 3.0];
+        let ys = [1.0, 1.0, 1.0, 2.0];
+        assert_eq!(7.0, dot(&xs, &ys));
+
+        // (1 + 2^-30)(1 - 2^-30) = 1 - 2^-60, which needs the error of the product.
+        let e = 2f64.powi(-30);
+        assert_eq!(-2f64.powi(-60), dot(&[1.0 + e, -1.0], &[1.0 - e, 1.0]));
+    }
+
+    #[test]
+    fn test_norm() {
+        assert_eq!(5.0, norm(&[3.0, -4.0]));
+        assert_eq!(0.0, norm(&[]));
+        assert_eq!(f64::INFINITY, norm(&[1.0, f64::NEG_INFINITY]));
+        assert!(norm(&[f64::INFINITY, f64::NAN]).is_nan());
+        assert_eq!(1e300 * 2f64.sqrt(), norm(&[1e300, 1e300]));
+    }
+}
@@ -0,0 +1,641 @@
+//! Lua - A loadable Lua C module for moonalloy
+//!
+//! When the crate is built with the `lua` feature, the shared library exports
+//! `luaopen_moonalloy`, so it can be loaded with `require("moonalloy")` from PUC Lua 5.4
+//! without an external FFI wrapper.
+//!
+//! Arrays and matrices are exposed as userdata. Their metatables implement the arithmetic
+//! operators (`+`, `-`, `*` and unary `-`), 1-based indexing, the length operator and
+//! `tostring`. Arrays and matrices own their memory, so it is released when the garbage
+//! collector finalizes the userdata.
+//!
+//! As in the `moonalloy` binary, `*` is the matrix product when a matrix is involved, and it
+//! multiplies every element when one operand is a number. Element-wise products are the
+//! `mult` method of Arrays and the `elem_mult` method of matrices. Indexing a matrix returns a
+//! view of the row, so `m[i][j] = v` writes to the matrix.
+//!
+//! ```lua
+//! local ma = require("moonalloy")
+//! local a = ma.array({1, 2, 3})
+//! local b = ma.ones(3)
+//! print(a + b, -a, a * 2, #a, a[2], a:dotp(b), a:mult(b))
+//! ```
+
+use crate::linalg::array::Array;
+use crate::linalg::matrix::Matrix;
+use crate::linalg::methods::gauss_elimination;
+
+use mlua::prelude::*;
+use mlua::{AnyUserData, MetaMethod, UserData, UserDataMethods, Value};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// An Array owned by Lua.
+struct LuaArray(Array);
+
+/// A Matrix owned by Lua, which shares its rows with the row views returned by indexing.
+struct LuaMatrix(Rc<RefCell<Matrix>>);
+
+impl LuaMatrix {
+    fn new(mat: Matrix) -> LuaMatrix {
+        LuaMatrix(Rc::new(RefCell::new(mat)))
+    }
+
+    /// Returns a view of a row, which is a 0-based index.
+    fn row(&self, row: usize) -> LuaRow {
+        LuaRow {
+            matrix: Rc::clone(&self.0),
+            row,
+        }
+    }
+}
+
+/// A row of a Matrix owned by Lua. Writing to the row writes to the matrix.
+struct LuaRow {
+    matrix: Rc<RefCell<Matrix>>,
+    row: usize,
+}
+
+/// A userdata that can be read and written like an Array.
+trait ArrayView {
+    /// Returns the result of `f` on the Array.
+    fn with<R>(&self, f: impl FnOnce(&Array) -> R) -> R;
+
+    /// Sets the element at a 0-based index.
+    fn set(&mut self, i: usize, val: f64);
+}
+
+impl ArrayView for LuaArray {
+    fn with<R>(&self, f: impl FnOnce(&Array) -> R) -> R {
+        f(&self.0)
+    }
+
+    fn set(&mut self, i: usize, val: f64) {
+        self.0[i] = val;
+    }
+}
+
+impl ArrayView for LuaRow {
+    fn with<R>(&self, f: impl FnOnce(&Array) -> R) -> R {
+        f(&self.matrix.borrow()[self.row])
+    }
+
+    fn set(&mut self, i: usize, val: f64) {
+        self.matrix.borrow_mut()[(self.row, i)] = val;
+    }
+}
+
+/// An operand of an arithmetic metamethod.
+enum Operand {
+    Scalar(f64),
+    Array(Array),
+    Matrix(Matrix),
+}
+
+impl<'lua> FromLua<'lua> for Operand {
+    fn from_lua(value: Value<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            Value::UserData(ud) => {
+                if let Ok(arr) = ud.borrow::<LuaArray>() {
+                    Ok(Operand::Array(arr.0.clone()))
+                } else if let Ok(row) = ud.borrow::<LuaRow>() {
+                    Ok(Operand::Array(row.with(Array::clone)))
+                } else if let Ok(mat) = ud.borrow::<LuaMatrix>() {
+                    Ok(Operand::Matrix(mat.0.borrow().clone()))
+                } else {
+                    Err(LuaError::FromLuaConversionError {
+                        from: "userdata",
+                        to: "Operand",
+                        message: Some("expected an Array or a Matrix".to_string()),
+                    })
+                }
+            }
+            value => Ok(Operand::Scalar(f64::from_lua(value, lua)?)),
+        }
+    }
+}
+
+/// Converts the result of an arithmetic metamethod back into a Lua value.
+fn into_value(lua: &Lua, operand: Operand) -> LuaResult<Value<'_>> {
+    match operand {
+        Operand::Scalar(val) => val.into_lua(lua),
+        Operand::Array(arr) => LuaArray(arr).into_lua(lua),
+        Operand::Matrix(mat) => LuaMatrix::new(mat).into_lua(lua),
+    }
+}
+
+/// Returns the Array of an operand, or an error if it is not an Array.
+fn expect_array(operand: Operand) -> LuaResult<Array> {
+    match operand {
+        Operand::Array(arr) => Ok(arr),
+        _ => Err(LuaError::RuntimeError("expected an Array".to_string())),
+    }
+}
+
+/// Returns the Matrix of an operand, or an error if it is not a Matrix.
+fn expect_matrix(operand: Operand) -> LuaResult<Matrix> {
+    match operand {
+        Operand::Matrix(mat) => Ok(mat),
+        _ => Err(LuaError::RuntimeError("expected a Matrix".to_string())),
+    }
+}
+
+/// Returns an error if two Arrays cannot be combined element by element.
+fn check_lengths(a: &Array, b: &Array) -> LuaResult<()> {
+    if a.len() != b.len() {
+        return Err(LuaError::RuntimeError(format!(
+            "Arrays have different lengths: {} and {}",
+            a.len(),
+            b.len()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Returns an error if two matrices cannot be combined element by element.
+fn check_dimensions(a: &Matrix, b: &Matrix) -> LuaResult<()> {
+    if a.dimensions() != b.dimensions() {
+        return Err(LuaError::RuntimeError(format!(
+            "Matrices have different dimensions: {:?} and {:?}",
+            a.dimensions(),
+            b.dimensions()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Returns the matrix with `f` applied to every element.
+fn map_matrix(mat: &Matrix, f: impl Fn(f64) -> f64) -> Matrix {
+    Matrix::from_rows(
+        mat.iter()
+            .map(|row| Array::from_vec(row.iter().map(|&x| f(x)).collect()))
+            .collect(),
+    )
+}
+
+/// Adds two operands.
+fn add(a: Operand, b: Operand) -> LuaResult<Operand> {
+    match (a, b) {
+        (Operand::Array(a), Operand::Array(b)) => {
+            check_lengths(&a, &b)?;
+            Ok(Operand::Array(a.plus(&b)))
+        }
+        (Operand::Array(a), Operand::Scalar(s)) | (Operand::Scalar(s), Operand::Array(a)) => {
+            Ok(Operand::Array(a.scalar_add(s)))
+        }
+        (Operand::Matrix(a), Operand::Matrix(b)) => {
+            check_dimensions(&a, &b)?;
+            Ok(Operand::Matrix(a.plus(&b)))
+        }
+        (Operand::Matrix(a), Operand::Scalar(s)) | (Operand::Scalar(s), Operand::Matrix(a)) => {
+            Ok(Operand::Matrix(map_matrix(&a, |x| x + s)))
+        }
+        _ => Err(LuaError::RuntimeError(
+            "attempt to add incompatible operands".to_string(),
+        )),
+    }
+}
+
+/// Subtracts two operands.
+fn sub(a: Operand, b: Operand) -> LuaResult<Operand> {
+    match (a, b) {
+        (Operand::Array(a), Operand::Array(b)) => {
+            check_lengths(&a, &b)?;
+            Ok(Operand::Array(a.minus(&b)))
+        }
+        (Operand::Array(a), Operand::Scalar(s)) => Ok(Operand::Array(a.scalar_sub(s))),
+        (Operand::Scalar(s), Operand::Array(a)) => {
+            let mut result = a;
+            for val in result.iter_mut() {
+                *val = s - *val;
+            }
+            Ok(Operand::Array(result))
+        }
+        (Operand::Matrix(a), Operand::Matrix(b)) => {
+            check_dimensions(&a, &b)?;
+            Ok(Operand::Matrix(a.minus(&b)))
+        }
+        (Operand::Matrix(a), Operand::Scalar(s)) => Ok(Operand::Matrix(map_matrix(&a, |x| x - s))),
+        (Operand::Scalar(s), Operand::Matrix(a)) => Ok(Operand::Matrix(map_matrix(&a, |x| s - x))),
+        _ => Err(LuaError::RuntimeError(
+            "attempt to subtract incompatible operands".to_string(),
+        )),
+    }
+}
+
+/// Multiplies two operands: the matrix product if a matrix is involved, and element by element
+/// if one operand is a number.
+fn mul(a: Operand, b: Operand) -> LuaResult<Operand> {
+    match (a, b) {
+        (Operand::Scalar(x), Operand::Scalar(y)) => Ok(Operand::Scalar(x * y)),
+        (Operand::Array(a), Operand::Scalar(s)) | (Operand::Scalar(s), Operand::Array(a)) => {
+            Ok(Operand::Array(a.scalar_mult(s)))
+        }
+        (Operand::Matrix(a), Operand::Scalar(s)) | (Operand::Scalar(s), Operand::Matrix(a)) => {
+            Ok(Operand::Matrix(a.scalar(s)))
+        }
+        (Operand::Matrix(a), Operand::Matrix(b)) => {
+            let ((rows, cols), (other_rows, other_cols)) = (a.dimensions(), b.dimensions());
+            if cols != other_rows {
+                return Err(LuaError::RuntimeError(format!(
+                    "cannot multiply a {}x{} matrix with a {}x{} matrix",
+                    rows, cols, other_rows, other_cols
+                )));
+            }
+            Ok(Operand::Matrix(a.mult(&b)))
+        }
+        (Operand::Matrix(a), Operand::Array(x)) => {
+            let (rows, cols) = a.dimensions();
+            if cols != x.len() {
+                return Err(LuaError::RuntimeError(format!(
+                    "cannot multiply a {}x{} matrix with an Array of length {}",
+                    rows,
+                    cols,
+                    x.len()
+                )));
+            }
+            Ok(Operand::Array(Array::from_vec(
+                a.iter().map(|row| row.dotp(&x)).collect(),
+            )))
+        }
+        (Operand::Array(x), Operand::Matrix(a)) => {
+            let (rows, cols) = a.dimensions();
+            if rows != x.len() {
+                return Err(LuaError::RuntimeError(format!(
+                    "cannot multiply an Array of length {} with a {}x{} matrix",
+                    x.len(),
+                    rows,
+                    cols
+                )));
+            }
+            Ok(Operand::Array(Array::from_vec(
+                (0..cols)
+                    .map(|j| (0..rows).map(|i| x[i] * a[i][j]).sum())
+                    .collect(),
+            )))
+        }
+        (Operand::Array(_), Operand::Array(_)) => Err(LuaError::RuntimeError(
+            "cannot multiply two Arrays, use `mult` for element-wise multiplication or `dotp`"
+                .to_string(),
+        )),
+    }
+}
+
+/// Converts a 1-based Lua index into a 0-based index.
+fn to_index(index: i64, len: usize) -> LuaResult<usize> {
+    if index < 1 || index as usize > len {
+        return Err(LuaError::RuntimeError(format!(
+            "index {} out of bounds for length {}",
+            index, len
+        )));
+    }
+
+    Ok(index as usize - 1)
+}
+
+/// Creates a Matrix from a Lua sequence of rows.
+fn matrix_from_table(rows: Vec<Vec<f64>>) -> LuaResult<Matrix> {
+    let cols = rows.first().map_or(0, |row| row.len());
+
+    if cols == 0 {
+        return Err(LuaError::RuntimeError(
+            "a matrix must have at least one row and one column".to_string(),
+        ));
+    }
+
+    if rows.iter().any(|row| row.len() != cols) {
+        return Err(LuaError::RuntimeError(
+            "all rows of a matrix must have the same length".to_string(),
+        ));
+    }
+
+    Ok(Matrix::from_rows(
+        rows.into_iter().map(Array::from_vec).collect(),
+    ))
+}
+
+/// Adds the methods and metamethods of Arrays, which rows of matrices share.
+fn add_array_methods<'lua, T, M>(methods: &mut M)
+where
+    T: ArrayView + UserData + 'static,
+    M: UserDataMethods<'lua, T>,
+{
+    methods.add_method("sum", |_, this, ()| Ok(this.with(Array::sum)));
+    methods.add_method("average", |_, this, ()| Ok(this.with(Array::average)));
+    methods.add_method("norm", |_, this, ()| Ok(this.with(Array::norm)));
+    methods.add_method("dotp", |_, this, other: Operand| {
+        let other = expect_array(other)?;
+        this.with(|arr| {
+            check_lengths(arr, &other)?;
+            Ok(arr.dotp(&other))
+        })
+    });
+    methods.add_method("mult", |_, this, other: Operand| {
+        let other = expect_array(other)?;
+        this.with(|arr| {
+            check_lengths(arr, &other)?;
+            Ok(LuaArray(arr.mult(&other)))
+        })
+    });
+    methods.add_method("concat", |_, this, other: Operand| {
+        let other = expect_array(other)?;
+        Ok(LuaArray(this.with(|arr| arr.concat(&other))))
+    });
+    methods.add_method("copy", |_, this, ()| Ok(LuaArray(this.with(Array::clone))));
+    methods.add_method("totable", |_, this, ()| Ok(this.with(|arr| arr.to_vec())));
+
+    methods.add_meta_function(MetaMethod::Add, |lua, (a, b): (Operand, Operand)| {
+        into_value(lua, add(a, b)?)
+    });
+    methods.add_meta_function(MetaMethod::Sub, |lua, (a, b): (Operand, Operand)| {
+        into_value(lua, sub(a, b)?)
+    });
+    methods.add_meta_function(MetaMethod::Mul, |lua, (a, b): (Operand, Operand)| {
+        into_value(lua, mul(a, b)?)
+    });
+    methods.add_meta_method(MetaMethod::Unm, |_, this, ()| {
+        Ok(LuaArray(this.with(|arr| arr.scalar_mult(-1.0))))
+    });
+    methods.add_meta_method(MetaMethod::Index, |_, this, index: Value| match index {
+        Value::Integer(i) => this.with(|arr| Ok(Some(arr[to_index(i, arr.len())?]))),
+        _ => Ok(None),
+    });
+    methods.add_meta_method_mut(MetaMethod::NewIndex, |_, this, (index, val): (i64, f64)| {
+        let i = to_index(index, this.with(Array::len))?;
+        this.set(i, val);
+        Ok(())
+    });
+    methods.add_meta_method(MetaMethod::Len, |_, this, ()| Ok(this.with(Array::len)));
+    methods.add_meta_method(MetaMethod::ToString, |_, this, ()| {
+        Ok(this.with(Array::to_string))
+    });
+}
+
+impl UserData for LuaArray {
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        add_array_methods(methods);
+    }
+}
+
+impl UserData for LuaRow {
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        add_array_methods(methods);
+    }
+}
+
+impl UserData for LuaMatrix {
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method("dimensions", |_, this, ()| Ok(this.0.borrow().dimensions()));
+        methods.add_method("get", |_, this, (i, j): (i64, i64)| {
+            let mat = this.0.borrow();
+            let (rows, cols) = mat.dimensions();
+            Ok(mat.get(to_index(i, rows)?, to_index(j, cols)?))
+        });
+        methods.add_method("set", |_, this, (val, i, j): (f64, i64, i64)| {
+            let mut mat = this.0.borrow_mut();
+            let (rows, cols) = mat.dimensions();
+            mat.set(val, to_index(i, rows)?, to_index(j, cols)?);
+            Ok(())
+        });
+        methods.add_method("transpose", |_, this, ()| {
+            Ok(LuaMatrix::new(this.0.borrow().transpose()))
+        });
+        methods.add_method("mult", |_, this, other: Operand| {
+            let other = Operand::Matrix(expect_matrix(other)?);
+            let product = mul(Operand::Matrix(this.0.borrow().clone()), other)?;
+            Ok(LuaMatrix::new(expect_matrix(product)?))
+        });
+        methods.add_method("elem_mult", |_, this, other: Operand| {
+            let other = expect_matrix(other)?;
+            let mat = this.0.borrow();
+            check_dimensions(&mat, &other)?;
+            Ok(LuaMatrix::new(mat.elem_mult(&other)))
+        });
+        methods.add_method("copy", |_, this, ()| {
+            Ok(LuaMatrix::new(this.0.borrow().clone()))
+        });
+        methods.add_method("totable", |_, this, ()| {
+            Ok(this
+                .0
+                .borrow()
+                .iter()
+                .map(|row| row.to_vec())
+                .collect::<Vec<_>>())
+        });
+
+        methods.add_meta_function(MetaMethod::Add, |lua, (a, b): (Operand, Operand)| {
+            into_value(lua, add(a, b)?)
+        });
+        methods.add_meta_function(MetaMethod::Sub, |lua, (a, b): (Operand, Operand)| {
+            into_value(lua, sub(a, b)?)
+        });
+        methods.add_meta_function(MetaMethod::Mul, |lua, (a, b): (Operand, Operand)| {
+            into_value(lua, mul(a, b)?)
+        });
+        methods.add_meta_method(MetaMethod::Unm, |_, this, ()| {
+            Ok(LuaMatrix::new(this.0.borrow().scalar(-1.0)))
+        });
+        methods.add_meta_method(MetaMethod::Index, |_, this, index: Value| match index {
+            Value::Integer(i) => {
+                let row = to_index(i, this.0.borrow().dimensions().0)?;
+                Ok(Some(this.row(row)))
+            }
+            _ => Ok(None),
+        });
+        methods.add_meta_method(
+            MetaMethod::NewIndex,
+            |_, this, (index, row): (i64, Operand)| {
+                let row = expect_array(row)?;
+                let mut mat = this.0.borrow_mut();
+                let (rows, cols) = mat.dimensions();
+                let i = to_index(index, rows)?;
+                check_lengths(&row, &Array::zeros(cols))?;
+                mat.row_mut(i).copy_from_slice(&row);
+                Ok(())
+            },
+        );
+        methods.add_meta_method(MetaMethod::Len, |_, this, ()| {
+            Ok(this.0.borrow().dimensions().0)
+        });
+        methods.add_meta_method(MetaMethod::ToString, |_, this, ()| {
+            Ok(this.0.borrow().to_string())
+        });
+    }
+}
+
+/// Entrypoint of the Lua module, called by `require("moonalloy")`.
+#[mlua::lua_module(name = "moonalloy")]
+fn open(lua: &Lua) -> LuaResult<LuaTable<'_>> {
+    let exports = lua.create_table()?;
+
+    exports.set(
+        "array",
+        lua.create_function(|_, values: Vec<f64>| Ok(LuaArray(Array::from_vec(values))))?,
+    )?;
+    exports.set(
+        "zeros",
+        lua.create_function(|_, len: usize| Ok(LuaArray(Array::from_vec(vec![0.0; len]))))?,
+    )?;
+    exports.set(
+        "ones",
+        lua.create_function(|_, len: usize| Ok(LuaArray(Array::from_vec(vec![1.0; len]))))?,
+    )?;
+    exports.set(
+        "matrix",
+        lua.create_function(|_, rows: Vec<Vec<f64>>| Ok(LuaMatrix::new(matrix_from_table(rows)?)))?,
+    )?;
+    exports.set(
+        "matrix_zeros",
+        lua.create_function(|_, (rows, cols): (usize, usize)| {
+            Ok(LuaMatrix::new(matrix_from_table(vec![
+                vec![0.0; cols];
+                rows
+            ])?))
+        })?,
+    )?;
+    exports.set(
+        "matrix_ones",
+        lua.create_function(|_, (rows, cols): (usize, usize)| {
+            Ok(LuaMatrix::new(matrix_from_table(vec![
+                vec![1.0; cols];
+                rows
+            ])?))
+        })?,
+    )?;
+    exports.set(
+        "identity",
+        lua.create_function(|_, len: usize| {
+            let mut rows = vec![vec![0.0; len]; len];
+            for (i, row) in rows.iter_mut().enumerate() {
+                row[i] = 1.0;
+            }
+            Ok(LuaMatrix::new(matrix_from_table(rows)?))
+        })?,
+    )?;
+    exports.set(
+        "gauss",
+        lua.create_function(|_, (a, b): (AnyUserData, Operand)| {
+            let a = a.borrow::<LuaMatrix>()?;
+            let a = a.0.borrow();
+            let b = expect_array(b)?;
+            let (rows, cols) = a.dimensions();
+            if rows != cols || rows != b.len() || rows == 0 {
+                return Err(LuaError::RuntimeError(
+                    "gauss expects an n x n matrix and an array of length n".to_string(),
+                ));
+            }
+            Ok(LuaArray(gauss_elimination(a.clone(), b)))
+        })?,
+    )?;
+    Ok(exports)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn array(values: &[f64]) -> Operand {
+        Operand::Array(Array::from(values))
+    }
+
+    fn matrix(rows: &[&[f64]]) -> Operand {
+        Operand::Matrix(matrix_from_table(rows.iter().map(|row| row.to_vec()).collect()).unwrap())
+    }
+
+    fn assert_array(expected: &[f64], operand: LuaResult<Operand>) {
+        assert_eq!(
+            Array::from(expected),
+            expect_array(operand.unwrap()).unwrap()
+        );
+    }
+
+    fn assert_matrix(expected: &[&[f64]], operand: LuaResult<Operand>) {
+        let mat = expect_matrix(operand.unwrap()).unwrap();
+        let rows: Vec<Vec<f64>> = mat.iter().map(|row| row.to_vec()).collect();
+        assert_eq!(expected, rows.iter().map(Vec::as_slice).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_array_arithmetic() {
+        assert_array(&[5.0, 7.0], add(array(&[1.0, 2.0]), array(&[4.0, 5.0])));
+        assert_array(&[3.0, 4.0], add(Operand::Scalar(2.0), array(&[1.0, 2.0])));
+        assert_array(&[-3.0, -3.0], sub(array(&[1.0, 2.0]), array(&[4.0, 5.0])));
+        assert_array(&[1.0, 0.0], sub(array(&[3.0, 2.0]), Operand::Scalar(2.0)));
+        assert_array(&[1.0, 0.0], sub(Operand::Scalar(2.0), array(&[1.0, 2.0])));
+        assert_array(&[2.0, 4.0], mul(array(&[1.0, 2.0]), Operand::Scalar(2.0)));
+    }
+
+    #[test]
+    fn test_matrix_arithmetic() {
+        let a: &[&[f64]] = &[&[1.0, 2.0], &[3.0, 4.0]];
+        assert_matrix(&[&[2.0, 4.0], &[6.0, 8.0]], add(matrix(a), matrix(a)));
+        assert_matrix(
+            &[&[2.0, 3.0], &[4.0, 5.0]],
+            add(matrix(a), Operand::Scalar(1.0)),
+        );
+        assert_matrix(
+            &[&[0.0, 1.0], &[2.0, 3.0]],
+            sub(matrix(a), Operand::Scalar(1.0)),
+        );
+        assert_matrix(
+            &[&[0.0, -1.0], &[-2.0, -3.0]],
+            sub(Operand::Scalar(1.0), matrix(a)),
+        );
+        assert_matrix(
+            &[&[-1.0, -2.0], &[-3.0, -4.0]],
+            mul(Operand::Scalar(-1.0), matrix(a)),
+        );
+    }
+
+    #[test]
+    fn test_products() {
+        let a: &[&[f64]] = &[&[1.0, 2.0], &[3.0, 4.0]];
+        assert_matrix(&[&[7.0, 10.0], &[15.0, 22.0]], mul(matrix(a), matrix(a)));
+        assert_matrix(
+            &[&[1.0, 2.0, 3.0], &[3.0, 4.0, 7.0]],
+            mul(matrix(a), matrix(&[&[1.0, 0.0, 1.0], &[0.0, 1.0, 1.0]])),
+        );
+        assert_array(&[5.0, 11.0], mul(matrix(a), array(&[1.0, 2.0])));
+        assert_array(&[7.0, 10.0], mul(array(&[1.0, 2.0]), matrix(a)));
+    }
+
+    #[test]
+    fn test_row_views() {
+        let mat = LuaMatrix::new(Matrix::zeros(2, 2));
+        let mut row = mat.row(1);
+        row.set(0, 5.0);
+        assert_eq!(5.0, mat.0.borrow()[1][0]);
+
+        mat.0.borrow_mut().set(3.0, 1, 1);
+        assert_eq!(Array::from(&[5.0, 3.0]), row.with(Array::clone));
+    }
+
+    #[test]
+    fn test_errors() {
+        let a: &[&[f64]] = &[&[1.0, 2.0], &[3.0, 4.0]];
+        assert!(add(array(&[1.0]), array(&[1.0, 2.0])).is_err());
+        assert!(add(array(&[1.0, 2.0]), matrix(a)).is_err());
+        assert!(sub(matrix(a), matrix(&[&[1.0, 2.0]])).is_err());
+        assert!(mul(array(&[1.0, 2.0]), array(&[1.0, 2.0])).is_err());
+        assert!(mul(matrix(a), matrix(&[&[1.0, 2.0]])).is_err());
+        assert!(mul(matrix(a), array(&[1.0])).is_err());
+        assert!(mul(array(&[1.0, 2.0, 3.0]), matrix(a)).is_err());
+        assert!(expect_matrix(array(&[1.0])).is_err());
+    }
+
+    #[test]
+    fn test_indices() {
+        assert_eq!(0, to_index(1, 3).unwrap());
+        assert_eq!(2, to_index(3, 3).unwrap());
+        assert!(to_index(0, 3).is_err());
+        assert!(to_index(4, 3).is_err());
+        assert!(to_index(-1, 3).is_err());
+
+        assert!(matrix_from_table(vec![]).is_err());
+        assert!(matrix_from_table(vec![vec![]]).is_err());
+        assert!(matrix_from_table(vec![vec![1.0, 2.0], vec![3.0]]).is_err());
+    }
+}
@@ -0,0 +1,1247 @@
+//! Gradient Boosting - Additive ensembles of small regression trees
+//!
+//! Gradient boosting fits a sequence of small regression trees, each to the gradient of the
+//! loss of the predictions so far, and adds it to the predictions with a small weight, the
+//! learning rate:
+//!
+//! * `GradientBoostingRegressor` minimizes the squared, absolute or Huber loss.
+//! * `GradientBoostingClassifier` minimizes the log-loss, with one tree per round for two
+//!   classes and one tree per class and round otherwise.
+//!
+//! The trees are grown on histograms: every feature is binned once into at most 256 bins by its
+//! quantiles, and a split is chosen from the sums of the gradients and Hessians per bin rather
+//! than by sorting the samples of every node. The histogram of the larger child of a split is
+//! the histogram of its parent minus that of the smaller child, so growing a tree costs about
+//! one pass over the samples per level.
+//!
+//! Every round can use a random fraction of the samples and of the features, and training can
+//! stop once the loss on a validation set stops improving.
+
+use crate::linalg::array::Array;
+use crate::linalg::matrix::Matrix;
+use crate::machine_learning::model::{
+    check_features, check_targets, encode_classes, Classifier, Estimator, ModelError, Predictor,
+    Result,
+};
+use crate::machine_learning::optimizer::EarlyStopping;
+use crate::random::pcg::Pcg64;
+use crate::random::{choice, Rng};
+
+/// The smallest sum of Hessians of a child of a split.
+const MIN_HESSIAN: f64 = 1e-3;
+
+/// The number of samples the bins of large datasets are computed from.
+const BINNING_SAMPLES: usize = 200_000;
+
+/// The loss minimized by `GradientBoostingRegressor`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RegressionLoss {
+    /// Half the squared error. This is the default.
+    #[default]
+    Squared,
+    /// The absolute error, which is robust to outliers.
+    Absolute,
+    /// The squared error up to the given absolute error and the absolute error beyond.
+    Huber(f64),
+}
+
+/// The controls of gradient boosting.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoostingOptions {
+    /// The maximum number of boosting rounds.
+    pub rounds: usize,
+    /// The weight of every tree, which shrinks its contribution.
+    pub learning_rate: f64,
+    /// The maximum depth of a leaf.
+    pub max_depth: usize,
+    /// The minimum number of samples of a leaf.
+    pub min_samples_leaf: usize,
+    /// The L2 penalty on the leaf values.
+    pub l2_regularization: f64,
+    /// The maximum number of bins of a feature, at most 256.
+    pub max_bins: usize,
+    /// The fraction of the samples every round is fitted to.
+    pub subsample: f64,
+    /// The fraction of the features every round can split on.
+    pub colsample: f64,
+    /// The patience and minimum improvement of early stopping, or `None` to boost for all
+    /// rounds. The validation loss is monitored if there is a validation set, and the training
+    /// loss otherwise.
+    pub early_stopping: Option<(usize, f64)>,
+    /// The seed of the subsamples.
+    pub seed: u64,
+}
+
+impl Default for BoostingOptions {
+    fn default() -> Self {
+        BoostingOptions {
+            rounds: 100,
+            learning_rate: 0.1,
+            max_depth: 3,
+            min_samples_leaf: 1,
+            l2_regularization: 0.0,
+            max_bins: 256,
+            subsample: 1.0,
+            colsample: 1.0,
+            early_stopping: None,
+            seed: 0,
+        }
+    }
+}
+
+impl BoostingOptions {
+    /// Panics unless the options are valid.
+    fn check(&self, name: &str) {
+        assert!(
+            self.rounds > 0 && self.max_depth > 0 && self.min_samples_leaf > 0,
+            "ERROR - {}: rounds, max_depth and min_samples_leaf must be positive.",
+            name
+        );
+        assert!(
+            self.learning_rate > 0.0 && self.l2_regularization >= 0.0,
+            "ERROR - {}: The learning rate must be positive and the L2 penalty non-negative.",
+            name
+        );
+        assert!(
+            (2..=256).contains(&self.max_bins),
+            "ERROR - {}: max_bins must be between 2 and 256.",
+            name
+        );
+        assert!(
+            self.subsample > 0.0
+                && self.subsample <= 1.0
+                && self.colsample > 0.0
+                && self.colsample <= 1.0,
+            "ERROR - {}: subsample and colsample must be in (0, 1].",
+            name
+        );
+    }
+}
+
+/// The loss of the raw predictions, the scores, of a model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Objective {
+    Regression(RegressionLoss),
+    /// The log-loss of the log-odds of the second of two classes.
+    Binomial,
+    /// The log-loss of the softmax of a score per class.
+    Multinomial(usize),
+}
+
+impl Objective {
+    /// Returns the number of scores of a sample.
+    fn outputs(&self) -> usize {
+        match self {
+            Objective::Multinomial(classes) => *classes,
+            _ => 1,
+        }
+    }
+
+    /// Returns the constant scores that minimize the loss of the targets.
+    fn initial(&self, ys: &Array) -> Vec<f64> {
+        match self {
+            Objective::Regression(RegressionLoss::Squared) => vec![ys.average()],
+            Objective::Regression(_) => vec![ys.median()],
+            Objective::Binomial => {
+                let p = ys.average().clamp(1e-12, 1.0 - 1e-12);
+                vec![(p / (1.0 - p)).ln()]
+            }
+            Objective::Multinomial(classes) => {
+                let mut counts = vec![0.0; *classes];
+                for &y in ys.iter() {
+                    counts[y as usize] += 1.0;
+                }
+                counts
+                    .iter()
+                    .map(|c| (c / ys.len() as f64).max(1e-12).ln())
+                    .collect()
+            }
+        }
+    }
+
+    /// Writes the gradient and Hessian of the loss with respect to every score of a sample.
+    fn gradients(&self, y: f64, scores: &[f64], out: &mut [(f64, f64)]) {
+        match self {
+            Objective::Regression(loss) => {
+                let residual = y - scores[0];
+                out[0] = match loss {
+                    RegressionLoss::Squared => (-residual, 1.0),
+                    RegressionLoss::Absolute => (-residual.signum(), 1.0),
+                    RegressionLoss::Huber(delta) => (-residual.clamp(-delta, *delta), 1.0),
+                };
+            }
+            Objective::Binomial => {
+                let p = sigmoid(scores[0]);
+                out[0] = (p - y, (p * (1.0 - p)).max(1e-16));
+            }
+            Objective::Multinomial(_) => {
+                let p = softmax(scores);
+                for (k, (o, pk)) in out.iter_mut().zip(p).enumerate() {
+                    let target = if y as usize == k { 1.0 } else { 0.0 };
+                    *o = (pk - target, (pk * (1.0 - pk)).max(1e-16));
+                }
+            }
+        }
+    }
+
+    /// Returns the loss of the scores of a sample.
+    fn loss(&self, y: f64, scores: &[f64]) -> f64 {
+        match self {
+            Objective::Regression(loss) => {
+                let residual = (y - scores[0]).abs();
+                match loss {
+                    RegressionLoss::Squared => 0.5 * residual * residual,
+                    RegressionLoss::Absolute => residual,
+                    RegressionLoss::Huber(delta) if residual <= *delta => 0.5 * residual * residual,
+                    RegressionLoss::Huber(delta) => delta * (residual - 0.5 * delta),
+                }
+            }
+            Objective::Binomial => softplus(scores[0]) - y * scores[0],
+            Objective::Multinomial(_) => {
+                let max = scores.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+                let log_sum = max + scores.iter().map(|s| (s - max).exp()).sum::<f64>().ln();
+                log_sum - scores[y as usize]
+            }
+        }
+    }
+
+    /// Returns the value of a leaf with the samples, before the learning rate: a Newton step
+    /// for the smooth losses, and the minimizer of the loss of the residuals otherwise. `score`
+    /// returns the current score of a sample.
+    fn leaf(
+        &self,
+        samples: &[usize],
+        ys: &Array,
+        score: &dyn Fn(usize) -> f64,
+        g: f64,
+        h: f64,
+        l2: f64,
+    ) -> f64 {
+        match self {
+            Objective::Regression(RegressionLoss::Absolute) => {
+                Array::from_vec(samples.iter().map(|&i| ys[i] - score(i)).collect()).median()
+            }
+            Objective::Regression(RegressionLoss::Huber(delta)) => {
+                // One step of Friedman's M-estimate from the median.
+                let residuals: Vec<f64> = samples.iter().map(|&i| ys[i] - score(i)).collect();
+                let median = Array::from(&residuals).median();
+                let correction: f64 = residuals
+                    .iter()
+                    .map(|r| (r - median).clamp(-delta, *delta))
+                    .sum();
+                median + correction / residuals.len() as f64
+            }
+            Objective::Multinomial(classes) => {
+                let k = *classes as f64;
+                -(k - 1.0) / k * g / (h + l2)
+            }
+            _ => -g / (h + l2),
+        }
+    }
+}
+
+fn sigmoid(z: f64) -> f64 {
+    1.0 / (1.0 + (-z).exp())
+}
+
+/// Returns `ln(1 + e^z)` without overflow.
+fn softplus(z: f64) -> f64 {
+    if z > 0.0 {
+        z + (-z).exp().ln_1p()
+    } else {
+        z.exp().ln_1p()
+    }
+}
+
+fn softmax(scores: &[f64]) -> Vec<f64> {
+    let max = scores.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+    let exps: Vec<f64> = scores.iter().map(|s| (s - max).exp()).collect();
+    let total: f64 = exps.iter().sum();
+    exps.iter().map(|e| e / total).collect()
+}
+
+/// The features binned by their quantiles. A value is in bin `b` of a feature if it is at most
+/// the `b`-th edge and greater than the previous one, and in the last bin if it is greater than
+/// every edge.
+#[derive(Debug, Clone, PartialEq)]
+struct Binned {
+    edges: Vec<Vec<f64>>,
+    bins: Vec<Vec<u8>>,
+}
+
+impl Binned {
+    fn new(xs: &Matrix, max_bins: usize, rng: &mut dyn Rng) -> Binned {
+        let (rows, cols) = xs.dimensions();
+        let sample: Vec<usize> = if rows > BINNING_SAMPLES {
+            choice(&(0..rows).collect::<Vec<_>>(), BINNING_SAMPLES, false, rng)
+        } else {
+            (0..rows).collect()
+        };
+
+        let mut edges = Vec::with_capacity(cols);
+        let mut bins = Vec::with_capacity(cols);
+        for j in 0..cols {
+            let mut values: Vec<f64> = sample.iter().map(|&i| xs[i][j]).collect();
+            values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let mut distinct = values.clone();
+            distinct.dedup();
+
+            let feature_edges: Vec<f64> = if distinct.len() <= max_bins {
+                // The midpoint can round up to the larger value, or be NaN or infinite next to an
+                // infinite value.
+                distinct
+                    .windows(2)
+                    .map(|w| {
+                        let middle = w[0] + (w[1] - w[0]) / 2.0;
+                        if middle < w[1] {
+                            middle
+                        } else {
+                            w[0]
+                        }
+                    })
+                    .collect()
+            } else {
+                let mut quantiles: Vec<f64> = (1..max_bins)
+                    .map(|b| values[b * values.len() / max_bins])
+                    .collect();
+                quantiles.dedup();
+                // The largest value needs a bin of its own.
+                quantiles.retain(|&q| q < distinct[distinct.len() - 1]);
+                quantiles
+            };
+
+            bins.push(
+                (0..rows)
+                    .map(|i| feature_edges.partition_point(|&e| e < xs[i][j]) as u8)
+                    .collect(),
+            );
+            edges.push(feature_edges);
+        }
+        Binned { edges, bins }
+    }
+}
+
+/// The sums of the gradients, Hessians and number of samples in a bin.
+#[derive(Debug, Clone, Copy, Default)]
+struct Bin {
+    g: f64,
+    h: f64,
+    n: f64,
+}
+
+/// The bins of every feature, which are empty for features the tree cannot split on.
+type Histogram = Vec<Vec<Bin>>;
+
+/// A node of a boosted tree, which is either a leaf with its contribution to the score or a
+/// split that sends a sample left if its feature is at most the threshold.
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Leaf(f64),
+    Split {
+        feature: usize,
+        threshold: f64,
+        gain: f64,
+        left: usize,
+        right: usize,
+    },
+}
+
+/// A boosted tree, whose root is its first node.
+#[derive(Debug, Clone, PartialEq)]
+struct BoostedTree {
+    nodes: Vec<Node>,
+}
+
+impl BoostedTree {
+    fn predict(&self, x: &[f64]) -> f64 {
+        let mut node = 0;
+        loop {
+            match &self.nodes[node] {
+                Node::Leaf(value) => return *value,
+                Node::Split {
+                    feature,
+                    threshold,
+                    left,
+                    right,
+                    ..
+                } => {
+                    node = if x[*feature] <= *threshold {
+                        *left
+                    } else {
+                        *right
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Grows a boosted tree for one score from histograms.
+struct Grower<'a> {
+    binned: &'a Binned,
+    features: &'a [usize],
+    gradients: &'a [(f64, f64)],
+    objective: Objective,
+    options: &'a BoostingOptions,
+    ys: &'a Array,
+    scores: &'a [f64],
+    output: usize,
+    nodes: Vec<Node>,
+}
+
+impl Grower<'_> {
+    fn histogram(&self, samples: &[usize]) -> Histogram {
+        let mut histogram: Histogram = vec![Vec::new(); self.binned.edges.len()];
+        for &f in self.features {
+            let mut bins = vec![Bin::default(); self.binned.edges[f].len() + 1];
+            let column = &self.binned.bins[f];
+            for &i in samples {
+                let (g, h) = self.gradients[i];
+                let bin = &mut bins[column[i] as usize];
+                bin.g += g;
+                bin.h += h;
+                bin.n += 1.0;
+            }
+            histogram[f] = bins;
+        }
+        histogram
+    }
+
+    /// Adds the node of the samples and its descendants, and returns its index.
+    fn node(&mut self, samples: Vec<usize>, histogram: Histogram, depth: usize) -> usize {
+        let (g, h) = samples.iter().fold((0.0, 0.0), |(g, h), &i| {
+            (g + self.gradients[i].0, h + self.gradients[i].1)
+        });
+        let index = self.nodes.len();
+        self.nodes.push(Node::Leaf(0.0));
+
+        if depth < self.options.max_depth && samples.len() >= 2 * self.options.min_samples_leaf {
+            if let Some((feature, bin, gain)) = self.best_split(&histogram, g, h, samples.len()) {
+                let column = &self.binned.bins[feature];
+                let (left, right): (Vec<usize>, Vec<usize>) = samples
+                    .into_iter()
+                    .partition(|&i| column[i] as usize <= bin);
+
+                // Only the histogram of the smaller child is computed from its samples.
+                let left_smaller = left.len() <= right.len();
+                let small = self.histogram(if left_smaller { &left } else { &right });
+                let large = subtract(&histogram, &small);
+                drop(histogram);
+                let (left_histogram, right_histogram) = if left_smaller {
+                    (small, large)
+                } else {
+                    (large, small)
+                };
+
+                let left = self.node(left, left_histogram, depth + 1);
+                let right = self.node(right, right_histogram, depth + 1);
+                self.nodes[index] = Node::Split {
+                    feature,
+                    threshold: self.binned.edges[feature][bin],
+                    gain,
+                    left,
+                    right,
+                };
+                return index;
+            }
+        }
+
+        let (scores, outputs, output) = (self.scores, self.objective.outputs(), self.output);
+        let score = |i: usize| scores[i * outputs + output];
+        let value = self.objective.leaf(
+            &samples,
+            self.ys,
+            &score,
+            g,
+            h,
+            self.options.l2_regularization,
+        );
+        self.nodes[index] = Node::Leaf(self.options.learning_rate * value);
+        index
+    }
+
+    /// Returns the feature, the last bin on the left and the gain of the split with the largest
+    /// reduction of the regularized loss, or `None` if no split reduces it.
+    fn best_split(
+        &self,
+        histogram: &Histogram,
+        g: f64,
+        h: f64,
+        n: usize,
+    ) -> Option<(usize, usize, f64)> {
+        let l2 = self.options.l2_regularization;
+        let min_leaf = self.options.min_samples_leaf as f64;
+        let n = n as f64;
+        let parent = g * g / (h + l2);
+        let mut best: Option<(usize, usize, f64)> = None;
+
+        for &f in self.features {
+            let (mut gl, mut hl, mut nl) = (0.0, 0.0, 0.0);
+            for (bin, b) in histogram[f].iter().enumerate().take(histogram[f].len() - 1) {
+                gl += b.g;
+                hl += b.h;
+                nl += b.n;
+                if n - nl < min_leaf {
+                    break;
+                }
+                let (gr, hr) = (g - gl, h - hl);
+                if nl < min_leaf || hl < MIN_HESSIAN || hr < MIN_HESSIAN {
+                    continue;
+                }
+                let gain = gl * gl / (hl + l2) + gr * gr / (hr + l2) - parent;
+                if gain > best.map_or(1e-12 * parent.abs(), |(_, _, b)| b) {
+                    best = Some((f, bin, gain));
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Returns the histogram of the samples of a parent that are not in a child.
+fn subtract(parent: &Histogram, child: &Histogram) -> Histogram {
+    parent
+        .iter()
+        .zip(child)
+        .map(|(p, c)| {
+            p.iter()
+                .zip(c)
+                .map(|(a, b)| Bin {
+                    g: a.g - b.g,
+                    h: a.h - b.h,
+                    n: a.n - b.n,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// A fitted sequence of boosted trees.
+#[derive(Debug, Clone, PartialEq)]
+struct Ensemble {
+    objective: Objective,
+    initial: Vec<f64>,
+    /// The trees of every round, one for every score.
+    rounds: Vec<Vec<BoostedTree>>,
+    features: usize,
+    training_loss: Vec<f64>,
+    validation_loss: Vec<f64>,
+}
+
+impl Ensemble {
+    /// Returns the scores of every sample, with a row for every sample.
+    fn scores(&self, xs: &Matrix) -> Result<Vec<Vec<f64>>> {
+        check_features(xs, self.features)?;
+        Ok(xs
+            .iter()
+            .map(|row| {
+                let mut scores = self.initial.clone();
+                for trees in &self.rounds {
+                    for (s, tree) in scores.iter_mut().zip(trees) {
+                        *s += tree.predict(row);
+                    }
+                }
+                scores
+            })
+            .collect())
+    }
+
+    /// Returns the total split gain of every feature, relative to the total.
+    fn importances(&self) -> Array {
+        let mut gains = vec![0.0; self.features];
+        for tree in self.rounds.iter().flatten() {
+            for node in &tree.nodes {
+                if let Node::Split { feature, gain, .. } = node {
+                    gains[*feature] += gain;
+                }
+            }
+        }
+        let total: f64 = gains.iter().sum();
+        if total > 0.0 {
+            Array::from_vec(gains.iter().map(|g| g / total).collect())
+        } else {
+            Array::zeros(self.features)
+        }
+    }
+}
+
+/// Returns the ensemble boosted on the samples and targets, which are class indices for the
+/// log-losses.
+fn boost(
+    xs: &Matrix,
+    ys: &Array,
+    objective: Objective,
+    options: &BoostingOptions,
+    validation: Option<(&Matrix, &Array)>,
+) -> Ensemble {
+    let (rows, cols) = xs.dimensions();
+    let outputs = objective.outputs();
+    let mut rng = Pcg64::seed_from_u64(options.seed);
+    let binned = Binned::new(xs, options.max_bins, &mut rng);
+
+    let initial = objective.initial(ys);
+    let mut scores: Vec<f64> = initial
+        .iter()
+        .cycle()
+        .take(rows * outputs)
+        .copied()
+        .collect();
+    let mut validation_scores: Vec<f64> = match validation {
+        Some((vx, _)) => initial
+            .iter()
+            .cycle()
+            .take(vx.dimensions().0 * outputs)
+            .copied()
+            .collect(),
+        None => Vec::new(),
+    };
+    let mean_loss = |ys: &Array, scores: &[f64]| {
+        ys.iter()
+            .zip(scores.chunks(outputs))
+            .map(|(&y, s)| objective.loss(y, s))
+            .sum::<f64>()
+            / ys.len() as f64
+    };
+
+    let mut stopping = options
+        .early_stopping
+        .map(|(patience, min_delta)| EarlyStopping::new(patience, min_delta));
+    let all_samples: Vec<usize> = (0..rows).collect();
+    let all_features: Vec<usize> = (0..cols).collect();
+    let mut gradients = vec![vec![(0.0, 0.0); rows]; outputs];
+    let mut buffer = vec![(0.0, 0.0); outputs];
+    let mut ensemble = Ensemble {
+        objective,
+        initial,
+        rounds: Vec::with_capacity(options.rounds),
+        features: cols,
+        training_loss: Vec::with_capacity(options.rounds),
+        validation_loss: Vec::new(),
+    };
+
+    for _ in 0..options.rounds {
+        for i in 0..rows {
+            objective.gradients(ys[i], &scores[i * outputs..(i + 1) * outputs], &mut buffer);
+            for (k, &gh) in buffer.iter().enumerate() {
+                gradients[k][i] = gh;
+            }
+        }
+
+        let samples = if options.subsample < 1.0 {
+            let count = ((options.subsample * rows as f64).round() as usize).max(1);
+            choice(&all_samples, count, false, &mut rng)
+        } else {
+            all_samples.clone()
+        };
+        let features = if options.colsample < 1.0 {
+            let count = ((options.colsample * cols as f64).round() as usize).max(1);
+            let mut chosen = choice(&all_features, count, false, &mut rng);
+            chosen.sort_unstable();
+            chosen
+        } else {
+            all_features.clone()
+        };
+
+        let trees: Vec<BoostedTree> = (0..outputs)
+            .map(|output| {
+                let mut grower = Grower {
+                    binned: &binned,
+                    features: &features,
+                    gradients: &gradients[output],
+                    objective,
+                    options,
+                    ys,
+                    scores: &scores,
+                    output,
+                    nodes: Vec::new(),
+                };
+                let histogram = grower.histogram(&samples);
+                grower.node(samples.clone(), histogram, 0);
+                BoostedTree {
+                    nodes: grower.nodes,
+                }
+            })
+            .collect();
+
+        for (i, row) in xs.iter().enumerate() {
+            for (k, tree) in trees.iter().enumerate() {
+                scores[i * outputs + k] += tree.predict(row);
+            }
+        }
+        let training_loss = mean_loss(ys, &scores);
+        ensemble.training_loss.push(training_loss);
+
+        let monitored = match validation {
+            Some((vx, vy)) => {
+                for (i, row) in vx.iter().enumerate() {
+                    for (k, tree) in trees.iter().enumerate() {
+                        validation_scores[i * outputs + k] += tree.predict(row);
+                    }
+                }
+                let validation_loss = mean_loss(vy, &validation_scores);
+                ensemble.validation_loss.push(validation_loss);
+                validation_loss
+            }
+            None => training_loss,
+        };
+        ensemble.rounds.push(trees);
+
+        if let Some(stopping) = stopping.as_mut() {
+            if stopping.update(monitored) {
+                break;
+            }
+        }
+    }
+
+    if let Some(stopping) = stopping {
+        ensemble.rounds.truncate(stopping.best_epoch() + 1);
+    }
+    ensemble
+}
+
+/// Returns an error unless the validation samples have targets and the features of the
+/// training samples.
+fn check_validation(xs: &Matrix, validation_xs: &Matrix, validation_ys: &Array) -> Result<()> {
+    check_targets(validation_xs, validation_ys)?;
+    check_features(validation_xs, xs.dimensions().1)
+}
+
+/// Gradient boosted regression trees.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::boosting::{GradientBoostingRegressor, RegressionLoss};
+/// use moonalloy::machine_learning::model::Predictor;
+///
+/// let xs = Matrix::from_rows((0..100).map(|i| Array::from(&[i as f64 / 10.0])).collect());
+/// let ys = Array::from_vec(xs.iter().map(|row| row[0].sin()).collect());
+///
+/// let mut boosting = GradientBoostingRegressor::new(RegressionLoss::Squared);
+/// boosting.fit(&xs, &ys).unwrap();
+///
+/// assert!(boosting.score(&xs, &ys).unwrap() > 0.99);
+/// assert_eq!(100, boosting.get_rounds());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradientBoostingRegressor {
+    loss: RegressionLoss,
+    options: BoostingOptions,
+    ensemble: Option<Ensemble>,
+}
+
+impl GradientBoostingRegressor {
+    /// Returns an unfitted model that minimizes a loss.
+    ///
+    /// # Panics
+    ///
+    /// The threshold of the Huber loss must be positive, otherwise the code will panic.
+    pub fn new(loss: RegressionLoss) -> Self {
+        if let RegressionLoss::Huber(delta) = loss {
+            assert!(
+                delta > 0.0,
+                "ERROR - GradientBoostingRegressor: The Huber threshold must be positive."
+            );
+        }
+        GradientBoostingRegressor {
+            loss,
+            options: BoostingOptions::default(),
+            ensemble: None,
+        }
+    }
+
+    /// Sets the controls of the boosting.
+    ///
+    /// # Panics
+    ///
+    /// The options must be valid, see `BoostingOptions`, otherwise the code will panic.
+    pub fn set_options(&mut self, options: BoostingOptions) {
+        options.check("GradientBoostingRegressor");
+        self.options = options;
+    }
+
+    /// Fits the model to samples and their targets, and monitors the loss on a validation set
+    /// for early stopping.
+    pub fn fit_with_validation(
+        &mut self,
+        xs: &Matrix,
+        ys: &Array,
+        validation_xs: &Matrix,
+        validation_ys: &Array,
+    ) -> Result<()> {
+        check_targets(xs, ys)?;
+        check_validation(xs, validation_xs, validation_ys)?;
+        self.ensemble = Some(boost(
+            xs,
+            ys,
+            Objective::Regression(self.loss),
+            &self.options,
+            Some((validation_xs, validation_ys)),
+        ));
+        Ok(())
+    }
+
+    /// Returns the number of boosting rounds kept, which is fewer than `rounds` after early
+    /// stopping, or 0 if the model is not fitted.
+    pub fn get_rounds(&self) -> usize {
+        self.ensemble.as_ref().map_or(0, |e| e.rounds.len())
+    }
+
+    /// Returns the mean training loss after every round, including rounds dropped by early
+    /// stopping.
+    pub fn get_training_loss(&self) -> &[f64] {
+        self.ensemble.as_ref().map_or(&[], |e| &e.training_loss)
+    }
+
+    /// Returns the mean validation loss after every round, which is empty without a validation
+    /// set.
+    pub fn get_validation_loss(&self) -> &[f64] {
+        self.ensemble.as_ref().map_or(&[], |e| &e.validation_loss)
+    }
+
+    /// Returns the total split gain of every feature, relative to the total, which is empty if
+    /// the model is not fitted.
+    pub fn get_feature_importances(&self) -> Array {
+        self.ensemble
+            .as_ref()
+            .map_or_else(Array::new, Ensemble::importances)
+    }
+}
+
+impl Estimator for GradientBoostingRegressor {
+    fn is_fitted(&self) -> bool {
+        self.ensemble.is_some()
+    }
+}
+
+impl Predictor for GradientBoostingRegressor {
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        check_targets(xs, ys)?;
+        self.ensemble = Some(boost(
+            xs,
+            ys,
+            Objective::Regression(self.loss),
+            &self.options,
+            None,
+        ));
+        Ok(())
+    }
+
+    fn predict(&self, xs: &Matrix) -> Result<Array> {
+        let ensemble = self.ensemble.as_ref().ok_or(ModelError::NotFitted)?;
+        Ok(Array::from_vec(
+            ensemble.scores(xs)?.iter().map(|s| s[0]).collect(),
+        ))
+    }
+}
+
+/// Gradient boosted classification trees, which minimize the log-loss.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::boosting::GradientBoostingClassifier;
+/// use moonalloy::machine_learning::model::Classifier;
+///
+/// let xs = Matrix::from_rows(
+///     (0..60)
+///         .map(|i| Array::from(&[(i % 20) as f64, (i / 20) as f64]))
+///         .collect(),
+/// );
+/// // The class is the second feature.
+/// let ys = Array::from_vec(xs.iter().map(|row| row[1]).collect());
+///
+/// let mut boosting = GradientBoostingClassifier::new();
+/// boosting.fit(&xs, &ys).unwrap();
+///
+/// assert_eq!(Array::from(&[0.0, 1.0, 2.0]), boosting.classes().unwrap());
+/// assert_eq!(1.0, boosting.score(&xs, &ys).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradientBoostingClassifier {
+    options: BoostingOptions,
+    ensemble: Option<Ensemble>,
+    classes: Array,
+}
+
+impl Default for GradientBoostingClassifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GradientBoostingClassifier {
+    /// Returns an unfitted model.
+    pub fn new() -> Self {
+        GradientBoostingClassifier {
+            options: BoostingOptions::default(),
+            ensemble: None,
+            classes: Array::new(),
+        }
+    }
+
+    /// Sets the controls of the boosting.
+    ///
+    /// # Panics
+    ///
+    /// The options must be valid, see `BoostingOptions`, otherwise the code will panic.
+    pub fn set_options(&mut self, options: BoostingOptions) {
+        options.check("GradientBoostingClassifier");
+        self.options = options;
+    }
+
+    /// Fits the model to samples and their class labels, and monitors the log-loss on a
+    /// validation set for early stopping. The validation set must only contain classes of the
+    /// training set.
+    pub fn fit_with_validation(
+        &mut self,
+        xs: &Matrix,
+        ys: &Array,
+        validation_xs: &Matrix,
+        validation_ys: &Array,
+    ) -> Result<()> {
+        check_targets(xs, ys)?;
+        check_validation(xs, validation_xs, validation_ys)?;
+        let (classes, labels) = encode_classes(ys);
+        let validation_labels = validation_ys
+            .iter()
+            .map(|y| {
+                classes
+                    .binary_search_by(|c| c.partial_cmp(y).unwrap())
+                    .map(|k| k as f64)
+                    .map_err(|_| {
+                        ModelError::InvalidInput(format!(
+                            "the validation class {} is not in the training set",
+                            y
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<f64>>>()?;
+        self.fit_labels(
+            xs,
+            classes,
+            labels,
+            Some((validation_xs, &Array::from_vec(validation_labels))),
+        )
+    }
+
+    fn fit_labels(
+        &mut self,
+        xs: &Matrix,
+        classes: Array,
+        labels: Vec<usize>,
+        validation: Option<(&Matrix, &Array)>,
+    ) -> Result<()> {
+        if classes.len() < 2 {
+            return Err(ModelError::InvalidInput(
+                "gradient boosting needs at least 2 classes".to_string(),
+            ));
+        }
+        let objective = if classes.len() == 2 {
+            Objective::Binomial
+        } else {
+            Objective::Multinomial(classes.len())
+        };
+        let labels = Array::from_vec(labels.iter().map(|&l| l as f64).collect());
+        self.ensemble = Some(boost(xs, &labels, objective, &self.options, validation));
+        self.classes = classes;
+        Ok(())
+    }
+
+    /// Returns the number of boosting rounds kept, which is fewer than `rounds` after early
+    /// stopping, or 0 if the model is not fitted.
+    pub fn get_rounds(&self) -> usize {
+        self.ensemble.as_ref().map_or(0, |e| e.rounds.len())
+    }
+
+    /// Returns the mean training log-loss after every round, including rounds dropped by early
+    /// stopping.
+    pub fn get_training_loss(&self) -> &[f64] {
+        self.ensemble.as_ref().map_or(&[], |e| &e.training_loss)
+    }
+
+    /// Returns the mean validation log-loss after every round, which is empty without a
+    /// validation set.
+    pub fn get_validation_loss(&self) -> &[f64] {
+        self.ensemble.as_ref().map_or(&[], |e| &e.validation_loss)
+    }
+
+    /// Returns the total split gain of every feature, relative to the total, which is empty if
+    /// the model is not fitted.
+    pub fn get_feature_importances(&self) -> Array {
+        self.ensemble
+            .as_ref()
+            .map_or_else(Array::new, Ensemble::importances)
+    }
+}
+
+impl Estimator for GradientBoostingClassifier {
+    fn is_fitted(&self) -> bool {
+        self.ensemble.is_some()
+    }
+}
+
+impl Classifier for GradientBoostingClassifier {
+    /// Fits the model to samples with at least two class labels.
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        check_targets(xs, ys)?;
+        let (classes, labels) = encode_classes(ys);
+        self.fit_labels(xs, classes, labels, None)
+    }
+
+    fn classes(&self) -> Result<Array> {
+        self.check_fitted()?;
+        Ok(self.classes.clone())
+    }
+
+    fn predict_proba(&self, xs: &Matrix) -> Result<Matrix> {
+        let ensemble = self.ensemble.as_ref().ok_or(ModelError::NotFitted)?;
+        Ok(Matrix::from_rows(
+            ensemble
+                .scores(xs)?
+                .iter()
+                .map(|s| match ensemble.objective {
+                    Objective::Binomial => Array::from(&[sigmoid(-s[0]), sigmoid(s[0])]),
+                    _ => Array::from_vec(softmax(s)),
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::machine_learning::model::test::{
+        check_classifier_errors, check_predictor_errors, normal_samples,
+    };
+
+    /// Returns samples with the target `sin(x0) + x1^2 / 2`, a third irrelevant feature and
+    /// noise.
+    fn regression_data(n: usize, seed: u64) -> (Matrix, Array) {
+        let mut rng = Pcg64::seed_from_u64(seed);
+        let xs = Matrix::random_normal(n, 3, &mut rng);
+        let noise = Array::random_normal(n, &mut rng);
+        let ys = Array::from_vec(
+            xs.iter()
+                .zip(noise.iter())
+                .map(|(row, e)| row[0].sin() + 0.5 * row[1] * row[1] + 0.1 * e)
+                .collect(),
+        );
+        (xs, ys)
+    }
+
+    /// Returns samples of three classes by the angle of the first two features.
+    fn classification_data(n: usize, seed: u64) -> (Matrix, Array) {
+        normal_samples(n, 3, seed, |row| {
+            let angle = row[1].atan2(row[0]) + std::f64::consts::PI;
+            (angle / (2.0 * std::f64::consts::PI / 3.0))
+                .floor()
+                .min(2.0)
+        })
+    }
+
+    #[test]
+    fn test_regression() {
+        let (xs, ys) = regression_data(500, 1);
+        let (test_xs, test_ys) = regression_data(500, 2);
+
+        let mut boosting = GradientBoostingRegressor::new(RegressionLoss::Squared);
+        boosting.fit(&xs, &ys).unwrap();
+        assert!(boosting.score(&test_xs, &test_ys).unwrap() > 0.9);
+
+        let losses = boosting.get_training_loss();
+        assert_eq!(100, losses.len());
+        assert!(losses.windows(2).all(|w| w[1] <= w[0] + 1e-12));
+
+        let importances = boosting.get_feature_importances();
+        assert!((importances.sum() - 1.0).abs() < 1e-12);
+        assert!(importances[2] < 0.05);
+    }
+
+    #[test]
+    fn test_robust_losses() {
+        let (xs, mut ys) = regression_data(400, 3);
+        let (test_xs, test_ys) = regression_data(400, 4);
+        // Every tenth target is a gross outlier.
+        for i in (0..400).step_by(10) {
+            ys[i] += 50.0;
+        }
+
+        let mean_absolute_error = |loss: RegressionLoss| {
+            let mut boosting = GradientBoostingRegressor::new(loss);
+            boosting.fit(&xs, &ys).unwrap();
+            let predictions = boosting.predict(&test_xs).unwrap();
+            predictions
+                .minus(&test_ys)
+                .iter()
+                .map(|e| e.abs())
+                .sum::<f64>()
+                / 400.0
+        };
+
+        let squared = mean_absolute_error(RegressionLoss::Squared);
+        let absolute = mean_absolute_error(RegressionLoss::Absolute);
+        let huber = mean_absolute_error(RegressionLoss::Huber(1.0));
+        assert!(absolute < 0.5 * squared);
+        assert!(huber < 0.5 * squared);
+    }
+
+    #[test]
+    fn test_leaf_values() {
+        let objective = Objective::Regression(RegressionLoss::Huber(1.0));
+        let ys = Array::from(&[0.0, 1.0, 2.0, 10.0]);
+        let zero = |_: usize| 0.0;
+
+        // The median is 1.5 and the clipped deviations are -1, -0.5, 0.5 and 1.
+        assert_eq!(
+            1.5,
+            objective.leaf(&[0, 1, 2, 3], &ys, &zero, 0.0, 4.0, 0.0)
+        );
+        let absolute = Objective::Regression(RegressionLoss::Absolute);
+        assert_eq!(1.0, absolute.leaf(&[0, 1, 3], &ys, &zero, 0.0, 3.0, 0.0));
+        assert_eq!(
+            -2.0,
+            Objective::Binomial.leaf(&[0], &ys, &zero, 1.0, 0.25, 0.25)
+        );
+    }
+
+    #[test]
+    fn test_classification() {
+        let (xs, ys) = classification_data(600, 5);
+        let (test_xs, test_ys) = classification_data(600, 6);
+
+        let mut boosting = GradientBoostingClassifier::new();
+        boosting.fit(&xs, &ys).unwrap();
+        assert!(boosting.score(&test_xs, &test_ys).unwrap() > 0.9);
+        let probabilities = boosting.predict_proba(&test_xs).unwrap();
+        assert_eq!((600, 3), probabilities.dimensions());
+        assert!(probabilities
+            .iter()
+            .all(|row| (row.sum() - 1.0).abs() < 1e-12));
+
+        let binary = Array::from_vec(ys.iter().map(|&y| (y == 2.0) as u8 as f64).collect());
+        let test_binary =
+            Array::from_vec(test_ys.iter().map(|&y| (y == 2.0) as u8 as f64).collect());
+        boosting.fit(&xs, &binary).unwrap();
+        assert!(boosting.score(&test_xs, &test_binary).unwrap() > 0.9);
+        assert_eq!(
+            (600, 2),
+            boosting.predict_proba(&test_xs).unwrap().dimensions()
+        );
+    }
+
+    #[test]
+    fn test_early_stopping() {
+        let (xs, ys) = regression_data(200, 7);
+        let (validation_xs, validation_ys) = regression_data(200, 8);
+
+        let mut boosting = GradientBoostingRegressor::new(RegressionLoss::Squared);
+        boosting.set_options(BoostingOptions {
+            rounds: 1000,
+            learning_rate: 0.3,
+            max_depth: 6,
+            early_stopping: Some((10, 0.0)),
+            ..BoostingOptions::default()
+        });
+        boosting
+            .fit_with_validation(&xs, &ys, &validation_xs, &validation_ys)
+            .unwrap();
+
+        let losses = boosting.get_validation_loss();
+        assert!(losses.len() < 1000);
+        let best = Array::from(losses).argmin().unwrap();
+        assert_eq!(best + 1, boosting.get_rounds());
+        assert_eq!(losses.len(), best + 11);
+
+        // The kept rounds reproduce the best validation loss.
+        let predictions = boosting.predict(&validation_xs).unwrap();
+        let loss = predictions
+            .minus(&validation_ys)
+            .iter()
+            .map(|e| 0.5 * e * e)
+            .sum::<f64>()
+            / 200.0;
+        assert!((loss - losses[best]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_subsampling() {
+        let (xs, ys) = classification_data(300, 9);
+        let options = BoostingOptions {
+            rounds: 20,
+            subsample: 0.5,
+            colsample: 0.5,
+            ..BoostingOptions::default()
+        };
+
+        let fit = |options: BoostingOptions| {
+            let mut boosting = GradientBoostingClassifier::new();
+            boosting.set_options(options);
+            boosting.fit(&xs, &ys).unwrap();
+            boosting
+        };
+        let first = fit(options);
+        assert_eq!(first, fit(options));
+        assert_ne!(first, fit(BoostingOptions { seed: 1, ..options }));
+        assert!(first.score(&xs, &ys).unwrap() > 0.8);
+    }
+
+    #[test]
+    fn test_binning() {
+        let mut rng = Pcg64::seed_from_u64(10);
+        let xs = Matrix::random_normal(1000, 1, &mut rng);
+        let binned = Binned::new(&xs, 16, &mut rng);
+        assert!(binned.edges[0].len() <= 15);
+        for (i, row) in xs.iter().enumerate() {
+            let bin = binned.bins[0][i] as usize;
+            assert!(bin == binned.edges[0].len() || row[0] <= binned.edges[0][bin]);
+            assert!(bin == 0 || row[0] > binned.edges[0][bin - 1]);
+        }
+
+        // Few distinct values are split at their midpoints.
+        let few = Matrix::from_rows((0..30).map(|i| Array::from(&[(i % 3) as f64])).collect());
+        assert_eq!(vec![0.5, 1.5], Binned::new(&few, 256, &mut rng).edges[0]);
+    }
+
+    #[test]
+    fn test_infinite_features() {
+        let xs = Matrix::from_rows(
+            [0.0, f64::INFINITY, f64::NEG_INFINITY, 5.0]
+                .iter()
+                .map(|&x| Array::from(&[x]))
+                .collect(),
+        );
+        let ys = Array::from(&[0.0, 1.0, 0.0, 1.0]);
+
+        let mut rng = Pcg64::seed_from_u64(12);
+        let binned = Binned::new(&xs, 256, &mut rng);
+        assert_eq!(vec![f64::NEG_INFINITY, 2.5, 5.0], binned.edges[0]);
+        assert_eq!(vec![1, 3, 0, 2], binned.bins[0]);
+
+        // The thresholds send the samples the same way as their bins.
+        let mut boosting = GradientBoostingClassifier::new();
+        boosting.fit(&xs, &ys).unwrap();
+        assert_eq!(ys, boosting.predict(&xs).unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        let (xs, ys) = classification_data(20, 11);
+        let mut boosting = GradientBoostingClassifier::new();
+        assert!(boosting.fit(&xs, &Array::of(1.0, 20)).is_err());
+
+        let unseen = Array::of(5.0, 20);
+        assert!(boosting
+            .fit_with_validation(&xs, &ys, &xs, &unseen)
+            .is_err());
+
+        check_classifier_errors(&mut boosting, &xs, &ys);
+        let mut regressor = GradientBoostingRegressor::new(RegressionLoss::Absolute);
+        check_predictor_errors(&mut regressor, &xs, &ys);
+    }
+}
@@ -1,25 +1,84 @@
 use crate::linalg::array::Array;
-use crate::machine_learning::model::Model;
+use crate::linalg::matrix::Matrix;
+use crate::machine_learning::model::{ModelError, Predictor, Result};
 
+/// Returns the absolute cosine of the angle between the observations and the predictions of a
+/// model with a single feature.
+///
+/// # Arguments
+///
+/// * `observations` - the observed values.
+/// * `xs` - the single feature of every sample.
+/// * `model` - a fitted model with a single feature.
 pub fn evaluate_simple_linear_regression(
     observations: Array,
     xs: Array,
-    model: &mut dyn Model,
-) -> f64 {
-    let predictions = model.predict(xs);
+    model: &dyn Predictor,
+) -> Result<f64> {
+    let samples = Matrix::from_rows(xs.iter().map(|&x| Array::from(&[x])).collect());
+    let predictions = model.predict(&samples)?;
 
     if observations.len() == predictions.len() {
         let cos = cos_angle(observations, predictions);
         if cos < 0.0 {
-            cos * -1.0
+            Ok(cos * -1.0)
         } else {
-            cos
+            Ok(cos)
         }
     } else {
-        panic!("Error: Arrays lengths differ.");
+        Err(ModelError::InvalidInput(
+            "Arrays lengths differ.".to_string(),
+        ))
     }
 }
 
 fn cos_angle(v1: Array, v2: Array) -> f64 {
     v1.dotp(&v2) / (v1.norm() * v2.norm())
 }
+
+/// Returns the coefficient of determination (R²) of a set of predictions.
+///
+/// A perfect fit scores 1.0, while a model that always predicts the average of the
+/// observations scores 0.0. Worse models can score arbitrarily negative.
+///
+/// # Arguments
+///
+/// * `observations` - the observed values.
+/// * `predictions` - the values predicted by a model.
+///
+/// # Panics
+///
+/// The two Arrays must have the same length otherwise the code will panic.
+pub fn coefficient_of_determination(observations: &Array, predictions: &Array) -> f64 {
+    assert_eq!(
+        observations.len(),
+        predictions.len(),
+        "Error: Arrays lengths differ."
+    );
+
+    let residuals = observations.minus(predictions);
+    let deviations = observations.scalar_sub(observations.average());
+
+    1.0 - residuals.dotp(&residuals) / deviations.dotp(&deviations)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_coefficient_of_determination_perfect_fit() {
+        let observations = Array::from(&[1.0, 2.0, 3.0]);
+        let predictions = Array::from(&[1.0, 2.0, 3.0]);
+
+        assert_eq!(1.0, coefficient_of_determination(&observations, &predictions));
+    }
+
+    #[test]
+    fn test_coefficient_of_determination_average() {
+        let observations = Array::from(&[1.0, 2.0, 3.0]);
+        let predictions = Array::from(&[2.0, 2.0, 2.0]);
+
+        assert_eq!(0.0, coefficient_of_determination(&observations, &predictions));
+    }
+}
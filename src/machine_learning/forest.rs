@@ -0,0 +1,535 @@
+//! Random Forests - Averaging many randomized decision trees
+//!
+//! A random forest grows every tree on a bootstrap sample of the training samples, considering
+//! a random subset of the features for every split, and averages their predictions. The trees
+//! overfit on their own, but their errors are weakly correlated and average out.
+//!
+//! Every training sample is left out of about a third of the bootstrap samples. Predicting it
+//! by those trees alone gives the out-of-bag score, an estimate of the score on new samples
+//! that needs no separate validation set.
+//!
+//! # Reproducibility
+//!
+//! The `i`-th tree is grown with the `i`-th block of 2^64 numbers of a `Pcg64` generator seeded
+//! from `ForestOptions::seed`, so the forest only depends on the seed, not on the number of
+//! threads.
+
+use crate::linalg::array::Array;
+use crate::linalg::matrix::Matrix;
+use crate::machine_learning::evaluation::coefficient_of_determination;
+use crate::machine_learning::model::{
+    check_features, check_targets, encode_classes, Classifier, Estimator, ModelError, Predictor,
+    Result,
+};
+use crate::machine_learning::tree::{grow, Criterion, MaxFeatures, Task, Tree, TreeOptions};
+use crate::random::pcg::Pcg64;
+use crate::random::Rng;
+
+use std::thread;
+
+/// The controls of a random forest.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ForestOptions {
+    /// The number of trees.
+    pub trees: usize,
+    /// Whether every tree is grown on a bootstrap sample rather than all samples.
+    pub bootstrap: bool,
+    /// The controls of every tree. Their seed is ignored in favour of `seed`.
+    pub tree: TreeOptions,
+    /// The number of threads to grow the trees on.
+    pub threads: usize,
+    /// The seed of the bootstrap samples and the choice of features.
+    pub seed: u64,
+}
+
+impl Default for ForestOptions {
+    /// Returns 100 bootstrapped trees, which consider every feature for every split, grown
+    /// with the seed 0 on a single thread.
+    fn default() -> Self {
+        ForestOptions {
+            trees: 100,
+            bootstrap: true,
+            tree: TreeOptions::default(),
+            threads: 1,
+            seed: 0,
+        }
+    }
+}
+
+impl ForestOptions {
+    /// Panics unless the options are valid.
+    fn check(&self, name: &str) {
+        assert!(
+            self.trees > 0 && self.threads > 0,
+            "ERROR - {}: The number of trees and threads must be positive.",
+            name
+        );
+        self.tree.check(name);
+    }
+}
+
+/// A tree and the samples left out of its bootstrap sample.
+struct Grown {
+    tree: Tree,
+    out_of_bag: Vec<usize>,
+}
+
+/// Returns the trees of a forest.
+fn grow_forest(xs: &Matrix, task: Task, options: &ForestOptions) -> Vec<Grown> {
+    let rows = xs.dimensions().0;
+    let base = Pcg64::seed_from_u64(options.seed);
+    let run = |range: std::ops::Range<usize>| -> Vec<Grown> {
+        range
+            .map(|i| {
+                let mut rng = base.clone();
+                rng.advance((i as u128) << 64);
+
+                let mut weights = vec![if options.bootstrap { 0.0 } else { 1.0 }; rows];
+                if options.bootstrap {
+                    for _ in 0..rows {
+                        weights[rng.below(rows as u64) as usize] += 1.0;
+                    }
+                }
+                let out_of_bag = (0..rows).filter(|&i| weights[i] == 0.0).collect();
+                let tree = grow(xs, task, &weights, &options.tree, &mut rng);
+                Grown { tree, out_of_bag }
+            })
+            .collect()
+    };
+
+    let n = options.trees;
+    let threads = options.threads.min(n);
+    if threads == 1 {
+        return run(0..n);
+    }
+
+    let chunk = n.div_ceil(threads);
+    thread::scope(|scope| {
+        let run = &run;
+        let handles: Vec<_> = (0..n)
+            .step_by(chunk)
+            .map(|start| scope.spawn(move || run(start..(start + chunk).min(n))))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+/// Returns the sum of the leaf predictions of the trees that left out every sample, and the
+/// number of those trees.
+fn out_of_bag_sums(xs: &Matrix, grown: &[Grown], width: usize) -> (Vec<Array>, Vec<usize>) {
+    let rows = xs.dimensions().0;
+    let mut sums = vec![Array::zeros(width); rows];
+    let mut counts = vec![0; rows];
+    for g in grown {
+        for &i in &g.out_of_bag {
+            sums[i] = sums[i].plus(g.tree.leaf(&xs[i]));
+            counts[i] += 1;
+        }
+    }
+    (sums, counts)
+}
+
+/// Returns the mean of the feature importances of the trees.
+fn mean_importances(trees: &[Tree]) -> Array {
+    let mut total = Array::zeros(trees[0].importances().len());
+    for tree in trees {
+        total = total.plus(tree.importances());
+    }
+    total.scalar_mult(1.0 / trees.len() as f64)
+}
+
+/// Returns the mean leaf prediction of the trees for every sample.
+fn mean_leaves(trees: &[Tree], xs: &Matrix, width: usize) -> Vec<Array> {
+    xs.iter()
+        .map(|row| {
+            let mut total = Array::zeros(width);
+            for tree in trees {
+                total = total.plus(tree.leaf(row));
+            }
+            total.scalar_mult(1.0 / trees.len() as f64)
+        })
+        .collect()
+}
+
+/// A random forest of classification trees, which predicts the mean class probabilities of its
+/// trees.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::forest::RandomForestClassifier;
+/// use moonalloy::machine_learning::model::Classifier;
+///
+/// let xs = Matrix::from_rows(
+///     (0..40)
+///         .map(|i| Array::from(&[i as f64, (i % 3) as f64]))
+///         .collect(),
+/// );
+/// let ys = Array::from_vec((0..40).map(|i| if i < 20 { 0.0 } else { 1.0 }).collect());
+///
+/// let mut forest = RandomForestClassifier::new();
+/// forest.fit(&xs, &ys).unwrap();
+///
+/// let new = Matrix::new(&[Array::from(&[3.0, 1.0]), Array::from(&[35.0, 0.0])]);
+/// assert_eq!(Array::from(&[0.0, 1.0]), forest.predict(&new).unwrap());
+/// assert!(forest.get_oob_score().unwrap() > 0.9);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RandomForestClassifier {
+    criterion: Criterion,
+    options: ForestOptions,
+    trees: Vec<Tree>,
+    features: usize,
+    classes: Array,
+    oob_score: Option<f64>,
+}
+
+impl Default for RandomForestClassifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RandomForestClassifier {
+    /// Returns an unfitted model with the default `ForestOptions`, except that every split
+    /// considers the square root of the number of features, and the Gini index.
+    pub fn new() -> Self {
+        RandomForestClassifier {
+            criterion: Criterion::Gini,
+            options: ForestOptions {
+                tree: TreeOptions {
+                    max_features: MaxFeatures::Sqrt,
+                    ..TreeOptions::default()
+                },
+                ..ForestOptions::default()
+            },
+            trees: Vec::new(),
+            features: 0,
+            classes: Array::new(),
+            oob_score: None,
+        }
+    }
+
+    /// Sets the impurity of the splits.
+    pub fn set_criterion(&mut self, criterion: Criterion) {
+        self.criterion = criterion;
+    }
+
+    /// Sets the controls of the forest.
+    ///
+    /// # Panics
+    ///
+    /// The number of trees and threads must be positive and the tree options valid, otherwise
+    /// the code will panic.
+    pub fn set_options(&mut self, options: ForestOptions) {
+        options.check("RandomForestClassifier");
+        self.options = options;
+    }
+
+    /// Returns the mean feature importances of the trees, which is empty if the model is not
+    /// fitted.
+    pub fn get_feature_importances(&self) -> Array {
+        if self.trees.is_empty() {
+            Array::new()
+        } else {
+            mean_importances(&self.trees)
+        }
+    }
+
+    /// Returns the accuracy of the out-of-bag predictions, of the samples left out by at least
+    /// one tree, or `None` without bootstrap samples or such samples.
+    pub fn get_oob_score(&self) -> Option<f64> {
+        self.oob_score
+    }
+}
+
+impl Estimator for RandomForestClassifier {
+    fn is_fitted(&self) -> bool {
+        !self.trees.is_empty()
+    }
+}
+
+impl Classifier for RandomForestClassifier {
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        check_targets(xs, ys)?;
+        let (classes, labels) = encode_classes(ys);
+        let task = Task::Classification {
+            labels: &labels,
+            classes: classes.len(),
+            criterion: self.criterion,
+        };
+        let grown = grow_forest(xs, task, &self.options);
+
+        let (sums, counts) = out_of_bag_sums(xs, &grown, classes.len());
+        let scored: Vec<bool> = (0..labels.len())
+            .filter(|&i| counts[i] > 0)
+            .map(|i| sums[i].argmax() == Some(labels[i]))
+            .collect();
+        self.oob_score = if scored.is_empty() {
+            None
+        } else {
+            Some(scored.iter().filter(|&&correct| correct).count() as f64 / scored.len() as f64)
+        };
+
+        self.trees = grown.into_iter().map(|g| g.tree).collect();
+        self.features = xs.dimensions().1;
+        self.classes = classes;
+        Ok(())
+    }
+
+    fn classes(&self) -> Result<Array> {
+        self.check_fitted()?;
+        Ok(self.classes.clone())
+    }
+
+    /// Returns the mean class probabilities of the trees.
+    fn predict_proba(&self, xs: &Matrix) -> Result<Matrix> {
+        self.check_fitted()?;
+        check_features(xs, self.features)?;
+        Ok(Matrix::from_rows(mean_leaves(
+            &self.trees,
+            xs,
+            self.classes.len(),
+        )))
+    }
+}
+
+/// A random forest of regression trees, which predicts the mean prediction of its trees.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::forest::{ForestOptions, RandomForestRegressor};
+/// use moonalloy::machine_learning::model::Predictor;
+///
+/// let xs = Matrix::from_rows((0..50).map(|i| Array::from(&[i as f64 / 10.0])).collect());
+/// let ys = Array::from_vec(xs.iter().map(|row| row[0] * row[0]).collect());
+///
+/// let mut forest = RandomForestRegressor::new();
+/// forest.set_options(ForestOptions {
+///     trees: 50,
+///     threads: 2,
+///     ..ForestOptions::default()
+/// });
+/// forest.fit(&xs, &ys).unwrap();
+///
+/// assert!(forest.score(&xs, &ys).unwrap() > 0.99);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RandomForestRegressor {
+    options: ForestOptions,
+    trees: Vec<Tree>,
+    features: usize,
+    oob_score: Option<f64>,
+}
+
+impl Default for RandomForestRegressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RandomForestRegressor {
+    /// Returns an unfitted model with the default `ForestOptions`.
+    pub fn new() -> Self {
+        RandomForestRegressor {
+            options: ForestOptions::default(),
+            trees: Vec::new(),
+            features: 0,
+            oob_score: None,
+        }
+    }
+
+    /// Sets the controls of the forest.
+    ///
+    /// # Panics
+    ///
+    /// The number of trees and threads must be positive and the tree options valid, otherwise
+    /// the code will panic.
+    pub fn set_options(&mut self, options: ForestOptions) {
+        options.check("RandomForestRegressor");
+        self.options = options;
+    }
+
+    /// Returns the mean feature importances of the trees, which is empty if the model is not
+    /// fitted.
+    pub fn get_feature_importances(&self) -> Array {
+        if self.trees.is_empty() {
+            Array::new()
+        } else {
+            mean_importances(&self.trees)
+        }
+    }
+
+    /// Returns the coefficient of determination (R²) of the out-of-bag predictions, of the
+    /// samples left out by at least one tree, or `None` without bootstrap samples or such
+    /// samples.
+    pub fn get_oob_score(&self) -> Option<f64> {
+        self.oob_score
+    }
+}
+
+impl Estimator for RandomForestRegressor {
+    fn is_fitted(&self) -> bool {
+        !self.trees.is_empty()
+    }
+}
+
+impl Predictor for RandomForestRegressor {
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        check_targets(xs, ys)?;
+        let task = Task::Regression { targets: ys };
+        let grown = grow_forest(xs, task, &self.options);
+
+        let (sums, counts) = out_of_bag_sums(xs, &grown, 1);
+        let scored: Vec<usize> = (0..ys.len()).filter(|&i| counts[i] > 0).collect();
+        self.oob_score = if scored.is_empty() {
+            None
+        } else {
+            let observed = Array::from_vec(scored.iter().map(|&i| ys[i]).collect());
+            let predicted = Array::from_vec(
+                scored
+                    .iter()
+                    .map(|&i| sums[i][0] / counts[i] as f64)
+                    .collect(),
+            );
+            Some(coefficient_of_determination(&observed, &predicted))
+        };
+
+        self.trees = grown.into_iter().map(|g| g.tree).collect();
+        self.features = xs.dimensions().1;
+        Ok(())
+    }
+
+    /// Returns the mean prediction of the trees.
+    fn predict(&self, xs: &Matrix) -> Result<Array> {
+        if self.trees.is_empty() {
+            return Err(ModelError::NotFitted);
+        }
+        check_features(xs, self.features)?;
+        Ok(Array::from_vec(
+            mean_leaves(&self.trees, xs, 1)
+                .iter()
+                .map(|leaf| leaf[0])
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::machine_learning::model::test::{
+        check_classifier_errors, check_predictor_errors, normal_samples,
+    };
+
+    /// Returns samples of two classes on either side of a circle, with two irrelevant
+    /// features.
+    fn circle(n: usize, seed: u64) -> (Matrix, Array) {
+        normal_samples(n, 4, seed, |row| {
+            if row[0] * row[0] + row[1] * row[1] < 1.4 {
+                0.0
+            } else {
+                1.0
+            }
+        })
+    }
+
+    #[test]
+    fn test_classifier() {
+        let (xs, ys) = circle(400, 1);
+        let (test_xs, test_ys) = circle(400, 2);
+
+        let mut forest = RandomForestClassifier::new();
+        forest.fit(&xs, &ys).unwrap();
+        let score = forest.score(&test_xs, &test_ys).unwrap();
+        assert!(score > 0.85);
+
+        // The out-of-bag score estimates the score on new samples.
+        let oob = forest.get_oob_score().unwrap();
+        assert!((oob - score).abs() < 0.05);
+
+        let importances = forest.get_feature_importances();
+        assert!((importances.sum() - 1.0).abs() < 1e-12);
+        assert!(importances[0] > importances[2] && importances[1] > importances[3]);
+
+        let mut tree = crate::machine_learning::tree::DecisionTreeClassifier::new(Criterion::Gini);
+        tree.fit(&xs, &ys).unwrap();
+        assert!(score > tree.score(&test_xs, &test_ys).unwrap());
+    }
+
+    #[test]
+    fn test_threads() {
+        let (xs, ys) = circle(100, 3);
+        let options = ForestOptions {
+            trees: 10,
+            ..ForestOptions::default()
+        };
+
+        let mut single = RandomForestRegressor::new();
+        single.set_options(options);
+        single.fit(&xs, &ys).unwrap();
+
+        let mut parallel = RandomForestRegressor::new();
+        parallel.set_options(ForestOptions {
+            threads: 3,
+            ..options
+        });
+        parallel.fit(&xs, &ys).unwrap();
+        assert_eq!(single.trees, parallel.trees);
+        assert_eq!(single.get_oob_score(), parallel.get_oob_score());
+
+        let mut other = RandomForestRegressor::new();
+        other.set_options(ForestOptions { seed: 1, ..options });
+        other.fit(&xs, &ys).unwrap();
+        assert_ne!(single.trees, other.trees);
+    }
+
+    #[test]
+    fn test_regressor() {
+        let mut rng = Pcg64::seed_from_u64(4);
+        let xs = Matrix::random_normal(300, 2, &mut rng);
+        let ys = Array::from_vec(xs.iter().map(|row| row[0].sin() + 0.1 * row[1]).collect());
+
+        let mut forest = RandomForestRegressor::new();
+        forest.fit(&xs, &ys).unwrap();
+        assert!(forest.score(&xs, &ys).unwrap() > 0.95);
+        assert!(forest.get_oob_score().unwrap() > 0.8);
+
+        // Without bootstrap samples, every tree fits the training samples exactly.
+        forest.set_options(ForestOptions {
+            trees: 5,
+            bootstrap: false,
+            ..ForestOptions::default()
+        });
+        forest.fit(&xs, &ys).unwrap();
+        assert_eq!(None, forest.get_oob_score());
+        let predictions = forest.predict(&xs).unwrap();
+        assert!(predictions.minus(&ys).norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_errors() {
+        let (xs, ys) = circle(10, 5);
+        let mut forest = RandomForestClassifier::new();
+        assert!(forest.get_feature_importances().is_empty());
+        check_classifier_errors(&mut forest, &xs, &ys);
+        check_predictor_errors(&mut RandomForestRegressor::new(), &xs, &ys);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_options() {
+        RandomForestRegressor::new().set_options(ForestOptions {
+            trees: 0,
+            ..ForestOptions::default()
+        });
+    }
+}
@@ -0,0 +1,809 @@
+//! K-Means - Clustering observations around centers
+//!
+//! k-means partitions the rows of a `Matrix` into `k` clusters so that the sum of squared
+//! distances of the observations to the center of their cluster, the inertia, is small:
+//!
+//! * `KMeans` alternates between assigning every observation to its nearest center and moving
+//!   every center to the mean of its observations (Lloyd's algorithm).
+//! * `MiniBatchKMeans` moves the centers towards small random batches of observations, which
+//!   is much faster on large datasets at the cost of a slightly larger inertia.
+//!
+//! Both start from centers chosen by k-means++, which picks observations far from the centers
+//! chosen so far.
+
+use crate::linalg::array::Array;
+use crate::linalg::matrix::Matrix;
+use crate::machine_learning::model::{check_features, Estimator, ModelError, Result, Transformer};
+use crate::random::pcg::Pcg64;
+use crate::random::Rng;
+use crate::statistics::descriptive;
+
+/// The controls of `KMeans`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KMeansOptions {
+    /// The number of runs from different initial centers, of which the one with the smallest
+    /// inertia is kept.
+    pub restarts: usize,
+    /// The maximum number of iterations of every run.
+    pub max_iterations: usize,
+    /// A run stops once the squared distances the centers move add up to less than the
+    /// tolerance times the mean variance of the features.
+    pub tolerance: f64,
+    /// The seed of the choice of initial centers.
+    pub seed: u64,
+}
+
+impl Default for KMeansOptions {
+    fn default() -> Self {
+        KMeansOptions {
+            restarts: 10,
+            max_iterations: 300,
+            tolerance: 1e-4,
+            seed: 0,
+        }
+    }
+}
+
+/// Returns the squared Euclidean distance between two points.
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+/// Returns the index of the center nearest to a point and the squared distance to it.
+fn nearest(point: &[f64], centers: &Matrix) -> (usize, f64) {
+    centers
+        .iter()
+        .enumerate()
+        .map(|(i, center)| (i, squared_distance(point, center)))
+        .fold((0, f64::INFINITY), |best, candidate| {
+            if candidate.1 < best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+}
+
+/// Returns the mean variance of the features, which scales the tolerance.
+fn mean_variance(xs: &Matrix) -> f64 {
+    descriptive::var(xs, 0).average()
+}
+
+/// Returns an error unless there are at least as many observations as clusters, and they have
+/// finite features.
+fn check_observations(xs: &Matrix, clusters: usize) -> Result<()> {
+    let (rows, cols) = xs.dimensions();
+    if rows < clusters || cols == 0 {
+        return Err(ModelError::InvalidInput(format!(
+            "{} clusters need at least as many observations with features, but there are {}",
+            clusters, rows
+        )));
+    }
+    check_finite(xs)
+}
+
+/// Returns an error unless every feature of the observations is finite.
+fn check_finite(xs: &Matrix) -> Result<()> {
+    if xs.iter().any(|row| row.iter().any(|x| !x.is_finite())) {
+        return Err(ModelError::InvalidInput(
+            "the observations must be finite".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns `k` initial centers chosen by greedy k-means++.
+///
+/// Every center is drawn among the observations with probability proportional to the squared
+/// distance to the nearest center so far. Of `2 + ln(k)` candidates, the one that reduces the
+/// inertia most is kept.
+fn kmeans_plus_plus(xs: &Matrix, k: usize, rng: &mut dyn Rng) -> Matrix {
+    let (rows, _) = xs.dimensions();
+    let trials = 2 + (k as f64).ln() as usize;
+
+    let first = rng.below(rows as u64) as usize;
+    let mut centers = vec![xs[first].clone()];
+    let mut distances: Vec<f64> = xs
+        .iter()
+        .map(|row| squared_distance(row, &xs[first]))
+        .collect();
+
+    while centers.len() < k {
+        let total: f64 = distances.iter().sum();
+        let mut best: Option<(f64, usize, Vec<f64>)> = None;
+
+        for _ in 0..trials {
+            let candidate = if total > 0.0 {
+                let target = rng.next_f64() * total;
+                let mut cumulative = 0.0;
+                let mut chosen = rows - 1;
+                for (i, d) in distances.iter().enumerate() {
+                    cumulative += d;
+                    if cumulative > target {
+                        chosen = i;
+                        break;
+                    }
+                }
+                chosen
+            } else {
+                // Every observation is a center already, so any one will do.
+                rng.below(rows as u64) as usize
+            };
+
+            let updated: Vec<f64> = xs
+                .iter()
+                .zip(&distances)
+                .map(|(row, &d)| d.min(squared_distance(row, &xs[candidate])))
+                .collect();
+            let inertia: f64 = updated.iter().sum();
+            if best.as_ref().is_none_or(|(b, _, _)| inertia < *b) {
+                best = Some((inertia, candidate, updated));
+            }
+        }
+
+        let (_, chosen, updated) = best.unwrap();
+        centers.push(xs[chosen].clone());
+        distances = updated;
+    }
+
+    Matrix::from_rows(centers)
+}
+
+/// The result of a run of Lloyd's algorithm.
+struct Run {
+    centers: Matrix,
+    labels: Vec<usize>,
+    inertia: f64,
+    iterations: usize,
+}
+
+/// Runs Lloyd's algorithm from the given centers.
+fn lloyd(xs: &Matrix, mut centers: Matrix, max_iterations: usize, tolerance: f64) -> Run {
+    let (rows, cols) = xs.dimensions();
+    let k = centers.dimensions().0;
+    let mut labels = vec![0; rows];
+    let mut distances = vec![0.0; rows];
+    let mut iterations = 0;
+
+    while iterations < max_iterations {
+        iterations += 1;
+        for (i, row) in xs.iter().enumerate() {
+            let (label, distance) = nearest(row, &centers);
+            labels[i] = label;
+            distances[i] = distance;
+        }
+
+        let mut sums = vec![vec![0.0; cols]; k];
+        let mut counts = vec![0usize; k];
+        for (row, &label) in xs.iter().zip(&labels) {
+            counts[label] += 1;
+            for (s, x) in sums[label].iter_mut().zip(row.iter()) {
+                *s += x;
+            }
+        }
+
+        // An empty cluster takes over the observation farthest from its center.
+        for cluster in 0..k {
+            if counts[cluster] > 0 {
+                continue;
+            }
+            let farthest = (0..rows)
+                .filter(|&i| counts[labels[i]] > 1)
+                .max_by(|&a, &b| distances[a].total_cmp(&distances[b]))
+                .unwrap();
+            let old = labels[farthest];
+            counts[old] -= 1;
+            for (s, x) in sums[old].iter_mut().zip(xs[farthest].iter()) {
+                *s -= x;
+            }
+            labels[farthest] = cluster;
+            distances[farthest] = 0.0;
+            counts[cluster] = 1;
+            sums[cluster] = xs[farthest].to_vec();
+        }
+
+        let updated = Matrix::from_rows(
+            sums.iter()
+                .zip(&counts)
+                .map(|(s, &c)| Array::from_vec(s.iter().map(|v| v / c as f64).collect()))
+                .collect(),
+        );
+        let shift: f64 = centers
+            .iter()
+            .zip(updated.iter())
+            .map(|(a, b)| squared_distance(a, b))
+            .sum();
+        centers = updated;
+        if shift <= tolerance {
+            break;
+        }
+    }
+
+    // The labels and inertia of the final centers.
+    let mut inertia = 0.0;
+    for (i, row) in xs.iter().enumerate() {
+        let (label, distance) = nearest(row, &centers);
+        labels[i] = label;
+        inertia += distance;
+    }
+
+    Run {
+        centers,
+        labels,
+        inertia,
+        iterations,
+    }
+}
+
+/// k-means clustering by Lloyd's algorithm with k-means++ initialization.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::kmeans::KMeans;
+/// use moonalloy::machine_learning::model::Transformer;
+///
+/// let xs = Matrix::new(&[
+///     Array::from(&[1.0, 1.0]),
+///     Array::from(&[1.5, 2.0]),
+///     Array::from(&[8.0, 8.0]),
+///     Array::from(&[9.0, 8.5]),
+///     Array::from(&[1.0, 1.5]),
+/// ]);
+///
+/// let mut kmeans = KMeans::new(2);
+/// kmeans.fit(&xs).unwrap();
+///
+/// let labels = kmeans.labels();
+/// assert_eq!(labels[0], labels[1]);
+/// assert_ne!(labels[0], labels[2]);
+///
+/// let new = Matrix::new(&[Array::from(&[8.5, 9.0])]);
+/// assert_eq!(vec![labels[2]], kmeans.predict(&new).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct KMeans {
+    clusters: usize,
+    options: KMeansOptions,
+    centers: Matrix,
+    labels: Vec<usize>,
+    inertia: f64,
+    iterations: usize,
+}
+
+impl KMeans {
+    /// Returns an unfitted model.
+    ///
+    /// # Panics
+    ///
+    /// There must be at least one cluster, otherwise the code will panic.
+    pub fn new(clusters: usize) -> Self {
+        assert!(
+            clusters > 0,
+            "ERROR - KMeans: There must be at least one cluster."
+        );
+
+        KMeans {
+            clusters,
+            options: KMeansOptions::default(),
+            centers: Matrix::zeros(0, 0),
+            labels: Vec::new(),
+            inertia: 0.0,
+            iterations: 0,
+        }
+    }
+
+    /// Sets the controls of the fit.
+    ///
+    /// # Panics
+    ///
+    /// There must be at least one restart and one iteration, otherwise the code will panic.
+    pub fn set_options(&mut self, options: KMeansOptions) {
+        assert!(
+            options.restarts > 0 && options.max_iterations > 0,
+            "ERROR - KMeans: There must be at least one restart and one iteration."
+        );
+        self.options = options;
+    }
+
+    /// Returns the centers, with a row for every cluster.
+    pub fn cluster_centers(&self) -> &Matrix {
+        &self.centers
+    }
+
+    /// Returns the cluster of every observation the model was fitted to.
+    pub fn labels(&self) -> &[usize] {
+        &self.labels
+    }
+
+    /// Returns the sum of squared distances of the observations the model was fitted to, to
+    /// their nearest center.
+    pub fn inertia(&self) -> f64 {
+        self.inertia
+    }
+
+    /// Returns the number of iterations of the best run.
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Returns the nearest cluster of every observation.
+    pub fn predict(&self, xs: &Matrix) -> Result<Vec<usize>> {
+        predict_clusters(self, &self.centers, xs)
+    }
+}
+
+/// Returns the nearest center of every observation.
+fn predict_clusters(model: &dyn Estimator, centers: &Matrix, xs: &Matrix) -> Result<Vec<usize>> {
+    model.check_fitted()?;
+    check_features(xs, centers.dimensions().1)?;
+    Ok(xs.iter().map(|row| nearest(row, centers).0).collect())
+}
+
+/// Returns the distance of every observation to every center.
+fn center_distances(model: &dyn Estimator, centers: &Matrix, xs: &Matrix) -> Result<Matrix> {
+    model.check_fitted()?;
+    check_features(xs, centers.dimensions().1)?;
+    Ok(Matrix::from_rows(
+        xs.iter()
+            .map(|row| {
+                Array::from_vec(
+                    centers
+                        .iter()
+                        .map(|c| squared_distance(row, c).sqrt())
+                        .collect(),
+                )
+            })
+            .collect(),
+    ))
+}
+
+impl Estimator for KMeans {
+    fn is_fitted(&self) -> bool {
+        !self.labels.is_empty()
+    }
+}
+
+impl Transformer for KMeans {
+    /// Fits the centers to the observations, with a row for every observation.
+    fn fit(&mut self, xs: &Matrix) -> Result<()> {
+        check_observations(xs, self.clusters)?;
+        let tolerance = self.options.tolerance * mean_variance(xs);
+        let mut rng = Pcg64::seed_from_u64(self.options.seed);
+
+        let mut best: Option<Run> = None;
+        for _ in 0..self.options.restarts {
+            let initial = kmeans_plus_plus(xs, self.clusters, &mut rng);
+            let run = lloyd(xs, initial, self.options.max_iterations, tolerance);
+            if best.as_ref().is_none_or(|b| run.inertia < b.inertia) {
+                best = Some(run);
+            }
+        }
+
+        let best = best.unwrap();
+        self.centers = best.centers;
+        self.labels = best.labels;
+        self.inertia = best.inertia;
+        self.iterations = best.iterations;
+        Ok(())
+    }
+
+    /// Returns the distance of every observation to every center, with a column for every
+    /// cluster.
+    fn transform(&self, xs: &Matrix) -> Result<Matrix> {
+        center_distances(self, &self.centers, xs)
+    }
+}
+
+/// The controls of `MiniBatchKMeans`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MiniBatchOptions {
+    /// The number of observations per batch.
+    pub batch_size: usize,
+    /// The maximum number of batches.
+    pub max_iterations: usize,
+    /// The fit stops once the squared distances the centers move in a batch add up to less
+    /// than the tolerance times the mean variance of the features.
+    pub tolerance: f64,
+    /// The seed of the initial centers and the batches.
+    pub seed: u64,
+}
+
+impl Default for MiniBatchOptions {
+    fn default() -> Self {
+        MiniBatchOptions {
+            batch_size: 1024,
+            max_iterations: 100,
+            tolerance: 1e-6,
+            seed: 0,
+        }
+    }
+}
+
+/// k-means clustering on random batches of observations (Sculley's mini-batch k-means).
+///
+/// Every center moves towards the observations of a batch assigned to it, with a step size of
+/// one over the number of observations assigned to it so far, so it is the running mean of its
+/// observations. Batches can also be fed one by one with `partial_fit`, e.g. from a stream.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::kmeans::MiniBatchKMeans;
+/// use moonalloy::machine_learning::model::Transformer;
+///
+/// let xs = Matrix::from_rows(
+///     (0..1000)
+///         .map(|i| {
+///             let center = if i % 2 == 0 { 0.0 } else { 10.0 };
+///             Array::from(&[center + (i % 7) as f64 * 0.1, center - (i % 5) as f64 * 0.1])
+///         })
+///         .collect(),
+/// );
+///
+/// let mut kmeans = MiniBatchKMeans::new(2);
+/// kmeans.fit(&xs).unwrap();
+///
+/// let labels = kmeans.labels();
+/// assert!(labels.iter().step_by(2).all(|&l| l == labels[0]));
+/// assert!(labels.iter().skip(1).step_by(2).all(|&l| l != labels[0]));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MiniBatchKMeans {
+    clusters: usize,
+    options: MiniBatchOptions,
+    centers: Matrix,
+    counts: Vec<usize>,
+    labels: Vec<usize>,
+    inertia: f64,
+    iterations: usize,
+    rng: Pcg64,
+}
+
+impl MiniBatchKMeans {
+    /// Returns an unfitted model.
+    ///
+    /// # Panics
+    ///
+    /// There must be at least one cluster, otherwise the code will panic.
+    pub fn new(clusters: usize) -> Self {
+        assert!(
+            clusters > 0,
+            "ERROR - MiniBatchKMeans: There must be at least one cluster."
+        );
+
+        let options = MiniBatchOptions::default();
+        MiniBatchKMeans {
+            clusters,
+            options,
+            centers: Matrix::zeros(0, 0),
+            counts: Vec::new(),
+            labels: Vec::new(),
+            inertia: 0.0,
+            iterations: 0,
+            rng: Pcg64::seed_from_u64(options.seed),
+        }
+    }
+
+    /// Sets the controls of the fit.
+    ///
+    /// # Panics
+    ///
+    /// The batch size and the maximum number of batches must be positive, otherwise the code
+    /// will panic.
+    pub fn set_options(&mut self, options: MiniBatchOptions) {
+        assert!(
+            options.batch_size > 0 && options.max_iterations > 0,
+            "ERROR - MiniBatchKMeans: The batch size and max_iterations must be positive."
+        );
+        self.options = options;
+        self.rng = Pcg64::seed_from_u64(options.seed);
+    }
+
+    /// Returns the centers, with a row for every cluster.
+    pub fn cluster_centers(&self) -> &Matrix {
+        &self.centers
+    }
+
+    /// Returns the cluster of every observation of the last `fit`, which is empty after only
+    /// `partial_fit`.
+    pub fn labels(&self) -> &[usize] {
+        &self.labels
+    }
+
+    /// Returns the sum of squared distances of the observations of the last `fit` to their
+    /// nearest center.
+    pub fn inertia(&self) -> f64 {
+        self.inertia
+    }
+
+    /// Returns the number of batches the centers were updated with.
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Returns the nearest cluster of every observation.
+    pub fn predict(&self, xs: &Matrix) -> Result<Vec<usize>> {
+        predict_clusters(self, &self.centers, xs)
+    }
+
+    /// Updates the centers with a batch of observations, and returns the squared distances
+    /// they moved. The first batch chooses the initial centers by k-means++.
+    pub fn partial_fit(&mut self, batch: &Matrix) -> Result<f64> {
+        if self.counts.is_empty() {
+            check_observations(batch, self.clusters)?;
+            self.centers = kmeans_plus_plus(batch, self.clusters, &mut self.rng);
+            self.counts = vec![0; self.clusters];
+        } else {
+            check_features(batch, self.centers.dimensions().1)?;
+            check_finite(batch)?;
+        }
+
+        let previous = self.centers.clone();
+        let labels: Vec<usize> = batch.iter().map(|row| nearest(row, &previous).0).collect();
+        for (row, &label) in batch.iter().zip(&labels) {
+            self.counts[label] += 1;
+            let rate = 1.0 / self.counts[label] as f64;
+            for (c, x) in self.centers.row_mut(label).iter_mut().zip(row.iter()) {
+                *c += rate * (x - *c);
+            }
+        }
+        self.iterations += 1;
+
+        Ok(previous
+            .iter()
+            .zip(self.centers.iter())
+            .map(|(a, b)| squared_distance(a, b))
+            .sum())
+    }
+}
+
+impl Estimator for MiniBatchKMeans {
+    fn is_fitted(&self) -> bool {
+        !self.counts.is_empty()
+    }
+}
+
+impl Transformer for MiniBatchKMeans {
+    /// Fits the centers to random batches of the observations, starting over, and assigns
+    /// every observation to its nearest center.
+    ///
+    /// The initial centers are chosen among a random sample of three batches.
+    fn fit(&mut self, xs: &Matrix) -> Result<()> {
+        check_observations(xs, self.clusters)?;
+        let (rows, _) = xs.dimensions();
+        let tolerance = self.options.tolerance * mean_variance(xs);
+        self.rng = Pcg64::seed_from_u64(self.options.seed);
+        self.counts = Vec::new();
+        self.iterations = 0;
+
+        let sample_size = (3 * self.options.batch_size)
+            .max(3 * self.clusters)
+            .min(rows);
+        let sample = |size: usize, rng: &mut Pcg64| {
+            Matrix::from_rows(
+                (0..size)
+                    .map(|_| xs[rng.below(rows as u64) as usize].clone())
+                    .collect(),
+            )
+        };
+        let initial = sample(sample_size, &mut self.rng);
+        check_observations(&initial, self.clusters)?;
+        self.centers = kmeans_plus_plus(&initial, self.clusters, &mut self.rng);
+        self.counts = vec![0; self.clusters];
+
+        let batch_size = self.options.batch_size.min(rows);
+        for _ in 0..self.options.max_iterations {
+            let batch = sample(batch_size, &mut self.rng);
+            let shift = self.partial_fit(&batch)?;
+            if shift <= tolerance {
+                break;
+            }
+        }
+
+        self.labels = Vec::with_capacity(rows);
+        self.inertia = 0.0;
+        for row in xs.iter() {
+            let (label, distance) = nearest(row, &self.centers);
+            self.labels.push(label);
+            self.inertia += distance;
+        }
+        Ok(())
+    }
+
+    /// Returns the distance of every observation to every center, with a column for every
+    /// cluster.
+    fn transform(&self, xs: &Matrix) -> Result<Matrix> {
+        center_distances(self, &self.centers, xs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const CENTERS: [[f64; 2]; 3] = [[0.0, 0.0], [6.0, 1.0], [2.0, 7.0]];
+
+    /// Returns `per_cluster` observations around each of three centers, in turn.
+    fn blobs(per_cluster: usize, seed: u64) -> Matrix {
+        let mut rng = Pcg64::seed_from_u64(seed);
+        let noise = Matrix::random_normal(3 * per_cluster, 2, &mut rng);
+        Matrix::from_rows(
+            noise
+                .iter()
+                .enumerate()
+                .map(|(i, e)| {
+                    let c = CENTERS[i % 3];
+                    Array::from(&[c[0] + 0.5 * e[0], c[1] + 0.5 * e[1]])
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the largest distance of a true center to its nearest fitted center.
+    fn center_error(centers: &Matrix) -> f64 {
+        CENTERS
+            .iter()
+            .map(|c| nearest(c, centers).1.sqrt())
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_blobs() {
+        let xs = blobs(100, 1);
+        let mut kmeans = KMeans::new(3);
+        kmeans.fit(&xs).unwrap();
+
+        assert!(center_error(kmeans.cluster_centers()) < 0.15);
+        let labels = kmeans.labels();
+        for i in 3..300 {
+            assert_eq!(labels[i % 3], labels[i]);
+        }
+
+        let inertia: f64 = xs
+            .iter()
+            .zip(labels)
+            .map(|(row, &l)| squared_distance(row, &kmeans.cluster_centers()[l]))
+            .sum();
+        assert!((kmeans.inertia() - inertia).abs() < 1e-9);
+        assert_eq!(labels.to_vec(), kmeans.predict(&xs).unwrap());
+
+        let distances = kmeans.transform(&xs).unwrap();
+        assert_eq!((300, 3), distances.dimensions());
+        assert_eq!(labels[0], distances[0].argmin().unwrap());
+    }
+
+    #[test]
+    fn test_centers_are_means() {
+        let xs = blobs(30, 2);
+        let mut kmeans = KMeans::new(4);
+        kmeans.set_options(KMeansOptions {
+            tolerance: 0.0,
+            ..KMeansOptions::default()
+        });
+        kmeans.fit(&xs).unwrap();
+
+        // At convergence, every center is the mean of its observations.
+        for (cluster, center) in kmeans.cluster_centers().iter().enumerate() {
+            let members: Vec<&Array> = xs
+                .iter()
+                .zip(kmeans.labels())
+                .filter(|(_, &l)| l == cluster)
+                .map(|(row, _)| row)
+                .collect();
+            assert!(!members.is_empty());
+            for j in 0..2 {
+                let mean = members.iter().map(|row| row[j]).sum::<f64>() / members.len() as f64;
+                assert!((center[j] - mean).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_restarts() {
+        let xs = blobs(20, 3);
+        let single = KMeansOptions {
+            restarts: 1,
+            ..KMeansOptions::default()
+        };
+
+        let mut best = KMeans::new(6);
+        best.fit(&xs).unwrap();
+        for seed in 0..5 {
+            let mut once = KMeans::new(6);
+            once.set_options(KMeansOptions { seed, ..single });
+            once.fit(&xs).unwrap();
+            assert!(best.inertia() <= once.inertia() + 1e-9);
+        }
+
+        let mut again = KMeans::new(6);
+        again.fit(&xs).unwrap();
+        assert_eq!(best, again);
+    }
+
+    #[test]
+    fn test_duplicate_observations() {
+        let xs = Matrix::new(&[
+            Array::from(&[1.0, 1.0]),
+            Array::from(&[1.0, 1.0]),
+            Array::from(&[5.0, 5.0]),
+            Array::from(&[5.0, 5.0]),
+            Array::from(&[9.0, 0.0]),
+        ]);
+        let mut kmeans = KMeans::new(3);
+        kmeans.fit(&xs).unwrap();
+        assert_eq!(0.0, kmeans.inertia());
+
+        // More clusters than distinct observations leaves clusters with a duplicate.
+        let mut kmeans = KMeans::new(5);
+        kmeans.fit(&xs).unwrap();
+        assert_eq!(0.0, kmeans.inertia());
+    }
+
+    #[test]
+    fn test_errors() {
+        let xs = blobs(1, 4);
+        let mut kmeans = KMeans::new(4);
+
+        assert_eq!(Err(ModelError::NotFitted), kmeans.predict(&xs));
+        assert!(kmeans.fit(&xs).is_err());
+
+        let mut kmeans = KMeans::new(2);
+        kmeans.fit(&xs).unwrap();
+        assert!(kmeans.predict(&Matrix::zeros(1, 3)).is_err());
+
+        let mut infinite = xs.clone();
+        infinite[(0, 1)] = f64::INFINITY;
+        assert!(matches!(
+            kmeans.fit(&infinite),
+            Err(ModelError::InvalidInput(_))
+        ));
+        let mut mini = MiniBatchKMeans::new(2);
+        mini.partial_fit(&xs).unwrap();
+        assert!(mini.partial_fit(&infinite).is_err());
+    }
+
+    #[test]
+    fn test_mini_batch() {
+        let xs = blobs(2000, 5);
+        let mut mini = MiniBatchKMeans::new(3);
+        mini.set_options(MiniBatchOptions {
+            batch_size: 256,
+            ..MiniBatchOptions::default()
+        });
+        mini.fit(&xs).unwrap();
+
+        assert!(center_error(mini.cluster_centers()) < 0.1);
+        assert_eq!(mini.labels().to_vec(), mini.predict(&xs).unwrap());
+
+        let mut full = KMeans::new(3);
+        full.set_options(KMeansOptions {
+            restarts: 1,
+            ..KMeansOptions::default()
+        });
+        full.fit(&xs).unwrap();
+        assert!(mini.inertia() < 1.01 * full.inertia());
+    }
+
+    #[test]
+    fn test_partial_fit() {
+        let xs = blobs(300, 6);
+        let mut mini = MiniBatchKMeans::new(3);
+        assert!(mini.predict(&xs).is_err());
+
+        let rows: Vec<Array> = xs.iter().cloned().collect();
+        for chunk in rows.chunks(90) {
+            mini.partial_fit(&Matrix::from_rows(chunk.to_vec()))
+                .unwrap();
+        }
+
+        assert_eq!(10, mini.iterations());
+        assert!(center_error(mini.cluster_centers()) < 0.2);
+        assert!(mini.labels().is_empty());
+        assert!(mini.partial_fit(&Matrix::zeros(2, 3)).is_err());
+    }
+}
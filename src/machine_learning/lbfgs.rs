@@ -0,0 +1,328 @@
+//! L-BFGS - Limited-memory quasi-Newton minimization
+//!
+//! L-BFGS approximates the inverse Hessian of a smooth objective from the last few steps and
+//! gradient changes, which makes it nearly as fast as Newton's method without ever forming a
+//! Hessian. With an L1 penalty the objective is not differentiable at 0, and the orthant-wise
+//! variant OWL-QN of Andrew and Gao is used instead: every step stays in the orthant of the
+//! current point, so coefficients can become exactly 0.
+
+use crate::linalg::array::Array;
+
+use std::collections::VecDeque;
+
+/// The controls of L-BFGS.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LbfgsOptions {
+    /// The number of past steps used to approximate the Hessian.
+    pub memory: usize,
+    /// The largest absolute gradient component at which the minimum is reached.
+    pub tolerance: f64,
+    /// The maximum number of steps.
+    pub max_iterations: usize,
+}
+
+impl Default for LbfgsOptions {
+    fn default() -> Self {
+        LbfgsOptions {
+            memory: 10,
+            tolerance: 1e-6,
+            max_iterations: 500,
+        }
+    }
+}
+
+/// The result of a minimization.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Minimum {
+    /// The point with the smallest value found.
+    pub x: Array,
+    /// The value of the objective, including the L1 penalty, at `x`.
+    pub value: f64,
+    /// The largest absolute component of the (pseudo-)gradient at `x`.
+    pub gradient_norm: f64,
+    /// The number of steps taken.
+    pub iterations: usize,
+    /// Whether `gradient_norm` fell below the tolerance.
+    pub converged: bool,
+}
+
+/// The Armijo constant of the backtracking line search.
+const SUFFICIENT_DECREASE: f64 = 1e-4;
+
+/// The number of times the line search halves the step before giving up.
+const MAX_HALVINGS: usize = 60;
+
+/// Returns the minimum of `f(x) + sum_i l1_i |x_i|` found by L-BFGS, or by OWL-QN when any
+/// L1 weight is positive.
+///
+/// # Arguments
+///
+/// * `objective` - returns the value and the gradient of the smooth part `f` at a point.
+/// * `x0` - the starting point.
+/// * `l1` - the L1 penalty weight of every coordinate, or `None` for a smooth objective.
+/// * `options` - the controls of the solver.
+///
+/// # Panics
+///
+/// `l1` must have the length of `x0` and non-negative weights, and the memory must be
+/// positive, otherwise the code will panic.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::machine_learning::lbfgs::{minimize, LbfgsOptions};
+///
+/// // The Rosenbrock function has its minimum at (1, 1).
+/// let rosenbrock = |x: &Array| {
+///     let (a, b) = (x[0], x[1]);
+///     let value = (1.0 - a).powi(2) + 100.0 * (b - a * a).powi(2);
+///     let gradient = Array::from(&[
+///         -2.0 * (1.0 - a) - 400.0 * a * (b - a * a),
+///         200.0 * (b - a * a),
+///     ]);
+///     (value, gradient)
+/// };
+///
+/// let minimum = minimize(rosenbrock, Array::from(&[-1.2, 1.0]), None, &LbfgsOptions::default());
+///
+/// assert!(minimum.converged);
+/// assert!((minimum.x[0] - 1.0).abs() < 1e-5 && (minimum.x[1] - 1.0).abs() < 1e-5);
+/// ```
+pub fn minimize<F>(objective: F, x0: Array, l1: Option<&Array>, options: &LbfgsOptions) -> Minimum
+where
+    F: Fn(&Array) -> (f64, Array),
+{
+    let l1 = match l1 {
+        Some(weights) => weights.clone(),
+        None => Array::zeros(x0.len()),
+    };
+    assert!(
+        l1.len() == x0.len() && l1.iter().all(|&w| w >= 0.0),
+        "ERROR - minimize: There must be a non-negative L1 weight for every coordinate."
+    );
+    assert!(
+        options.memory > 0,
+        "ERROR - minimize: The memory must be positive."
+    );
+
+    let penalized = |x: &Array, f: f64| {
+        f + x
+            .iter()
+            .zip(l1.iter())
+            .map(|(xi, wi)| wi * xi.abs())
+            .sum::<f64>()
+    };
+
+    let mut x = x0;
+    let (f, mut gradient) = objective(&x);
+    let mut value = penalized(&x, f);
+    let mut steps: VecDeque<(Array, Array, f64)> = VecDeque::with_capacity(options.memory);
+
+    for iteration in 0..options.max_iterations {
+        let pseudo = pseudo_gradient(&x, &gradient, &l1);
+        let gradient_norm = largest_magnitude(&pseudo);
+        if gradient_norm <= options.tolerance {
+            return Minimum {
+                x,
+                value,
+                gradient_norm,
+                iterations: iteration,
+                converged: true,
+            };
+        }
+
+        let mut direction = two_loop(&pseudo, &steps);
+        // OWL-QN: only move coordinates in the direction of the negative pseudo-gradient.
+        for i in 0..direction.len() {
+            if l1[i] > 0.0 && direction[i] * pseudo[i] >= 0.0 {
+                direction[i] = 0.0;
+            }
+        }
+        if direction.dotp(&pseudo) >= 0.0 {
+            steps.clear();
+            direction = pseudo.scalar_mult(-1.0);
+        }
+
+        let orthant: Vec<f64> = x
+            .iter()
+            .zip(pseudo.iter())
+            .map(|(&xi, &pi)| if xi != 0.0 { xi.signum() } else { -pi.signum() })
+            .collect();
+
+        // The first step has no curvature information, so its length is bounded.
+        let mut t = if steps.is_empty() {
+            1.0 / gradient_norm.max(1.0)
+        } else {
+            1.0
+        };
+        let mut accepted = None;
+        for _ in 0..MAX_HALVINGS {
+            let mut candidate = x.plus(&direction.scalar_mult(t));
+            for i in 0..candidate.len() {
+                if l1[i] > 0.0 && candidate[i] * orthant[i] <= 0.0 {
+                    candidate[i] = 0.0;
+                }
+            }
+            let (f_new, gradient_new) = objective(&candidate);
+            let value_new = penalized(&candidate, f_new);
+            let decrease = pseudo.dotp(&candidate.minus(&x));
+            if value_new <= value + SUFFICIENT_DECREASE * decrease {
+                accepted = Some((candidate, gradient_new, value_new));
+                break;
+            }
+            t *= 0.5;
+        }
+
+        let (candidate, gradient_new, value_new) = match accepted {
+            Some(step) => step,
+            None => {
+                return Minimum {
+                    x,
+                    value,
+                    gradient_norm,
+                    iterations: iteration,
+                    converged: false,
+                }
+            }
+        };
+
+        let s = candidate.minus(&x);
+        let y = gradient_new.minus(&gradient);
+        let curvature = s.dotp(&y);
+        if curvature > f64::EPSILON * y.dotp(&y) {
+            if steps.len() == options.memory {
+                steps.pop_front();
+            }
+            steps.push_back((s, y, 1.0 / curvature));
+        }
+
+        x = candidate;
+        gradient = gradient_new;
+        value = value_new;
+    }
+
+    let gradient_norm = largest_magnitude(&pseudo_gradient(&x, &gradient, &l1));
+    Minimum {
+        x,
+        value,
+        gradient_norm,
+        iterations: options.max_iterations,
+        converged: gradient_norm <= options.tolerance,
+    }
+}
+
+/// Returns the largest absolute value.
+fn largest_magnitude(xs: &Array) -> f64 {
+    xs.iter().fold(0.0, |acc: f64, x| acc.max(x.abs()))
+}
+
+/// Returns the gradient of the smooth part plus the one-sided derivative of the L1 penalty that
+/// points downhill, or 0 where neither side does.
+fn pseudo_gradient(x: &Array, gradient: &Array, l1: &Array) -> Array {
+    Array::from_vec(
+        (0..x.len())
+            .map(|i| {
+                let (g, w) = (gradient[i], l1[i]);
+                if w == 0.0 {
+                    g
+                } else if x[i] > 0.0 {
+                    g + w
+                } else if x[i] < 0.0 {
+                    g - w
+                } else if g + w < 0.0 {
+                    g + w
+                } else if g - w > 0.0 {
+                    g - w
+                } else {
+                    0.0
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Returns the quasi-Newton direction `-H g`, where `H` is the inverse Hessian approximated
+/// from the stored steps `s`, gradient changes `y` and `1 / (s^T y)`.
+fn two_loop(gradient: &Array, steps: &VecDeque<(Array, Array, f64)>) -> Array {
+    let mut q = gradient.clone();
+    let mut alphas = Vec::with_capacity(steps.len());
+
+    for (s, y, rho) in steps.iter().rev() {
+        let alpha = rho * s.dotp(&q);
+        q = q.minus(&y.scalar_mult(alpha));
+        alphas.push(alpha);
+    }
+
+    let gamma = match steps.back() {
+        Some((_, y, rho)) => 1.0 / (rho * y.dotp(y)),
+        None => 1.0,
+    };
+    let mut r = q.scalar_mult(gamma);
+
+    for ((s, y, rho), alpha) in steps.iter().zip(alphas.iter().rev()) {
+        let beta = rho * y.dotp(&r);
+        r = r.plus(&s.scalar_mult(alpha - beta));
+    }
+
+    r.scalar_mult(-1.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Returns the value and gradient of `|A x - b|^2 / 2` for an ill-conditioned `A`.
+    fn quadratic(x: &Array) -> (f64, Array) {
+        let scales = [1.0, 10.0, 100.0];
+        let targets = [1.0, -2.0, 3.0];
+        let residuals: Vec<f64> = (0..3).map(|i| scales[i] * x[i] - targets[i]).collect();
+        let value = 0.5 * residuals.iter().map(|r| r * r).sum::<f64>();
+        let gradient = Array::from_vec((0..3).map(|i| scales[i] * residuals[i]).collect());
+        (value, gradient)
+    }
+
+    #[test]
+    fn test_smooth() {
+        let minimum = minimize(quadratic, Array::zeros(3), None, &LbfgsOptions::default());
+
+        assert!(minimum.converged);
+        let expected = [1.0, -0.2, 0.03];
+        for (x, e) in minimum.x.iter().zip(expected.iter()) {
+            assert!((x - e).abs() < 1e-8);
+        }
+        assert!(minimum.value < 1e-12);
+    }
+
+    #[test]
+    fn test_l1() {
+        // Every coordinate is minimized separately: the minimum of (s x - t)^2 / 2 + w |x| is
+        // x = sign(t) max(s |t| - w, 0) / s^2.
+        let l1 = Array::from(&[1.5, 1.0, 0.0]);
+        let minimum = minimize(
+            quadratic,
+            Array::zeros(3),
+            Some(&l1),
+            &LbfgsOptions::default(),
+        );
+
+        assert!(minimum.converged);
+        assert_eq!(0.0, minimum.x[0]);
+        assert!((minimum.x[1] + 0.19).abs() < 1e-8);
+        assert!((minimum.x[2] - 0.03).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_iteration_limit() {
+        let options = LbfgsOptions {
+            max_iterations: 2,
+            tolerance: 0.0,
+            ..LbfgsOptions::default()
+        };
+        let minimum = minimize(quadratic, Array::zeros(3), None, &options);
+
+        assert!(!minimum.converged);
+        assert_eq!(2, minimum.iterations);
+        assert!(minimum.value < quadratic(&Array::zeros(3)).0);
+    }
+}
@@ -1,22 +1,37 @@
+//! Linear Regression - Linear models fitted by least squares
+
 use crate::linalg::array::Array;
-use crate::machine_learning::model::Model;
+use crate::linalg::matrix::Matrix;
+use crate::linalg::methods::householder_least_squares;
+use crate::machine_learning::model::{
+    check_features, check_targets, Estimator, ModelError, Predictor, Result,
+};
 
-///
+/// Linear regression with a single feature: `y = slope * x + feature`, where `feature` is the
+/// intercept.
 pub struct SimpleLinearRegression {
     slope: f64,
     feature: f64,
+    fitted: bool,
 }
 
 impl SimpleLinearRegression {
+    /// Returns an unfitted model.
     pub fn new() -> Self {
         SimpleLinearRegression {
             slope: 0.0,
             feature: 0.0,
+            fitted: false,
         }
     }
 
+    /// Returns a fitted model with the given slope and intercept.
     pub fn from(slope: f64, feature: f64) -> Self {
-        SimpleLinearRegression { slope, feature }
+        SimpleLinearRegression {
+            slope,
+            feature,
+            fitted: true,
+        }
     }
 
     pub fn get_slope(&self) -> f64 {
@@ -28,19 +43,300 @@ impl SimpleLinearRegression {
     }
 }
 
-impl Model for SimpleLinearRegression {
-    fn predict(&mut self, observed_xs: Array) -> Array {
-        observed_xs.scalar_mult(self.slope).scalar_add(self.feature)
+impl Estimator for SimpleLinearRegression {
+    fn is_fitted(&self) -> bool {
+        self.fitted
     }
+}
 
-    fn optimize(&mut self, xs: Array, ys: Array) {
-        self.slope = xs
-            .scalar_sub(xs.average())
-            .mult(&ys.scalar_sub(ys.average()))
-            .sum()
-            / xs.scalar_sub(xs.average())
-                .mult(&xs.scalar_sub(xs.average()))
-                .sum();
+impl Predictor for SimpleLinearRegression {
+    /// Fits the line to samples with exactly one feature.
+    ///
+    /// The samples must have at least two distinct values of the feature.
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        check_targets(xs, ys)?;
+        check_features(xs, 1)?;
+
+        let xs = xs.column(0);
+        let dx = xs.scalar_sub(xs.average());
+        let sxx = dx.dotp(&dx);
+        if sxx == 0.0 {
+            return Err(ModelError::InvalidInput(
+                "a linear fit needs at least two distinct values of x".to_string(),
+            ));
+        }
+
+        self.slope = dx.dotp(&ys.scalar_sub(ys.average())) / sxx;
         self.feature = ys.average() - (self.slope * xs.average());
+        self.fitted = true;
+        Ok(())
+    }
+
+    fn predict(&self, xs: &Matrix) -> Result<Array> {
+        self.check_fitted()?;
+        check_features(xs, 1)?;
+
+        Ok(xs
+            .column(0)
+            .scalar_mult(self.slope)
+            .scalar_add(self.feature))
+    }
+}
+
+/// Linear regression with any number of features, fitted by ordinary least squares.
+///
+/// The coefficients minimize the sum of squared residuals. They are computed from a QR
+/// decomposition of the feature matrix instead of the normal equations, which would square its
+/// condition number.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::linear_regression::LinearRegression;
+/// use moonalloy::machine_learning::model::Predictor;
+///
+/// let xs = Matrix::new(&[
+///     Array::from(&[0.0, 1.0]),
+///     Array::from(&[1.0, 0.0]),
+///     Array::from(&[1.0, 1.0]),
+///     Array::from(&[2.0, 1.0]),
+/// ]);
+/// let ys = Array::from(&[4.0, 3.0, 6.0, 8.0]);
+///
+/// let mut model = LinearRegression::new(true);
+/// model.fit(&xs, &ys).unwrap();
+///
+/// // y = 1 + 2 x0 + 3 x1
+/// assert!((model.get_intercept() - 1.0).abs() < 1e-12);
+/// assert!((model.get_coefficients()[1] - 3.0).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearRegression {
+    fit_intercept: bool,
+    coefficients: Array,
+    intercept: f64,
+    residuals: Array,
+}
+
+impl LinearRegression {
+    /// Returns an unfitted model.
+    ///
+    /// # Arguments
+    ///
+    /// * `fit_intercept` - whether to fit an intercept, or to fit a model through the origin.
+    pub fn new(fit_intercept: bool) -> Self {
+        LinearRegression {
+            fit_intercept,
+            coefficients: Array::new(),
+            intercept: 0.0,
+            residuals: Array::new(),
+        }
+    }
+
+    /// Returns a model with the given coefficients and intercept.
+    pub fn from(coefficients: Array, intercept: f64) -> Self {
+        LinearRegression {
+            fit_intercept: true,
+            coefficients,
+            intercept,
+            residuals: Array::new(),
+        }
+    }
+
+    /// Returns the coefficient of every feature.
+    pub fn get_coefficients(&self) -> &Array {
+        &self.coefficients
+    }
+
+    /// Returns the intercept, which is 0 for a model without intercept.
+    pub fn get_intercept(&self) -> f64 {
+        self.intercept
+    }
+
+    /// Returns the residuals `ys - predict(xs)` of the samples the model was fitted to.
+    pub fn get_residuals(&self) -> &Array {
+        &self.residuals
+    }
+}
+
+impl Estimator for LinearRegression {
+    fn is_fitted(&self) -> bool {
+        !self.coefficients.is_empty()
+    }
+}
+
+impl Predictor for LinearRegression {
+    /// Fits the model to samples.
+    ///
+    /// With an intercept, the features and targets are centered first, and the intercept is
+    /// recovered from their means.
+    ///
+    /// There must be a target for every sample, more samples than features, and the features
+    /// must be linearly independent, otherwise an error is returned.
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        check_targets(xs, ys)?;
+        let (rows, cols) = xs.dimensions();
+        let parameters = cols + self.fit_intercept as usize;
+        if cols == 0 || rows < parameters {
+            return Err(ModelError::InvalidInput(format!(
+                "{} samples cannot determine {} parameters",
+                rows, parameters
+            )));
+        }
+
+        let dependent =
+            || ModelError::InvalidInput("the features are linearly dependent".to_string());
+        if self.fit_intercept {
+            let means = Array::from_vec((0..cols).map(|j| xs.column(j).average()).collect());
+            let centered = Matrix::from_rows(xs.iter().map(|row| row.minus(&means)).collect());
+            let y_mean = ys.average();
+
+            self.coefficients = householder_least_squares(&centered, &ys.scalar_sub(y_mean))
+                .ok_or_else(dependent)?;
+            self.intercept = y_mean - means.dotp(&self.coefficients);
+        } else {
+            self.coefficients = householder_least_squares(xs, ys).ok_or_else(dependent)?;
+            self.intercept = 0.0;
+        }
+
+        self.residuals = ys.minus(&self.predict(xs)?);
+        Ok(())
+    }
+
+    fn predict(&self, xs: &Matrix) -> Result<Array> {
+        self.check_fitted()?;
+        check_features(xs, self.coefficients.len())?;
+
+        Ok(Array::from_vec(
+            xs.iter()
+                .map(|row| row.dotp(&self.coefficients) + self.intercept)
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn features() -> Matrix {
+        Matrix::new(&[
+            Array::from(&[1.0, 2.0, 0.5]),
+            Array::from(&[2.0, 0.0, 1.5]),
+            Array::from(&[3.0, 1.0, -1.0]),
+            Array::from(&[4.0, 3.0, 2.0]),
+            Array::from(&[5.0, 1.0, 0.0]),
+            Array::from(&[6.0, 2.0, 1.0]),
+        ])
+    }
+
+    #[test]
+    fn test_exact_fit() {
+        let xs = features();
+        let ys = Array::from_vec(
+            xs.iter()
+                .map(|row| 0.5 + 2.0 * row[0] - row[1] + 4.0 * row[2])
+                .collect(),
+        );
+
+        let mut model = LinearRegression::new(true);
+        model.fit(&xs, &ys).unwrap();
+
+        let expected = [2.0, -1.0, 4.0];
+        for (e, c) in expected.iter().zip(model.get_coefficients().iter()) {
+            assert!((e - c).abs() < 1e-12);
+        }
+        assert!((model.get_intercept() - 0.5).abs() < 1e-12);
+        assert!(model.get_residuals().norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_residuals_are_orthogonal() {
+        let xs = features();
+        let ys = Array::from(&[1.0, 4.0, 2.0, 8.0, 5.0, 7.0]);
+
+        let mut model = LinearRegression::new(true);
+        model.fit(&xs, &ys).unwrap();
+        let residuals = model.get_residuals();
+
+        // The residuals of a least-squares fit are orthogonal to the features and the ones.
+        assert!(residuals.sum().abs() < 1e-12);
+        for j in 0..3 {
+            assert!(residuals.dotp(&xs.column(j)).abs() < 1e-12);
+        }
+        assert_eq!(&ys.minus(&model.predict(&xs).unwrap()), residuals);
+    }
+
+    #[test]
+    fn test_without_intercept() {
+        let xs = Matrix::new(&[
+            Array::from(&[1.0]),
+            Array::from(&[2.0]),
+            Array::from(&[3.0]),
+        ]);
+        let ys = Array::from(&[2.0, 3.0, 7.0]);
+
+        let mut model = LinearRegression::new(false);
+        model.fit(&xs, &ys).unwrap();
+
+        // The slope through the origin is sum(x y) / sum(x^2) = 29 / 14.
+        assert!((model.get_coefficients()[0] - 29.0 / 14.0).abs() < 1e-12);
+        assert_eq!(0.0, model.get_intercept());
+    }
+
+    #[test]
+    fn test_agrees_with_simple_linear_regression() {
+        let xs = Array::from(&[1.0, 2.0, 4.0, 5.0, 7.0]);
+        let ys = Array::from(&[1.5, 2.0, 4.5, 4.0, 8.0]);
+
+        let column = Matrix::from_rows(xs.iter().map(|&x| Array::from(&[x])).collect());
+        let mut simple = SimpleLinearRegression::new();
+        simple.fit(&column, &ys).unwrap();
+
+        let mut model = LinearRegression::new(true);
+        model.fit(&column, &ys).unwrap();
+
+        assert!((simple.get_slope() - model.get_coefficients()[0]).abs() < 1e-12);
+        assert!((simple.get_feature() - model.get_intercept()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_errors() {
+        let xs = features();
+        let ys = Array::from(&[1.0, 4.0, 2.0, 8.0, 5.0, 7.0]);
+        let mut model = LinearRegression::new(true);
+
+        assert!(!model.is_fitted());
+        assert_eq!(Err(ModelError::NotFitted), model.predict(&xs));
+        assert!(model.fit(&xs, &ys.splice(0, 5)).is_err());
+
+        let dependent = Matrix::from_rows(
+            xs.iter()
+                .map(|row| Array::from(&[row[0], 2.0 * row[0]]))
+                .collect(),
+        );
+        assert!(model.fit(&dependent, &ys).is_err());
+
+        model.fit(&xs, &ys).unwrap();
+        assert!(model.is_fitted());
+        assert!(model.predict(&dependent).is_err());
+    }
+
+    #[test]
+    fn test_simple_linear_regression() {
+        let xs = Matrix::from_rows([1.0, 2.0, 3.0].iter().map(|&x| Array::from(&[x])).collect());
+        let ys = Array::from(&[3.0, 5.0, 7.0]);
+        let mut model = SimpleLinearRegression::new();
+
+        assert_eq!(Err(ModelError::NotFitted), model.predict(&xs));
+        model.fit(&xs, &ys).unwrap();
+
+        assert_eq!(2.0, model.get_slope());
+        assert_eq!(1.0, model.get_feature());
+        assert_eq!(ys, model.predict(&xs).unwrap());
+        assert_eq!(Ok(1.0), model.score(&xs, &ys));
+        assert!(model.fit(&features(), &Array::zeros(6)).is_err());
     }
 }
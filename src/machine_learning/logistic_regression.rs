@@ -0,0 +1,933 @@
+//! Logistic Regression - Linear classifiers fitted by maximum likelihood
+//!
+//! `LogisticRegression` models the probability of the larger of two class labels as
+//! `sigmoid(x w + b)`, and `SoftmaxRegression` (multinomial logistic regression) models the
+//! probability of each of `K` classes as `softmax(W x + b)`. Both minimize the weighted
+//! log-loss summed over the `n` samples and divided by `n`, plus a penalty on the coefficients:
+//!
+//! * `Penalty::L2(alpha)` adds `alpha |w|^2 / 2`, which shrinks all coefficients.
+//! * `Penalty::L1(alpha)` adds `alpha |w|_1`, which sets some coefficients to 0.
+//!
+//! The intercepts are never penalized. Without a penalty, the coefficients of classes that are
+//! separated by a hyperplane grow without bound, so a small L2 penalty is a safe default.
+
+use crate::linalg::array::Array;
+use crate::linalg::matrix::Matrix;
+use crate::linalg::methods::{cholesky_decomposition, cholesky_solve};
+use crate::machine_learning::lbfgs::{minimize, LbfgsOptions, Minimum};
+use crate::machine_learning::model::{
+    check_features, check_targets, encode_classes, ClassWeight, Classifier, Estimator, ModelError,
+    Result,
+};
+
+/// The penalty on the coefficients.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Penalty {
+    /// No penalty.
+    None,
+    /// `alpha |w|_1`.
+    L1(f64),
+    /// `alpha |w|^2 / 2`.
+    L2(f64),
+}
+
+/// How to minimize the penalized log-loss.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogisticSolver {
+    /// Limited-memory BFGS, or OWL-QN with an L1 penalty. This is the default.
+    #[default]
+    Lbfgs,
+    /// Newton's method, also called iteratively reweighted least squares. It converges in a
+    /// few iterations, but every iteration solves a system with the squared number of
+    /// parameters, and it does not support an L1 penalty.
+    Newton,
+}
+
+/// The settings shared by both classifiers.
+#[derive(Debug, Clone, PartialEq)]
+struct Settings {
+    penalty: Penalty,
+    fit_intercept: bool,
+    solver: LogisticSolver,
+    class_weight: ClassWeight,
+    options: LbfgsOptions,
+}
+
+impl Settings {
+    fn new(penalty: Penalty, fit_intercept: bool) -> Settings {
+        let alpha = match penalty {
+            Penalty::None => 0.0,
+            Penalty::L1(alpha) | Penalty::L2(alpha) => alpha,
+        };
+        assert!(
+            alpha >= 0.0 && alpha.is_finite(),
+            "ERROR - Logistic regression: The penalty must be non-negative."
+        );
+
+        Settings {
+            penalty,
+            fit_intercept,
+            solver: LogisticSolver::default(),
+            class_weight: ClassWeight::default(),
+            options: LbfgsOptions::default(),
+        }
+    }
+
+    fn set_options(&mut self, options: LbfgsOptions) {
+        assert!(
+            options.memory > 0 && options.tolerance >= 0.0,
+            "ERROR - Logistic regression: The memory must be positive and the tolerance \
+             non-negative."
+        );
+        self.options = options;
+    }
+}
+
+/// The samples, their class labels and their weights.
+struct Problem<'a> {
+    xs: &'a Matrix,
+    labels: Vec<usize>,
+    weights: Array,
+    /// The number of logits per sample: 1 for `LogisticRegression`, otherwise one per class.
+    logits: usize,
+    l2: f64,
+    fit_intercept: bool,
+}
+
+impl<'a> Problem<'a> {
+    fn features(&self) -> usize {
+        self.xs.dimensions().1
+    }
+
+    /// Returns the logits of a sample. Every logit has a block of `features + 1` parameters:
+    /// the coefficients followed by the intercept.
+    fn logits_of(&self, row: &Array, params: &Array) -> Vec<f64> {
+        logits(row, params, self.logits, self.features())
+    }
+
+    /// Returns the loss of a sample and its derivatives with respect to the logits.
+    fn loss(&self, z: &[f64], label: usize) -> (f64, Vec<f64>) {
+        if self.logits == 1 {
+            let y = label as f64;
+            let p = sigmoid(z[0]);
+            (softplus(z[0]) - y * z[0], vec![p - y])
+        } else {
+            let probabilities = softmax(z);
+            let log_normalizer = log_sum_exp(z);
+            let mut derivatives = probabilities;
+            derivatives[label] -= 1.0;
+            (log_normalizer - z[label], derivatives)
+        }
+    }
+
+    /// Returns the penalized mean loss and its gradient, without the L1 penalty.
+    fn objective(&self, params: &Array) -> (f64, Array) {
+        let p = self.features();
+        let n = self.xs.dimensions().0 as f64;
+        let mut loss = 0.0;
+        let mut gradient = Array::zeros(params.len());
+
+        for ((row, &label), &weight) in self.xs.iter().zip(&self.labels).zip(self.weights.iter()) {
+            let z = self.logits_of(row, params);
+            let (l, derivatives) = self.loss(&z, label);
+            loss += weight * l;
+            for (k, d) in derivatives.iter().enumerate() {
+                let scale = weight * d / n;
+                let block = k * (p + 1);
+                for (j, x) in row.iter().enumerate() {
+                    gradient[block + j] += scale * x;
+                }
+                gradient[block + p] += scale;
+            }
+        }
+        loss /= n;
+
+        for k in 0..self.logits {
+            let block = k * (p + 1);
+            for j in 0..p {
+                let w = params[block + j];
+                loss += 0.5 * self.l2 * w * w;
+                gradient[block + j] += self.l2 * w;
+            }
+            if !self.fit_intercept {
+                gradient[block + p] = 0.0;
+            }
+        }
+
+        (loss, gradient)
+    }
+
+    /// Returns the Hessian of the penalized mean loss.
+    fn hessian(&self, params: &Array) -> Matrix {
+        let p = self.features();
+        let n = self.xs.dimensions().0 as f64;
+        let size = params.len();
+        let mut hessian = Matrix::zeros(size, size);
+
+        for (row, &weight) in self.xs.iter().zip(self.weights.iter()) {
+            let z = self.logits_of(row, params);
+            let curvature: Vec<Vec<f64>> = if self.logits == 1 {
+                let s = sigmoid(z[0]);
+                vec![vec![s * (1.0 - s)]]
+            } else {
+                let q = softmax(&z);
+                (0..self.logits)
+                    .map(|k| {
+                        (0..self.logits)
+                            .map(|l| q[k] * ((k == l) as u8 as f64 - q[l]))
+                            .collect()
+                    })
+                    .collect()
+            };
+
+            let extended: Vec<f64> = row.iter().copied().chain(std::iter::once(1.0)).collect();
+            for (k, curvature_k) in curvature.iter().enumerate() {
+                for (l, &c) in curvature_k.iter().enumerate() {
+                    let c = weight * c / n;
+                    for (a, xa) in extended.iter().enumerate() {
+                        for (b, xb) in extended.iter().enumerate() {
+                            hessian[(k * (p + 1) + a, l * (p + 1) + b)] += c * xa * xb;
+                        }
+                    }
+                }
+            }
+        }
+
+        for k in 0..self.logits {
+            let block = k * (p + 1);
+            for j in 0..p {
+                hessian[(block + j, block + j)] += self.l2;
+            }
+            if !self.fit_intercept {
+                // The intercept stays at 0, so it is decoupled from the other parameters.
+                for i in 0..size {
+                    hessian[(block + p, i)] = 0.0;
+                    hessian[(i, block + p)] = 0.0;
+                }
+                hessian[(block + p, block + p)] = 1.0;
+            }
+        }
+
+        hessian
+    }
+}
+
+/// Returns the logits of a sample for parameters with a block of `features + 1` per logit.
+fn logits(row: &Array, params: &Array, count: usize, features: usize) -> Vec<f64> {
+    (0..count)
+        .map(|k| {
+            let block = &params.as_slice()[k * (features + 1)..(k + 1) * (features + 1)];
+            block[features]
+                + row
+                    .iter()
+                    .zip(&block[..features])
+                    .map(|(x, w)| x * w)
+                    .sum::<f64>()
+        })
+        .collect()
+}
+
+/// Returns `1 / (1 + exp(-z))` without overflow.
+fn sigmoid(z: f64) -> f64 {
+    if z >= 0.0 {
+        1.0 / (1.0 + (-z).exp())
+    } else {
+        let e = z.exp();
+        e / (1.0 + e)
+    }
+}
+
+/// Returns `ln(1 + exp(z))` without overflow.
+fn softplus(z: f64) -> f64 {
+    z.max(0.0) + (-z.abs()).exp().ln_1p()
+}
+
+/// Returns `ln(sum_k exp(z_k))` without overflow.
+fn log_sum_exp(z: &[f64]) -> f64 {
+    let largest = z.iter().fold(f64::NEG_INFINITY, |acc, &v| acc.max(v));
+    largest + z.iter().map(|v| (v - largest).exp()).sum::<f64>().ln()
+}
+
+/// Returns the probabilities `exp(z_k) / sum_l exp(z_l)`.
+fn softmax(z: &[f64]) -> Vec<f64> {
+    let largest = z.iter().fold(f64::NEG_INFINITY, |acc, &v| acc.max(v));
+    let exps: Vec<f64> = z.iter().map(|v| (v - largest).exp()).collect();
+    let total: f64 = exps.iter().sum();
+    exps.iter().map(|e| e / total).collect()
+}
+
+/// Minimizes a smooth objective by Newton's method with a backtracking line search.
+///
+/// When the Hessian is singular, a multiple of the identity is added until it is positive
+/// definite, which turns the step towards gradient descent.
+fn newton<F, H>(objective: F, hessian: H, x0: Array, options: &LbfgsOptions) -> Minimum
+where
+    F: Fn(&Array) -> (f64, Array),
+    H: Fn(&Array) -> Matrix,
+{
+    let mut x = x0;
+    let (mut value, mut gradient) = objective(&x);
+    let largest = |g: &Array| g.iter().fold(0.0, |acc: f64, v| acc.max(v.abs()));
+
+    for iteration in 0..options.max_iterations {
+        let gradient_norm = largest(&gradient);
+        let stop = |x: Array, converged| Minimum {
+            x,
+            value,
+            gradient_norm,
+            iterations: iteration,
+            converged,
+        };
+        if gradient_norm <= options.tolerance {
+            return stop(x, true);
+        }
+
+        let mut h = hessian(&x);
+        let size = gradient.len();
+        let scale = 1.0 + (0..size).fold(0.0, |acc: f64, i| acc.max(h[i][i].abs()));
+        let mut jitter = 0.0;
+        let factor = loop {
+            if let Some(factor) = cholesky_decomposition(&h) {
+                break Some(factor);
+            }
+            let added = if jitter == 0.0 {
+                1e-10 * scale
+            } else {
+                9.0 * jitter
+            };
+            if jitter > scale {
+                break None;
+            }
+            for i in 0..size {
+                h[(i, i)] += added;
+            }
+            jitter += added;
+        };
+        let step = match factor {
+            Some(factor) => cholesky_solve(&factor, &gradient.scalar_mult(-1.0)),
+            None => return stop(x, false),
+        };
+
+        let slope = gradient.dotp(&step);
+        let mut t = 1.0;
+        let mut accepted = None;
+        for _ in 0..60 {
+            let candidate = x.plus(&step.scalar_mult(t));
+            let (value_new, gradient_new) = objective(&candidate);
+            if value_new <= value + 1e-4 * t * slope {
+                accepted = Some((candidate, value_new, gradient_new));
+                break;
+            }
+            t *= 0.5;
+        }
+        match accepted {
+            Some((candidate, value_new, gradient_new)) => {
+                x = candidate;
+                value = value_new;
+                gradient = gradient_new;
+            }
+            None => return stop(x, false),
+        }
+    }
+
+    let gradient_norm = largest(&gradient);
+    Minimum {
+        x,
+        value,
+        gradient_norm,
+        iterations: options.max_iterations,
+        converged: gradient_norm <= options.tolerance,
+    }
+}
+
+/// Fits the parameters of a classifier with the given number of logits per sample, starting
+/// from 0.
+fn fit_parameters(
+    settings: &Settings,
+    xs: &Matrix,
+    labels: Vec<usize>,
+    weights: Array,
+    logits: usize,
+) -> Result<Minimum> {
+    let (l1, l2) = match settings.penalty {
+        Penalty::None => (0.0, 0.0),
+        Penalty::L1(alpha) => (alpha, 0.0),
+        Penalty::L2(alpha) => (0.0, alpha),
+    };
+    let problem = Problem {
+        xs,
+        labels,
+        weights,
+        logits,
+        l2,
+        fit_intercept: settings.fit_intercept,
+    };
+
+    let p = problem.features();
+    let x0 = Array::zeros(logits * (p + 1));
+    let objective = |params: &Array| problem.objective(params);
+
+    match settings.solver {
+        LogisticSolver::Lbfgs => {
+            let l1_weights = Array::from_vec(
+                (0..x0.len())
+                    .map(|i| if i % (p + 1) == p { 0.0 } else { l1 })
+                    .collect(),
+            );
+            Ok(minimize(
+                objective,
+                x0,
+                Some(&l1_weights),
+                &settings.options,
+            ))
+        }
+        LogisticSolver::Newton => {
+            if l1 > 0.0 {
+                return Err(ModelError::InvalidInput(
+                    "the Newton solver does not support an L1 penalty".to_string(),
+                ));
+            }
+            let hessian = |params: &Array| problem.hessian(params);
+            Ok(newton(objective, hessian, x0, &settings.options))
+        }
+    }
+}
+
+/// Binary logistic regression, which predicts the probability of the larger of two class
+/// labels as `sigmoid(x w + b)`.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::logistic_regression::{LogisticRegression, Penalty};
+/// use moonalloy::machine_learning::model::Classifier;
+///
+/// // Hours of study and whether the exam was passed.
+/// let xs = Matrix::from_rows(
+///     [0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.5, 4.0, 4.5, 5.0]
+///         .iter()
+///         .map(|&x| Array::from(&[x]))
+///         .collect(),
+/// );
+/// let ys = Array::from(&[0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0]);
+///
+/// let mut model = LogisticRegression::new(Penalty::None, true);
+/// model.fit(&xs, &ys).unwrap();
+///
+/// let new = Matrix::new(&[Array::from(&[1.0]), Array::from(&[4.5])]);
+/// assert_eq!(Array::from(&[0.0, 1.0]), model.predict(&new).unwrap());
+/// assert!(model.predict_proba(&new).unwrap()[1][1] > 0.8);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogisticRegression {
+    settings: Settings,
+    classes: Array,
+    coefficients: Array,
+    intercept: f64,
+    iterations: usize,
+    converged: bool,
+}
+
+impl LogisticRegression {
+    /// Returns an unfitted model.
+    ///
+    /// # Arguments
+    ///
+    /// * `penalty` - the penalty on the coefficients.
+    /// * `fit_intercept` - whether to fit an intercept, or to fix it at 0.
+    ///
+    /// # Panics
+    ///
+    /// The strength of the penalty must be non-negative, otherwise the code will panic.
+    pub fn new(penalty: Penalty, fit_intercept: bool) -> Self {
+        LogisticRegression {
+            settings: Settings::new(penalty, fit_intercept),
+            classes: Array::new(),
+            coefficients: Array::new(),
+            intercept: 0.0,
+            iterations: 0,
+            converged: false,
+        }
+    }
+
+    /// Sets how the log-loss is minimized.
+    pub fn set_solver(&mut self, solver: LogisticSolver) {
+        self.settings.solver = solver;
+    }
+
+    /// Sets the weights of the classes, e.g. `ClassWeight::Balanced` for imbalanced data.
+    pub fn set_class_weight(&mut self, class_weight: ClassWeight) {
+        self.settings.class_weight = class_weight;
+    }
+
+    /// Sets the controls of the solver. Newton's method ignores the memory.
+    ///
+    /// # Panics
+    ///
+    /// The memory must be positive and the tolerance non-negative, otherwise the code will
+    /// panic.
+    pub fn set_options(&mut self, options: LbfgsOptions) {
+        self.settings.set_options(options);
+    }
+
+    /// Returns the coefficient of every feature.
+    pub fn get_coefficients(&self) -> &Array {
+        &self.coefficients
+    }
+
+    /// Returns the intercept.
+    pub fn get_intercept(&self) -> f64 {
+        self.intercept
+    }
+
+    /// Returns the number of iterations of the last fit.
+    pub fn get_iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Returns whether the last fit reached the tolerance within the maximum number of
+    /// iterations.
+    pub fn has_converged(&self) -> bool {
+        self.converged
+    }
+
+    /// Returns the log-odds `x w + b` of the larger class label for every sample.
+    pub fn decision_function(&self, xs: &Matrix) -> Result<Array> {
+        self.check_fitted()?;
+        check_features(xs, self.coefficients.len())?;
+
+        Ok(Array::from_vec(
+            xs.iter()
+                .map(|row| row.dotp(&self.coefficients) + self.intercept)
+                .collect(),
+        ))
+    }
+}
+
+impl Estimator for LogisticRegression {
+    fn is_fitted(&self) -> bool {
+        !self.classes.is_empty()
+    }
+}
+
+impl Classifier for LogisticRegression {
+    /// Fits the model to samples with exactly two class labels.
+    ///
+    /// The fit succeeds even if the solver did not converge, which `has_converged` reports.
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        check_targets(xs, ys)?;
+        let (classes, labels) = encode_classes(ys);
+        if classes.len() != 2 {
+            return Err(ModelError::InvalidInput(format!(
+                "logistic regression needs 2 classes but there are {}",
+                classes.len()
+            )));
+        }
+        let weights = self
+            .settings
+            .class_weight
+            .sample_weights(&classes, &labels)?;
+
+        let minimum = fit_parameters(&self.settings, xs, labels, weights, 1)?;
+        let p = xs.dimensions().1;
+        self.coefficients = Array::from(&minimum.x.as_slice()[..p]);
+        self.intercept = minimum.x[p];
+        self.iterations = minimum.iterations;
+        self.converged = minimum.converged;
+        self.classes = classes;
+        Ok(())
+    }
+
+    fn classes(&self) -> Result<Array> {
+        self.check_fitted()?;
+        Ok(self.classes.clone())
+    }
+
+    fn predict_proba(&self, xs: &Matrix) -> Result<Matrix> {
+        let scores = self.decision_function(xs)?;
+        Ok(Matrix::from_rows(
+            scores
+                .iter()
+                .map(|&z| Array::from(&[sigmoid(-z), sigmoid(z)]))
+                .collect(),
+        ))
+    }
+}
+
+/// Multinomial logistic regression, which predicts the probabilities of `K` class labels as
+/// `softmax(W x + b)`.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::logistic_regression::{Penalty, SoftmaxRegression};
+/// use moonalloy::machine_learning::model::Classifier;
+///
+/// let xs = Matrix::new(&[
+///     Array::from(&[0.0, 0.2]),
+///     Array::from(&[0.3, -0.1]),
+///     Array::from(&[3.0, 0.1]),
+///     Array::from(&[2.8, 0.4]),
+///     Array::from(&[0.1, 3.0]),
+///     Array::from(&[-0.2, 2.7]),
+/// ]);
+/// let ys = Array::from(&[0.0, 0.0, 1.0, 1.0, 2.0, 2.0]);
+///
+/// let mut model = SoftmaxRegression::new(Penalty::L2(0.01), true);
+/// model.fit(&xs, &ys).unwrap();
+///
+/// assert_eq!(ys, model.predict(&xs).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoftmaxRegression {
+    settings: Settings,
+    classes: Array,
+    coefficients: Matrix,
+    intercepts: Array,
+    iterations: usize,
+    converged: bool,
+}
+
+impl SoftmaxRegression {
+    /// Returns an unfitted model.
+    ///
+    /// # Arguments
+    ///
+    /// * `penalty` - the penalty on the coefficients.
+    /// * `fit_intercept` - whether to fit the intercepts, or to fix them at 0.
+    ///
+    /// # Panics
+    ///
+    /// The strength of the penalty must be non-negative, otherwise the code will panic.
+    pub fn new(penalty: Penalty, fit_intercept: bool) -> Self {
+        SoftmaxRegression {
+            settings: Settings::new(penalty, fit_intercept),
+            classes: Array::new(),
+            coefficients: Matrix::zeros(0, 0),
+            intercepts: Array::new(),
+            iterations: 0,
+            converged: false,
+        }
+    }
+
+    /// Sets how the log-loss is minimized.
+    pub fn set_solver(&mut self, solver: LogisticSolver) {
+        self.settings.solver = solver;
+    }
+
+    /// Sets the weights of the classes, e.g. `ClassWeight::Balanced` for imbalanced data.
+    pub fn set_class_weight(&mut self, class_weight: ClassWeight) {
+        self.settings.class_weight = class_weight;
+    }
+
+    /// Sets the controls of the solver. Newton's method ignores the memory.
+    ///
+    /// # Panics
+    ///
+    /// The memory must be positive and the tolerance non-negative, otherwise the code will
+    /// panic.
+    pub fn set_options(&mut self, options: LbfgsOptions) {
+        self.settings.set_options(options);
+    }
+
+    /// Returns the coefficients, with a row for every class and a column for every feature.
+    pub fn get_coefficients(&self) -> &Matrix {
+        &self.coefficients
+    }
+
+    /// Returns the intercept of every class.
+    pub fn get_intercepts(&self) -> &Array {
+        &self.intercepts
+    }
+
+    /// Returns the number of iterations of the last fit.
+    pub fn get_iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Returns whether the last fit reached the tolerance within the maximum number of
+    /// iterations.
+    pub fn has_converged(&self) -> bool {
+        self.converged
+    }
+
+    /// Returns the logits `W x + b` of every class for every sample, with a row for every
+    /// sample.
+    pub fn decision_function(&self, xs: &Matrix) -> Result<Matrix> {
+        self.check_fitted()?;
+        check_features(xs, self.coefficients.dimensions().1)?;
+
+        Ok(Matrix::from_rows(
+            xs.iter()
+                .map(|row| {
+                    Array::from_vec(
+                        self.coefficients
+                            .iter()
+                            .zip(self.intercepts.iter())
+                            .map(|(w, b)| row.dotp(w) + b)
+                            .collect(),
+                    )
+                })
+                .collect(),
+        ))
+    }
+}
+
+impl Estimator for SoftmaxRegression {
+    fn is_fitted(&self) -> bool {
+        !self.classes.is_empty()
+    }
+}
+
+impl Classifier for SoftmaxRegression {
+    /// Fits the model to samples with at least two class labels.
+    ///
+    /// The fit succeeds even if the solver did not converge, which `has_converged` reports.
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        check_targets(xs, ys)?;
+        let (classes, labels) = encode_classes(ys);
+        if classes.len() < 2 {
+            return Err(ModelError::InvalidInput(
+                "softmax regression needs at least 2 classes".to_string(),
+            ));
+        }
+        let weights = self
+            .settings
+            .class_weight
+            .sample_weights(&classes, &labels)?;
+
+        let k = classes.len();
+        let minimum = fit_parameters(&self.settings, xs, labels, weights, k)?;
+        let p = xs.dimensions().1;
+        let blocks: Vec<&[f64]> = minimum.x.as_slice().chunks(p + 1).collect();
+        self.coefficients =
+            Matrix::from_rows(blocks.iter().map(|b| Array::from(&b[..p])).collect());
+        self.intercepts = Array::from_vec(blocks.iter().map(|b| b[p]).collect());
+        self.iterations = minimum.iterations;
+        self.converged = minimum.converged;
+        self.classes = classes;
+        Ok(())
+    }
+
+    fn classes(&self) -> Result<Array> {
+        self.check_fitted()?;
+        Ok(self.classes.clone())
+    }
+
+    fn predict_proba(&self, xs: &Matrix) -> Result<Matrix> {
+        let scores = self.decision_function(xs)?;
+        Ok(Matrix::from_rows(
+            scores.iter().map(|z| Array::from_vec(softmax(z))).collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::machine_learning::model::test::check_classifier_errors;
+    use crate::random::pcg::Pcg64;
+    use crate::random::Rng;
+
+    /// Returns samples of two features whose class is 1 with probability
+    /// `sigmoid(0.5 + 2 x0 - x1)`.
+    fn binary_problem(rows: usize) -> (Matrix, Array) {
+        let mut rng = Pcg64::seed_from_u64(11);
+        let xs = Matrix::random_normal(rows, 2, &mut rng);
+        let ys = Array::from_vec(
+            xs.iter()
+                .map(|row| {
+                    let p = sigmoid(0.5 + 2.0 * row[0] - row[1]);
+                    (rng.next_f64() < p) as u8 as f64
+                })
+                .collect(),
+        );
+        (xs, ys)
+    }
+
+    /// Returns the largest component of the gradient of the mean log-loss plus an L2 penalty.
+    fn gradient_norm(model: &LogisticRegression, xs: &Matrix, ys: &Array, l2: f64) -> f64 {
+        let scores = model.decision_function(xs).unwrap();
+        let n = ys.len() as f64;
+        let mut gradient = [0.0; 3];
+        for ((row, &z), &y) in xs.iter().zip(scores.iter()).zip(ys.iter()) {
+            let residual = (sigmoid(z) - y) / n;
+            gradient[0] += residual * row[0];
+            gradient[1] += residual * row[1];
+            gradient[2] += residual;
+        }
+        gradient[0] += l2 * model.get_coefficients()[0];
+        gradient[1] += l2 * model.get_coefficients()[1];
+        gradient.iter().fold(0.0, |acc: f64, g| acc.max(g.abs()))
+    }
+
+    #[test]
+    fn test_solvers_agree() {
+        let (xs, ys) = binary_problem(200);
+
+        let mut lbfgs = LogisticRegression::new(Penalty::L2(0.01), true);
+        lbfgs.fit(&xs, &ys).unwrap();
+        let mut newton = LogisticRegression::new(Penalty::L2(0.01), true);
+        newton.set_solver(LogisticSolver::Newton);
+        newton.fit(&xs, &ys).unwrap();
+
+        assert!(lbfgs.has_converged() && newton.has_converged());
+        assert!(newton.get_iterations() < 10);
+        assert!(gradient_norm(&lbfgs, &xs, &ys, 0.01) < 1e-6);
+        assert!(gradient_norm(&newton, &xs, &ys, 0.01) < 1e-6);
+        let difference = lbfgs.get_coefficients().minus(newton.get_coefficients());
+        assert!(difference.norm() < 1e-4);
+        assert!((lbfgs.get_intercept() - newton.get_intercept()).abs() < 1e-4);
+
+        // The model roughly recovers the coefficients the labels were drawn with.
+        assert!((newton.get_coefficients()[0] - 2.0).abs() < 0.7);
+        assert!((newton.get_coefficients()[1] + 1.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_probabilities() {
+        let (xs, ys) = binary_problem(100);
+        // Labels other than 0 and 1 keep their order.
+        let labels = Array::from_vec(ys.iter().map(|y| 3.0 + 4.0 * y).collect());
+        let mut model = LogisticRegression::new(Penalty::L2(0.1), true);
+        model.fit(&xs, &labels).unwrap();
+
+        assert_eq!(Array::from(&[3.0, 7.0]), model.classes().unwrap());
+        let probabilities = model.predict_proba(&xs).unwrap();
+        let scores = model.decision_function(&xs).unwrap();
+        let predictions = model.predict(&xs).unwrap();
+        for i in 0..100 {
+            assert!((probabilities[i].sum() - 1.0).abs() < 1e-15);
+            let expected = if scores[i] > 0.0 { 7.0 } else { 3.0 };
+            assert_eq!(expected, predictions[i]);
+        }
+        assert!(model.score(&xs, &labels).unwrap() > 0.7);
+    }
+
+    #[test]
+    fn test_l1_penalty() {
+        let (xs, ys) = binary_problem(200);
+        // A third feature that is unrelated to the labels.
+        let mut rng = Pcg64::seed_from_u64(3);
+        let noise = Matrix::random_normal(200, 1, &mut rng);
+        let xs = Matrix::from_rows(
+            xs.iter()
+                .zip(noise.iter())
+                .map(|(row, e)| row.concat(e))
+                .collect(),
+        );
+
+        let mut model = LogisticRegression::new(Penalty::L1(0.05), true);
+        model.fit(&xs, &ys).unwrap();
+
+        assert!(model.has_converged());
+        assert_eq!(0.0, model.get_coefficients()[2]);
+        assert!(model.get_coefficients()[0] > 0.5);
+
+        model.set_solver(LogisticSolver::Newton);
+        assert!(model.fit(&xs, &ys).is_err());
+    }
+
+    #[test]
+    fn test_class_weights() {
+        // Only a tenth of the samples are positive, and they overlap with the negatives.
+        let mut rng = Pcg64::seed_from_u64(5);
+        let xs = Matrix::random_normal(300, 1, &mut rng);
+        let ys = Array::from_vec(
+            xs.iter()
+                .map(|row| (row[0] + 0.7 * rng.next_f64() > 1.6) as u8 as f64)
+                .collect(),
+        );
+
+        let mut plain = LogisticRegression::new(Penalty::L2(0.01), true);
+        plain.fit(&xs, &ys).unwrap();
+        let mut balanced = plain.clone();
+        balanced.set_class_weight(ClassWeight::Balanced);
+        balanced.fit(&xs, &ys).unwrap();
+
+        let positives = |model: &LogisticRegression| model.predict(&xs).unwrap().sum();
+        assert!(positives(&balanced) > positives(&plain));
+        assert!(balanced.get_intercept() > plain.get_intercept());
+    }
+
+    #[test]
+    fn test_softmax_matches_binary() {
+        // With two classes the softmax logits differ by the binary logit, and an L2 penalty
+        // of alpha on both rows of W is a penalty of alpha / 2 on their difference.
+        let (xs, ys) = binary_problem(150);
+        let mut binary = LogisticRegression::new(Penalty::L2(0.05), true);
+        binary.fit(&xs, &ys).unwrap();
+        let mut softmax = SoftmaxRegression::new(Penalty::L2(0.1), true);
+        softmax.set_solver(LogisticSolver::Newton);
+        softmax.fit(&xs, &ys).unwrap();
+
+        let logits = softmax.decision_function(&xs).unwrap();
+        let scores = binary.decision_function(&xs).unwrap();
+        for i in 0..150 {
+            assert!((logits[i][1] - logits[i][0] - scores[i]).abs() < 1e-5);
+        }
+        let difference = softmax
+            .predict_proba(&xs)
+            .unwrap()
+            .minus(&binary.predict_proba(&xs).unwrap());
+        assert!(difference.iter().all(|row| row.norm() < 1e-6));
+    }
+
+    #[test]
+    fn test_softmax() {
+        let mut rng = Pcg64::seed_from_u64(9);
+        let centers = [[0.0, 0.0], [4.0, 0.0], [0.0, 4.0], [4.0, 4.0]];
+        let noise = Matrix::random_normal(200, 2, &mut rng);
+        let xs = Matrix::from_rows(
+            noise
+                .iter()
+                .enumerate()
+                .map(|(i, e)| Array::from(&[centers[i % 4][0] + e[0], centers[i % 4][1] + e[1]]))
+                .collect(),
+        );
+        let ys = Array::from_vec((0..200).map(|i| (i % 4) as f64).collect());
+
+        let mut lbfgs = SoftmaxRegression::new(Penalty::L2(0.01), true);
+        lbfgs.fit(&xs, &ys).unwrap();
+        let mut newton = lbfgs.clone();
+        newton.set_solver(LogisticSolver::Newton);
+        newton.fit(&xs, &ys).unwrap();
+
+        assert!(lbfgs.has_converged() && newton.has_converged());
+        assert_eq!((4, 2), lbfgs.get_coefficients().dimensions());
+        assert!(lbfgs.score(&xs, &ys).unwrap() > 0.9);
+        let difference = lbfgs
+            .predict_proba(&xs)
+            .unwrap()
+            .minus(&newton.predict_proba(&xs).unwrap());
+        assert!(difference.iter().all(|row| row.norm() < 1e-4));
+    }
+
+    #[test]
+    fn test_errors() {
+        let xs = Matrix::from_rows((0..3).map(|i| Array::from(&[i as f64])).collect());
+        let ys = Array::from(&[0.0, 1.0, 0.0]);
+        let mut model = LogisticRegression::new(Penalty::None, true);
+
+        assert!(model.fit(&xs, &Array::from(&[0.0, 1.0, 2.0])).is_err());
+        assert!(SoftmaxRegression::new(Penalty::None, true)
+            .fit(&xs, &Array::of(1.0, 3))
+            .is_err());
+
+        check_classifier_errors(&mut model, &xs, &ys);
+        check_classifier_errors(&mut SoftmaxRegression::new(Penalty::None, true), &xs, &ys);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_no_memory() {
+        SoftmaxRegression::new(Penalty::None, true).set_options(LbfgsOptions {
+            memory: 0,
+            ..LbfgsOptions::default()
+        });
+    }
+}
@@ -1,5 +1,18 @@
 //! Implementations of Machine Learning models
 
+pub mod boosting;
 pub mod evaluation;
+pub mod forest;
+pub mod kmeans;
+pub mod lbfgs;
 pub mod linear_regression;
+pub mod logistic_regression;
 pub mod model;
+pub mod naive_bayes;
+pub mod neighbors;
+pub mod optimizer;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod regularized_regression;
+pub mod spatial;
+pub mod tree;
@@ -1,6 +1,406 @@
+//! Model - The interfaces shared by all Machine Learning models
+//!
+//! Models take their samples as a `Matrix` with a row for every sample and a column for every
+//! feature, and their targets as an `Array`. They are split by what they do once fitted:
+//!
+//! * A `Predictor` predicts a continuous target, e.g. a regression.
+//! * A `Classifier` predicts one of a finite set of class labels.
+//! * A `Transformer` maps samples to new features, e.g. a scaler, and needs no targets.
+//!
+//! All of them are an `Estimator`, which knows whether it has been fitted. Fitting and using a
+//! model returns a `Result`, which is an error for invalid input or an unfitted model.
+
 use crate::linalg::array::Array;
+use crate::linalg::matrix::Matrix;
+use crate::machine_learning::evaluation::coefficient_of_determination;
+
+use std::fmt;
+
+/// The ways fitting or using a model can fail.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModelError {
+    /// The model is used before it has been fitted.
+    NotFitted,
+    /// The samples or targets are not valid for the model, e.g. they have the wrong shape.
+    InvalidInput(String),
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelError::NotFitted => write!(f, "the model has not been fitted"),
+            ModelError::InvalidInput(msg) => write!(f, "invalid input: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ModelError {}
+
+/// The result of fitting or using a model.
+pub type Result<T> = std::result::Result<T, ModelError>;
+
+/// A model whose parameters are estimated from data.
+pub trait Estimator {
+    /// Returns whether the model has been fitted.
+    fn is_fitted(&self) -> bool;
+
+    /// Returns an error if the model has not been fitted.
+    fn check_fitted(&self) -> Result<()> {
+        if self.is_fitted() {
+            Ok(())
+        } else {
+            Err(ModelError::NotFitted)
+        }
+    }
+}
+
+/// A model that predicts a continuous target from the features of a sample.
+pub trait Predictor: Estimator {
+    /// Fits the model to samples and their targets.
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()>;
+
+    /// Returns the predicted target of every sample.
+    fn predict(&self, xs: &Matrix) -> Result<Array>;
+
+    /// Returns the coefficient of determination (R²) of the predictions for the samples.
+    fn score(&self, xs: &Matrix, ys: &Array) -> Result<f64> {
+        check_targets(xs, ys)?;
+        Ok(coefficient_of_determination(ys, &self.predict(xs)?))
+    }
+}
+
+/// A model that predicts the class of a sample from its features.
+///
+/// The classes are labelled by numbers, e.g. 0 and 1 for two classes.
+pub trait Classifier: Estimator {
+    /// Fits the model to samples and their class labels.
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()>;
+
+    /// Returns the distinct class labels seen while fitting, in increasing order.
+    fn classes(&self) -> Result<Array>;
+
+    /// Returns the probability of every class for every sample, with a row for every sample
+    /// and a column for every class in the order of `classes`.
+    fn predict_proba(&self, xs: &Matrix) -> Result<Matrix>;
+
+    /// Returns the most likely class label of every sample.
+    fn predict(&self, xs: &Matrix) -> Result<Array> {
+        let classes = self.classes()?;
+        let probabilities = self.predict_proba(xs)?;
+        Ok(Array::from_vec(
+            probabilities
+                .iter()
+                .map(|row| classes[row.argmax().unwrap_or(0)])
+                .collect(),
+        ))
+    }
+
+    /// Returns the accuracy, the fraction of samples whose class is predicted correctly.
+    fn score(&self, xs: &Matrix, ys: &Array) -> Result<f64> {
+        check_targets(xs, ys)?;
+        let predictions = self.predict(xs)?;
+        let correct = predictions
+            .iter()
+            .zip(ys.iter())
+            .filter(|(p, y)| p == y)
+            .count();
+        Ok(correct as f64 / ys.len() as f64)
+    }
+}
+
+/// A model that maps samples to new features.
+pub trait Transformer: Estimator {
+    /// Fits the model to samples.
+    fn fit(&mut self, xs: &Matrix) -> Result<()>;
+
+    /// Returns the new features of every sample.
+    fn transform(&self, xs: &Matrix) -> Result<Matrix>;
+
+    /// Fits the model to samples and returns their new features.
+    fn fit_transform(&mut self, xs: &Matrix) -> Result<Matrix> {
+        self.fit(xs)?;
+        self.transform(xs)
+    }
+}
+
+/// Returns an error unless there is a target for every sample and at least one sample, and
+/// neither contain NaN.
+pub fn check_targets(xs: &Matrix, ys: &Array) -> Result<()> {
+    let (rows, _) = xs.dimensions();
+    if rows != ys.len() {
+        return Err(ModelError::InvalidInput(format!(
+            "there are {} samples but {} targets",
+            rows,
+            ys.len()
+        )));
+    }
+    if rows == 0 {
+        return Err(ModelError::InvalidInput("there are no samples".to_string()));
+    }
+    if ys.iter().any(|y| y.is_nan()) || xs.iter().any(|row| row.iter().any(|x| x.is_nan())) {
+        return Err(ModelError::InvalidInput(
+            "the samples or targets contain NaN".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns an error unless the samples have the given number of features.
+pub fn check_features(xs: &Matrix, features: usize) -> Result<()> {
+    let (_, cols) = xs.dimensions();
+    if cols != features {
+        return Err(ModelError::InvalidInput(format!(
+            "expected {} features but the samples have {}",
+            features, cols
+        )));
+    }
+    Ok(())
+}
+
+/// How to weight the samples of every class when fitting a classifier.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum ClassWeight {
+    /// Every sample has weight 1.
+    #[default]
+    Uniform,
+    /// Every class has the same total weight, so a sample of a class with `count` samples has
+    /// weight `samples / (classes * count)`. This is meant for imbalanced data.
+    Balanced,
+    /// The given weight for every listed class label, and 1 for the other classes.
+    Given(Vec<(f64, f64)>),
+}
+
+impl ClassWeight {
+    /// Returns the weight of every sample.
+    ///
+    /// # Arguments
+    ///
+    /// * `classes` - the distinct class labels.
+    /// * `labels` - the index into `classes` of the class of every sample.
+    pub fn sample_weights(&self, classes: &Array, labels: &[usize]) -> Result<Array> {
+        let class_weights = match self {
+            ClassWeight::Uniform => vec![1.0; classes.len()],
+            ClassWeight::Balanced => {
+                let mut counts = vec![0usize; classes.len()];
+                for &label in labels {
+                    counts[label] += 1;
+                }
+                let scale = labels.len() as f64 / classes.len() as f64;
+                counts
+                    .iter()
+                    .map(|&count| if count > 0 { scale / count as f64 } else { 0.0 })
+                    .collect()
+            }
+            ClassWeight::Given(weights) => {
+                if weights.iter().any(|&(_, w)| !(w > 0.0 && w.is_finite())) {
+                    return Err(ModelError::InvalidInput(
+                        "the class weights must be positive".to_string(),
+                    ));
+                }
+                classes
+                    .iter()
+                    .map(|class| {
+                        weights
+                            .iter()
+                            .find(|(label, _)| label == class)
+                            .map_or(1.0, |&(_, w)| w)
+                    })
+                    .collect()
+            }
+        };
+
+        Ok(Array::from_vec(
+            labels.iter().map(|&label| class_weights[label]).collect(),
+        ))
+    }
+}
+
+/// Returns the distinct class labels in increasing order, and the index into them of the
+/// class of every target.
+///
+/// # Panics
+///
+/// The targets must not be NaN, otherwise the code will panic. `check_targets` returns an error
+/// for them first.
+pub fn encode_classes(ys: &Array) -> (Array, Vec<usize>) {
+    let mut classes = ys.to_vec();
+    classes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    classes.dedup();
+
+    let labels = ys
+        .iter()
+        .map(|y| {
+            classes
+                .binary_search_by(|c| c.partial_cmp(y).unwrap())
+                .unwrap()
+        })
+        .collect();
+
+    (Array::from_vec(classes), labels)
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::random::pcg::Pcg64;
+
+    /// Returns `n` samples of independent standard normal features, and the target of every
+    /// sample.
+    pub(crate) fn normal_samples(
+        n: usize,
+        features: usize,
+        seed: u64,
+        target: impl Fn(&Array) -> f64,
+    ) -> (Matrix, Array) {
+        let mut rng = Pcg64::seed_from_u64(seed);
+        let xs = Matrix::random_normal(n, features, &mut rng);
+        let ys = Array::from_vec(xs.iter().map(target).collect());
+        (xs, ys)
+    }
+
+    /// Checks the errors of an unfitted model: predicting before fitting, fitting fewer
+    /// targets than samples, and predicting samples with more features than it was fitted to.
+    /// The model is fitted to the samples and targets afterwards.
+    fn check_errors<M>(
+        model: &mut M,
+        xs: &Matrix,
+        ys: &Array,
+        fit: fn(&mut M, &Matrix, &Array) -> Result<()>,
+        predict: fn(&M, &Matrix) -> Result<Array>,
+    ) {
+        let (rows, cols) = xs.dimensions();
+        assert_eq!(Err(ModelError::NotFitted), predict(model, xs));
+
+        let fewer = Array::from(&ys.as_slice()[..rows - 1]);
+        assert!(matches!(
+            fit(model, xs, &fewer),
+            Err(ModelError::InvalidInput(_))
+        ));
+
+        fit(model, xs, ys).unwrap();
+        assert!(matches!(
+            predict(model, &Matrix::zeros(1, cols + 1)),
+            Err(ModelError::InvalidInput(_))
+        ));
+    }
+
+    /// Checks the errors shared by every `Predictor`, see `check_errors`.
+    pub(crate) fn check_predictor_errors<M: Predictor>(model: &mut M, xs: &Matrix, ys: &Array) {
+        check_errors(
+            model,
+            xs,
+            ys,
+            <M as Predictor>::fit,
+            <M as Predictor>::predict,
+        );
+    }
+
+    /// Checks the errors shared by every `Classifier`, see `check_errors`.
+    pub(crate) fn check_classifier_errors<M: Classifier>(model: &mut M, xs: &Matrix, ys: &Array) {
+        check_errors(
+            model,
+            xs,
+            ys,
+            <M as Classifier>::fit,
+            <M as Classifier>::predict,
+        );
+    }
+
+    /// Predicts the class 1 for positive values of the single feature.
+    struct Threshold;
+
+    impl Estimator for Threshold {
+        fn is_fitted(&self) -> bool {
+            true
+        }
+    }
+
+    impl Classifier for Threshold {
+        fn fit(&mut self, _xs: &Matrix, _ys: &Array) -> Result<()> {
+            Ok(())
+        }
+
+        fn classes(&self) -> Result<Array> {
+            Ok(Array::from(&[0.0, 1.0]))
+        }
+
+        fn predict_proba(&self, xs: &Matrix) -> Result<Matrix> {
+            check_features(xs, 1)?;
+            Ok(Matrix::from_rows(
+                xs.iter()
+                    .map(|row| {
+                        let p = if row[0] > 0.0 { 0.9 } else { 0.2 };
+                        Array::from(&[1.0 - p, p])
+                    })
+                    .collect(),
+            ))
+        }
+    }
+
+    #[test]
+    fn test_classifier_defaults() {
+        let xs = Matrix::from_rows(
+            [-1.0, 2.0, 3.0, -4.0]
+                .iter()
+                .map(|&x| Array::from(&[x]))
+                .collect(),
+        );
+        let ys = Array::from(&[0.0, 1.0, 0.0, 0.0]);
+
+        assert_eq!(
+            Array::from(&[0.0, 1.0, 1.0, 0.0]),
+            Threshold.predict(&xs).unwrap()
+        );
+        assert_eq!(0.75, Threshold.score(&xs, &ys).unwrap());
+    }
+
+    #[test]
+    fn test_checks() {
+        let xs = Matrix::new(&[Array::from(&[1.0, 2.0]), Array::from(&[3.0, f64::NAN])]);
+
+        assert!(check_targets(&xs, &Array::from(&[1.0])).is_err());
+        assert!(check_targets(&xs, &Array::from(&[1.0, 2.0])).is_err());
+        assert_eq!(Ok(()), check_features(&xs, 2));
+        assert_eq!(
+            Err(ModelError::InvalidInput(
+                "expected 1 features but the samples have 2".to_string()
+            )),
+            Threshold.predict(&xs)
+        );
+        assert_eq!(
+            "the model has not been fitted",
+            ModelError::NotFitted.to_string()
+        );
+    }
+
+    #[test]
+    fn test_classes() {
+        let (classes, labels) = encode_classes(&Array::from(&[2.0, -1.0, 2.0, 5.0, 2.0]));
+        assert_eq!(Array::from(&[-1.0, 2.0, 5.0]), classes);
+        assert_eq!(vec![1, 0, 1, 2, 1], labels);
+
+        let balanced = ClassWeight::Balanced
+            .sample_weights(&classes, &labels)
+            .unwrap();
+        assert_eq!(
+            Array::from(&[5.0 / 9.0, 5.0 / 3.0, 5.0 / 9.0, 5.0 / 3.0, 5.0 / 9.0]),
+            balanced
+        );
+        assert_eq!(5.0, balanced.sum());
+
+        let given = ClassWeight::Given(vec![(5.0, 4.0)]);
+        assert_eq!(
+            Array::from(&[1.0, 1.0, 1.0, 4.0, 1.0]),
+            given.sample_weights(&classes, &labels).unwrap()
+        );
+        assert!(ClassWeight::Given(vec![(5.0, -1.0)])
+            .sample_weights(&classes, &labels)
+            .is_err());
+    }
 
-pub trait Model {
-    fn optimize(&mut self, xs: Array, ys: Array);
-    fn predict(&mut self, observed_xs: Array) -> Array;
+    #[test]
+    #[should_panic]
+    fn test_nan_classes() {
+        encode_classes(&Array::from(&[1.0, f64::NAN]));
+    }
 }
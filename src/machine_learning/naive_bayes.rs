@@ -0,0 +1,1022 @@
+//! Naive Bayes - Classifiers that assume independent features within every class
+//!
+//! A naive Bayes classifier models the distribution of every feature within every class on its
+//! own, and predicts the class with the largest posterior probability by Bayes' theorem:
+//!
+//! * `GaussianNaiveBayes` models every feature as normally distributed, for continuous features
+//!   such as sensor readings.
+//! * `MultinomialNaiveBayes` models the features as counts of events, such as word counts.
+//! * `BernoulliNaiveBayes` models every feature as present or absent, such as the occurrence
+//!   of words.
+//!
+//! The models only keep sums over the samples of every class, so they can be updated with new
+//! batches of samples by `partial_fit`, and `fit` is `partial_fit` on a fresh model. The class
+//! priors are the class frequencies of the samples unless they are set.
+
+use crate::linalg::array::Array;
+use crate::linalg::matrix::Matrix;
+use crate::machine_learning::model::{
+    check_features, check_targets, Classifier, Estimator, ModelError, Result,
+};
+use crate::statistics::online::{Accumulator, RunningStats};
+use crate::statistics::probability::{Binomial, Distribution, Normal};
+
+/// The classes seen by a model, the number of samples of every class and the fixed priors.
+#[derive(Debug, Clone, PartialEq, Default)]
+struct ClassTable {
+    classes: Vec<f64>,
+    counts: Vec<f64>,
+    priors: Option<Array>,
+    features: Option<usize>,
+}
+
+impl ClassTable {
+    /// Adds the classes of the targets that are not in the table and returns the index of the
+    /// class of every target. `insert` is called with the index of every added class, so the
+    /// model can insert its statistics at the same position.
+    fn update(
+        &mut self,
+        xs: &Matrix,
+        ys: &Array,
+        mut insert: impl FnMut(usize),
+    ) -> Result<Vec<usize>> {
+        check_targets(xs, ys)?;
+        if let Some(features) = self.features {
+            check_features(xs, features)?;
+        }
+
+        let mut classes = self.classes.clone();
+        classes.extend(ys.iter());
+        classes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        classes.dedup();
+        self.check_priors(classes.len())?;
+
+        for (k, &class) in classes.iter().enumerate() {
+            if self.classes.get(k) != Some(&class) {
+                self.classes.insert(k, class);
+                self.counts.insert(k, 0.0);
+                insert(k);
+            }
+        }
+        self.features = Some(xs.dimensions().1);
+
+        Ok(ys
+            .iter()
+            .map(|y| {
+                let k = self
+                    .classes
+                    .binary_search_by(|c| c.partial_cmp(y).unwrap())
+                    .unwrap();
+                self.counts[k] += 1.0;
+                k
+            })
+            .collect())
+    }
+
+    /// Returns an error unless the model is fitted, the samples have its features and there is
+    /// a prior for every class.
+    fn check(&self, xs: &Matrix) -> Result<()> {
+        let features = self.features.ok_or(ModelError::NotFitted)?;
+        check_features(xs, features)?;
+        self.check_priors(self.classes.len())
+    }
+
+    /// Returns an error if the priors are set but their number is not the number of classes.
+    fn check_priors(&self, classes: usize) -> Result<()> {
+        match &self.priors {
+            Some(priors) if priors.len() != classes => Err(ModelError::InvalidInput(format!(
+                "there are {} priors but {} classes",
+                priors.len(),
+                classes
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Sets the priors of the model.
+    ///
+    /// Panics unless the priors are positive and sum to 1, and there is a prior for every class
+    /// of a fitted model.
+    fn set_priors(&mut self, priors: Array, name: &str) {
+        assert!(
+            priors.iter().all(|&p| p > 0.0 && p.is_finite()) && (priors.sum() - 1.0).abs() < 1e-9,
+            "ERROR - {}: The priors must be positive and sum to 1.",
+            name
+        );
+        assert!(
+            self.features.is_none() || priors.len() == self.classes.len(),
+            "ERROR - {}: There must be a prior for every class of the fitted model.",
+            name
+        );
+        self.priors = Some(priors);
+    }
+
+    /// Returns the logarithm of the prior probability of every class.
+    fn log_priors(&self) -> Vec<f64> {
+        match &self.priors {
+            Some(priors) => priors.iter().map(|p| p.ln()).collect(),
+            None => {
+                let total: f64 = self.counts.iter().sum();
+                self.counts.iter().map(|c| (c / total).ln()).collect()
+            }
+        }
+    }
+
+    /// Returns the logarithms of the posterior probabilities from the logarithms of the joint
+    /// probabilities of every sample and class.
+    fn log_posteriors(joint: Vec<Vec<f64>>) -> Matrix {
+        Matrix::from_rows(
+            joint
+                .into_iter()
+                .map(|row| {
+                    let max = row.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+                    let log_sum = max + row.iter().map(|j| (j - max).exp()).sum::<f64>().ln();
+                    Array::from_vec(row.iter().map(|j| j - log_sum).collect())
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Panics unless the smoothing is positive.
+fn check_alpha(alpha: f64, name: &str) {
+    assert!(
+        alpha > 0.0 && alpha.is_finite(),
+        "ERROR - {}: The smoothing must be positive.",
+        name
+    );
+}
+
+/// Returns the matrix with the exponential of every element.
+fn exp(log_probabilities: Matrix) -> Matrix {
+    Matrix::from_rows(
+        log_probabilities
+            .iter()
+            .map(|row| Array::from_vec(row.iter().map(|l| l.exp()).collect()))
+            .collect(),
+    )
+}
+
+/// Naive Bayes with normally distributed features.
+///
+/// Every variance is increased by `var_smoothing` times the largest variance of a feature over
+/// all samples, which keeps features that are constant within a class from dominating.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::model::Classifier;
+/// use moonalloy::machine_learning::naive_bayes::GaussianNaiveBayes;
+///
+/// let xs = Matrix::new(&[
+///     Array::from(&[1.0, 2.0]),
+///     Array::from(&[2.0, 1.0]),
+///     Array::from(&[8.0, 9.0]),
+///     Array::from(&[9.0, 8.0]),
+/// ]);
+/// let ys = Array::from(&[0.0, 0.0, 1.0, 1.0]);
+///
+/// let mut bayes = GaussianNaiveBayes::new();
+/// bayes.fit(&xs, &ys).unwrap();
+/// // More samples of the second class arrive later.
+/// bayes
+///     .partial_fit(&Matrix::new(&[Array::from(&[8.5, 8.5])]), &Array::from(&[1.0]))
+///     .unwrap();
+///
+/// let test = Matrix::new(&[Array::from(&[1.5, 1.5]), Array::from(&[7.0, 7.0])]);
+/// assert_eq!(Array::from(&[0.0, 1.0]), bayes.predict(&test).unwrap());
+/// assert_eq!(Array::from(&[8.5, 8.5]), bayes.get_means().unwrap()[1]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaussianNaiveBayes {
+    var_smoothing: f64,
+    table: ClassTable,
+    /// The statistics of every feature within every class.
+    stats: Vec<Vec<RunningStats>>,
+    /// The statistics of every feature over all samples.
+    overall: Vec<RunningStats>,
+}
+
+impl Default for GaussianNaiveBayes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GaussianNaiveBayes {
+    /// Returns an unfitted model with `var_smoothing` 1e-9 and the class frequencies as priors.
+    pub fn new() -> Self {
+        GaussianNaiveBayes {
+            var_smoothing: 1e-9,
+            table: ClassTable::default(),
+            stats: Vec::new(),
+            overall: Vec::new(),
+        }
+    }
+
+    /// Sets the fraction of the largest feature variance added to every variance.
+    ///
+    /// # Panics
+    ///
+    /// The smoothing must be positive, otherwise the code will panic.
+    pub fn set_var_smoothing(&mut self, var_smoothing: f64) {
+        check_alpha(var_smoothing, "GaussianNaiveBayes");
+        self.var_smoothing = var_smoothing;
+    }
+
+    /// Sets the prior probability of every class in increasing order of the class labels.
+    ///
+    /// # Panics
+    ///
+    /// The priors must be positive and sum to 1, and a fitted model must have as many classes as
+    /// there are priors, otherwise the code will panic.
+    pub fn set_priors(&mut self, priors: Array) {
+        self.table.set_priors(priors, "GaussianNaiveBayes");
+    }
+
+    /// Updates the model with more samples of finite features and their class labels, which may
+    /// include classes that were not seen before.
+    pub fn partial_fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        if xs.iter().any(|row| row.iter().any(|x| !x.is_finite())) {
+            return Err(ModelError::InvalidInput(
+                "the features must be finite".to_string(),
+            ));
+        }
+        let features = xs.dimensions().1;
+        let stats = &mut self.stats;
+        let labels = self.table.update(xs, ys, |k| {
+            stats.insert(k, vec![RunningStats::new(); features])
+        })?;
+        self.overall.resize(features, RunningStats::new());
+
+        for (row, &k) in xs.iter().zip(&labels) {
+            for (j, &x) in row.iter().enumerate() {
+                self.stats[k][j].push(x);
+                self.overall[j].push(x);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the mean of every feature within every class, with a row for every class.
+    pub fn get_means(&self) -> Result<Matrix> {
+        self.check_fitted()?;
+        Ok(Matrix::from_rows(
+            self.stats
+                .iter()
+                .map(|class| Array::from_vec(class.iter().map(|s| s.mean()).collect()))
+                .collect(),
+        ))
+    }
+
+    /// Returns the smoothed variance of every feature within every class, with a row for every
+    /// class.
+    pub fn get_variances(&self) -> Result<Matrix> {
+        self.check_fitted()?;
+        let epsilon = self.epsilon();
+        Ok(Matrix::from_rows(
+            self.stats
+                .iter()
+                .map(|class| Array::from_vec(class.iter().map(|s| s.var(0) + epsilon).collect()))
+                .collect(),
+        ))
+    }
+
+    /// Returns the variance added to every variance, which is `var_smoothing` itself if every
+    /// feature is constant.
+    fn epsilon(&self) -> f64 {
+        let largest = self.overall.iter().map(|s| s.var(0)).fold(0.0, f64::max);
+        if largest > 0.0 {
+            self.var_smoothing * largest
+        } else {
+            self.var_smoothing
+        }
+    }
+
+    /// Returns the logarithm of the posterior probability of every class for every sample, with
+    /// a row for every sample and a column for every class.
+    pub fn predict_log_proba(&self, xs: &Matrix) -> Result<Matrix> {
+        self.table.check(xs)?;
+        let epsilon = self.epsilon();
+        let normals: Vec<Vec<Normal>> = self
+            .stats
+            .iter()
+            .map(|class| {
+                class
+                    .iter()
+                    .map(|s| Normal::new(s.mean(), (s.var(0) + epsilon).sqrt()))
+                    .collect()
+            })
+            .collect();
+        let log_priors = self.table.log_priors();
+
+        Ok(ClassTable::log_posteriors(
+            xs.iter()
+                .map(|row| {
+                    normals
+                        .iter()
+                        .zip(&log_priors)
+                        .map(|(class, prior)| {
+                            prior
+                                + class
+                                    .iter()
+                                    .zip(row.iter())
+                                    .map(|(normal, &x)| normal.logpdf(x))
+                                    .sum::<f64>()
+                        })
+                        .collect()
+                })
+                .collect(),
+        ))
+    }
+}
+
+impl Estimator for GaussianNaiveBayes {
+    fn is_fitted(&self) -> bool {
+        self.table.features.is_some()
+    }
+}
+
+impl Classifier for GaussianNaiveBayes {
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        let mut fitted = GaussianNaiveBayes {
+            var_smoothing: self.var_smoothing,
+            table: ClassTable {
+                priors: self.table.priors.clone(),
+                ..ClassTable::default()
+            },
+            stats: Vec::new(),
+            overall: Vec::new(),
+        };
+        fitted.partial_fit(xs, ys)?;
+        *self = fitted;
+        Ok(())
+    }
+
+    fn classes(&self) -> Result<Array> {
+        self.check_fitted()?;
+        Ok(Array::from(&self.table.classes))
+    }
+
+    fn predict_proba(&self, xs: &Matrix) -> Result<Matrix> {
+        Ok(exp(self.predict_log_proba(xs)?))
+    }
+}
+
+/// Naive Bayes with features that are counts of events, such as the number of times every word
+/// of a vocabulary occurs in a document.
+///
+/// The probability of every feature within a class is its share of the total count of the class
+/// after adding `alpha` to every count, which is Laplace smoothing for `alpha` 1.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::model::Classifier;
+/// use moonalloy::machine_learning::naive_bayes::MultinomialNaiveBayes;
+///
+/// // The counts of the words "goal", "match" and "vote" in four documents.
+/// let xs = Matrix::new(&[
+///     Array::from(&[3.0, 2.0, 0.0]),
+///     Array::from(&[1.0, 3.0, 0.0]),
+///     Array::from(&[0.0, 1.0, 4.0]),
+///     Array::from(&[0.0, 0.0, 2.0]),
+/// ]);
+/// let ys = Array::from(&[0.0, 0.0, 1.0, 1.0]);
+///
+/// let mut bayes = MultinomialNaiveBayes::new();
+/// bayes.fit(&xs, &ys).unwrap();
+///
+/// let test = Matrix::new(&[Array::from(&[2.0, 0.0, 0.0]), Array::from(&[0.0, 1.0, 1.0])]);
+/// assert_eq!(Array::from(&[0.0, 1.0]), bayes.predict(&test).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultinomialNaiveBayes {
+    alpha: f64,
+    table: ClassTable,
+    /// The total count of every feature within every class.
+    counts: Vec<Vec<f64>>,
+}
+
+impl Default for MultinomialNaiveBayes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MultinomialNaiveBayes {
+    /// Returns an unfitted model with Laplace smoothing and the class frequencies as priors.
+    pub fn new() -> Self {
+        MultinomialNaiveBayes {
+            alpha: 1.0,
+            table: ClassTable::default(),
+            counts: Vec::new(),
+        }
+    }
+
+    /// Sets the pseudo-count added to every feature count. The smoothing is applied when
+    /// predicting, so it can also be changed after fitting.
+    ///
+    /// # Panics
+    ///
+    /// The smoothing must be positive, otherwise the code will panic.
+    pub fn set_alpha(&mut self, alpha: f64) {
+        check_alpha(alpha, "MultinomialNaiveBayes");
+        self.alpha = alpha;
+    }
+
+    /// Sets the prior probability of every class in increasing order of the class labels.
+    ///
+    /// # Panics
+    ///
+    /// The priors must be positive and sum to 1, and a fitted model must have as many classes as
+    /// there are priors, otherwise the code will panic.
+    pub fn set_priors(&mut self, priors: Array) {
+        self.table.set_priors(priors, "MultinomialNaiveBayes");
+    }
+
+    /// Updates the model with more samples of non-negative counts and their class labels,
+    /// which may include classes that were not seen before.
+    pub fn partial_fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        check_counts(xs)?;
+        let features = xs.dimensions().1;
+        let counts = &mut self.counts;
+        let labels = self
+            .table
+            .update(xs, ys, |k| counts.insert(k, vec![0.0; features]))?;
+
+        for (row, &k) in xs.iter().zip(&labels) {
+            for (count, &x) in self.counts[k].iter_mut().zip(row.iter()) {
+                *count += x;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the logarithm of the smoothed probability of every feature within every class,
+    /// with a row for every class.
+    pub fn get_feature_log_prob(&self) -> Result<Matrix> {
+        self.check_fitted()?;
+        Ok(Matrix::from_rows(
+            self.counts
+                .iter()
+                .map(|class| {
+                    let total = (class.iter().sum::<f64>() + self.alpha * class.len() as f64).ln();
+                    Array::from_vec(
+                        class
+                            .iter()
+                            .map(|c| (c + self.alpha).ln() - total)
+                            .collect(),
+                    )
+                })
+                .collect(),
+        ))
+    }
+
+    /// Returns the logarithm of the posterior probability of every class for every sample, with
+    /// a row for every sample and a column for every class.
+    pub fn predict_log_proba(&self, xs: &Matrix) -> Result<Matrix> {
+        self.table.check(xs)?;
+        check_counts(xs)?;
+        let log_probabilities = self.get_feature_log_prob()?;
+        let log_priors = self.table.log_priors();
+
+        Ok(ClassTable::log_posteriors(
+            xs.iter()
+                .map(|row| {
+                    log_probabilities
+                        .iter()
+                        .zip(&log_priors)
+                        .map(|(class, prior)| prior + row.dotp(class))
+                        .collect()
+                })
+                .collect(),
+        ))
+    }
+}
+
+/// Returns an error unless every feature is a non-negative count.
+fn check_counts(xs: &Matrix) -> Result<()> {
+    if xs.iter().any(|row| row.iter().any(|&x| x < 0.0)) {
+        return Err(ModelError::InvalidInput(
+            "the counts must not be negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+impl Estimator for MultinomialNaiveBayes {
+    fn is_fitted(&self) -> bool {
+        self.table.features.is_some()
+    }
+}
+
+impl Classifier for MultinomialNaiveBayes {
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        let mut fitted = MultinomialNaiveBayes {
+            alpha: self.alpha,
+            table: ClassTable {
+                priors: self.table.priors.clone(),
+                ..ClassTable::default()
+            },
+            counts: Vec::new(),
+        };
+        fitted.partial_fit(xs, ys)?;
+        *self = fitted;
+        Ok(())
+    }
+
+    fn classes(&self) -> Result<Array> {
+        self.check_fitted()?;
+        Ok(Array::from(&self.table.classes))
+    }
+
+    fn predict_proba(&self, xs: &Matrix) -> Result<Matrix> {
+        Ok(exp(self.predict_log_proba(xs)?))
+    }
+}
+
+/// Naive Bayes with binary features, such as whether every word of a vocabulary occurs in a
+/// document.
+///
+/// Every feature is a Bernoulli variable within every class, whose probability is the
+/// fraction of the samples of the class in which it is present, after adding `alpha` to the
+/// number of samples with and without the feature. Unlike the multinomial model, absent
+/// features count as evidence too.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::model::Classifier;
+/// use moonalloy::machine_learning::naive_bayes::BernoulliNaiveBayes;
+///
+/// let xs = Matrix::new(&[
+///     Array::from(&[1.0, 1.0, 0.0]),
+///     Array::from(&[1.0, 0.0, 0.0]),
+///     Array::from(&[0.0, 0.0, 1.0]),
+///     Array::from(&[0.0, 1.0, 1.0]),
+/// ]);
+/// let ys = Array::from(&[0.0, 0.0, 1.0, 1.0]);
+///
+/// let mut bayes = BernoulliNaiveBayes::new();
+/// bayes.fit(&xs, &ys).unwrap();
+///
+/// // Counts are present if they are positive.
+/// let test = Matrix::new(&[Array::from(&[5.0, 0.0, 0.0]), Array::from(&[0.0, 0.0, 2.0])]);
+/// assert_eq!(Array::from(&[0.0, 1.0]), bayes.predict(&test).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BernoulliNaiveBayes {
+    alpha: f64,
+    binarize: Option<f64>,
+    table: ClassTable,
+    /// The number of samples of every class in which every feature is present.
+    counts: Vec<Vec<f64>>,
+}
+
+impl Default for BernoulliNaiveBayes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BernoulliNaiveBayes {
+    /// Returns an unfitted model with Laplace smoothing, features that are present if they are
+    /// positive and the class frequencies as priors.
+    pub fn new() -> Self {
+        BernoulliNaiveBayes {
+            alpha: 1.0,
+            binarize: Some(0.0),
+            table: ClassTable::default(),
+            counts: Vec::new(),
+        }
+    }
+
+    /// Sets the pseudo-count added to the number of samples with and without every feature.
+    /// The smoothing is applied when predicting, so it can also be changed after fitting.
+    ///
+    /// # Panics
+    ///
+    /// The smoothing must be positive, otherwise the code will panic.
+    pub fn set_alpha(&mut self, alpha: f64) {
+        check_alpha(alpha, "BernoulliNaiveBayes");
+        self.alpha = alpha;
+    }
+
+    /// Sets the threshold above which a feature is present, or `None` if the features are
+    /// already 0 or 1.
+    pub fn set_binarize(&mut self, binarize: Option<f64>) {
+        self.binarize = binarize;
+    }
+
+    /// Sets the prior probability of every class in increasing order of the class labels.
+    ///
+    /// # Panics
+    ///
+    /// The priors must be positive and sum to 1, and a fitted model must have as many classes as
+    /// there are priors, otherwise the code will panic.
+    pub fn set_priors(&mut self, priors: Array) {
+        self.table.set_priors(priors, "BernoulliNaiveBayes");
+    }
+
+    /// Updates the model with more samples and their class labels, which may include classes
+    /// that were not seen before.
+    pub fn partial_fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        let present = self.binary(xs)?;
+        let features = xs.dimensions().1;
+        let counts = &mut self.counts;
+        let labels = self
+            .table
+            .update(xs, ys, |k| counts.insert(k, vec![0.0; features]))?;
+
+        for (row, &k) in present.iter().zip(&labels) {
+            for (count, &x) in self.counts[k].iter_mut().zip(row) {
+                *count += x;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the samples as 0 or 1 for absent or present features, or an error if the
+    /// features are not binary and there is no threshold.
+    fn binary(&self, xs: &Matrix) -> Result<Vec<Vec<f64>>> {
+        xs.iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&x| match self.binarize {
+                        Some(threshold) => Ok(if x > threshold { 1.0 } else { 0.0 }),
+                        None if x == 0.0 || x == 1.0 => Ok(x),
+                        None => Err(ModelError::InvalidInput(format!(
+                            "the feature {} is neither 0 nor 1",
+                            x
+                        ))),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the Bernoulli distribution of every feature within every class.
+    fn distributions(&self) -> Vec<Vec<Binomial>> {
+        self.counts
+            .iter()
+            .zip(&self.table.counts)
+            .map(|(class, samples)| {
+                class
+                    .iter()
+                    .map(|c| Binomial::new(1, (c + self.alpha) / (samples + 2.0 * self.alpha)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the logarithm of the smoothed probability that every feature is present within
+    /// every class, with a row for every class.
+    pub fn get_feature_log_prob(&self) -> Result<Matrix> {
+        self.check_fitted()?;
+        Ok(Matrix::from_rows(
+            self.distributions()
+                .iter()
+                .map(|class| Array::from_vec(class.iter().map(|b| b.logpdf(1.0)).collect()))
+                .collect(),
+        ))
+    }
+
+    /// Returns the logarithm of the posterior probability of every class for every sample, with
+    /// a row for every sample and a column for every class.
+    pub fn predict_log_proba(&self, xs: &Matrix) -> Result<Matrix> {
+        self.table.check(xs)?;
+        let present = self.binary(xs)?;
+        // The log-probabilities of every feature being absent and present.
+        let log_probabilities: Vec<Vec<[f64; 2]>> = self
+            .distributions()
+            .iter()
+            .map(|class| {
+                class
+                    .iter()
+                    .map(|b| [b.logpdf(0.0), b.logpdf(1.0)])
+                    .collect()
+            })
+            .collect();
+        let log_priors = self.table.log_priors();
+
+        Ok(ClassTable::log_posteriors(
+            present
+                .iter()
+                .map(|row| {
+                    log_probabilities
+                        .iter()
+                        .zip(&log_priors)
+                        .map(|(class, prior)| {
+                            prior
+                                + class
+                                    .iter()
+                                    .zip(row)
+                                    .map(|(l, &x)| l[x as usize])
+                                    .sum::<f64>()
+                        })
+                        .collect()
+                })
+                .collect(),
+        ))
+    }
+}
+
+impl Estimator for BernoulliNaiveBayes {
+    fn is_fitted(&self) -> bool {
+        self.table.features.is_some()
+    }
+}
+
+impl Classifier for BernoulliNaiveBayes {
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        let mut fitted = BernoulliNaiveBayes {
+            alpha: self.alpha,
+            binarize: self.binarize,
+            table: ClassTable {
+                priors: self.table.priors.clone(),
+                ..ClassTable::default()
+            },
+            counts: Vec::new(),
+        };
+        fitted.partial_fit(xs, ys)?;
+        *self = fitted;
+        Ok(())
+    }
+
+    fn classes(&self) -> Result<Array> {
+        self.check_fitted()?;
+        Ok(Array::from(&self.table.classes))
+    }
+
+    fn predict_proba(&self, xs: &Matrix) -> Result<Matrix> {
+        Ok(exp(self.predict_log_proba(xs)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::machine_learning::model::test::check_classifier_errors;
+    use crate::random::pcg::Pcg64;
+
+    /// Returns normal samples of three classes with different means.
+    fn gaussian_data(n: usize, seed: u64) -> (Matrix, Array) {
+        let mut rng = Pcg64::seed_from_u64(seed);
+        let noise = Matrix::random_normal(n, 2, &mut rng);
+        let ys = Array::from_vec((0..n).map(|i| (i % 3) as f64).collect());
+        let xs = Matrix::from_rows(
+            noise
+                .iter()
+                .zip(ys.iter())
+                .map(|(row, &y)| Array::from(&[row[0] + 3.0 * y, row[1] * (1.0 + y)]))
+                .collect(),
+        );
+        (xs, ys)
+    }
+
+    fn assert_close(a: &Matrix, b: &Matrix, tolerance: f64) {
+        assert_eq!(a.dimensions(), b.dimensions());
+        for (x, y) in a.iter().zip(b.iter()) {
+            for (u, v) in x.iter().zip(y.iter()) {
+                assert!((u - v).abs() <= tolerance, "{} != {}", u, v);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gaussian() {
+        let (xs, ys) = gaussian_data(600, 1);
+        let (test_xs, test_ys) = gaussian_data(600, 2);
+
+        let mut bayes = GaussianNaiveBayes::new();
+        bayes.fit(&xs, &ys).unwrap();
+        assert!(bayes.score(&test_xs, &test_ys).unwrap() > 0.85);
+
+        let means = bayes.get_means().unwrap();
+        let variances = bayes.get_variances().unwrap();
+        for k in 0..3 {
+            assert!((means[k][0] - 3.0 * k as f64).abs() < 0.2);
+            assert!((variances[k][1] - (1.0 + k as f64).powi(2)).abs() < 0.5 * (1.0 + k as f64));
+        }
+
+        let log_probabilities = bayes.predict_log_proba(&test_xs).unwrap();
+        let probabilities = bayes.predict_proba(&test_xs).unwrap();
+        assert_close(&exp(log_probabilities), &probabilities, 1e-15);
+        assert!(probabilities
+            .iter()
+            .all(|row| (row.sum() - 1.0).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_gaussian_by_hand() {
+        let xs = Matrix::new(&[
+            Array::from(&[0.0]),
+            Array::from(&[2.0]),
+            Array::from(&[4.0]),
+        ]);
+        let ys = Array::from(&[0.0, 0.0, 1.0]);
+        let mut bayes = GaussianNaiveBayes::new();
+        bayes.set_var_smoothing(0.25);
+        bayes.fit(&xs, &ys).unwrap();
+
+        // The variance of all samples is 8/3, so the class variances are 1 + 2/3 and 2/3.
+        let epsilon: f64 = 2.0 / 3.0;
+        let first = (2.0f64 / 3.0).ln() + Normal::new(1.0, (1.0 + epsilon).sqrt()).logpdf(3.0);
+        let second = (1.0f64 / 3.0).ln() + Normal::new(4.0, epsilon.sqrt()).logpdf(3.0);
+        let expected = second - (first.exp() + second.exp()).ln();
+
+        let log_probabilities = bayes
+            .predict_log_proba(&Matrix::new(&[Array::from(&[3.0])]))
+            .unwrap();
+        assert!((log_probabilities[0][1] - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_partial_fit() {
+        let (xs, ys) = gaussian_data(300, 3);
+        let mut whole = GaussianNaiveBayes::new();
+        whole.fit(&xs, &ys).unwrap();
+
+        // The batches are sorted by class, so the first batch has a single class.
+        let mut order: Vec<usize> = (0..300).collect();
+        order.sort_by_key(|&i| ys[i] as usize);
+        let mut batches = GaussianNaiveBayes::new();
+        for chunk in order.chunks(70) {
+            let batch_xs = Matrix::from_rows(chunk.iter().map(|&i| xs[i].clone()).collect());
+            let batch_ys = Array::from_vec(chunk.iter().map(|&i| ys[i]).collect());
+            batches.partial_fit(&batch_xs, &batch_ys).unwrap();
+        }
+
+        assert_eq!(whole.classes(), batches.classes());
+        assert_close(
+            &whole.get_means().unwrap(),
+            &batches.get_means().unwrap(),
+            1e-12,
+        );
+        assert_close(
+            &whole.predict_log_proba(&xs).unwrap(),
+            &batches.predict_log_proba(&xs).unwrap(),
+            1e-9,
+        );
+    }
+
+    #[test]
+    fn test_priors() {
+        let xs = Matrix::new(&[Array::from(&[0.0]), Array::from(&[1.0])]);
+        let ys = Array::from(&[0.0, 1.0]);
+        let midpoint = Matrix::new(&[Array::from(&[0.5])]);
+
+        let mut bayes = GaussianNaiveBayes::new();
+        bayes.set_var_smoothing(1.0);
+        bayes.fit(&xs, &ys).unwrap();
+        assert!((bayes.predict_proba(&midpoint).unwrap()[0][0] - 0.5).abs() < 1e-12);
+
+        bayes.set_priors(Array::from(&[0.8, 0.2]));
+        let probabilities = bayes.predict_proba(&midpoint).unwrap();
+        assert!((probabilities[0][0] - 0.8).abs() < 1e-12);
+
+        let mut unfitted = GaussianNaiveBayes::new();
+        unfitted.set_priors(Array::from(&[0.5, 0.25, 0.25]));
+        assert!(unfitted.fit(&xs, &ys).is_err());
+    }
+
+    #[test]
+    fn test_gaussian_infinite_features() {
+        let xs = Matrix::new(&[Array::from(&[0.0]), Array::from(&[f64::INFINITY])]);
+        let ys = Array::from(&[0.0, 1.0]);
+        let mut bayes = GaussianNaiveBayes::new();
+
+        assert!(matches!(
+            bayes.fit(&xs, &ys),
+            Err(ModelError::InvalidInput(_))
+        ));
+        assert!(!bayes.is_fitted());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_priors_of_fitted_model() {
+        let (xs, ys) = gaussian_data(30, 7);
+        let mut bayes = GaussianNaiveBayes::new();
+        bayes.fit(&xs, &ys).unwrap();
+        bayes.set_priors(Array::from(&[1.0]));
+    }
+
+    #[test]
+    fn test_multinomial() {
+        let xs = Matrix::new(&[
+            Array::from(&[2.0, 1.0, 0.0]),
+            Array::from(&[1.0, 0.0, 0.0]),
+            Array::from(&[0.0, 1.0, 3.0]),
+        ]);
+        let ys = Array::from(&[0.0, 0.0, 1.0]);
+        let mut bayes = MultinomialNaiveBayes::new();
+        bayes.fit(&xs, &ys).unwrap();
+
+        // The smoothed counts are 4, 2, 1 and 1, 2, 4.
+        let expected = Matrix::new(&[
+            Array::from(&[
+                (4.0f64 / 7.0).ln(),
+                (2.0f64 / 7.0).ln(),
+                (1.0f64 / 7.0).ln(),
+            ]),
+            Array::from(&[
+                (1.0f64 / 7.0).ln(),
+                (2.0f64 / 7.0).ln(),
+                (4.0f64 / 7.0).ln(),
+            ]),
+        ]);
+        assert_close(&bayes.get_feature_log_prob().unwrap(), &expected, 1e-12);
+
+        // The first and last word together are as likely in both classes, so the posterior is
+        // the prior.
+        let test = Matrix::new(&[Array::from(&[1.0, 0.0, 1.0])]);
+        assert!((bayes.predict_proba(&test).unwrap()[0][0] - 2.0 / 3.0).abs() < 1e-12);
+
+        bayes.set_alpha(0.5);
+        assert!(bayes.get_feature_log_prob().unwrap()[1][0] < expected[1][0]);
+        let negative = Matrix::new(&[Array::from(&[-1.0, 0.0, 0.0])]);
+        assert!(bayes.predict(&negative).is_err());
+        assert!(bayes.partial_fit(&negative, &Array::from(&[0.0])).is_err());
+    }
+
+    #[test]
+    fn test_bernoulli() {
+        let xs = Matrix::new(&[
+            Array::from(&[1.0, 0.0]),
+            Array::from(&[1.0, 1.0]),
+            Array::from(&[0.0, 1.0]),
+        ]);
+        let ys = Array::from(&[0.0, 0.0, 1.0]);
+        let mut bayes = BernoulliNaiveBayes::new();
+        bayes.fit(&xs, &ys).unwrap();
+
+        let expected = Matrix::new(&[
+            Array::from(&[(3.0f64 / 4.0).ln(), (2.0f64 / 4.0).ln()]),
+            Array::from(&[(1.0f64 / 3.0).ln(), (2.0f64 / 3.0).ln()]),
+        ]);
+        assert_close(&bayes.get_feature_log_prob().unwrap(), &expected, 1e-12);
+
+        // Absent features are evidence: P(x | 0) = 1/4 * 1/2 and P(x | 1) = 2/3 * 1/3.
+        let test = Matrix::new(&[Array::from(&[0.0, 0.0])]);
+        let first = 2.0 / 3.0 * 1.0 / 8.0;
+        let second = 1.0 / 3.0 * 2.0 / 9.0;
+        let probabilities = bayes.predict_proba(&test).unwrap();
+        assert!((probabilities[0][0] - first / (first + second)).abs() < 1e-12);
+
+        // Counts above the threshold are present.
+        let counts = Matrix::new(&[Array::from(&[3.0, 1.0])]);
+        bayes.set_binarize(Some(2.0));
+        let thresholded = bayes.predict_proba(&counts).unwrap();
+        bayes.set_binarize(None);
+        let binary = Matrix::new(&[Array::from(&[1.0, 0.0])]);
+        assert_close(&bayes.predict_proba(&binary).unwrap(), &thresholded, 0.0);
+        assert!(bayes.predict(&counts).is_err());
+    }
+
+    #[test]
+    fn test_new_classes() {
+        let mut bayes = MultinomialNaiveBayes::new();
+        bayes
+            .partial_fit(
+                &Matrix::new(&[Array::from(&[1.0, 0.0])]),
+                &Array::from(&[5.0]),
+            )
+            .unwrap();
+        bayes
+            .partial_fit(
+                &Matrix::new(&[Array::from(&[0.0, 1.0]), Array::from(&[1.0, 1.0])]),
+                &Array::from(&[2.0, 5.0]),
+            )
+            .unwrap();
+
+        assert_eq!(Array::from(&[2.0, 5.0]), bayes.classes().unwrap());
+        let expected = Matrix::new(&[
+            Array::from(&[(1.0f64 / 3.0).ln(), (2.0f64 / 3.0).ln()]),
+            Array::from(&[(3.0f64 / 5.0).ln(), (2.0f64 / 5.0).ln()]),
+        ]);
+        assert_close(&bayes.get_feature_log_prob().unwrap(), &expected, 1e-12);
+    }
+
+    #[test]
+    fn test_errors() {
+        let xs = Matrix::new(&[Array::from(&[1.0, 0.0]), Array::from(&[0.0, 1.0])]);
+        let ys = Array::from(&[0.0, 1.0]);
+        let mut bayes = BernoulliNaiveBayes::new();
+        assert_eq!(Err(ModelError::NotFitted), bayes.predict_log_proba(&xs));
+        assert_eq!(Err(ModelError::NotFitted), bayes.get_feature_log_prob());
+
+        check_classifier_errors(&mut bayes, &xs, &ys);
+        let wider = Matrix::new(&[Array::from(&[1.0, 0.0, 1.0])]);
+        assert!(bayes.partial_fit(&wider, &Array::from(&[0.0])).is_err());
+        check_classifier_errors(&mut GaussianNaiveBayes::new(), &xs, &ys);
+        check_classifier_errors(&mut MultinomialNaiveBayes::new(), &xs, &ys);
+    }
+}
@@ -0,0 +1,461 @@
+//! Nearest Neighbors - Predicting from the closest training samples
+//!
+//! A k-nearest neighbours model remembers its training samples in a spatial index and predicts
+//! a sample from the targets of the `k` training samples nearest to it:
+//!
+//! * `KNeighborsClassifier` predicts the class that most of them have.
+//! * `KNeighborsRegressor` predicts the mean of their targets.
+//!
+//! The neighbours either count equally or with the inverse of their distance.
+
+use crate::linalg::array::Array;
+use crate::linalg::matrix::Matrix;
+use crate::machine_learning::model::{
+    check_features, check_targets, encode_classes, Classifier, Estimator, ModelError, Predictor,
+    Result,
+};
+use crate::machine_learning::spatial::{BallTree, KdTree, Metric, Neighbor, SpatialIndex};
+
+/// How much every neighbour counts.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Weighting {
+    /// Every neighbour counts equally. This is the default.
+    #[default]
+    Uniform,
+    /// Every neighbour counts with the inverse of its distance. Neighbours at distance 0 count
+    /// alone if there are any.
+    Distance,
+}
+
+/// The spatial index of the training samples.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Algorithm {
+    /// A `KdTree`, best for a few features. This is the default.
+    #[default]
+    KdTree,
+    /// A `BallTree`, best for many features.
+    BallTree,
+}
+
+/// The settings shared by the classifier and the regressor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Settings {
+    k: usize,
+    weighting: Weighting,
+    metric: Metric,
+    algorithm: Algorithm,
+}
+
+impl Settings {
+    fn new(k: usize, name: &str) -> Self {
+        assert!(k > 0, "ERROR - {}: k must be positive.", name);
+        Settings {
+            k,
+            weighting: Weighting::default(),
+            metric: Metric::default(),
+            algorithm: Algorithm::default(),
+        }
+    }
+
+    /// Returns the index of the training samples, or an error if there are fewer than `k`.
+    fn index(&self, xs: &Matrix) -> Result<Index> {
+        let (rows, _) = xs.dimensions();
+        if rows < self.k {
+            return Err(ModelError::InvalidInput(format!(
+                "{} neighbours need at least as many samples, but there are {}",
+                self.k, rows
+            )));
+        }
+        Ok(match self.algorithm {
+            Algorithm::KdTree => Index::Kd(KdTree::new(xs, self.metric)),
+            Algorithm::BallTree => Index::Ball(BallTree::new(xs, self.metric)),
+        })
+    }
+}
+
+/// The spatial index of a fitted model.
+#[derive(Debug, Clone, PartialEq)]
+enum Index {
+    Kd(KdTree),
+    Ball(BallTree),
+}
+
+impl Index {
+    fn get(&self) -> &dyn SpatialIndex {
+        match self {
+            Index::Kd(tree) => tree,
+            Index::Ball(tree) => tree,
+        }
+    }
+}
+
+/// Returns the `k` nearest training samples of every sample.
+fn query(
+    index: &Option<Index>,
+    xs: &Matrix,
+    k: usize,
+    features: usize,
+) -> Result<Vec<Vec<Neighbor>>> {
+    let index = index.as_ref().ok_or(ModelError::NotFitted)?.get();
+    check_features(xs, features)?;
+    Ok(xs.iter().map(|row| index.nearest(row, k)).collect())
+}
+
+/// Returns the weight of every neighbour.
+fn weights(neighbors: &[Neighbor], weighting: Weighting) -> Vec<f64> {
+    match weighting {
+        Weighting::Uniform => vec![1.0; neighbors.len()],
+        Weighting::Distance if neighbors.iter().any(|n| n.distance == 0.0) => neighbors
+            .iter()
+            .map(|n| if n.distance == 0.0 { 1.0 } else { 0.0 })
+            .collect(),
+        Weighting::Distance => neighbors.iter().map(|n| 1.0 / n.distance).collect(),
+    }
+}
+
+/// A classifier that predicts the class most of the `k` nearest training samples have.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::model::Classifier;
+/// use moonalloy::machine_learning::neighbors::KNeighborsClassifier;
+///
+/// let xs = Matrix::new(&[
+///     Array::from(&[0.0, 0.0]),
+///     Array::from(&[0.5, 0.0]),
+///     Array::from(&[0.0, 0.5]),
+///     Array::from(&[3.0, 3.0]),
+///     Array::from(&[3.5, 3.0]),
+/// ]);
+/// let ys = Array::from(&[1.0, 1.0, 1.0, 2.0, 2.0]);
+///
+/// let mut knn = KNeighborsClassifier::new(3);
+/// knn.fit(&xs, &ys).unwrap();
+///
+/// let new = Matrix::new(&[Array::from(&[0.2, 0.2]), Array::from(&[3.2, 2.8])]);
+/// assert_eq!(Array::from(&[1.0, 2.0]), knn.predict(&new).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct KNeighborsClassifier {
+    settings: Settings,
+    index: Option<Index>,
+    features: usize,
+    classes: Array,
+    labels: Vec<usize>,
+}
+
+impl KNeighborsClassifier {
+    /// Returns an unfitted model that uses `k` neighbours.
+    ///
+    /// # Panics
+    ///
+    /// `k` must be positive, otherwise the code will panic.
+    pub fn new(k: usize) -> Self {
+        KNeighborsClassifier {
+            settings: Settings::new(k, "KNeighborsClassifier"),
+            index: None,
+            features: 0,
+            classes: Array::new(),
+            labels: Vec::new(),
+        }
+    }
+
+    /// Sets how much every neighbour counts.
+    pub fn set_weighting(&mut self, weighting: Weighting) {
+        self.settings.weighting = weighting;
+    }
+
+    /// Sets the metric of the distances, which takes effect at the next fit.
+    ///
+    /// # Panics
+    ///
+    /// A Minkowski exponent must be finite and at least 1, otherwise the code will panic.
+    pub fn set_metric(&mut self, metric: Metric) {
+        metric.check();
+        self.settings.metric = metric;
+    }
+
+    /// Sets the spatial index of the training samples, which takes effect at the next fit.
+    pub fn set_algorithm(&mut self, algorithm: Algorithm) {
+        self.settings.algorithm = algorithm;
+    }
+
+    /// Returns the number of neighbours.
+    pub fn get_k(&self) -> usize {
+        self.settings.k
+    }
+
+    /// Returns the `k` nearest training samples of every sample, by increasing distance.
+    pub fn kneighbors(&self, xs: &Matrix) -> Result<Vec<Vec<Neighbor>>> {
+        query(&self.index, xs, self.settings.k, self.features)
+    }
+}
+
+impl Estimator for KNeighborsClassifier {
+    fn is_fitted(&self) -> bool {
+        self.index.is_some()
+    }
+}
+
+impl Classifier for KNeighborsClassifier {
+    /// Fits the model to at least `k` samples.
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        check_targets(xs, ys)?;
+        let index = self.settings.index(xs)?;
+        let (classes, labels) = encode_classes(ys);
+
+        self.index = Some(index);
+        self.features = xs.dimensions().1;
+        self.classes = classes;
+        self.labels = labels;
+        Ok(())
+    }
+
+    fn classes(&self) -> Result<Array> {
+        self.check_fitted()?;
+        Ok(self.classes.clone())
+    }
+
+    /// Returns the weighted share of the neighbours in every class.
+    fn predict_proba(&self, xs: &Matrix) -> Result<Matrix> {
+        let neighbors = self.kneighbors(xs)?;
+        Ok(Matrix::from_rows(
+            neighbors
+                .iter()
+                .map(|row| {
+                    let mut votes = Array::zeros(self.classes.len());
+                    for (n, w) in row.iter().zip(weights(row, self.settings.weighting)) {
+                        votes[self.labels[n.index]] += w;
+                    }
+                    let total = votes.sum();
+                    votes.scalar_mult(1.0 / total)
+                })
+                .collect(),
+        ))
+    }
+}
+
+/// A regression that predicts the mean target of the `k` nearest training samples.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::model::Predictor;
+/// use moonalloy::machine_learning::neighbors::{KNeighborsRegressor, Weighting};
+///
+/// let xs = Matrix::new(&[
+///     Array::from(&[0.0]),
+///     Array::from(&[1.0]),
+///     Array::from(&[2.0]),
+///     Array::from(&[3.0]),
+/// ]);
+/// let ys = Array::from(&[0.0, 10.0, 20.0, 30.0]);
+///
+/// let mut knn = KNeighborsRegressor::new(2);
+/// knn.fit(&xs, &ys).unwrap();
+/// assert_eq!(Array::from(&[15.0]), knn.predict(&Matrix::new(&[Array::from(&[1.4])])).unwrap());
+///
+/// knn.set_weighting(Weighting::Distance);
+/// let prediction = knn.predict(&Matrix::new(&[Array::from(&[1.25])])).unwrap();
+/// assert!((prediction[0] - 12.5).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct KNeighborsRegressor {
+    settings: Settings,
+    index: Option<Index>,
+    features: usize,
+    targets: Array,
+}
+
+impl KNeighborsRegressor {
+    /// Returns an unfitted model that uses `k` neighbours.
+    ///
+    /// # Panics
+    ///
+    /// `k` must be positive, otherwise the code will panic.
+    pub fn new(k: usize) -> Self {
+        KNeighborsRegressor {
+            settings: Settings::new(k, "KNeighborsRegressor"),
+            index: None,
+            features: 0,
+            targets: Array::new(),
+        }
+    }
+
+    /// Sets how much every neighbour counts.
+    pub fn set_weighting(&mut self, weighting: Weighting) {
+        self.settings.weighting = weighting;
+    }
+
+    /// Sets the metric of the distances, which takes effect at the next fit.
+    ///
+    /// # Panics
+    ///
+    /// A Minkowski exponent must be finite and at least 1, otherwise the code will panic.
+    pub fn set_metric(&mut self, metric: Metric) {
+        metric.check();
+        self.settings.metric = metric;
+    }
+
+    /// Sets the spatial index of the training samples, which takes effect at the next fit.
+    pub fn set_algorithm(&mut self, algorithm: Algorithm) {
+        self.settings.algorithm = algorithm;
+    }
+
+    /// Returns the number of neighbours.
+    pub fn get_k(&self) -> usize {
+        self.settings.k
+    }
+
+    /// Returns the `k` nearest training samples of every sample, by increasing distance.
+    pub fn kneighbors(&self, xs: &Matrix) -> Result<Vec<Vec<Neighbor>>> {
+        query(&self.index, xs, self.settings.k, self.features)
+    }
+}
+
+impl Estimator for KNeighborsRegressor {
+    fn is_fitted(&self) -> bool {
+        self.index.is_some()
+    }
+}
+
+impl Predictor for KNeighborsRegressor {
+    /// Fits the model to at least `k` samples.
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        check_targets(xs, ys)?;
+        self.index = Some(self.settings.index(xs)?);
+        self.features = xs.dimensions().1;
+        self.targets = ys.clone();
+        Ok(())
+    }
+
+    /// Returns the weighted mean target of the neighbours of every sample.
+    fn predict(&self, xs: &Matrix) -> Result<Array> {
+        let neighbors = self.kneighbors(xs)?;
+        Ok(Array::from_vec(
+            neighbors
+                .iter()
+                .map(|row| {
+                    let weights = weights(row, self.settings.weighting);
+                    let total: f64 = weights.iter().sum();
+                    row.iter()
+                        .zip(&weights)
+                        .map(|(n, w)| w * self.targets[n.index])
+                        .sum::<f64>()
+                        / total
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::machine_learning::model::test::{
+        check_classifier_errors, check_predictor_errors, normal_samples,
+    };
+    use crate::random::pcg::Pcg64;
+
+    /// Returns noisy samples of two classes on either side of the line `x + y = 0`.
+    fn halves(n: usize, seed: u64) -> (Matrix, Array) {
+        normal_samples(
+            n,
+            2,
+            seed,
+            |row| if row[0] + row[1] > 0.0 { 1.0 } else { -1.0 },
+        )
+    }
+
+    #[test]
+    fn test_classifier() {
+        let (xs, ys) = halves(400, 1);
+        let (test_xs, test_ys) = halves(200, 2);
+
+        for &algorithm in [Algorithm::KdTree, Algorithm::BallTree].iter() {
+            for &weighting in [Weighting::Uniform, Weighting::Distance].iter() {
+                let mut knn = KNeighborsClassifier::new(5);
+                knn.set_algorithm(algorithm);
+                knn.set_weighting(weighting);
+                knn.fit(&xs, &ys).unwrap();
+
+                assert_eq!(Array::from(&[-1.0, 1.0]), knn.classes().unwrap());
+                assert!(knn.score(&test_xs, &test_ys).unwrap() > 0.93);
+                let probabilities = knn.predict_proba(&test_xs).unwrap();
+                assert!(probabilities
+                    .iter()
+                    .all(|row| (row.sum() - 1.0).abs() < 1e-12));
+            }
+        }
+
+        // Distance weighting predicts the training samples exactly.
+        let mut knn = KNeighborsClassifier::new(7);
+        knn.set_weighting(Weighting::Distance);
+        knn.fit(&xs, &ys).unwrap();
+        assert_eq!(1.0, knn.score(&xs, &ys).unwrap());
+    }
+
+    #[test]
+    fn test_proba() {
+        let xs = Matrix::new(&[
+            Array::from(&[0.0]),
+            Array::from(&[1.0]),
+            Array::from(&[3.0]),
+            Array::from(&[10.0]),
+        ]);
+        let ys = Array::from(&[0.0, 1.0, 1.0, 2.0]);
+        let mut knn = KNeighborsClassifier::new(3);
+        knn.set_metric(Metric::Manhattan);
+        knn.fit(&xs, &ys).unwrap();
+
+        let query = Matrix::new(&[Array::from(&[0.5])]);
+        let uniform = knn.predict_proba(&query).unwrap();
+        assert_eq!(Array::from(&[1.0 / 3.0, 2.0 / 3.0, 0.0]), uniform[0]);
+
+        // The weights are 2, 2 and 0.4.
+        knn.set_weighting(Weighting::Distance);
+        let weighted = knn.predict_proba(&query).unwrap();
+        assert!((weighted[0][0] - 2.0 / 4.4).abs() < 1e-12);
+        assert!((weighted[0][1] - 2.4 / 4.4).abs() < 1e-12);
+
+        let neighbors = knn.kneighbors(&query).unwrap();
+        assert_eq!(
+            vec![0, 1, 2],
+            neighbors[0].iter().map(|n| n.index).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_regressor() {
+        let mut rng = Pcg64::seed_from_u64(3);
+        let xs = Matrix::random_normal(500, 1, &mut rng);
+        let ys = Array::from_vec(xs.iter().map(|row| row[0].sin()).collect());
+
+        let mut knn = KNeighborsRegressor::new(4);
+        knn.set_algorithm(Algorithm::BallTree);
+        knn.fit(&xs, &ys).unwrap();
+        assert!(knn.score(&xs, &ys).unwrap() > 0.99);
+
+        let query = Matrix::new(&[Array::from(&[0.3])]);
+        let neighbors = knn.kneighbors(&query).unwrap();
+        let mean = neighbors[0].iter().map(|n| ys[n.index]).sum::<f64>() / 4.0;
+        assert!((knn.predict(&query).unwrap()[0] - mean).abs() < 1e-12);
+
+        knn.set_weighting(Weighting::Distance);
+        assert_eq!(ys, knn.predict(&xs).unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        let (xs, ys) = halves(3, 4);
+        assert!(KNeighborsClassifier::new(5).fit(&xs, &ys).is_err());
+
+        check_classifier_errors(&mut KNeighborsClassifier::new(2), &xs, &ys);
+        check_predictor_errors(&mut KNeighborsRegressor::new(2), &xs, &ys);
+    }
+}
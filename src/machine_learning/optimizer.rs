@@ -0,0 +1,858 @@
+//! Optimizer - First-order optimizers for models without a closed-form fit
+//!
+//! An `Optimizer` updates the parameters of a model, stored in an `Array`, from the gradient of
+//! the loss at the current parameters. The optimizers differ in how they turn gradients into
+//! steps:
+//!
+//! * `Sgd` steps against the gradient, optionally with (Nesterov) momentum.
+//! * `AdaGrad` divides by the root of the sum of all squared gradients so far.
+//! * `RmsProp` divides by the root of a moving average of the squared gradients.
+//! * `Adam` combines momentum with the moving average of `RmsProp`.
+//!
+//! `train` runs an optimizer over shuffled mini-batches of the rows of a `Matrix` for a number
+//! of epochs, with a learning-rate schedule and early stopping on a validation loss.
+
+use crate::linalg::array::Array;
+use crate::linalg::matrix::Matrix;
+use crate::random::pcg::Pcg64;
+use crate::random::{self, Rng};
+
+use std::f64::consts::PI;
+
+/// An algorithm that updates parameters from the gradient of a loss.
+pub trait Optimizer {
+    /// Updates the parameters with the gradient of the loss at them.
+    ///
+    /// # Panics
+    ///
+    /// The gradient must have the length of the parameters, which must not change between
+    /// steps, otherwise the code will panic.
+    fn step(&mut self, params: &mut Array, gradient: &Array);
+
+    /// Returns the current learning rate.
+    fn learning_rate(&self) -> f64;
+
+    /// Sets the learning rate of the next steps, e.g. from a `Schedule`.
+    fn set_learning_rate(&mut self, rate: f64);
+
+    /// Forgets the state built up by the previous steps, such as the momentum.
+    fn reset(&mut self);
+}
+
+/// Returns the state of an optimizer for the given parameters, which starts at 0.
+fn state_for(state: &mut Array, params: &Array, gradient: &Array) {
+    assert_eq!(
+        params.len(),
+        gradient.len(),
+        "ERROR - Optimizer step: The gradient must have the length of the parameters."
+    );
+    if state.is_empty() {
+        *state = Array::zeros(params.len());
+    }
+    assert_eq!(
+        params.len(),
+        state.len(),
+        "ERROR - Optimizer step: The number of parameters changed."
+    );
+}
+
+/// Stochastic gradient descent, optionally with momentum.
+///
+/// With momentum `mu`, the velocity `v = mu v + g` accumulates the gradients, and the step is
+/// `-rate v`, or `-rate (g + mu v)` with Nesterov momentum, which looks ahead along the
+/// velocity.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::machine_learning::optimizer::{Optimizer, Sgd};
+///
+/// // Minimize |x - (1, 2)|^2.
+/// let mut optimizer = Sgd::with_momentum(0.1, 0.9, true);
+/// let mut x = Array::zeros(2);
+/// for _ in 0..200 {
+///     let gradient = x.minus(&Array::from(&[1.0, 2.0])).scalar_mult(2.0);
+///     optimizer.step(&mut x, &gradient);
+/// }
+///
+/// assert!(x.minus(&Array::from(&[1.0, 2.0])).norm() < 1e-6);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sgd {
+    rate: f64,
+    momentum: f64,
+    nesterov: bool,
+    velocity: Array,
+}
+
+impl Sgd {
+    /// Returns plain gradient descent with the given learning rate.
+    pub fn new(rate: f64) -> Sgd {
+        Sgd::with_momentum(rate, 0.0, false)
+    }
+
+    /// Returns gradient descent with momentum.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - the learning rate.
+    /// * `momentum` - the share of the velocity kept between steps, usually 0.9.
+    /// * `nesterov` - whether to use Nesterov momentum.
+    ///
+    /// # Panics
+    ///
+    /// The momentum must be in [0, 1), otherwise the code will panic.
+    pub fn with_momentum(rate: f64, momentum: f64, nesterov: bool) -> Sgd {
+        assert!(
+            (0.0..1.0).contains(&momentum),
+            "ERROR - Sgd: The momentum must be in [0, 1)."
+        );
+
+        Sgd {
+            rate,
+            momentum,
+            nesterov,
+            velocity: Array::new(),
+        }
+    }
+}
+
+impl Optimizer for Sgd {
+    fn step(&mut self, params: &mut Array, gradient: &Array) {
+        state_for(&mut self.velocity, params, gradient);
+
+        for i in 0..params.len() {
+            let g = gradient[i];
+            self.velocity[i] = self.momentum * self.velocity[i] + g;
+            let direction = if self.nesterov {
+                g + self.momentum * self.velocity[i]
+            } else {
+                self.velocity[i]
+            };
+            params[i] -= self.rate * direction;
+        }
+    }
+
+    fn learning_rate(&self) -> f64 {
+        self.rate
+    }
+
+    fn set_learning_rate(&mut self, rate: f64) {
+        self.rate = rate;
+    }
+
+    fn reset(&mut self) {
+        self.velocity = Array::new();
+    }
+}
+
+/// The constant that keeps the adaptive optimizers from dividing by 0.
+const EPSILON: f64 = 1e-8;
+
+/// AdaGrad, which scales the step of every parameter by the inverse root of the sum of its
+/// squared gradients, so rarely updated parameters take larger steps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdaGrad {
+    rate: f64,
+    squares: Array,
+}
+
+impl AdaGrad {
+    /// Returns AdaGrad with the given learning rate.
+    pub fn new(rate: f64) -> AdaGrad {
+        AdaGrad {
+            rate,
+            squares: Array::new(),
+        }
+    }
+}
+
+impl Optimizer for AdaGrad {
+    fn step(&mut self, params: &mut Array, gradient: &Array) {
+        state_for(&mut self.squares, params, gradient);
+
+        for i in 0..params.len() {
+            let g = gradient[i];
+            self.squares[i] += g * g;
+            params[i] -= self.rate * g / (self.squares[i].sqrt() + EPSILON);
+        }
+    }
+
+    fn learning_rate(&self) -> f64 {
+        self.rate
+    }
+
+    fn set_learning_rate(&mut self, rate: f64) {
+        self.rate = rate;
+    }
+
+    fn reset(&mut self) {
+        self.squares = Array::new();
+    }
+}
+
+/// RMSProp, which scales the step of every parameter by the inverse root of an exponential
+/// moving average of its squared gradients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RmsProp {
+    rate: f64,
+    decay: f64,
+    squares: Array,
+}
+
+impl RmsProp {
+    /// Returns RMSProp.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - the learning rate.
+    /// * `decay` - the share of the moving average kept between steps, usually 0.9.
+    ///
+    /// # Panics
+    ///
+    /// The decay must be in [0, 1), otherwise the code will panic.
+    pub fn new(rate: f64, decay: f64) -> RmsProp {
+        assert!(
+            (0.0..1.0).contains(&decay),
+            "ERROR - RmsProp: The decay must be in [0, 1)."
+        );
+
+        RmsProp {
+            rate,
+            decay,
+            squares: Array::new(),
+        }
+    }
+}
+
+impl Optimizer for RmsProp {
+    fn step(&mut self, params: &mut Array, gradient: &Array) {
+        state_for(&mut self.squares, params, gradient);
+
+        for i in 0..params.len() {
+            let g = gradient[i];
+            self.squares[i] = self.decay * self.squares[i] + (1.0 - self.decay) * g * g;
+            params[i] -= self.rate * g / (self.squares[i].sqrt() + EPSILON);
+        }
+    }
+
+    fn learning_rate(&self) -> f64 {
+        self.rate
+    }
+
+    fn set_learning_rate(&mut self, rate: f64) {
+        self.rate = rate;
+    }
+
+    fn reset(&mut self) {
+        self.squares = Array::new();
+    }
+}
+
+/// Adam, which steps along a moving average of the gradients, scaled by the inverse root of a
+/// moving average of the squared gradients. Both averages are corrected for their start at 0.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::machine_learning::optimizer::{Adam, Optimizer};
+///
+/// // Minimize (x - 3)^2 + 100 y^2, which is badly scaled for plain gradient descent.
+/// let mut optimizer = Adam::new(0.05);
+/// let mut params = Array::from(&[0.0, 1.0]);
+/// for _ in 0..2000 {
+///     let gradient = Array::from(&[2.0 * (params[0] - 3.0), 200.0 * params[1]]);
+///     optimizer.step(&mut params, &gradient);
+/// }
+///
+/// assert!((params[0] - 3.0).abs() < 1e-3 && params[1].abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Adam {
+    rate: f64,
+    beta1: f64,
+    beta2: f64,
+    steps: i32,
+    mean: Array,
+    squares: Array,
+}
+
+impl Adam {
+    /// Returns Adam with the usual decays 0.9 and 0.999 of the moving averages.
+    pub fn new(rate: f64) -> Adam {
+        Adam::with_betas(rate, 0.9, 0.999)
+    }
+
+    /// Returns Adam.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - the learning rate.
+    /// * `beta1` - the decay of the moving average of the gradients.
+    /// * `beta2` - the decay of the moving average of the squared gradients.
+    ///
+    /// # Panics
+    ///
+    /// The decays must be in [0, 1), otherwise the code will panic.
+    pub fn with_betas(rate: f64, beta1: f64, beta2: f64) -> Adam {
+        assert!(
+            (0.0..1.0).contains(&beta1) && (0.0..1.0).contains(&beta2),
+            "ERROR - Adam: The decays must be in [0, 1)."
+        );
+
+        Adam {
+            rate,
+            beta1,
+            beta2,
+            steps: 0,
+            mean: Array::new(),
+            squares: Array::new(),
+        }
+    }
+}
+
+impl Optimizer for Adam {
+    fn step(&mut self, params: &mut Array, gradient: &Array) {
+        state_for(&mut self.mean, params, gradient);
+        state_for(&mut self.squares, params, gradient);
+        self.steps = self.steps.saturating_add(1);
+        let correction1 = 1.0 - self.beta1.powi(self.steps);
+        let correction2 = 1.0 - self.beta2.powi(self.steps);
+
+        for i in 0..params.len() {
+            let g = gradient[i];
+            self.mean[i] = self.beta1 * self.mean[i] + (1.0 - self.beta1) * g;
+            self.squares[i] = self.beta2 * self.squares[i] + (1.0 - self.beta2) * g * g;
+            let mean = self.mean[i] / correction1;
+            let squares = self.squares[i] / correction2;
+            params[i] -= self.rate * mean / (squares.sqrt() + EPSILON);
+        }
+    }
+
+    fn learning_rate(&self) -> f64 {
+        self.rate
+    }
+
+    fn set_learning_rate(&mut self, rate: f64) {
+        self.rate = rate;
+    }
+
+    fn reset(&mut self) {
+        self.steps = 0;
+        self.mean = Array::new();
+        self.squares = Array::new();
+    }
+}
+
+/// How the learning rate changes from epoch to epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Schedule {
+    /// The initial rate in every epoch. This is the default.
+    #[default]
+    Constant,
+    /// The rate is multiplied by `factor` every `every` epochs, which must be positive.
+    Step { every: usize, factor: f64 },
+    /// The rate is multiplied by `factor` every epoch.
+    Exponential { factor: f64 },
+    /// The rate in epoch `t` is `initial / (1 + decay t)`.
+    InverseTime { decay: f64 },
+    /// The rate follows half a cosine from the initial rate down to `minimum` over `epochs`
+    /// epochs, and stays at `minimum` afterwards.
+    Cosine { epochs: usize, minimum: f64 },
+}
+
+impl Schedule {
+    /// Returns the learning rate in an epoch, counted from 0.
+    ///
+    /// # Panics
+    ///
+    /// A step schedule must change the rate every positive number of epochs, otherwise the code
+    /// will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moonalloy::machine_learning::optimizer::Schedule;
+    ///
+    /// let schedule = Schedule::Step { every: 10, factor: 0.5 };
+    ///
+    /// assert_eq!(0.1, schedule.rate(0.1, 9));
+    /// assert_eq!(0.05, schedule.rate(0.1, 10));
+    /// assert_eq!(0.025, schedule.rate(0.1, 25));
+    /// ```
+    pub fn rate(&self, initial: f64, epoch: usize) -> f64 {
+        match *self {
+            Schedule::Constant => initial,
+            Schedule::Step { every, factor } => {
+                assert!(
+                    every > 0,
+                    "ERROR - Schedule: The number of epochs of a step must be positive."
+                );
+                initial * factor.powi((epoch / every) as i32)
+            }
+            Schedule::Exponential { factor } => initial * factor.powi(epoch as i32),
+            Schedule::InverseTime { decay } => initial / (1.0 + decay * epoch as f64),
+            Schedule::Cosine { epochs, minimum } => {
+                if epoch >= epochs {
+                    minimum
+                } else {
+                    let progress = epoch as f64 / epochs as f64;
+                    minimum + 0.5 * (initial - minimum) * (1.0 + (PI * progress).cos())
+                }
+            }
+        }
+    }
+}
+
+/// Stops training once the monitored loss has not improved for a number of epochs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EarlyStopping {
+    patience: usize,
+    min_delta: f64,
+    best_loss: f64,
+    best_epoch: usize,
+    epochs: usize,
+}
+
+impl EarlyStopping {
+    /// Returns early stopping that has seen no losses yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `patience` - the number of epochs without improvement after which to stop.
+    /// * `min_delta` - the smallest decrease of the loss that counts as an improvement.
+    pub fn new(patience: usize, min_delta: f64) -> EarlyStopping {
+        EarlyStopping {
+            patience,
+            min_delta,
+            best_loss: f64::INFINITY,
+            best_epoch: 0,
+            epochs: 0,
+        }
+    }
+
+    /// Records the loss after an epoch, and returns whether to stop.
+    pub fn update(&mut self, loss: f64) -> bool {
+        if loss < self.best_loss - self.min_delta {
+            self.best_loss = loss;
+            self.best_epoch = self.epochs;
+        }
+        self.epochs += 1;
+
+        self.epochs - self.best_epoch > self.patience
+    }
+
+    /// Returns whether the last recorded loss was the best so far.
+    pub fn improved(&self) -> bool {
+        self.epochs > 0 && self.best_epoch == self.epochs - 1
+    }
+
+    /// Returns the smallest loss recorded.
+    pub fn best_loss(&self) -> f64 {
+        self.best_loss
+    }
+
+    /// Returns the epoch of the smallest loss recorded, counted from 0.
+    pub fn best_epoch(&self) -> usize {
+        self.best_epoch
+    }
+}
+
+/// Returns the rows of `xs` and the matching targets in shuffled batches of `batch_size`
+/// samples. The last batch is smaller if the samples do not divide evenly.
+///
+/// # Panics
+///
+/// There must be a target for every row and a positive batch size, otherwise the code will
+/// panic.
+pub fn mini_batches(
+    xs: &Matrix,
+    ys: &Array,
+    batch_size: usize,
+    rng: &mut dyn Rng,
+) -> Vec<(Matrix, Array)> {
+    let (rows, _) = xs.dimensions();
+    assert_eq!(
+        rows,
+        ys.len(),
+        "ERROR - mini_batches: There must be a target for every row."
+    );
+    assert!(
+        batch_size > 0,
+        "ERROR - mini_batches: The batch size must be positive."
+    );
+
+    let mut order: Vec<usize> = (0..rows).collect();
+    random::shuffle(&mut order, rng);
+
+    order
+        .chunks(batch_size)
+        .map(|batch| {
+            let batch_xs = Matrix::from_rows(batch.iter().map(|&i| xs[i].clone()).collect());
+            let batch_ys = Array::from_vec(batch.iter().map(|&i| ys[i]).collect());
+            (batch_xs, batch_ys)
+        })
+        .collect()
+}
+
+/// The controls of `train`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrainingOptions {
+    /// The maximum number of passes over the training samples.
+    pub epochs: usize,
+    /// The number of samples per step.
+    pub batch_size: usize,
+    /// How the learning rate changes from epoch to epoch, starting from the rate of the
+    /// optimizer.
+    pub schedule: Schedule,
+    /// The patience and minimum improvement of early stopping, or `None` to train for all
+    /// epochs.
+    pub early_stopping: Option<(usize, f64)>,
+    /// The seed of the shuffling of the samples.
+    pub seed: u64,
+}
+
+impl Default for TrainingOptions {
+    fn default() -> Self {
+        TrainingOptions {
+            epochs: 100,
+            batch_size: 32,
+            schedule: Schedule::Constant,
+            early_stopping: None,
+            seed: 0,
+        }
+    }
+}
+
+/// The losses during training.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingHistory {
+    /// The loss on all training samples after every epoch.
+    pub training_loss: Vec<f64>,
+    /// The loss on the validation samples after every epoch, empty without validation samples.
+    pub validation_loss: Vec<f64>,
+    /// The epoch whose parameters were kept, counted from 0.
+    pub best_epoch: usize,
+    /// Whether training stopped before the last epoch.
+    pub stopped_early: bool,
+}
+
+/// Trains parameters with an optimizer on mini-batches of samples.
+///
+/// After every epoch the loss is computed on the validation samples, or on the training
+/// samples without them. With early stopping, the parameters of the epoch with the smallest
+/// loss are kept.
+///
+/// # Arguments
+///
+/// * `optimizer` - the optimizer, whose learning rate is the initial rate of the schedule.
+/// * `params` - the initial parameters, which are overwritten by the trained ones.
+/// * `loss` - returns the loss of the parameters on samples and targets.
+/// * `gradient` - returns the gradient of the loss of the parameters on samples and targets.
+/// * `training` - the samples and targets the parameters are trained on.
+/// * `validation` - the samples and targets to monitor, if any.
+/// * `options` - the controls of training.
+///
+/// # Panics
+///
+/// The samples must have a target for every row, the batch size must be positive and a step
+/// schedule must have a positive number of epochs, otherwise the code will panic.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::optimizer::{train, Adam, TrainingOptions};
+///
+/// // Fit y = w x + b by minimizing the mean squared error.
+/// let xs = Matrix::from_rows((0..50).map(|i| Array::from(&[i as f64 / 10.0])).collect());
+/// let ys = Array::from_vec(xs.iter().map(|row| 3.0 * row[0] - 1.0).collect());
+/// let loss = |p: &Array, xs: &Matrix, ys: &Array| {
+///     let errors = Array::from_vec(
+///         xs.iter().zip(ys.iter()).map(|(row, y)| p[0] * row[0] + p[1] - y).collect(),
+///     );
+///     errors.dotp(&errors) / ys.len() as f64
+/// };
+/// let gradient = |p: &Array, xs: &Matrix, ys: &Array| {
+///     let mut g = Array::zeros(2);
+///     for (row, y) in xs.iter().zip(ys.iter()) {
+///         let error = p[0] * row[0] + p[1] - y;
+///         g[0] += 2.0 * error * row[0] / ys.len() as f64;
+///         g[1] += 2.0 * error / ys.len() as f64;
+///     }
+///     g
+/// };
+///
+/// let mut params = Array::zeros(2);
+/// let options = TrainingOptions { epochs: 500, batch_size: 10, ..TrainingOptions::default() };
+/// let mut optimizer = Adam::new(0.05);
+/// let history = train(&mut optimizer, &mut params, loss, gradient, (&xs, &ys), None, &options);
+///
+/// assert!(history.training_loss[499] < 1e-4);
+/// assert!((params[0] - 3.0).abs() < 1e-2 && (params[1] + 1.0).abs() < 1e-2);
+/// ```
+pub fn train<L, G>(
+    optimizer: &mut dyn Optimizer,
+    params: &mut Array,
+    loss: L,
+    gradient: G,
+    training: (&Matrix, &Array),
+    validation: Option<(&Matrix, &Array)>,
+    options: &TrainingOptions,
+) -> TrainingHistory
+where
+    L: Fn(&Array, &Matrix, &Array) -> f64,
+    G: Fn(&Array, &Matrix, &Array) -> Array,
+{
+    let (xs, ys) = training;
+    let initial_rate = optimizer.learning_rate();
+    let mut rng = Pcg64::seed_from_u64(options.seed);
+    let mut stopping = options
+        .early_stopping
+        .map(|(patience, min_delta)| EarlyStopping::new(patience, min_delta));
+    let mut best_params = params.clone();
+
+    let mut history = TrainingHistory {
+        training_loss: Vec::with_capacity(options.epochs),
+        validation_loss: Vec::new(),
+        best_epoch: options.epochs.saturating_sub(1),
+        stopped_early: false,
+    };
+
+    for epoch in 0..options.epochs {
+        optimizer.set_learning_rate(options.schedule.rate(initial_rate, epoch));
+        for (batch_xs, batch_ys) in mini_batches(xs, ys, options.batch_size, &mut rng) {
+            let g = gradient(params, &batch_xs, &batch_ys);
+            optimizer.step(params, &g);
+        }
+
+        let training_loss = loss(params, xs, ys);
+        history.training_loss.push(training_loss);
+        let monitored = match validation {
+            Some((vx, vy)) => {
+                let validation_loss = loss(params, vx, vy);
+                history.validation_loss.push(validation_loss);
+                validation_loss
+            }
+            None => training_loss,
+        };
+
+        if let Some(stopping) = stopping.as_mut() {
+            let stop = stopping.update(monitored);
+            if stopping.improved() {
+                best_params = params.clone();
+            }
+            history.best_epoch = stopping.best_epoch();
+            if stop {
+                history.stopped_early = epoch + 1 < options.epochs;
+                break;
+            }
+        }
+    }
+
+    optimizer.set_learning_rate(initial_rate);
+    if stopping.is_some() {
+        *params = best_params;
+    }
+    history
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Returns the gradient of `sum_i s_i (x_i - 1)^2 / 2` with badly scaled `s`.
+    fn gradient(x: &Array) -> Array {
+        let scales = [1.0, 10.0, 0.1];
+        Array::from_vec((0..3).map(|i| scales[i] * (x[i] - 1.0)).collect())
+    }
+
+    /// Runs an optimizer on the scaled quadratic and returns the distance to its minimum.
+    fn distance(optimizer: &mut dyn Optimizer, steps: usize) -> f64 {
+        let mut x = Array::zeros(3);
+        for _ in 0..steps {
+            let g = gradient(&x);
+            optimizer.step(&mut x, &g);
+        }
+        x.minus(&Array::of(1.0, 3)).norm()
+    }
+
+    #[test]
+    fn test_optimizers_converge() {
+        assert!(distance(&mut Sgd::new(0.15), 2000) < 1e-6);
+        assert!(distance(&mut Sgd::with_momentum(0.05, 0.9, false), 2000) < 1e-6);
+        assert!(distance(&mut Sgd::with_momentum(0.05, 0.9, true), 2000) < 1e-6);
+        assert!(distance(&mut AdaGrad::new(0.5), 5000) < 1e-4);
+        assert!(distance(&mut RmsProp::new(0.01, 0.9), 2000) < 2e-2);
+        assert!(distance(&mut Adam::new(0.05), 3000) < 1e-3);
+    }
+
+    #[test]
+    fn test_first_steps() {
+        let mut x = Array::from(&[1.0, 1.0]);
+        let g = Array::from(&[2.0, -0.5]);
+
+        // The first Adam step moves every parameter by the learning rate.
+        let mut adam = Adam::new(0.1);
+        adam.step(&mut x, &g);
+        assert!((x[0] - 0.9).abs() < 1e-8 && (x[1] - 1.1).abs() < 1e-8);
+
+        // Momentum accumulates the gradients: v = g, then v = 0.5 g + g.
+        let mut sgd = Sgd::with_momentum(1.0, 0.5, false);
+        let mut y = Array::zeros(1);
+        sgd.step(&mut y, &Array::from(&[1.0]));
+        sgd.step(&mut y, &Array::from(&[1.0]));
+        assert_eq!(-2.5, y[0]);
+
+        let mut nesterov = Sgd::with_momentum(1.0, 0.5, true);
+        let mut z = Array::zeros(1);
+        nesterov.step(&mut z, &Array::from(&[1.0]));
+        assert_eq!(-1.5, z[0]);
+
+        sgd.reset();
+        let mut w = Array::zeros(1);
+        sgd.step(&mut w, &Array::from(&[1.0]));
+        assert_eq!(-1.0, w[0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_changing_length() {
+        let mut adam = Adam::new(0.1);
+        adam.step(&mut Array::zeros(2), &Array::zeros(2));
+        adam.step(&mut Array::zeros(3), &Array::zeros(3));
+    }
+
+    #[test]
+    fn test_schedules() {
+        assert_eq!(0.2, Schedule::Constant.rate(0.2, 50));
+        assert_eq!(
+            0.2 * 0.9f64.powi(3),
+            Schedule::Exponential { factor: 0.9 }.rate(0.2, 3)
+        );
+        assert_eq!(0.1, Schedule::InverseTime { decay: 0.5 }.rate(0.2, 2));
+
+        let cosine = Schedule::Cosine {
+            epochs: 10,
+            minimum: 0.01,
+        };
+        assert_eq!(0.2, cosine.rate(0.2, 0));
+        assert!((cosine.rate(0.2, 5) - 0.105).abs() < 1e-15);
+        assert_eq!(0.01, cosine.rate(0.2, 10));
+        assert_eq!(0.01, cosine.rate(0.2, 100));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_step_of_no_epochs() {
+        Schedule::Step {
+            every: 0,
+            factor: 0.5,
+        }
+        .rate(0.1, 3);
+    }
+
+    #[test]
+    fn test_early_stopping() {
+        let mut stopping = EarlyStopping::new(2, 0.1);
+        let losses = [1.0, 0.5, 0.45, 0.3, 0.35, 0.25];
+        let stops: Vec<bool> = losses.iter().map(|&l| stopping.update(l)).collect();
+
+        // 0.45 and 0.25 do not improve by more than 0.1 on the best loss.
+        assert_eq!(vec![false, false, false, false, false, true], stops);
+        assert_eq!(0.3, stopping.best_loss());
+        assert_eq!(3, stopping.best_epoch());
+    }
+
+    #[test]
+    fn test_mini_batches() {
+        let xs = Matrix::from_rows((0..10).map(|i| Array::from(&[i as f64])).collect());
+        let ys = Array::from_vec((0..10).map(|i| 10.0 * i as f64).collect());
+        let batches = mini_batches(&xs, &ys, 4, &mut Pcg64::seed_from_u64(1));
+
+        let sizes: Vec<usize> = batches.iter().map(|(_, y)| y.len()).collect();
+        assert_eq!(vec![4, 4, 2], sizes);
+        let mut seen: Vec<f64> = Vec::new();
+        for (batch_xs, batch_ys) in &batches {
+            for (row, y) in batch_xs.iter().zip(batch_ys.iter()) {
+                assert_eq!(10.0 * row[0], *y);
+                seen.push(row[0]);
+            }
+        }
+        seen.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!((0..10).map(|i| i as f64).collect::<Vec<_>>(), seen);
+    }
+
+    #[test]
+    fn test_train_early_stopping() {
+        // Fitting noise with a polynomial that is too flexible eventually overfits the
+        // validation samples; early stopping keeps the parameters of the best epoch.
+        let mut rng = Pcg64::seed_from_u64(2);
+        let sample = |n: usize, rng: &mut Pcg64| {
+            let xs = Matrix::from_rows(
+                (0..n)
+                    .map(|_| {
+                        let x = 2.0 * rng.next_f64() - 1.0;
+                        Array::from_vec((1..=6).map(|k| x.powi(k)).collect())
+                    })
+                    .collect(),
+            );
+            let ys = Array::from_vec(
+                xs.iter()
+                    .map(|row| row[0] + 0.3 * (2.0 * rng.next_f64() - 1.0))
+                    .collect(),
+            );
+            (xs, ys)
+        };
+        let (train_xs, train_ys) = sample(12, &mut rng);
+        let (valid_xs, valid_ys) = sample(200, &mut rng);
+
+        let mse = |p: &Array, xs: &Matrix, ys: &Array| {
+            let errors = Array::from_vec(
+                xs.iter()
+                    .zip(ys.iter())
+                    .map(|(row, y)| row.dotp(p) - y)
+                    .collect(),
+            );
+            errors.dotp(&errors) / ys.len() as f64
+        };
+        let gradient = |p: &Array, xs: &Matrix, ys: &Array| {
+            let mut g = Array::zeros(p.len());
+            for (row, y) in xs.iter().zip(ys.iter()) {
+                let error = row.dotp(p) - y;
+                for j in 0..p.len() {
+                    g[j] += 2.0 * error * row[j] / ys.len() as f64;
+                }
+            }
+            g
+        };
+
+        let options = TrainingOptions {
+            epochs: 3000,
+            batch_size: 12,
+            schedule: Schedule::Constant,
+            early_stopping: Some((50, 0.0)),
+            seed: 4,
+        };
+        let mut params = Array::zeros(6);
+        let mut optimizer = Adam::new(0.05);
+        let history = train(
+            &mut optimizer,
+            &mut params,
+            mse,
+            gradient,
+            (&train_xs, &train_ys),
+            Some((&valid_xs, &valid_ys)),
+            &options,
+        );
+
+        let best = history.validation_loss[history.best_epoch];
+        assert!(history.validation_loss.iter().all(|&l| l >= best));
+        assert_eq!(best, mse(&params, &valid_xs, &valid_ys));
+        assert_eq!(history.training_loss.len(), history.validation_loss.len());
+        assert_eq!(0.05, optimizer.learning_rate());
+    }
+}
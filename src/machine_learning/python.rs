@@ -0,0 +1,226 @@
+//! Python bindings for the Machine Learning models
+//!
+//! This module exposes the models and evaluation functions to Python through `pyo3` with an
+//! interface similar to the one found in scikit-learn: models are trained with `fit(X, y)`,
+//! used with `predict(X)` and evaluated with `score(X, y)`.
+
+use crate::linalg::array::Array;
+use crate::linalg::matrix::Matrix;
+use crate::machine_learning::evaluation::{
+    coefficient_of_determination, evaluate_simple_linear_regression,
+};
+use crate::machine_learning::linear_regression::SimpleLinearRegression;
+use crate::machine_learning::model::{Estimator, ModelError, Predictor};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Extracts a single feature from `X`.
+///
+/// `X` can either be a flat sequence of numbers or a sequence of rows with exactly one column,
+/// which is the shape scikit-learn expects for a single feature.
+fn extract_feature(xs: &PyAny) -> PyResult<Vec<f64>> {
+    if let Ok(values) = xs.extract::<Vec<f64>>() {
+        return Ok(values);
+    }
+
+    let rows = xs.extract::<Vec<Vec<f64>>>()?;
+    rows.into_iter()
+        .map(|row| match row.as_slice() {
+            [value] => Ok(*value),
+            _ => Err(PyValueError::new_err(
+                "Expected X to contain exactly one feature per sample.",
+            )),
+        })
+        .collect()
+}
+
+/// Returns a single feature as a matrix with one column.
+fn feature_matrix(xs: &[f64]) -> Matrix {
+    Matrix::from_rows(xs.iter().map(|&x| Array::from(&[x])).collect())
+}
+
+/// Converts a model error into a Python exception.
+fn to_py_err(err: ModelError) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+/// Checks that `X` and `y` contain the same number of samples.
+fn check_lengths(xs: &[f64], ys: &[f64]) -> PyResult<()> {
+    if xs.len() != ys.len() {
+        return Err(PyValueError::new_err(format!(
+            "X and y have different numbers of samples: {} and {}.",
+            xs.len(),
+            ys.len()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Python wrapper around `SimpleLinearRegression`.
+#[pyclass(name = "SimpleLinearRegression", module = "moonalloy")]
+pub struct PySimpleLinearRegression {
+    model: SimpleLinearRegression,
+}
+
+#[pymethods]
+impl PySimpleLinearRegression {
+    /// Returns an unfitted model, or a fitted one if the slope or intercept is given.
+    #[new]
+    #[args(slope = "None", intercept = "None")]
+    fn new(slope: Option<f64>, intercept: Option<f64>) -> Self {
+        let model = if slope.is_none() && intercept.is_none() {
+            SimpleLinearRegression::new()
+        } else {
+            SimpleLinearRegression::from(slope.unwrap_or(0.0), intercept.unwrap_or(0.0))
+        };
+        PySimpleLinearRegression { model }
+    }
+
+    /// The slope of the fitted line.
+    #[getter]
+    fn slope(&self) -> f64 {
+        self.model.get_slope()
+    }
+
+    /// The intercept of the fitted line.
+    #[getter]
+    fn intercept(&self) -> f64 {
+        self.model.get_feature()
+    }
+
+    /// Fits the model to the samples `X` and targets `y` and returns the model itself.
+    #[pyo3(text_signature = "($self, X, y)")]
+    fn fit<'p>(
+        mut slf: PyRefMut<'p, Self>,
+        xs: &PyAny,
+        ys: Vec<f64>,
+    ) -> PyResult<PyRefMut<'p, Self>> {
+        let xs = extract_feature(xs)?;
+        check_lengths(&xs, &ys)?;
+
+        slf.model
+            .fit(&feature_matrix(&xs), &Array::from_vec(ys))
+            .map_err(to_py_err)?;
+
+        Ok(slf)
+    }
+
+    /// Returns the predicted targets for the samples `X`.
+    #[pyo3(text_signature = "($self, X)")]
+    fn predict(&self, xs: &PyAny) -> PyResult<Vec<f64>> {
+        let xs = extract_feature(xs)?;
+
+        let predictions = self
+            .model
+            .predict(&feature_matrix(&xs))
+            .map_err(to_py_err)?;
+        Ok(predictions.to_vec())
+    }
+
+    /// Returns the coefficient of determination (R²) of the predictions for `X` against `y`.
+    #[pyo3(text_signature = "($self, X, y)")]
+    fn score(&self, xs: &PyAny, ys: Vec<f64>) -> PyResult<f64> {
+        let xs = extract_feature(xs)?;
+        check_lengths(&xs, &ys)?;
+
+        self.model
+            .score(&feature_matrix(&xs), &Array::from_vec(ys))
+            .map_err(to_py_err)
+    }
+
+    fn __getstate__(&self) -> (f64, f64, bool) {
+        (
+            self.model.get_slope(),
+            self.model.get_feature(),
+            self.model.is_fitted(),
+        )
+    }
+
+    fn __setstate__(&mut self, state: (f64, f64, bool)) {
+        let (slope, intercept, fitted) = state;
+        self.model = if fitted {
+            SimpleLinearRegression::from(slope, intercept)
+        } else {
+            SimpleLinearRegression::new()
+        };
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "SimpleLinearRegression(slope={}, intercept={})",
+            self.model.get_slope(),
+            self.model.get_feature()
+        )
+    }
+}
+
+/// Python version of `evaluate_simple_linear_regression`.
+#[pyfunction(name = "evaluate_simple_linear_regression")]
+#[pyo3(text_signature = "(observations, X, model)")]
+fn py_evaluate_simple_linear_regression(
+    observations: Vec<f64>,
+    xs: &PyAny,
+    model: PyRef<PySimpleLinearRegression>,
+) -> PyResult<f64> {
+    let xs = extract_feature(xs)?;
+    check_lengths(&xs, &observations)?;
+
+    evaluate_simple_linear_regression(
+        Array::from_vec(observations),
+        Array::from_vec(xs),
+        &model.model,
+    )
+    .map_err(to_py_err)
+}
+
+/// Python version of `coefficient_of_determination`.
+#[pyfunction(name = "coefficient_of_determination")]
+#[pyo3(text_signature = "(observations, predictions)")]
+fn py_coefficient_of_determination(
+    observations: Vec<f64>,
+    predictions: Vec<f64>,
+) -> PyResult<f64> {
+    check_lengths(&observations, &predictions)?;
+
+    Ok(coefficient_of_determination(
+        &Array::from_vec(observations),
+        &Array::from_vec(predictions),
+    ))
+}
+
+/// Adds the Machine Learning models and evaluation functions to the python module.
+pub fn register(m: &PyModule) -> PyResult<()> {
+    m.add_class::<PySimpleLinearRegression>()?;
+    m.add_function(wrap_pyfunction!(py_evaluate_simple_linear_regression, m)?)?;
+    m.add_function(wrap_pyfunction!(py_coefficient_of_determination, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        assert!(!PySimpleLinearRegression::new(None, None).model.is_fitted());
+
+        let model = PySimpleLinearRegression::new(Some(2.0), None).model;
+        assert!(model.is_fitted());
+        assert_eq!(2.0, model.get_slope());
+        assert_eq!(0.0, model.get_feature());
+    }
+
+    #[test]
+    fn test_state() {
+        let mut restored = PySimpleLinearRegression::new(None, None);
+        restored.__setstate__(PySimpleLinearRegression::new(Some(2.0), Some(1.0)).__getstate__());
+        assert!(restored.model.is_fitted());
+        assert_eq!((2.0, 1.0, true), restored.__getstate__());
+
+        let unfitted = PySimpleLinearRegression::new(None, None);
+        restored.__setstate__(unfitted.__getstate__());
+        assert!(!restored.model.is_fitted());
+    }
+}
@@ -0,0 +1,1071 @@
+//! Regularized Regression - Linear models with penalized coefficients
+//!
+//! Ordinary least squares overfits when there are nearly as many features as samples. These
+//! models add a penalty on the size of the coefficients to the squared error of `n` samples:
+//!
+//! * `Ridge` minimizes `|y - X b|^2 + alpha |b|^2`, which shrinks all coefficients.
+//! * `Lasso` minimizes `|y - X b|^2 / (2 n) + alpha |b|_1`, which sets some coefficients to 0.
+//! * `ElasticNet` minimizes `|y - X b|^2 / (2 n) + alpha r |b|_1 + alpha (1 - r) |b|^2 / 2`,
+//!   which mixes both penalties with the ratio `r`.
+//!
+//! The intercept is never penalized. Lasso and Elastic Net are fitted by coordinate descent,
+//! which stops once the duality gap shows that the objective is within the tolerance of its
+//! minimum.
+
+use crate::linalg::array::Array;
+use crate::linalg::matrix::Matrix;
+use crate::linalg::methods::{cholesky_decomposition, cholesky_solve};
+use crate::linalg::summation;
+use crate::machine_learning::model::{
+    check_features, check_targets, Estimator, ModelError, Predictor, Result,
+};
+
+/// The controls of an iterative solver.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolverOptions {
+    /// The tolerance on the duality gap, relative to the sum of squared (centered) targets.
+    pub tolerance: f64,
+    /// The maximum number of passes over the features.
+    pub max_iterations: usize,
+    /// Whether to start from the coefficients of the previous fit instead of from 0, which is
+    /// much faster when refitting with a slightly different `alpha`.
+    pub warm_start: bool,
+}
+
+impl Default for SolverOptions {
+    fn default() -> Self {
+        SolverOptions {
+            tolerance: 1e-4,
+            max_iterations: 1000,
+            warm_start: false,
+        }
+    }
+}
+
+/// How a solver finished.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Convergence {
+    /// The number of passes over the features.
+    pub iterations: usize,
+    /// Whether the duality gap fell below the tolerance within the maximum number of iterations.
+    pub converged: bool,
+    /// The last duality gap, an upper bound on the distance of the objective from its minimum
+    /// in units of the sum of squared errors over 2.
+    pub duality_gap: f64,
+}
+
+/// How to fit a `Ridge` model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RidgeSolver {
+    /// Solve the regularized normal equations with a Cholesky decomposition. This is exact and
+    /// the default.
+    #[default]
+    Cholesky,
+    /// Coordinate descent, which does not form the Gram matrix.
+    CoordinateDescent,
+}
+
+/// The coefficients of a model fitted for a sequence of regularization strengths.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegularizationPath {
+    /// The regularization strengths, from the largest to the smallest.
+    pub alphas: Array,
+    /// The coefficients, with a row for every value of `alphas`.
+    pub coefficients: Matrix,
+    /// The intercept for every value of `alphas`.
+    pub intercepts: Array,
+    /// How the solver finished for every value of `alphas`.
+    pub convergence: Vec<Convergence>,
+}
+
+/// The samples with their features stored by column, centered when fitting an intercept.
+struct Design {
+    columns: Vec<Array>,
+    ys: Array,
+    x_means: Array,
+    y_mean: f64,
+}
+
+impl Design {
+    fn new(xs: &Matrix, ys: &Array, fit_intercept: bool) -> Result<Design> {
+        check_targets(xs, ys)?;
+        let (_, cols) = xs.dimensions();
+        if cols == 0 {
+            return Err(ModelError::InvalidInput(
+                "there are no features".to_string(),
+            ));
+        }
+
+        let mut columns: Vec<Array> = (0..cols).map(|j| xs.column(j)).collect();
+        if !fit_intercept {
+            return Ok(Design {
+                columns,
+                ys: ys.clone(),
+                x_means: Array::zeros(cols),
+                y_mean: 0.0,
+            });
+        }
+
+        let x_means = Array::from_vec(columns.iter().map(|c| c.average()).collect());
+        for (column, &mean) in columns.iter_mut().zip(x_means.iter()) {
+            *column = column.scalar_sub(mean);
+        }
+        let y_mean = ys.average();
+
+        Ok(Design {
+            columns,
+            ys: ys.scalar_sub(y_mean),
+            x_means,
+            y_mean,
+        })
+    }
+
+    fn samples(&self) -> usize {
+        self.ys.len()
+    }
+
+    /// Returns the intercept that goes with the coefficients.
+    fn intercept(&self, coefficients: &Array) -> f64 {
+        self.y_mean - self.x_means.dotp(coefficients)
+    }
+
+    /// Returns `y - X w`.
+    fn residuals(&self, w: &Array) -> Array {
+        let mut residuals = self.ys.clone();
+        for (column, &wj) in self.columns.iter().zip(w.iter()) {
+            if wj != 0.0 {
+                subtract_scaled(&mut residuals, wj, column);
+            }
+        }
+        residuals
+    }
+}
+
+/// Subtracts `scale * xs` from `ys`.
+fn subtract_scaled(ys: &mut Array, scale: f64, xs: &Array) {
+    for (y, x) in ys.iter_mut().zip(xs.iter()) {
+        *y -= scale * x;
+    }
+}
+
+/// Returns the value of `x` moved towards 0 by `threshold`, or 0 if it is closer.
+fn soft_threshold(x: f64, threshold: f64) -> f64 {
+    if x > threshold {
+        x - threshold
+    } else if x < -threshold {
+        x + threshold
+    } else {
+        0.0
+    }
+}
+
+/// Returns the duality gap of `w` for the objective `|y - X w|^2 / 2 + l1 |w|_1 + l2 |w|^2 / 2`.
+///
+/// The dual point is built from the residuals: with `l2 > 0` every point is dual feasible,
+/// otherwise the residuals are scaled down until `|X^T theta|_inf <= l1`.
+fn duality_gap(design: &Design, l1: f64, l2: f64, w: &Array, residuals: &Array) -> f64 {
+    let correlations: Vec<f64> = design
+        .columns
+        .iter()
+        .map(|column| summation::dot(column, residuals))
+        .collect();
+    let r_norm2 = residuals.dotp(residuals);
+    let w_norm2 = w.dotp(w);
+    let w_l1 = summation::neumaier(w.iter().map(|wj| wj.abs()));
+    let primal = 0.5 * r_norm2 + l1 * w_l1 + 0.5 * l2 * w_norm2;
+
+    let dual = if l2 > 0.0 {
+        let excess = summation::neumaier(correlations.iter().map(|c| {
+            let e = (c.abs() - l1).max(0.0);
+            e * e
+        }));
+        residuals.dotp(&design.ys) - 0.5 * r_norm2 - 0.5 * excess / l2
+    } else {
+        let largest = correlations
+            .iter()
+            .fold(0.0, |acc: f64, c| acc.max(c.abs()));
+        // Without any penalty the optimal residuals are orthogonal to the features, so they
+        // are used unscaled.
+        let scale = if largest <= l1 || l1 == 0.0 {
+            1.0
+        } else {
+            l1 / largest
+        };
+        scale * residuals.dotp(&design.ys) - 0.5 * scale * scale * r_norm2
+    };
+
+    primal - dual
+}
+
+/// Minimizes `|y - X w|^2 / 2 + l1 |w|_1 + l2 |w|^2 / 2` by cyclic coordinate descent, starting
+/// from and overwriting `w`.
+fn coordinate_descent(
+    design: &Design,
+    l1: f64,
+    l2: f64,
+    w: &mut Array,
+    options: &SolverOptions,
+) -> Convergence {
+    let norms: Vec<f64> = design.columns.iter().map(|c| c.dotp(c)).collect();
+    let mut residuals = design.residuals(w);
+    let gap_tolerance = options.tolerance * design.ys.dotp(&design.ys);
+    let mut gap = f64::INFINITY;
+
+    for iteration in 1..=options.max_iterations {
+        let mut max_change: f64 = 0.0;
+        let mut max_coefficient: f64 = 0.0;
+
+        for (j, column) in design.columns.iter().enumerate() {
+            if norms[j] == 0.0 {
+                continue;
+            }
+            let old = w[j];
+            let rho = summation::dot(column, &residuals) + norms[j] * old;
+            let new = soft_threshold(rho, l1) / (norms[j] + l2);
+            if new != old {
+                subtract_scaled(&mut residuals, new - old, column);
+                w[j] = new;
+            }
+            max_change = max_change.max((new - old).abs());
+            max_coefficient = max_coefficient.max(new.abs());
+        }
+
+        // The gap costs as much as a pass, so it is only checked once the updates are small.
+        if max_coefficient == 0.0
+            || max_change <= options.tolerance * max_coefficient
+            || iteration == options.max_iterations
+        {
+            gap = duality_gap(design, l1, l2, w, &residuals);
+            if gap <= gap_tolerance {
+                return Convergence {
+                    iterations: iteration,
+                    converged: true,
+                    duality_gap: gap,
+                };
+            }
+        }
+    }
+
+    Convergence {
+        iterations: options.max_iterations,
+        converged: false,
+        duality_gap: gap,
+    }
+}
+
+/// Returns the predictions of a fitted linear model.
+fn linear_predict(coefficients: &Array, intercept: f64, xs: &Matrix) -> Result<Array> {
+    check_features(xs, coefficients.len())?;
+    Ok(Array::from_vec(
+        xs.iter()
+            .map(|row| row.dotp(coefficients) + intercept)
+            .collect(),
+    ))
+}
+
+/// Panics unless the solver options are valid.
+fn assert_options(options: &SolverOptions) {
+    assert!(
+        options.tolerance >= 0.0 && options.max_iterations > 0,
+        "ERROR - SolverOptions: The tolerance must be non-negative and max_iterations positive."
+    );
+}
+
+/// Linear regression with a penalty on the squared size of the coefficients, also called
+/// Tikhonov regularization. It minimizes `|y - X b|^2 + alpha |b|^2`.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::model::Predictor;
+/// use moonalloy::machine_learning::regularized_regression::Ridge;
+///
+/// // Two nearly identical features, for which least squares is unstable.
+/// let xs = Matrix::new(&[
+///     Array::from(&[1.0, 1.01]),
+///     Array::from(&[2.0, 1.99]),
+///     Array::from(&[3.0, 3.02]),
+///     Array::from(&[4.0, 3.98]),
+/// ]);
+/// let ys = Array::from(&[2.0, 4.1, 5.9, 8.0]);
+///
+/// let mut model = Ridge::new(1.0, true);
+/// model.fit(&xs, &ys).unwrap();
+///
+/// // The penalty splits the effect evenly between the features.
+/// let b = model.get_coefficients();
+/// assert!((b[0] - b[1]).abs() < 0.01);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ridge {
+    alpha: f64,
+    fit_intercept: bool,
+    solver: RidgeSolver,
+    options: SolverOptions,
+    coefficients: Array,
+    intercept: f64,
+    convergence: Option<Convergence>,
+}
+
+impl Ridge {
+    /// Returns an unfitted model.
+    ///
+    /// # Arguments
+    ///
+    /// * `alpha` - the strength of the penalty.
+    /// * `fit_intercept` - whether to fit an intercept, or to fit a model through the origin.
+    ///
+    /// # Panics
+    ///
+    /// `alpha` must be non-negative, otherwise the code will panic.
+    pub fn new(alpha: f64, fit_intercept: bool) -> Self {
+        assert!(alpha >= 0.0, "ERROR - Ridge: alpha must be non-negative.");
+
+        Ridge {
+            alpha,
+            fit_intercept,
+            solver: RidgeSolver::default(),
+            options: SolverOptions::default(),
+            coefficients: Array::new(),
+            intercept: 0.0,
+            convergence: None,
+        }
+    }
+
+    /// Sets the strength of the penalty for the next fit.
+    ///
+    /// # Panics
+    ///
+    /// `alpha` must be non-negative, otherwise the code will panic.
+    pub fn set_alpha(&mut self, alpha: f64) {
+        assert!(alpha >= 0.0, "ERROR - Ridge: alpha must be non-negative.");
+        self.alpha = alpha;
+    }
+
+    /// Sets how the model is fitted.
+    pub fn set_solver(&mut self, solver: RidgeSolver) {
+        self.solver = solver;
+    }
+
+    /// Sets the controls of coordinate descent.
+    ///
+    /// # Panics
+    ///
+    /// The tolerance must be non-negative and the maximum number of iterations positive,
+    /// otherwise the code will panic.
+    pub fn set_options(&mut self, options: SolverOptions) {
+        assert_options(&options);
+        self.options = options;
+    }
+
+    /// Returns the strength of the penalty.
+    pub fn get_alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Returns the coefficient of every feature.
+    pub fn get_coefficients(&self) -> &Array {
+        &self.coefficients
+    }
+
+    /// Returns the intercept, which is 0 for a model without intercept.
+    pub fn get_intercept(&self) -> f64 {
+        self.intercept
+    }
+
+    /// Returns how the last fit finished, or `None` before the model is fitted. The Cholesky
+    /// solver is exact and reports convergence after a single iteration.
+    pub fn get_convergence(&self) -> Option<Convergence> {
+        self.convergence
+    }
+
+    /// Returns the coefficients from the regularized normal equations, or `None` if they are
+    /// singular, which needs `alpha = 0`.
+    ///
+    /// With more features than samples, the smaller system `(X X^T + alpha I) c = y` is solved
+    /// instead, and the coefficients are `X^T c`.
+    fn solve_cholesky(&self, design: &Design) -> Option<Array> {
+        let columns = &design.columns;
+        let (n, p) = (design.samples(), columns.len());
+
+        if p <= n {
+            let mut gram = Matrix::zeros(p, p);
+            for i in 0..p {
+                for j in 0..=i {
+                    gram[(i, j)] = summation::dot(&columns[i], &columns[j]);
+                }
+                gram[(i, i)] += self.alpha;
+            }
+            let rhs = Array::from_vec(
+                columns
+                    .iter()
+                    .map(|c| summation::dot(c, &design.ys))
+                    .collect(),
+            );
+            Some(cholesky_solve(&cholesky_decomposition(&gram)?, &rhs))
+        } else {
+            let rows: Vec<Array> = (0..n)
+                .map(|i| Array::from_vec(columns.iter().map(|c| c[i]).collect()))
+                .collect();
+            let mut kernel = Matrix::zeros(n, n);
+            for i in 0..n {
+                for j in 0..=i {
+                    kernel[(i, j)] = summation::dot(&rows[i], &rows[j]);
+                }
+                kernel[(i, i)] += self.alpha;
+            }
+            let dual = cholesky_solve(&cholesky_decomposition(&kernel)?, &design.ys);
+            Some(Array::from_vec(
+                columns.iter().map(|c| summation::dot(c, &dual)).collect(),
+            ))
+        }
+    }
+}
+
+impl Estimator for Ridge {
+    fn is_fitted(&self) -> bool {
+        self.convergence.is_some()
+    }
+}
+
+impl Predictor for Ridge {
+    /// Fits the model to samples.
+    ///
+    /// With `alpha = 0` and linearly dependent features, the Cholesky solver returns an error.
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        let design = Design::new(xs, ys, self.fit_intercept)?;
+        let p = design.columns.len();
+
+        let (coefficients, convergence) = match self.solver {
+            RidgeSolver::Cholesky => {
+                let coefficients = self.solve_cholesky(&design).ok_or_else(|| {
+                    ModelError::InvalidInput(
+                        "the features are linearly dependent, alpha must be positive".to_string(),
+                    )
+                })?;
+                let convergence = Convergence {
+                    iterations: 1,
+                    converged: true,
+                    duality_gap: 0.0,
+                };
+                (coefficients, convergence)
+            }
+            RidgeSolver::CoordinateDescent => {
+                let mut w = if self.options.warm_start && self.coefficients.len() == p {
+                    self.coefficients.clone()
+                } else {
+                    Array::zeros(p)
+                };
+                // |y - X b|^2 + alpha |b|^2 is twice the objective of coordinate descent.
+                let convergence =
+                    coordinate_descent(&design, 0.0, self.alpha, &mut w, &self.options);
+                (w, convergence)
+            }
+        };
+
+        self.intercept = design.intercept(&coefficients);
+        self.coefficients = coefficients;
+        self.convergence = Some(convergence);
+        Ok(())
+    }
+
+    fn predict(&self, xs: &Matrix) -> Result<Array> {
+        self.check_fitted()?;
+        linear_predict(&self.coefficients, self.intercept, xs)
+    }
+}
+
+/// Linear regression with a mix of the absolute and the squared size of the coefficients as
+/// penalty. It minimizes `|y - X b|^2 / (2 n) + alpha r |b|_1 + alpha (1 - r) |b|^2 / 2`, where
+/// `n` is the number of samples and `r` the L1 ratio.
+///
+/// Like the Lasso it selects features, and like Ridge it keeps groups of correlated features
+/// together instead of picking one of them.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::model::Predictor;
+/// use moonalloy::machine_learning::regularized_regression::ElasticNet;
+///
+/// let xs = Matrix::new(&[
+///     Array::from(&[1.0, 0.3, -1.0]),
+///     Array::from(&[2.0, -0.1, 0.5]),
+///     Array::from(&[3.0, 0.2, 0.0]),
+///     Array::from(&[4.0, -0.4, 1.5]),
+///     Array::from(&[5.0, 0.1, -0.5]),
+/// ]);
+/// let ys = Array::from(&[2.1, 3.9, 6.0, 8.1, 9.9]);
+///
+/// let mut model = ElasticNet::new(0.1, 0.5, true);
+/// model.fit(&xs, &ys).unwrap();
+///
+/// assert!(model.get_convergence().unwrap().converged);
+/// assert!(model.get_coefficients()[0] > 1.5);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElasticNet {
+    alpha: f64,
+    l1_ratio: f64,
+    fit_intercept: bool,
+    options: SolverOptions,
+    coefficients: Array,
+    intercept: f64,
+    convergence: Option<Convergence>,
+}
+
+impl ElasticNet {
+    /// Returns an unfitted model.
+    ///
+    /// # Arguments
+    ///
+    /// * `alpha` - the strength of the penalty.
+    /// * `l1_ratio` - the share of the absolute size in the penalty, between 0 (Ridge) and 1
+    ///   (Lasso).
+    /// * `fit_intercept` - whether to fit an intercept, or to fit a model through the origin.
+    ///
+    /// # Panics
+    ///
+    /// `alpha` must be non-negative and `l1_ratio` between 0 and 1, otherwise the code will
+    /// panic.
+    pub fn new(alpha: f64, l1_ratio: f64, fit_intercept: bool) -> Self {
+        assert!(
+            alpha >= 0.0,
+            "ERROR - ElasticNet: alpha must be non-negative."
+        );
+        assert!(
+            (0.0..=1.0).contains(&l1_ratio),
+            "ERROR - ElasticNet: l1_ratio must be between 0 and 1."
+        );
+
+        ElasticNet {
+            alpha,
+            l1_ratio,
+            fit_intercept,
+            options: SolverOptions::default(),
+            coefficients: Array::new(),
+            intercept: 0.0,
+            convergence: None,
+        }
+    }
+
+    /// Sets the strength of the penalty for the next fit.
+    ///
+    /// # Panics
+    ///
+    /// `alpha` must be non-negative, otherwise the code will panic.
+    pub fn set_alpha(&mut self, alpha: f64) {
+        assert!(
+            alpha >= 0.0,
+            "ERROR - ElasticNet: alpha must be non-negative."
+        );
+        self.alpha = alpha;
+    }
+
+    /// Sets the controls of coordinate descent.
+    ///
+    /// # Panics
+    ///
+    /// The tolerance must be non-negative and the maximum number of iterations positive,
+    /// otherwise the code will panic.
+    pub fn set_options(&mut self, options: SolverOptions) {
+        assert_options(&options);
+        self.options = options;
+    }
+
+    /// Returns the strength of the penalty.
+    pub fn get_alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Returns the share of the absolute size in the penalty.
+    pub fn get_l1_ratio(&self) -> f64 {
+        self.l1_ratio
+    }
+
+    /// Returns the coefficient of every feature.
+    pub fn get_coefficients(&self) -> &Array {
+        &self.coefficients
+    }
+
+    /// Returns the intercept, which is 0 for a model without intercept.
+    pub fn get_intercept(&self) -> f64 {
+        self.intercept
+    }
+
+    /// Returns how the last fit finished, or `None` before the model is fitted.
+    pub fn get_convergence(&self) -> Option<Convergence> {
+        self.convergence
+    }
+
+    /// Returns the L1 and L2 penalties of the coordinate descent objective, which is `n` times
+    /// the objective of the model.
+    fn penalties(&self, alpha: f64, samples: usize) -> (f64, f64) {
+        let scale = alpha * samples as f64;
+        (scale * self.l1_ratio, scale * (1.0 - self.l1_ratio))
+    }
+
+    /// Returns `count` regularization strengths for `path`, spaced evenly on a log scale from
+    /// the smallest `alpha` for which all coefficients are 0 down to `ratio` times it.
+    ///
+    /// # Panics
+    ///
+    /// `count` must be at least 2 and `ratio` between 0 and 1, otherwise the code will panic.
+    pub fn alpha_grid(&self, xs: &Matrix, ys: &Array, count: usize, ratio: f64) -> Result<Array> {
+        assert!(
+            count >= 2 && ratio > 0.0 && ratio < 1.0,
+            "ERROR - ElasticNet alpha_grid: count must be at least 2 and ratio between 0 and 1."
+        );
+        if self.l1_ratio == 0.0 {
+            return Err(ModelError::InvalidInput(
+                "the coefficients are never all 0 without an L1 penalty".to_string(),
+            ));
+        }
+
+        let design = Design::new(xs, ys, self.fit_intercept)?;
+        let largest = design
+            .columns
+            .iter()
+            .map(|c| summation::dot(c, &design.ys).abs())
+            .fold(0.0, f64::max);
+        if largest == 0.0 {
+            return Err(ModelError::InvalidInput(
+                "the targets are uncorrelated with the features".to_string(),
+            ));
+        }
+
+        let alpha_max = largest / (design.samples() as f64 * self.l1_ratio);
+        let step = ratio.ln() / (count - 1) as f64;
+        Ok(Array::from_vec(
+            (0..count)
+                .map(|i| alpha_max * (step * i as f64).exp())
+                .collect(),
+        ))
+    }
+
+    /// Returns the coefficients for every regularization strength, fitted from the largest to
+    /// the smallest, each starting from the coefficients of the previous one. The model itself
+    /// is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// The regularization strengths must be non-negative, otherwise the code will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moonalloy::linalg::array::Array;
+    /// use moonalloy::linalg::matrix::Matrix;
+    /// use moonalloy::machine_learning::regularized_regression::ElasticNet;
+    ///
+    /// let xs = Matrix::new(&[
+    ///     Array::from(&[1.0, 0.5]),
+    ///     Array::from(&[2.0, -1.0]),
+    ///     Array::from(&[3.0, 0.0]),
+    ///     Array::from(&[4.0, 1.0]),
+    /// ]);
+    /// let ys = Array::from(&[1.0, 3.0, 2.0, 5.0]);
+    ///
+    /// let model = ElasticNet::new(1.0, 1.0, true);
+    /// let alphas = model.alpha_grid(&xs, &ys, 20, 1e-3).unwrap();
+    /// let path = model.path(&xs, &ys, &alphas).unwrap();
+    ///
+    /// // At the largest alpha, every coefficient is 0.
+    /// assert!(path.coefficients[0].iter().all(|&b| b == 0.0));
+    /// assert!(path.coefficients[19].iter().all(|&b| b != 0.0));
+    /// ```
+    pub fn path(&self, xs: &Matrix, ys: &Array, alphas: &Array) -> Result<RegularizationPath> {
+        assert!(
+            alphas.iter().all(|&a| a >= 0.0),
+            "ERROR - ElasticNet path: The alphas must be non-negative."
+        );
+
+        let design = Design::new(xs, ys, self.fit_intercept)?;
+        let mut sorted = alphas.to_vec();
+        sorted.sort_by(|a, b| b.partial_cmp(a).unwrap());
+
+        let mut w = Array::zeros(design.columns.len());
+        let mut coefficients = Vec::with_capacity(sorted.len());
+        let mut intercepts = Vec::with_capacity(sorted.len());
+        let mut convergence = Vec::with_capacity(sorted.len());
+        for &alpha in &sorted {
+            let (l1, l2) = self.penalties(alpha, design.samples());
+            convergence.push(coordinate_descent(&design, l1, l2, &mut w, &self.options));
+            intercepts.push(design.intercept(&w));
+            coefficients.push(w.clone());
+        }
+
+        Ok(RegularizationPath {
+            alphas: Array::from_vec(sorted),
+            coefficients: Matrix::from_rows(coefficients),
+            intercepts: Array::from_vec(intercepts),
+            convergence,
+        })
+    }
+}
+
+impl Estimator for ElasticNet {
+    fn is_fitted(&self) -> bool {
+        self.convergence.is_some()
+    }
+}
+
+impl Predictor for ElasticNet {
+    /// Fits the model to samples by coordinate descent.
+    ///
+    /// The fit succeeds even if the solver did not converge, which `get_convergence` reports.
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        let design = Design::new(xs, ys, self.fit_intercept)?;
+        let p = design.columns.len();
+
+        let mut w = if self.options.warm_start && self.coefficients.len() == p {
+            self.coefficients.clone()
+        } else {
+            Array::zeros(p)
+        };
+        let (l1, l2) = self.penalties(self.alpha, design.samples());
+        let convergence = coordinate_descent(&design, l1, l2, &mut w, &self.options);
+
+        self.intercept = design.intercept(&w);
+        self.coefficients = w;
+        self.convergence = Some(convergence);
+        Ok(())
+    }
+
+    fn predict(&self, xs: &Matrix) -> Result<Array> {
+        self.check_fitted()?;
+        linear_predict(&self.coefficients, self.intercept, xs)
+    }
+}
+
+/// Linear regression with a penalty on the absolute size of the coefficients, the Least
+/// Absolute Shrinkage and Selection Operator. It minimizes `|y - X b|^2 / (2 n) + alpha |b|_1`,
+/// where `n` is the number of samples.
+///
+/// The penalty sets the coefficients of the least useful features to exactly 0. This is an
+/// `ElasticNet` with an L1 ratio of 1.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::model::Predictor;
+/// use moonalloy::machine_learning::regularized_regression::Lasso;
+///
+/// // The target only depends on the first feature.
+/// let xs = Matrix::new(&[
+///     Array::from(&[1.0, 0.3]),
+///     Array::from(&[2.0, -0.2]),
+///     Array::from(&[3.0, 0.1]),
+///     Array::from(&[4.0, -0.3]),
+/// ]);
+/// let ys = Array::from(&[2.0, 4.0, 6.0, 8.0]);
+///
+/// let mut model = Lasso::new(0.1, true);
+/// model.fit(&xs, &ys).unwrap();
+///
+/// assert_eq!(0.0, model.get_coefficients()[1]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lasso {
+    net: ElasticNet,
+}
+
+impl Lasso {
+    /// Returns an unfitted model.
+    ///
+    /// # Arguments
+    ///
+    /// * `alpha` - the strength of the penalty.
+    /// * `fit_intercept` - whether to fit an intercept, or to fit a model through the origin.
+    ///
+    /// # Panics
+    ///
+    /// `alpha` must be non-negative, otherwise the code will panic.
+    pub fn new(alpha: f64, fit_intercept: bool) -> Self {
+        Lasso {
+            net: ElasticNet::new(alpha, 1.0, fit_intercept),
+        }
+    }
+
+    /// Sets the strength of the penalty for the next fit.
+    ///
+    /// # Panics
+    ///
+    /// `alpha` must be non-negative, otherwise the code will panic.
+    pub fn set_alpha(&mut self, alpha: f64) {
+        self.net.set_alpha(alpha);
+    }
+
+    /// Sets the controls of coordinate descent.
+    ///
+    /// # Panics
+    ///
+    /// The tolerance must be non-negative and the maximum number of iterations positive,
+    /// otherwise the code will panic.
+    pub fn set_options(&mut self, options: SolverOptions) {
+        self.net.set_options(options);
+    }
+
+    /// Returns the strength of the penalty.
+    pub fn get_alpha(&self) -> f64 {
+        self.net.get_alpha()
+    }
+
+    /// Returns the coefficient of every feature.
+    pub fn get_coefficients(&self) -> &Array {
+        self.net.get_coefficients()
+    }
+
+    /// Returns the intercept, which is 0 for a model without intercept.
+    pub fn get_intercept(&self) -> f64 {
+        self.net.get_intercept()
+    }
+
+    /// Returns how the last fit finished, or `None` before the model is fitted.
+    pub fn get_convergence(&self) -> Option<Convergence> {
+        self.net.get_convergence()
+    }
+
+    /// Returns `count` regularization strengths for `path`, see `ElasticNet::alpha_grid`.
+    pub fn alpha_grid(&self, xs: &Matrix, ys: &Array, count: usize, ratio: f64) -> Result<Array> {
+        self.net.alpha_grid(xs, ys, count, ratio)
+    }
+
+    /// Returns the coefficients for every regularization strength, see `ElasticNet::path`.
+    pub fn path(&self, xs: &Matrix, ys: &Array, alphas: &Array) -> Result<RegularizationPath> {
+        self.net.path(xs, ys, alphas)
+    }
+}
+
+impl Estimator for Lasso {
+    fn is_fitted(&self) -> bool {
+        self.net.is_fitted()
+    }
+}
+
+impl Predictor for Lasso {
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        self.net.fit(xs, ys)
+    }
+
+    fn predict(&self, xs: &Matrix) -> Result<Array> {
+        self.net.predict(xs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::random::pcg::Pcg64;
+
+    /// Returns samples with more features than are useful, and targets that depend on the
+    /// first three of them.
+    fn sparse_problem(rows: usize, cols: usize) -> (Matrix, Array) {
+        let mut rng = Pcg64::seed_from_u64(7);
+        let xs = Matrix::random_normal(rows, cols, &mut rng);
+        let noise = Matrix::random_normal(rows, 1, &mut rng).column(0);
+        let ys = Array::from_vec(
+            xs.iter()
+                .zip(noise.iter())
+                .map(|(row, e)| 1.0 + 3.0 * row[0] - 2.0 * row[1] + row[2] + 0.1 * e)
+                .collect(),
+        );
+        (xs, ys)
+    }
+
+    /// Returns `X_c^T (y_c - X_c b) / n` for the centered samples and targets.
+    fn gradient(xs: &Matrix, ys: &Array, coefficients: &Array) -> Array {
+        let design = Design::new(xs, ys, true).unwrap();
+        let residuals = design.residuals(coefficients);
+        let n = design.samples() as f64;
+        Array::from_vec(
+            design
+                .columns
+                .iter()
+                .map(|c| c.dotp(&residuals) / n)
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_ridge_normal_equations() {
+        // Fewer features than samples uses the Gram matrix, more uses the kernel matrix.
+        for &(rows, cols) in &[(30, 5), (10, 25)] {
+            let (xs, ys) = sparse_problem(rows, cols);
+            let alpha = 2.5;
+            let mut model = Ridge::new(alpha, true);
+            model.fit(&xs, &ys).unwrap();
+
+            // The gradient of |y - X b|^2 + alpha |b|^2 vanishes: X^T r = alpha b.
+            let g = gradient(&xs, &ys, model.get_coefficients());
+            for j in 0..cols {
+                let expected = alpha * model.get_coefficients()[j] / rows as f64;
+                assert!((g[j] - expected).abs() < 1e-10, "{} {}", rows, cols);
+            }
+
+            let predictions = model.predict(&xs).unwrap();
+            let residuals = ys.minus(&predictions);
+            assert!(residuals.sum().abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_ridge_solvers_agree() {
+        let (xs, ys) = sparse_problem(40, 8);
+        let mut exact = Ridge::new(1.0, true);
+        exact.fit(&xs, &ys).unwrap();
+
+        let mut iterative = Ridge::new(1.0, true);
+        iterative.set_solver(RidgeSolver::CoordinateDescent);
+        iterative.set_options(SolverOptions {
+            tolerance: 1e-12,
+            ..SolverOptions::default()
+        });
+        iterative.fit(&xs, &ys).unwrap();
+
+        assert!(iterative.get_convergence().unwrap().converged);
+        let difference = exact.get_coefficients().minus(iterative.get_coefficients());
+        assert!(difference.norm() < 1e-6);
+        assert!((exact.get_intercept() - iterative.get_intercept()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_ridge_without_penalty() {
+        let xs = Matrix::new(&[
+            Array::from(&[1.0, 2.0]),
+            Array::from(&[2.0, 4.0]),
+            Array::from(&[3.0, 6.0]),
+        ]);
+        let ys = Array::from(&[1.0, 2.0, 3.0]);
+
+        assert!(Ridge::new(0.0, true).fit(&xs, &ys).is_err());
+        assert!(Ridge::new(0.1, true).fit(&xs, &ys).is_ok());
+    }
+
+    #[test]
+    fn test_lasso_orthogonal_features() {
+        // With orthogonal features the Lasso soft-thresholds every coefficient of least squares.
+        let xs = Matrix::new(&[
+            Array::from(&[1.0, 0.0]),
+            Array::from(&[0.0, 1.0]),
+            Array::from(&[1.0, 0.0]),
+            Array::from(&[0.0, 1.0]),
+        ]);
+        let ys = Array::from(&[3.0, 1.0, 2.0, -0.5]);
+
+        let mut model = Lasso::new(0.5, false);
+        model.fit(&xs, &ys).unwrap();
+
+        // X^T y = (5, 0.5) and |x_j|^2 = 2, so b_j = S(X_j^T y, n alpha) / 2 with n alpha = 2.
+        assert_eq!(Array::from(&[1.5, 0.0]), *model.get_coefficients());
+        assert_eq!(0.0, model.get_intercept());
+    }
+
+    #[test]
+    fn test_elastic_net_optimality() {
+        let (xs, ys) = sparse_problem(20, 15);
+        let (alpha, ratio) = (0.1, 0.7);
+        let mut model = ElasticNet::new(alpha, ratio, true);
+        model.set_options(SolverOptions {
+            tolerance: 1e-10,
+            ..SolverOptions::default()
+        });
+        model.fit(&xs, &ys).unwrap();
+
+        let convergence = model.get_convergence().unwrap();
+        assert!(convergence.converged && convergence.duality_gap >= -1e-12);
+
+        // The subgradient conditions of the objective.
+        let b = model.get_coefficients();
+        let g = gradient(&xs, &ys, b);
+        let mut zeros = 0;
+        for j in 0..15 {
+            let smooth = g[j] - alpha * (1.0 - ratio) * b[j];
+            if b[j] == 0.0 {
+                assert!(smooth.abs() <= alpha * ratio + 1e-6);
+                zeros += 1;
+            } else {
+                assert!((smooth - alpha * ratio * b[j].signum()).abs() < 1e-6);
+            }
+        }
+        assert!(zeros > 0);
+        assert!(b[0] > 2.0 && b[1] < -1.0);
+    }
+
+    #[test]
+    fn test_warm_start() {
+        let (xs, ys) = sparse_problem(50, 10);
+        let mut model = Lasso::new(0.05, true);
+        model.fit(&xs, &ys).unwrap();
+        let cold = model.get_convergence().unwrap();
+
+        model.set_options(SolverOptions {
+            warm_start: true,
+            ..SolverOptions::default()
+        });
+        model.fit(&xs, &ys).unwrap();
+        let warm = model.get_convergence().unwrap();
+
+        assert!(cold.converged && warm.converged);
+        assert!(warm.iterations < cold.iterations);
+    }
+
+    #[test]
+    fn test_path() {
+        let (xs, ys) = sparse_problem(30, 6);
+        let model = Lasso::new(1.0, true);
+        let alphas = model.alpha_grid(&xs, &ys, 10, 1e-3).unwrap();
+        assert!((alphas[9] / alphas[0] - 1e-3).abs() < 1e-15);
+
+        // The path sorts the alphas from the largest.
+        let reversed = Array::from_vec(alphas.iter().rev().copied().collect());
+        let path = model.path(&xs, &ys, &reversed).unwrap();
+        assert_eq!(alphas, path.alphas);
+        assert_eq!((10, 6), path.coefficients.dimensions());
+        assert!(path.convergence.iter().all(|c| c.converged));
+
+        // Every coefficient is 0 at the largest alpha, and only then.
+        assert!(path.coefficients[0].iter().all(|&b| b == 0.0));
+        assert!(path.coefficients[1].iter().any(|&b| b != 0.0));
+        assert!((path.intercepts[0] - ys.average()).abs() < 1e-12);
+
+        // A single fit finds the same coefficients as the path.
+        let mut single = Lasso::new(alphas[5], true);
+        single.fit(&xs, &ys).unwrap();
+        assert!(
+            single
+                .get_coefficients()
+                .minus(&path.coefficients[5])
+                .norm()
+                < 1e-3
+        );
+
+        assert!(ElasticNet::new(1.0, 0.0, true)
+            .alpha_grid(&xs, &ys, 10, 1e-3)
+            .is_err());
+    }
+
+    #[test]
+    fn test_not_converged() {
+        let (xs, ys) = sparse_problem(20, 10);
+        let mut model = ElasticNet::new(0.01, 0.5, true);
+        model.set_options(SolverOptions {
+            tolerance: 0.0,
+            max_iterations: 3,
+            warm_start: false,
+        });
+
+        assert_eq!(Err(ModelError::NotFitted), model.predict(&xs));
+        model.fit(&xs, &ys).unwrap();
+        let convergence = model.get_convergence().unwrap();
+        assert!(!convergence.converged);
+        assert_eq!(3, convergence.iterations);
+        assert!(model.predict(&xs).is_ok());
+    }
+}
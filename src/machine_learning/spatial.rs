@@ -0,0 +1,748 @@
+//! Spatial - Indexes for nearest neighbour queries
+//!
+//! A spatial index organises points in a tree of nested regions, so that a query only computes
+//! the distance to the points in regions that can contain a result:
+//!
+//! * `KdTree` splits the points along one coordinate at a time into axis-aligned boxes. It is
+//!   the fastest for a small number of features.
+//! * `BallTree` splits the points into balls around their centroid, which stay tight in higher
+//!   dimensions where boxes become loose.
+//!
+//! Both answer k-nearest and radius queries under the metrics in `Metric`, with the same results
+//! as comparing the query to every point.
+
+use crate::linalg::matrix::Matrix;
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// The default number of points in a leaf of a tree.
+const LEAF_SIZE: usize = 30;
+
+/// The relative amount by which the lower bound of a node may exceed a distance before the node
+/// is skipped, since the bound of a ball can round above the distance of a point on its surface.
+const SLACK: f64 = 1e-10;
+
+/// The distance between two points.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Metric {
+    /// The square root of the sum of squared differences. This is the default.
+    #[default]
+    Euclidean,
+    /// The sum of absolute differences.
+    Manhattan,
+    /// The p-th root of the sum of absolute differences to the power p, with a finite p of at
+    /// least 1.
+    Minkowski(f64),
+    /// The largest absolute difference, which is the limit of the Minkowski distance for large
+    /// p.
+    Chebyshev,
+}
+
+impl Metric {
+    /// Returns the distance between two points.
+    ///
+    /// # Panics
+    ///
+    /// The points must have the same length, otherwise the code will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moonalloy::machine_learning::spatial::Metric;
+    ///
+    /// let (a, b) = ([0.0, 0.0], [3.0, 4.0]);
+    ///
+    /// assert_eq!(5.0, Metric::Euclidean.distance(&a, &b));
+    /// assert_eq!(7.0, Metric::Manhattan.distance(&a, &b));
+    /// assert!((Metric::Minkowski(3.0).distance(&a, &b) - 91f64.cbrt()).abs() < 1e-12);
+    /// assert_eq!(4.0, Metric::Chebyshev.distance(&a, &b));
+    /// ```
+    pub fn distance(&self, a: &[f64], b: &[f64]) -> f64 {
+        assert_eq!(
+            a.len(),
+            b.len(),
+            "ERROR - distance: The points must have the same length."
+        );
+        self.unreduce(self.reduced_distance(a, b))
+    }
+
+    /// Panics unless a Minkowski exponent is finite and at least 1, below which it is not a
+    /// metric.
+    pub(crate) fn check(&self) {
+        if let Metric::Minkowski(p) = self {
+            assert!(
+                *p >= 1.0 && p.is_finite(),
+                "ERROR - Metric: The Minkowski exponent must be finite and at least 1. Use \
+                 Metric::Chebyshev for the limit of large exponents."
+            );
+        }
+    }
+
+    /// Returns a monotone transform of the distance that is cheaper to compute, e.g. the squared
+    /// Euclidean distance.
+    fn reduced_distance(&self, a: &[f64], b: &[f64]) -> f64 {
+        let pairs = a.iter().zip(b);
+        match *self {
+            Metric::Euclidean => pairs.map(|(x, y)| (x - y) * (x - y)).sum(),
+            Metric::Manhattan => pairs.map(|(x, y)| (x - y).abs()).sum(),
+            Metric::Minkowski(p) => pairs.map(|(x, y)| (x - y).abs().powf(p)).sum(),
+            Metric::Chebyshev => self.combine(pairs.map(|(x, y)| (x - y).abs())),
+        }
+    }
+
+    /// Returns the reduced distance from the reduced distances along every coordinate.
+    fn combine(&self, reduced: impl Iterator<Item = f64>) -> f64 {
+        match *self {
+            Metric::Chebyshev => reduced.fold(0.0, f64::max),
+            _ => reduced.sum(),
+        }
+    }
+
+    /// Returns the reduced distance of a distance, which is also the reduced distance of a
+    /// difference along a single coordinate.
+    fn reduce(&self, distance: f64) -> f64 {
+        match *self {
+            Metric::Euclidean => distance * distance,
+            Metric::Manhattan | Metric::Chebyshev => distance,
+            Metric::Minkowski(p) => distance.powf(p),
+        }
+    }
+
+    /// Returns the distance of a reduced distance.
+    fn unreduce(&self, reduced: f64) -> f64 {
+        match *self {
+            Metric::Euclidean => reduced.sqrt(),
+            Metric::Manhattan | Metric::Chebyshev => reduced,
+            Metric::Minkowski(p) => reduced.powf(1.0 / p),
+        }
+    }
+}
+
+/// A point found by a query.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Neighbor {
+    /// The row of the point in the indexed `Matrix`.
+    pub index: usize,
+    /// The distance of the point to the query.
+    pub distance: f64,
+}
+
+/// An index of points for nearest neighbour queries.
+///
+/// Queries return their results by increasing distance, and points at the same distance by
+/// increasing index.
+pub trait SpatialIndex {
+    /// Returns the number of indexed points.
+    fn len(&self) -> usize;
+
+    /// Returns whether there are no indexed points.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the metric of the queries.
+    fn metric(&self) -> Metric;
+
+    /// Returns the `k` points nearest to a point, or all of them if there are fewer.
+    ///
+    /// # Panics
+    ///
+    /// The point must have a coordinate for every feature, otherwise the code will panic.
+    fn nearest(&self, point: &[f64], k: usize) -> Vec<Neighbor>;
+
+    /// Returns the points within a distance of a point.
+    ///
+    /// # Panics
+    ///
+    /// The point must have a coordinate for every feature, otherwise the code will panic.
+    fn within(&self, point: &[f64], radius: f64) -> Vec<Neighbor>;
+}
+
+/// The region of space a node of a tree covers.
+#[derive(Debug, Clone, PartialEq)]
+enum Region {
+    /// The bounding box of the points.
+    Box { lower: Vec<f64>, upper: Vec<f64> },
+    /// The ball around the centroid of the points.
+    Ball { center: Vec<f64>, radius: f64 },
+}
+
+/// A node of a tree, which covers the points at positions `start..end`.
+#[derive(Debug, Clone, PartialEq)]
+struct Node {
+    start: usize,
+    end: usize,
+    region: Region,
+    children: Option<(usize, usize)>,
+}
+
+/// A candidate result of a k-nearest query, ordered by reduced distance and then index.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    reduced: f64,
+    index: usize,
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.reduced
+            .partial_cmp(&other.reduced)
+            .unwrap_or(Ordering::Equal)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+/// The tree shared by `KdTree` and `BallTree`, which only differ in their regions.
+///
+/// The points are stored in the order of the tree, so that a leaf is contiguous in memory, and
+/// `order` maps them back to their rows.
+#[derive(Debug, Clone, PartialEq)]
+struct Tree {
+    points: Vec<f64>,
+    dimension: usize,
+    order: Vec<usize>,
+    nodes: Vec<Node>,
+    metric: Metric,
+}
+
+impl Tree {
+    fn build(xs: &Matrix, metric: Metric, leaf_size: usize, balls: bool, name: &str) -> Tree {
+        metric.check();
+        assert!(
+            leaf_size > 0,
+            "ERROR - {}: The leaf size must be positive.",
+            name
+        );
+        assert!(
+            xs.iter().all(|row| row.iter().all(|x| !x.is_nan())),
+            "ERROR - {}: The points must not contain NaN.",
+            name
+        );
+
+        let (rows, dimension) = xs.dimensions();
+        let mut order: Vec<usize> = (0..rows).collect();
+        let mut nodes = Vec::new();
+        if rows > 0 {
+            let builder = Builder {
+                xs,
+                metric,
+                leaf_size,
+                balls,
+            };
+            builder.node(&mut order, 0, rows, &mut nodes);
+        }
+        let points = order.iter().flat_map(|&i| xs[i].iter().copied()).collect();
+
+        Tree {
+            points,
+            dimension,
+            order,
+            nodes,
+            metric,
+        }
+    }
+
+    /// Returns the point at a position of the tree.
+    fn point(&self, position: usize) -> &[f64] {
+        &self.points[position * self.dimension..(position + 1) * self.dimension]
+    }
+
+    /// Returns a lower bound of the reduced distance of a point to the points of a node.
+    fn min_reduced(&self, node: &Node, point: &[f64]) -> f64 {
+        match &node.region {
+            Region::Box { lower, upper } => self.metric.combine(
+                point
+                    .iter()
+                    .zip(lower.iter().zip(upper))
+                    .map(|(&x, (&l, &u))| self.metric.reduce((l - x).max(x - u).max(0.0))),
+            ),
+            Region::Ball { center, radius } => {
+                let distance = self.metric.distance(point, center);
+                self.metric.reduce((distance - radius).max(0.0))
+            }
+        }
+    }
+
+    fn check_point(&self, point: &[f64]) {
+        assert_eq!(
+            point.len(),
+            self.dimension,
+            "ERROR - SpatialIndex: The point must have a coordinate for every feature."
+        );
+    }
+
+    fn nearest(&self, point: &[f64], k: usize) -> Vec<Neighbor> {
+        self.check_point(point);
+        if k == 0 || self.nodes.is_empty() {
+            return Vec::new();
+        }
+
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        let bound = self.min_reduced(&self.nodes[0], point);
+        self.search_nearest(0, bound, point, k, &mut heap);
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|c| Neighbor {
+                index: c.index,
+                distance: self.metric.unreduce(c.reduced),
+            })
+            .collect()
+    }
+
+    fn search_nearest(
+        &self,
+        node: usize,
+        bound: f64,
+        point: &[f64],
+        k: usize,
+        heap: &mut BinaryHeap<Candidate>,
+    ) {
+        // A node at the same distance as the k-th candidate can still hold a smaller index.
+        if heap.len() == k && bound > heap.peek().unwrap().reduced * (1.0 + SLACK) {
+            return;
+        }
+
+        let node = &self.nodes[node];
+        match node.children {
+            None => {
+                for position in node.start..node.end {
+                    let candidate = Candidate {
+                        reduced: self.metric.reduced_distance(point, self.point(position)),
+                        index: self.order[position],
+                    };
+                    if heap.len() < k {
+                        heap.push(candidate);
+                    } else if candidate < *heap.peek().unwrap() {
+                        heap.pop();
+                        heap.push(candidate);
+                    }
+                }
+            }
+            Some((left, right)) => {
+                let left_bound = self.min_reduced(&self.nodes[left], point);
+                let right_bound = self.min_reduced(&self.nodes[right], point);
+                if left_bound <= right_bound {
+                    self.search_nearest(left, left_bound, point, k, heap);
+                    self.search_nearest(right, right_bound, point, k, heap);
+                } else {
+                    self.search_nearest(right, right_bound, point, k, heap);
+                    self.search_nearest(left, left_bound, point, k, heap);
+                }
+            }
+        }
+    }
+
+    fn within(&self, point: &[f64], radius: f64) -> Vec<Neighbor> {
+        self.check_point(point);
+        let mut found = Vec::new();
+        if radius >= 0.0 && !self.nodes.is_empty() {
+            self.search_within(0, point, radius, &mut found);
+        }
+
+        found.sort();
+        found
+            .into_iter()
+            .map(|c| Neighbor {
+                index: c.index,
+                distance: self.metric.unreduce(c.reduced),
+            })
+            .collect()
+    }
+
+    /// Collects the points of a node within the radius. Distances rather than reduced distances
+    /// are compared, so that a point is found at exactly the distance `nearest` returns for it.
+    fn search_within(&self, node: usize, point: &[f64], radius: f64, found: &mut Vec<Candidate>) {
+        let node = &self.nodes[node];
+        if self.metric.unreduce(self.min_reduced(node, point)) > radius * (1.0 + SLACK) {
+            return;
+        }
+
+        match node.children {
+            None => {
+                for position in node.start..node.end {
+                    let reduced = self.metric.reduced_distance(point, self.point(position));
+                    if self.metric.unreduce(reduced) <= radius {
+                        found.push(Candidate {
+                            reduced,
+                            index: self.order[position],
+                        });
+                    }
+                }
+            }
+            Some((left, right)) => {
+                self.search_within(left, point, radius, found);
+                self.search_within(right, point, radius, found);
+            }
+        }
+    }
+}
+
+/// Builds the nodes of a tree by recursively splitting the points at the median of the
+/// coordinate with the largest spread.
+struct Builder<'a> {
+    xs: &'a Matrix,
+    metric: Metric,
+    leaf_size: usize,
+    balls: bool,
+}
+
+impl Builder<'_> {
+    /// Adds the node of the points at positions `start..end` and its descendants, and returns
+    /// its index.
+    fn node(&self, order: &mut [usize], start: usize, end: usize, nodes: &mut Vec<Node>) -> usize {
+        let dimension = self.xs.dimensions().1;
+        let mut lower = vec![f64::INFINITY; dimension];
+        let mut upper = vec![f64::NEG_INFINITY; dimension];
+        for &i in &order[start..end] {
+            for (j, &x) in self.xs[i].iter().enumerate() {
+                lower[j] = lower[j].min(x);
+                upper[j] = upper[j].max(x);
+            }
+        }
+
+        let (axis, spread) =
+            (0..dimension)
+                .map(|j| (j, upper[j] - lower[j]))
+                .fold((0, 0.0), |best, candidate| {
+                    if candidate.1 > best.1 {
+                        candidate
+                    } else {
+                        best
+                    }
+                });
+
+        let region = if self.balls {
+            let count = (end - start) as f64;
+            let mut center = vec![0.0; dimension];
+            for &i in &order[start..end] {
+                for (c, x) in center.iter_mut().zip(self.xs[i].iter()) {
+                    *c += x / count;
+                }
+            }
+            let radius = order[start..end]
+                .iter()
+                .map(|&i| self.metric.distance(&center, &self.xs[i]))
+                .fold(0.0, f64::max);
+            Region::Ball { center, radius }
+        } else {
+            Region::Box { lower, upper }
+        };
+
+        let index = nodes.len();
+        nodes.push(Node {
+            start,
+            end,
+            region,
+            children: None,
+        });
+
+        // Identical points cannot be split.
+        if end - start > self.leaf_size && spread > 0.0 {
+            let middle = (start + end) / 2;
+            order[start..end].select_nth_unstable_by(middle - start, |&a, &b| {
+                self.xs[a][axis].partial_cmp(&self.xs[b][axis]).unwrap()
+            });
+            let left = self.node(order, start, middle, nodes);
+            let right = self.node(order, middle, end, nodes);
+            nodes[index].children = Some((left, right));
+        }
+        index
+    }
+}
+
+/// A k-d tree, which recursively splits the points at the median of the coordinate with the
+/// largest spread.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::spatial::{KdTree, Metric, SpatialIndex};
+///
+/// let points = Matrix::new(&[
+///     Array::from(&[0.0, 0.0]),
+///     Array::from(&[1.0, 0.0]),
+///     Array::from(&[5.0, 5.0]),
+///     Array::from(&[0.0, 2.0]),
+/// ]);
+/// let tree = KdTree::new(&points, Metric::Euclidean);
+///
+/// let nearest = tree.nearest(&[0.9, 0.2], 2);
+/// assert_eq!(vec![1, 0], nearest.iter().map(|n| n.index).collect::<Vec<_>>());
+///
+/// let within = tree.within(&[0.0, 0.0], 2.0);
+/// assert_eq!(vec![0, 1, 3], within.iter().map(|n| n.index).collect::<Vec<_>>());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct KdTree {
+    tree: Tree,
+}
+
+impl KdTree {
+    /// Returns the index of the rows of a `Matrix`.
+    ///
+    /// # Panics
+    ///
+    /// The points must not contain NaN and a Minkowski exponent must be finite and at least 1,
+    /// otherwise the code will panic.
+    pub fn new(points: &Matrix, metric: Metric) -> Self {
+        KdTree::with_leaf_size(points, metric, LEAF_SIZE)
+    }
+
+    /// Returns the index of the rows of a `Matrix`, with at most `leaf_size` points in a leaf
+    /// unless they are identical.
+    ///
+    /// # Panics
+    ///
+    /// The leaf size must be positive, the points must not contain NaN and a Minkowski exponent
+    /// must be finite and at least 1, otherwise the code will panic.
+    pub fn with_leaf_size(points: &Matrix, metric: Metric, leaf_size: usize) -> Self {
+        KdTree {
+            tree: Tree::build(points, metric, leaf_size, false, "KdTree"),
+        }
+    }
+}
+
+impl SpatialIndex for KdTree {
+    fn len(&self) -> usize {
+        self.tree.order.len()
+    }
+
+    fn metric(&self) -> Metric {
+        self.tree.metric
+    }
+
+    fn nearest(&self, point: &[f64], k: usize) -> Vec<Neighbor> {
+        self.tree.nearest(point, k)
+    }
+
+    fn within(&self, point: &[f64], radius: f64) -> Vec<Neighbor> {
+        self.tree.within(point, radius)
+    }
+}
+
+/// A ball tree, which recursively splits the points like a `KdTree` but bounds every node by
+/// the ball around the centroid of its points.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::spatial::{BallTree, Metric, SpatialIndex};
+///
+/// let points = Matrix::new(&[
+///     Array::from(&[0.0, 0.0]),
+///     Array::from(&[1.0, 0.0]),
+///     Array::from(&[5.0, 5.0]),
+///     Array::from(&[0.0, 2.0]),
+/// ]);
+/// let tree = BallTree::new(&points, Metric::Manhattan);
+///
+/// let nearest = tree.nearest(&[4.0, 4.0], 1);
+/// assert_eq!(2, nearest[0].index);
+/// assert_eq!(2.0, nearest[0].distance);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BallTree {
+    tree: Tree,
+}
+
+impl BallTree {
+    /// Returns the index of the rows of a `Matrix`.
+    ///
+    /// # Panics
+    ///
+    /// The points must not contain NaN and a Minkowski exponent must be finite and at least 1,
+    /// otherwise the code will panic.
+    pub fn new(points: &Matrix, metric: Metric) -> Self {
+        BallTree::with_leaf_size(points, metric, LEAF_SIZE)
+    }
+
+    /// Returns the index of the rows of a `Matrix`, with at most `leaf_size` points in a leaf
+    /// unless they are identical.
+    ///
+    /// # Panics
+    ///
+    /// The leaf size must be positive, the points must not contain NaN and a Minkowski exponent
+    /// must be finite and at least 1, otherwise the code will panic.
+    pub fn with_leaf_size(points: &Matrix, metric: Metric, leaf_size: usize) -> Self {
+        BallTree {
+            tree: Tree::build(points, metric, leaf_size, true, "BallTree"),
+        }
+    }
+}
+
+impl SpatialIndex for BallTree {
+    fn len(&self) -> usize {
+        self.tree.order.len()
+    }
+
+    fn metric(&self) -> Metric {
+        self.tree.metric
+    }
+
+    fn nearest(&self, point: &[f64], k: usize) -> Vec<Neighbor> {
+        self.tree.nearest(point, k)
+    }
+
+    fn within(&self, point: &[f64], radius: f64) -> Vec<Neighbor> {
+        self.tree.within(point, radius)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::linalg::array::Array;
+    use crate::random::pcg::Pcg64;
+
+    const METRICS: [Metric; 4] = [
+        Metric::Euclidean,
+        Metric::Manhattan,
+        Metric::Minkowski(3.0),
+        Metric::Chebyshev,
+    ];
+
+    /// Returns every point by increasing distance to a query, then by increasing index.
+    fn brute_force(points: &Matrix, query: &[f64], metric: Metric) -> Vec<Neighbor> {
+        let mut all: Vec<Neighbor> = points
+            .iter()
+            .enumerate()
+            .map(|(index, row)| Neighbor {
+                index,
+                distance: metric.distance(query, row),
+            })
+            .collect();
+        all.sort_by(|a, b| {
+            a.distance
+                .partial_cmp(&b.distance)
+                .unwrap()
+                .then(a.index.cmp(&b.index))
+        });
+        all
+    }
+
+    fn indexes(neighbors: &[Neighbor]) -> Vec<usize> {
+        neighbors.iter().map(|n| n.index).collect()
+    }
+
+    fn check_queries(index: &dyn SpatialIndex, points: &Matrix, queries: &Matrix) {
+        for query in queries.iter() {
+            let expected = brute_force(points, query, index.metric());
+
+            let nearest = index.nearest(query, 7);
+            assert_eq!(indexes(&expected[..7]), indexes(&nearest));
+            for (n, e) in nearest.iter().zip(&expected) {
+                assert!((n.distance - e.distance).abs() < 1e-12);
+            }
+
+            let radius = expected[20].distance;
+            let within = index.within(query, radius);
+            let inside: Vec<Neighbor> = expected
+                .iter()
+                .copied()
+                .filter(|n| n.distance <= radius)
+                .collect();
+            assert_eq!(indexes(&inside), indexes(&within));
+        }
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        let mut rng = Pcg64::seed_from_u64(7);
+        let points = Matrix::random_normal(500, 3, &mut rng);
+        let queries = Matrix::random_normal(20, 3, &mut rng);
+
+        for &metric in METRICS.iter() {
+            check_queries(&KdTree::new(&points, metric), &points, &queries);
+            check_queries(&BallTree::new(&points, metric), &points, &queries);
+            check_queries(
+                &KdTree::with_leaf_size(&points, metric, 1),
+                &points,
+                &queries,
+            );
+            check_queries(
+                &BallTree::with_leaf_size(&points, metric, 1),
+                &points,
+                &queries,
+            );
+        }
+    }
+
+    #[test]
+    fn test_duplicates_and_ties() {
+        // A grid with every point twice, so many queries have ties.
+        let points = Matrix::from_rows(
+            (0..200)
+                .map(|i| Array::from(&[((i / 2) % 10) as f64, ((i / 2) / 10) as f64]))
+                .collect(),
+        );
+        let queries = Matrix::new(&[
+            Array::from(&[4.5, 4.5]),
+            Array::from(&[0.0, 0.0]),
+            Array::from(&[3.0, 7.0]),
+        ]);
+
+        for &metric in METRICS.iter() {
+            check_queries(
+                &KdTree::with_leaf_size(&points, metric, 4),
+                &points,
+                &queries,
+            );
+            check_queries(
+                &BallTree::with_leaf_size(&points, metric, 4),
+                &points,
+                &queries,
+            );
+        }
+
+        let same = Matrix::from_rows(vec![Array::from(&[1.0]); 50]);
+        let tree = KdTree::with_leaf_size(&same, Metric::Euclidean, 2);
+        assert_eq!(vec![0, 1, 2], indexes(&tree.nearest(&[0.0], 3)));
+    }
+
+    #[test]
+    fn test_small_queries() {
+        let points = Matrix::new(&[Array::from(&[0.0]), Array::from(&[3.0])]);
+        let tree = BallTree::new(&points, Metric::Euclidean);
+
+        assert_eq!(2, tree.len());
+        assert_eq!(vec![1, 0], indexes(&tree.nearest(&[2.0], 5)));
+        assert!(tree.nearest(&[2.0], 0).is_empty());
+        assert!(tree.within(&[10.0], 1.0).is_empty());
+        assert!(tree.within(&[10.0], -1.0).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_dimension() {
+        let points = Matrix::new(&[Array::from(&[0.0, 1.0])]);
+        KdTree::new(&points, Metric::Euclidean).nearest(&[0.0], 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_exponent() {
+        let points = Matrix::new(&[Array::from(&[0.0, 1.0])]);
+        BallTree::new(&points, Metric::Minkowski(0.5));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_infinite_exponent() {
+        let points = Matrix::new(&[Array::from(&[0.0, 1.0])]);
+        KdTree::new(&points, Metric::Minkowski(f64::INFINITY));
+    }
+}
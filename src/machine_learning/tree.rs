@@ -0,0 +1,794 @@
+//! Decision Trees - Classification and regression trees (CART)
+//!
+//! A decision tree recursively splits the samples in two by a threshold on one feature, choosing
+//! the split that reduces the impurity of the targets most, and predicts a sample from the
+//! training samples in the leaf it falls into:
+//!
+//! * `DecisionTreeClassifier` measures the impurity of class labels by the Gini index or the
+//!   entropy, and predicts the share of every class in the leaf.
+//! * `DecisionTreeRegressor` measures the impurity by the mean squared error, and predicts the
+//!   mean target of the leaf.
+//!
+//! The reduction of impurity by all splits on a feature is its importance.
+
+use crate::linalg::array::Array;
+use crate::linalg::matrix::Matrix;
+use crate::machine_learning::model::{
+    check_features, check_targets, encode_classes, Classifier, Estimator, ModelError, Predictor,
+    Result,
+};
+use crate::random::pcg::Pcg64;
+use crate::random::{shuffle, Rng};
+
+/// The impurity of the class labels of a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Criterion {
+    /// The probability that two samples drawn from the node have different classes. This is
+    /// the default.
+    #[default]
+    Gini,
+    /// The entropy of the classes of the node in bits.
+    Entropy,
+}
+
+/// The number of features considered for every split.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MaxFeatures {
+    /// Every feature. This is the default.
+    #[default]
+    All,
+    /// The square root of the number of features.
+    Sqrt,
+    /// The binary logarithm of the number of features.
+    Log2,
+    /// A given number of features.
+    Count(usize),
+    /// A given fraction of the features.
+    Fraction(f64),
+}
+
+impl MaxFeatures {
+    /// Returns the number of features out of `features`, at least 1.
+    fn count(&self, features: usize) -> usize {
+        let count = match *self {
+            MaxFeatures::All => features,
+            MaxFeatures::Sqrt => (features as f64).sqrt() as usize,
+            MaxFeatures::Log2 => (features as f64).log2() as usize,
+            MaxFeatures::Count(count) => count,
+            MaxFeatures::Fraction(fraction) => (fraction * features as f64) as usize,
+        };
+        count.clamp(1, features.max(1))
+    }
+}
+
+/// The controls of the growth of a tree.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TreeOptions {
+    /// The maximum depth of a leaf, or `None` to split until the leaves are pure.
+    pub max_depth: Option<usize>,
+    /// The minimum number of samples of a node to split it.
+    pub min_samples_split: usize,
+    /// The minimum number of samples of a leaf.
+    pub min_samples_leaf: usize,
+    /// The number of randomly chosen features considered for every split. Features that are
+    /// constant in a node do not count.
+    pub max_features: MaxFeatures,
+    /// The seed of the choice of features.
+    pub seed: u64,
+}
+
+impl Default for TreeOptions {
+    fn default() -> Self {
+        TreeOptions {
+            max_depth: None,
+            min_samples_split: 2,
+            min_samples_leaf: 1,
+            max_features: MaxFeatures::All,
+            seed: 0,
+        }
+    }
+}
+
+impl TreeOptions {
+    /// Panics unless the options are valid.
+    pub(crate) fn check(&self, name: &str) {
+        assert!(
+            self.min_samples_split >= 2 && self.min_samples_leaf >= 1,
+            "ERROR - {}: min_samples_split must be at least 2 and min_samples_leaf at least 1.",
+            name
+        );
+        let valid = match self.max_features {
+            MaxFeatures::Count(count) => count > 0,
+            MaxFeatures::Fraction(fraction) => fraction > 0.0 && fraction <= 1.0,
+            _ => true,
+        };
+        assert!(
+            valid,
+            "ERROR - {}: max_features must be a positive count or a fraction in (0, 1].",
+            name
+        );
+    }
+}
+
+/// A node of a tree, which is either a leaf with the prediction of its samples or a split that
+/// sends a sample left if its feature is at most the threshold.
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Leaf(Array),
+    Split {
+        feature: usize,
+        threshold: f64,
+        left: usize,
+        right: usize,
+    },
+}
+
+/// A fitted tree, whose root is its first node.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Tree {
+    nodes: Vec<Node>,
+    importances: Array,
+    depth: usize,
+}
+
+impl Tree {
+    /// Returns the prediction of the leaf a sample falls into: the probability of every class
+    /// for a classification, and the mean target for a regression.
+    pub(crate) fn leaf(&self, x: &[f64]) -> &Array {
+        let mut node = 0;
+        loop {
+            match &self.nodes[node] {
+                Node::Leaf(value) => return value,
+                Node::Split {
+                    feature,
+                    threshold,
+                    left,
+                    right,
+                } => {
+                    node = if x[*feature] <= *threshold {
+                        *left
+                    } else {
+                        *right
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the impurity reduction of every feature, relative to the total.
+    pub(crate) fn importances(&self) -> &Array {
+        &self.importances
+    }
+
+    fn leaves(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| matches!(node, Node::Leaf(_)))
+            .count()
+    }
+}
+
+/// The targets a tree is grown for.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Task<'a> {
+    /// Class labels `0..classes`.
+    Classification {
+        labels: &'a [usize],
+        classes: usize,
+        criterion: Criterion,
+    },
+    /// Continuous targets.
+    Regression { targets: &'a [f64] },
+}
+
+impl Task<'_> {
+    /// Returns the statistics of no samples: the weight of every class for a classification,
+    /// and the weight, weighted sum and weighted sum of squares of the targets for a
+    /// regression.
+    fn empty(&self) -> Vec<f64> {
+        match self {
+            Task::Classification { classes, .. } => vec![0.0; *classes],
+            Task::Regression { .. } => vec![0.0; 3],
+        }
+    }
+
+    /// Adds a sample with a weight to statistics.
+    fn add(&self, stats: &mut [f64], sample: usize, weight: f64) {
+        match self {
+            Task::Classification { labels, .. } => stats[labels[sample]] += weight,
+            Task::Regression { targets } => {
+                let y = targets[sample];
+                stats[0] += weight;
+                stats[1] += weight * y;
+                stats[2] += weight * y * y;
+            }
+        }
+    }
+
+    /// Returns the weight of the samples of statistics.
+    fn weight(&self, stats: &[f64]) -> f64 {
+        match self {
+            Task::Classification { .. } => stats.iter().sum(),
+            Task::Regression { .. } => stats[0],
+        }
+    }
+
+    /// Returns the impurity of statistics.
+    fn impurity(&self, stats: &[f64]) -> f64 {
+        let weight = self.weight(stats);
+        if weight <= 0.0 {
+            return 0.0;
+        }
+        match self {
+            Task::Classification { criterion, .. } => {
+                let shares = stats.iter().map(|s| s / weight);
+                match criterion {
+                    Criterion::Gini => 1.0 - shares.map(|p| p * p).sum::<f64>(),
+                    Criterion::Entropy => -shares
+                        .filter(|&p| p > 0.0)
+                        .map(|p| p * p.log2())
+                        .sum::<f64>(),
+                }
+            }
+            Task::Regression { .. } => {
+                let mean = stats[1] / weight;
+                (stats[2] / weight - mean * mean).max(0.0)
+            }
+        }
+    }
+
+    /// Returns the prediction of a leaf with statistics.
+    fn value(&self, stats: &[f64]) -> Array {
+        let weight = self.weight(stats);
+        match self {
+            Task::Classification { .. } => {
+                Array::from_vec(stats.iter().map(|s| s / weight).collect())
+            }
+            Task::Regression { .. } => Array::from(&[stats[1] / weight]),
+        }
+    }
+}
+
+/// The best split of a node found so far.
+struct Split {
+    feature: usize,
+    threshold: f64,
+    gain: f64,
+}
+
+/// Grows a tree by recursively splitting nodes.
+struct Grower<'a> {
+    xs: &'a Matrix,
+    task: Task<'a>,
+    weights: &'a [f64],
+    options: &'a TreeOptions,
+    max_features: usize,
+    rng: &'a mut dyn Rng,
+    nodes: Vec<Node>,
+    importances: Vec<f64>,
+    depth: usize,
+}
+
+impl Grower<'_> {
+    /// Adds the node of the samples and its descendants, and returns its index.
+    fn node(&mut self, samples: Vec<usize>, depth: usize) -> usize {
+        let mut stats = self.task.empty();
+        for &i in &samples {
+            self.task.add(&mut stats, i, self.weights[i]);
+        }
+        let weight = self.task.weight(&stats);
+        let impurity = self.task.impurity(&stats);
+
+        let index = self.nodes.len();
+        self.nodes.push(Node::Leaf(self.task.value(&stats)));
+        self.depth = self.depth.max(depth);
+
+        let options = self.options;
+        if options.max_depth.is_some_and(|max| depth >= max)
+            || samples.len() < options.min_samples_split
+            || samples.len() < 2 * options.min_samples_leaf
+            || impurity <= 0.0
+        {
+            return index;
+        }
+
+        if let Some(split) = self.best_split(&samples, &stats, weight * impurity) {
+            let (left, right): (Vec<usize>, Vec<usize>) = samples
+                .into_iter()
+                .partition(|&i| self.xs[i][split.feature] <= split.threshold);
+            self.importances[split.feature] += split.gain;
+            let left = self.node(left, depth + 1);
+            let right = self.node(right, depth + 1);
+            self.nodes[index] = Node::Split {
+                feature: split.feature,
+                threshold: split.threshold,
+                left,
+                right,
+            };
+        }
+        index
+    }
+
+    /// Returns the split of the samples that reduces the weighted impurity most, among the
+    /// first `max_features` randomly ordered features that are not constant, or `None` if no
+    /// split leaves enough samples on both sides.
+    fn best_split(&mut self, samples: &[usize], stats: &[f64], parent: f64) -> Option<Split> {
+        let (_, p) = self.xs.dimensions();
+        let mut features: Vec<usize> = (0..p).collect();
+        if self.max_features < p {
+            shuffle(&mut features, self.rng);
+        }
+
+        let min_leaf = self.options.min_samples_leaf;
+        let n = samples.len();
+        let mut order = samples.to_vec();
+        let mut right = self.task.empty();
+        let mut best: Option<Split> = None;
+        let mut evaluated = 0;
+
+        for feature in features {
+            if evaluated == self.max_features {
+                break;
+            }
+            let x = |i: usize| self.xs[i][feature];
+            order.sort_by(|&a, &b| x(a).partial_cmp(&x(b)).unwrap());
+            if x(order[0]) == x(order[n - 1]) {
+                continue;
+            }
+            evaluated += 1;
+
+            let mut left = self.task.empty();
+            for position in 0..n - 1 {
+                let sample = order[position];
+                self.task.add(&mut left, sample, self.weights[sample]);
+                let (count_left, count_right) = (position + 1, n - position - 1);
+                if count_right < min_leaf {
+                    break;
+                }
+                let (a, b) = (x(sample), x(order[position + 1]));
+                if count_left < min_leaf || a == b {
+                    continue;
+                }
+
+                for ((r, s), l) in right.iter_mut().zip(stats).zip(&left) {
+                    *r = s - l;
+                }
+                let gain = parent
+                    - self.task.weight(&left) * self.task.impurity(&left)
+                    - self.task.weight(&right) * self.task.impurity(&right);
+                if best.as_ref().is_none_or(|b| gain > b.gain) {
+                    // The midpoint can round up to the larger value.
+                    let middle = a + (b - a) / 2.0;
+                    let threshold = if middle < b { middle } else { a };
+                    best = Some(Split {
+                        feature,
+                        threshold,
+                        gain: gain.max(0.0),
+                    });
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Returns a tree grown on the samples with a positive weight.
+///
+/// The weights multiply the contribution of every sample to the statistics of a node, e.g. the
+/// number of times a sample is drawn into a bootstrap sample, but the minimum numbers of samples
+/// count distinct samples.
+pub(crate) fn grow(
+    xs: &Matrix,
+    task: Task,
+    weights: &[f64],
+    options: &TreeOptions,
+    rng: &mut dyn Rng,
+) -> Tree {
+    let (rows, p) = xs.dimensions();
+    let mut grower = Grower {
+        xs,
+        task,
+        weights,
+        options,
+        max_features: options.max_features.count(p),
+        rng,
+        nodes: Vec::new(),
+        importances: vec![0.0; p],
+        depth: 0,
+    };
+    let samples = (0..rows).filter(|&i| weights[i] > 0.0).collect();
+    grower.node(samples, 0);
+
+    let total: f64 = grower.importances.iter().sum();
+    let importances = if total > 0.0 {
+        Array::from_vec(grower.importances.iter().map(|g| g / total).collect())
+    } else {
+        Array::zeros(p)
+    };
+    Tree {
+        nodes: grower.nodes,
+        importances,
+        depth: grower.depth,
+    }
+}
+
+/// A classification tree.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::model::Classifier;
+/// use moonalloy::machine_learning::tree::{Criterion, DecisionTreeClassifier};
+///
+/// // The class is whether both features are positive.
+/// let xs = Matrix::new(&[
+///     Array::from(&[1.0, 1.0]),
+///     Array::from(&[1.0, -1.0]),
+///     Array::from(&[-1.0, 1.0]),
+///     Array::from(&[-1.0, -1.0]),
+///     Array::from(&[2.0, 0.5]),
+/// ]);
+/// let ys = Array::from(&[1.0, 0.0, 0.0, 0.0, 1.0]);
+///
+/// let mut tree = DecisionTreeClassifier::new(Criterion::Gini);
+/// tree.fit(&xs, &ys).unwrap();
+///
+/// assert_eq!(2, tree.get_depth());
+/// assert_eq!(ys, tree.predict(&xs).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecisionTreeClassifier {
+    criterion: Criterion,
+    options: TreeOptions,
+    tree: Option<Tree>,
+    features: usize,
+    classes: Array,
+}
+
+impl DecisionTreeClassifier {
+    /// Returns an unfitted model.
+    pub fn new(criterion: Criterion) -> Self {
+        DecisionTreeClassifier {
+            criterion,
+            options: TreeOptions::default(),
+            tree: None,
+            features: 0,
+            classes: Array::new(),
+        }
+    }
+
+    /// Sets the controls of the growth of the tree.
+    ///
+    /// # Panics
+    ///
+    /// `min_samples_split` must be at least 2, `min_samples_leaf` at least 1, and
+    /// `max_features` a positive count or a fraction in (0, 1], otherwise the code will panic.
+    pub fn set_options(&mut self, options: TreeOptions) {
+        options.check("DecisionTreeClassifier");
+        self.options = options;
+    }
+
+    /// Returns the depth of the deepest leaf, which is 0 if the root is a leaf or the model is
+    /// not fitted.
+    pub fn get_depth(&self) -> usize {
+        self.tree.as_ref().map_or(0, |tree| tree.depth)
+    }
+
+    /// Returns the number of leaves, which is 0 if the model is not fitted.
+    pub fn get_leaves(&self) -> usize {
+        self.tree.as_ref().map_or(0, Tree::leaves)
+    }
+
+    /// Returns the impurity reduction of every feature, relative to the total, which is empty
+    /// if the model is not fitted.
+    pub fn get_feature_importances(&self) -> Array {
+        self.tree
+            .as_ref()
+            .map_or_else(Array::new, |tree| tree.importances.clone())
+    }
+}
+
+impl Estimator for DecisionTreeClassifier {
+    fn is_fitted(&self) -> bool {
+        self.tree.is_some()
+    }
+}
+
+impl Classifier for DecisionTreeClassifier {
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        check_targets(xs, ys)?;
+        let (classes, labels) = encode_classes(ys);
+        let task = Task::Classification {
+            labels: &labels,
+            classes: classes.len(),
+            criterion: self.criterion,
+        };
+        let weights = vec![1.0; ys.len()];
+        let mut rng = Pcg64::seed_from_u64(self.options.seed);
+
+        self.tree = Some(grow(xs, task, &weights, &self.options, &mut rng));
+        self.features = xs.dimensions().1;
+        self.classes = classes;
+        Ok(())
+    }
+
+    fn classes(&self) -> Result<Array> {
+        self.check_fitted()?;
+        Ok(self.classes.clone())
+    }
+
+    /// Returns the share of every class among the training samples in the leaf of every
+    /// sample.
+    fn predict_proba(&self, xs: &Matrix) -> Result<Matrix> {
+        let tree = self.tree.as_ref().ok_or(ModelError::NotFitted)?;
+        check_features(xs, self.features)?;
+        Ok(Matrix::from_rows(
+            xs.iter().map(|row| tree.leaf(row).clone()).collect(),
+        ))
+    }
+}
+
+/// A regression tree.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::machine_learning::model::Predictor;
+/// use moonalloy::machine_learning::tree::{DecisionTreeRegressor, TreeOptions};
+///
+/// let xs = Matrix::new(&[
+///     Array::from(&[1.0]),
+///     Array::from(&[2.0]),
+///     Array::from(&[3.0]),
+///     Array::from(&[10.0]),
+///     Array::from(&[11.0]),
+/// ]);
+/// let ys = Array::from(&[1.0, 1.2, 0.8, 5.0, 5.5]);
+///
+/// let mut tree = DecisionTreeRegressor::new();
+/// tree.set_options(TreeOptions {
+///     max_depth: Some(1),
+///     ..TreeOptions::default()
+/// });
+/// tree.fit(&xs, &ys).unwrap();
+///
+/// let prediction = tree.predict(&Matrix::new(&[Array::from(&[0.0]), Array::from(&[20.0])]));
+/// assert_eq!(Array::from(&[1.0, 5.25]), prediction.unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecisionTreeRegressor {
+    options: TreeOptions,
+    tree: Option<Tree>,
+    features: usize,
+}
+
+impl Default for DecisionTreeRegressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DecisionTreeRegressor {
+    /// Returns an unfitted model that splits by the mean squared error.
+    pub fn new() -> Self {
+        DecisionTreeRegressor {
+            options: TreeOptions::default(),
+            tree: None,
+            features: 0,
+        }
+    }
+
+    /// Sets the controls of the growth of the tree.
+    ///
+    /// # Panics
+    ///
+    /// `min_samples_split` must be at least 2, `min_samples_leaf` at least 1, and
+    /// `max_features` a positive count or a fraction in (0, 1], otherwise the code will panic.
+    pub fn set_options(&mut self, options: TreeOptions) {
+        options.check("DecisionTreeRegressor");
+        self.options = options;
+    }
+
+    /// Returns the depth of the deepest leaf, which is 0 if the root is a leaf or the model is
+    /// not fitted.
+    pub fn get_depth(&self) -> usize {
+        self.tree.as_ref().map_or(0, |tree| tree.depth)
+    }
+
+    /// Returns the number of leaves, which is 0 if the model is not fitted.
+    pub fn get_leaves(&self) -> usize {
+        self.tree.as_ref().map_or(0, Tree::leaves)
+    }
+
+    /// Returns the impurity reduction of every feature, relative to the total, which is empty
+    /// if the model is not fitted.
+    pub fn get_feature_importances(&self) -> Array {
+        self.tree
+            .as_ref()
+            .map_or_else(Array::new, |tree| tree.importances.clone())
+    }
+}
+
+impl Estimator for DecisionTreeRegressor {
+    fn is_fitted(&self) -> bool {
+        self.tree.is_some()
+    }
+}
+
+impl Predictor for DecisionTreeRegressor {
+    fn fit(&mut self, xs: &Matrix, ys: &Array) -> Result<()> {
+        check_targets(xs, ys)?;
+        let task = Task::Regression { targets: ys };
+        let weights = vec![1.0; ys.len()];
+        let mut rng = Pcg64::seed_from_u64(self.options.seed);
+
+        self.tree = Some(grow(xs, task, &weights, &self.options, &mut rng));
+        self.features = xs.dimensions().1;
+        Ok(())
+    }
+
+    /// Returns the mean target of the training samples in the leaf of every sample.
+    fn predict(&self, xs: &Matrix) -> Result<Array> {
+        let tree = self.tree.as_ref().ok_or(ModelError::NotFitted)?;
+        check_features(xs, self.features)?;
+        Ok(Array::from_vec(
+            xs.iter().map(|row| tree.leaf(row)[0]).collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::machine_learning::model::test::{
+        check_classifier_errors, check_predictor_errors, normal_samples,
+    };
+
+    /// Returns samples with an irrelevant second feature and the class `x > 0.3`.
+    fn threshold_data(n: usize, seed: u64) -> (Matrix, Array) {
+        normal_samples(n, 2, seed, |row| if row[0] > 0.3 { 1.0 } else { 0.0 })
+    }
+
+    #[test]
+    fn test_pure_leaves() {
+        let (xs, ys) = threshold_data(200, 1);
+
+        for &criterion in [Criterion::Gini, Criterion::Entropy].iter() {
+            let mut tree = DecisionTreeClassifier::new(criterion);
+            tree.fit(&xs, &ys).unwrap();
+
+            assert_eq!(1, tree.get_depth());
+            assert_eq!(2, tree.get_leaves());
+            assert_eq!(Array::from(&[1.0, 0.0]), tree.get_feature_importances());
+            assert_eq!(1.0, tree.score(&xs, &ys).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_xor() {
+        // No single split reduces the impurity, but two levels separate the classes.
+        let xs = Matrix::new(&[
+            Array::from(&[0.0, 0.0]),
+            Array::from(&[0.0, 1.0]),
+            Array::from(&[1.0, 0.0]),
+            Array::from(&[1.0, 1.0]),
+        ]);
+        let ys = Array::from(&[0.0, 1.0, 1.0, 0.0]);
+        let mut tree = DecisionTreeClassifier::new(Criterion::Entropy);
+        tree.fit(&xs, &ys).unwrap();
+
+        assert_eq!(ys, tree.predict(&xs).unwrap());
+        assert_eq!(4, tree.get_leaves());
+    }
+
+    #[test]
+    fn test_limits() {
+        let mut rng = Pcg64::seed_from_u64(2);
+        let xs = Matrix::random_normal(300, 3, &mut rng);
+        let ys = Array::from_vec(
+            xs.iter()
+                .map(|row| (row[0] * row[1] + row[2]).signum())
+                .collect(),
+        );
+
+        let mut tree = DecisionTreeClassifier::new(Criterion::Gini);
+        tree.set_options(TreeOptions {
+            max_depth: Some(3),
+            ..TreeOptions::default()
+        });
+        tree.fit(&xs, &ys).unwrap();
+        assert_eq!(3, tree.get_depth());
+        assert!(tree.get_leaves() <= 8);
+
+        tree.set_options(TreeOptions {
+            min_samples_leaf: 20,
+            ..TreeOptions::default()
+        });
+        tree.fit(&xs, &ys).unwrap();
+        let root = tree.tree.as_ref().unwrap();
+        let mut counts = std::collections::HashMap::new();
+        for row in xs.iter() {
+            *counts
+                .entry(root.leaf(row) as *const Array as usize)
+                .or_insert(0) += 1;
+        }
+        assert_eq!(tree.get_leaves(), counts.len());
+        assert!(counts.values().all(|&count| count >= 20));
+
+        tree.set_options(TreeOptions {
+            min_samples_split: 301,
+            ..TreeOptions::default()
+        });
+        tree.fit(&xs, &ys).unwrap();
+        assert_eq!(1, tree.get_leaves());
+        assert_eq!(Array::zeros(3), tree.get_feature_importances());
+    }
+
+    #[test]
+    fn test_regressor() {
+        let mut rng = Pcg64::seed_from_u64(3);
+        let xs = Matrix::random_normal(200, 2, &mut rng);
+        let ys = Array::from_vec(xs.iter().map(|row| 3.0 * row[0].floor()).collect());
+
+        let mut tree = DecisionTreeRegressor::new();
+        tree.fit(&xs, &ys).unwrap();
+        assert_eq!(ys, tree.predict(&xs).unwrap());
+        let importances = tree.get_feature_importances();
+        assert!((importances[0] - 1.0).abs() < 1e-12);
+
+        // A stump predicts the mean on either side of the best threshold.
+        tree.set_options(TreeOptions {
+            max_depth: Some(1),
+            ..TreeOptions::default()
+        });
+        tree.fit(&xs, &ys).unwrap();
+        let predictions = tree.predict(&xs).unwrap();
+        let low: Vec<f64> = ys
+            .iter()
+            .zip(predictions.iter())
+            .filter(|(_, &p)| p == predictions.min())
+            .map(|(&y, _)| y)
+            .collect();
+        let mean = low.iter().sum::<f64>() / low.len() as f64;
+        assert!((predictions.min() - mean).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_max_features() {
+        assert_eq!(3, MaxFeatures::Sqrt.count(10));
+        assert_eq!(3, MaxFeatures::Log2.count(10));
+        assert_eq!(1, MaxFeatures::Fraction(0.01).count(10));
+        assert_eq!(10, MaxFeatures::Count(20).count(10));
+
+        // A single random feature still finds the only informative one eventually.
+        let (xs, ys) = threshold_data(100, 4);
+        let mut tree = DecisionTreeClassifier::new(Criterion::Gini);
+        tree.set_options(TreeOptions {
+            max_features: MaxFeatures::Count(1),
+            seed: 5,
+            ..TreeOptions::default()
+        });
+        tree.fit(&xs, &ys).unwrap();
+        assert_eq!(1.0, tree.score(&xs, &ys).unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        let (xs, ys) = threshold_data(10, 6);
+        check_predictor_errors(&mut DecisionTreeRegressor::new(), &xs, &ys);
+        check_classifier_errors(&mut DecisionTreeClassifier::new(Criterion::Gini), &xs, &ys);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_options() {
+        DecisionTreeClassifier::new(Criterion::Gini).set_options(TreeOptions {
+            min_samples_leaf: 0,
+            ..TreeOptions::default()
+        });
+    }
+}
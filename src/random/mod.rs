@@ -0,0 +1,258 @@
+//! Random - Seedable random number generation
+//!
+//! This module contains pseudo-random number generators, samplers for the common probability
+//! distributions and helpers for shuffling and sampling from slices. The generators are written
+//! in pure Rust and seeded explicitly, so a simulation can be reproduced by running it again
+//! with the same seed.
+//!
+//! # Reproducibility
+//!
+//! The generators only use integer arithmetic, and the samplers only use the IEEE 754 operations
+//! `+`, `-`, `*`, `/` and `sqrt` together with portable implementations of `ln` and `exp`. They
+//! never call the math library of the platform, which may round differently on other systems.
+//! Identical seeds therefore give bit-identical results on every platform.
+//!
+//! # Examples
+//!
+//! ```
+//! use moonalloy::random::pcg::Pcg64;
+//! use moonalloy::random::{samplers, Rng};
+//!
+//! let mut rng = Pcg64::seed_from_u64(42);
+//! let u = rng.next_f64();
+//! let z = samplers::normal(&mut rng, 0.0, 1.0);
+//!
+//! assert!((0.0..1.0).contains(&u));
+//! assert!(z.is_finite());
+//! ```
+
+pub mod pcg;
+pub mod samplers;
+pub mod xoshiro;
+
+/// A source of uniformly distributed random 64-bit integers.
+///
+/// Only `next_u64` has to be implemented; the other methods derive their results from it in a
+/// platform independent way.
+pub trait Rng {
+    /// Returns the next random 64-bit integer.
+    fn next_u64(&mut self) -> u64;
+
+    /// Returns the next random 32-bit integer, taken from the upper bits of `next_u64`.
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Returns a random number uniformly distributed in the half-open interval [0, 1).
+    ///
+    /// The result is a multiple of 2^-53.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Returns a random number uniformly distributed in the open interval (0, 1).
+    ///
+    /// The result is an odd multiple of 2^-53, so it is never 0 or 1.
+    fn next_open_f64(&mut self) -> f64 {
+        ((self.next_u64() >> 12) as f64 + 0.5) * (1.0 / (1u64 << 52) as f64)
+    }
+
+    /// Returns a random integer uniformly distributed in `0..n`.
+    ///
+    /// Uses Lemire's multiply-and-reject method, which is unbiased.
+    ///
+    /// # Panics
+    ///
+    /// `n` must be positive, otherwise the code will panic.
+    fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "ERROR - below: The upper bound must be positive.");
+
+        let mut m = self.next_u64() as u128 * n as u128;
+        if (m as u64) < n {
+            let threshold = n.wrapping_neg() % n;
+            while (m as u64) < threshold {
+                m = self.next_u64() as u128 * n as u128;
+            }
+        }
+        (m >> 64) as u64
+    }
+}
+
+/// Advances a SplitMix64 state and returns its next output.
+///
+/// SplitMix64 turns a single 64-bit seed into well-mixed state words for the other generators.
+pub(crate) fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Shuffles a slice in place.
+///
+/// Every permutation is equally likely (Fisher-Yates shuffle).
+///
+/// # Arguments
+///
+/// * `items` - the slice to shuffle. An Array can be passed directly.
+/// * `rng` - the random number generator.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::random::{self, xoshiro::Xoshiro256PlusPlus};
+///
+/// let mut rng = Xoshiro256PlusPlus::seed_from_u64(7);
+/// let mut array = Array::from(&[1.0, 2.0, 3.0, 4.0]);
+/// random::shuffle(&mut array, &mut rng);
+///
+/// assert_eq!(10.0, array.sum());
+/// ```
+pub fn shuffle<T>(items: &mut [T], rng: &mut dyn Rng) {
+    for i in (1..items.len()).rev() {
+        let j = rng.below(i as u64 + 1) as usize;
+        items.swap(i, j);
+    }
+}
+
+/// Returns `k` elements chosen at random from a slice.
+///
+/// # Arguments
+///
+/// * `items` - the slice to choose from.
+/// * `k` - the number of elements to choose.
+/// * `replace` - whether the same element may be chosen more than once.
+/// * `rng` - the random number generator.
+///
+/// # Panics
+///
+/// Without replacement `k` must not be larger than the length of `items`, and with replacement
+/// `items` must not be empty when `k` is positive, otherwise the code will panic.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::random::{self, pcg::Pcg64};
+///
+/// let mut rng = Pcg64::seed_from_u64(1);
+/// let chosen = random::choice(&[1, 2, 3, 4, 5], 3, false, &mut rng);
+///
+/// assert_eq!(3, chosen.len());
+/// assert!(chosen.iter().all(|x| (1..=5).contains(x)));
+/// ```
+pub fn choice<T: Clone>(items: &[T], k: usize, replace: bool, rng: &mut dyn Rng) -> Vec<T> {
+    if replace {
+        assert!(
+            k == 0 || !items.is_empty(),
+            "ERROR - choice: Cannot choose from an empty slice."
+        );
+        return (0..k)
+            .map(|_| items[rng.below(items.len() as u64) as usize].clone())
+            .collect();
+    }
+
+    assert!(
+        k <= items.len(),
+        "ERROR - choice: Cannot choose more elements than there are without replacement."
+    );
+
+    // A partial Fisher-Yates shuffle of the indices.
+    let mut indices: Vec<usize> = (0..items.len()).collect();
+    for i in 0..k {
+        let j = i + rng.below((items.len() - i) as u64) as usize;
+        indices.swap(i, j);
+    }
+    indices[..k].iter().map(|&i| items[i].clone()).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::random::pcg::Pcg64;
+
+    /// Returns the numbers 0, 1, 2, ... as "random" numbers.
+    struct Counter(u64);
+
+    impl Rng for Counter {
+        fn next_u64(&mut self) -> u64 {
+            self.0 = self.0.wrapping_add(1);
+            self.0.wrapping_sub(1)
+        }
+    }
+
+    #[test]
+    fn test_splitmix64() {
+        let mut state = 1234567;
+        let outputs: Vec<u64> = (0..3).map(|_| splitmix64(&mut state)).collect();
+
+        assert_eq!(
+            vec![
+                6457827717110365317,
+                3203168211198807973,
+                9817491932198370423
+            ],
+            outputs
+        );
+    }
+
+    #[test]
+    fn test_uniform_floats() {
+        let mut rng = Counter(0);
+        assert_eq!(0.0, rng.next_f64());
+
+        let mut rng = Counter(u64::MAX);
+        assert_eq!(1.0 - f64::EPSILON / 2.0, rng.next_f64());
+
+        let mut rng = Counter(0);
+        assert_eq!(f64::EPSILON / 2.0, rng.next_open_f64());
+
+        let mut rng = Counter(u64::MAX);
+        assert_eq!(1.0 - f64::EPSILON / 2.0, rng.next_open_f64());
+    }
+
+    #[test]
+    fn test_below() {
+        let mut rng = Pcg64::seed_from_u64(3);
+        let mut counts = [0; 6];
+        for _ in 0..6000 {
+            counts[rng.below(6) as usize] += 1;
+        }
+
+        assert!(counts.iter().all(|&count| (900..1100).contains(&count)));
+        assert_eq!(0, rng.below(1));
+    }
+
+    #[test]
+    fn test_shuffle_is_a_permutation() {
+        let mut rng = Pcg64::seed_from_u64(5);
+        let mut items: Vec<usize> = (0..50).collect();
+        shuffle(&mut items, &mut rng);
+
+        assert_ne!((0..50).collect::<Vec<usize>>(), items);
+        items.sort_unstable();
+        assert_eq!((0..50).collect::<Vec<usize>>(), items);
+    }
+
+    #[test]
+    fn test_choice() {
+        let mut rng = Pcg64::seed_from_u64(9);
+        let items: Vec<usize> = (0..10).collect();
+
+        let mut chosen = choice(&items, 10, false, &mut rng);
+        chosen.sort_unstable();
+        assert_eq!(items, chosen);
+
+        let chosen = choice(&items, 100, true, &mut rng);
+        assert_eq!(100, chosen.len());
+        assert!(chosen.iter().all(|&x| x < 10));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_choice_too_many() {
+        let mut rng = Pcg64::seed_from_u64(9);
+        choice(&[1, 2], 3, false, &mut rng);
+    }
+}
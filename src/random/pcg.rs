@@ -0,0 +1,184 @@
+//! PCG64 - A permuted congruential generator
+//!
+//! PCG64 (PCG XSL RR 128/64 by M. E. O'Neill) advances a 128-bit linear congruential generator
+//! and permutes its state into a 64-bit output. It has a period of 2^128, and every odd
+//! increment of the generator selects a different stream. The outputs match the reference C
+//! implementation `pcg64_random_r`.
+
+use crate::random::{splitmix64, Rng};
+
+/// The multiplier of the underlying linear congruential generator.
+const MULTIPLIER: u128 = 0x2360_ed05_1fc6_5da4_4385_df64_9fcc_f645;
+
+/// The PCG64 random number generator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pcg64 {
+    state: u128,
+    increment: u128,
+}
+
+impl Pcg64 {
+    /// Returns a generator with the given initial state on the given stream.
+    ///
+    /// This is the same seeding as `pcg64_srandom_r` in the reference implementation.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - the initial state.
+    /// * `stream` - selects one of 2^127 independent sequences.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moonalloy::random::{pcg::Pcg64, Rng};
+    /// let mut rng = Pcg64::new(42, 54);
+    ///
+    /// assert_eq!(0x86b1_da1d_7206_2b68, rng.next_u64());
+    /// ```
+    pub fn new(state: u128, stream: u128) -> Pcg64 {
+        let mut rng = Pcg64 {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        rng.step();
+        rng.state = rng.state.wrapping_add(state);
+        rng.step();
+        rng
+    }
+
+    /// Returns a generator seeded from a single 64-bit integer.
+    ///
+    /// The seed is expanded into the state and the stream with SplitMix64, so nearby seeds give
+    /// unrelated sequences.
+    pub fn seed_from_u64(seed: u64) -> Pcg64 {
+        let mut sm = seed;
+        let mut word = || splitmix64(&mut sm) as u128;
+        let state = (word() << 64) | word();
+        let stream = (word() << 64) | word();
+        Pcg64::new(state, stream)
+    }
+
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(MULTIPLIER)
+            .wrapping_add(self.increment);
+    }
+
+    /// Advances the generator by `delta` steps in O(log delta) time.
+    ///
+    /// Uses Brown's algorithm for jumping ahead in a linear congruential generator.
+    pub fn advance(&mut self, delta: u128) {
+        let (mut acc_mult, mut acc_plus) = (1u128, 0u128);
+        let (mut cur_mult, mut cur_plus) = (MULTIPLIER, self.increment);
+        let mut delta = delta;
+
+        while delta > 0 {
+            if delta & 1 == 1 {
+                acc_mult = acc_mult.wrapping_mul(cur_mult);
+                acc_plus = acc_plus.wrapping_mul(cur_mult).wrapping_add(cur_plus);
+            }
+            cur_plus = cur_mult.wrapping_add(1).wrapping_mul(cur_plus);
+            cur_mult = cur_mult.wrapping_mul(cur_mult);
+            delta >>= 1;
+        }
+
+        self.state = acc_mult.wrapping_mul(self.state).wrapping_add(acc_plus);
+    }
+
+    /// Advances the generator by 2^64 steps.
+    ///
+    /// Calling `jump` repeatedly on clones of a generator gives up to 2^64 non-overlapping
+    /// streams of 2^64 numbers each, e.g. one for every thread of a simulation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moonalloy::random::pcg::Pcg64;
+    /// let mut rng = Pcg64::seed_from_u64(42);
+    ///
+    /// let streams: Vec<Pcg64> = (0..4)
+    ///     .map(|_| {
+    ///         rng.jump();
+    ///         rng.clone()
+    ///     })
+    ///     .collect();
+    /// ```
+    pub fn jump(&mut self) {
+        self.advance(1 << 64);
+    }
+}
+
+impl Rng for Pcg64 {
+    fn next_u64(&mut self) -> u64 {
+        self.step();
+        let rotation = (self.state >> 122) as u32;
+        (((self.state >> 64) as u64) ^ (self.state as u64)).rotate_right(rotation)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_reference_outputs() {
+        let mut rng = Pcg64::new(42, 54);
+        let outputs: Vec<u64> = (0..6).map(|_| rng.next_u64()).collect();
+
+        assert_eq!(
+            vec![
+                0x86b1_da1d_7206_2b68,
+                0x1304_aa46_c985_3d39,
+                0xa367_0e9e_0dd5_0358,
+                0xf909_0e52_9a7d_ae00,
+                0xc85b_9fd8_3799_6f2c,
+                0x6061_21f8_e391_9196
+            ],
+            outputs
+        );
+    }
+
+    #[test]
+    fn test_seed_from_u64() {
+        let mut rng = Pcg64::seed_from_u64(42);
+        let outputs: Vec<u64> = (0..3).map(|_| rng.next_u64()).collect();
+
+        assert_eq!(
+            vec![
+                14521027216680878879,
+                18222601322544828755,
+                472411332899497233
+            ],
+            outputs
+        );
+        assert_ne!(Pcg64::seed_from_u64(43), Pcg64::seed_from_u64(42));
+    }
+
+    #[test]
+    fn test_advance() {
+        let mut stepped = Pcg64::seed_from_u64(1);
+        for _ in 0..1000 {
+            stepped.next_u64();
+        }
+
+        let mut jumped = Pcg64::seed_from_u64(1);
+        jumped.advance(1000);
+
+        assert_eq!(stepped, jumped);
+        assert_eq!(stepped.next_u64(), jumped.next_u64());
+    }
+
+    #[test]
+    fn test_jump_wraps_around() {
+        let rng = Pcg64::seed_from_u64(1);
+        let mut jumped = rng.clone();
+        for _ in 0..4 {
+            jumped.advance(1 << 126);
+        }
+
+        assert_eq!(rng, jumped);
+        jumped.jump();
+        assert_ne!(rng, jumped);
+    }
+}
@@ -0,0 +1,489 @@
+//! Samplers - Random variates from the common probability distributions
+//!
+//! Every sampler draws its uniform numbers from an `Rng` and transforms them with exact IEEE 754
+//! arithmetic and the portable `ln` and `exp` below, so that identical generators give
+//! bit-identical samples on every platform. The discrete samplers return the number of events as
+//! an integer.
+//!
+//! The distributions in `statistics::probability` use these samplers for their `sample` method.
+
+use crate::random::Rng;
+
+use std::f64::consts::LOG2_E;
+
+/// The upper bits of ln(2), such that `k * LN2_HI` is exact for the exponents of an f64.
+const LN2_HI: f64 = 6.931_471_803_691_238e-1;
+/// The rest of ln(2).
+const LN2_LO: f64 = 1.908_214_929_270_587_7e-10;
+
+/// Returns the natural logarithm of `x`.
+///
+/// A port of `log` from FDLIBM, accurate to less than 1 ulp. It only uses basic arithmetic, so
+/// it rounds the same way on every platform.
+fn ln(x: f64) -> f64 {
+    const LG1: f64 = 6.666_666_666_666_735e-1;
+    const LG2: f64 = 3.999_999_999_940_942e-1;
+    const LG3: f64 = 2.857_142_874_366_239e-1;
+    const LG4: f64 = 2.222_219_843_214_978_4e-1;
+    const LG5: f64 = 1.818_357_216_161_805e-1;
+    const LG6: f64 = 1.531_383_769_920_937_3e-1;
+    const LG7: f64 = 1.479_819_860_511_658_6e-1;
+
+    let mut bits = x.to_bits();
+    let mut hx = (bits >> 32) as u32;
+    let mut k: i32 = 0;
+
+    if hx < 0x0010_0000 || hx >> 31 != 0 {
+        if bits << 1 == 0 {
+            return f64::NEG_INFINITY;
+        }
+        if hx >> 31 != 0 {
+            return f64::NAN;
+        }
+        // Scale subnormal numbers up.
+        k -= 54;
+        bits = (x * 18_014_398_509_481_984.0).to_bits();
+        hx = (bits >> 32) as u32;
+    } else if hx >= 0x7ff0_0000 {
+        return x;
+    } else if hx == 0x3ff0_0000 && bits << 32 == 0 {
+        return 0.0;
+    }
+
+    // Reduce x into [sqrt(2)/2, sqrt(2)].
+    hx += 0x3ff0_0000 - 0x3fe6_a09e;
+    k += (hx >> 20) as i32 - 0x3ff;
+    hx = (hx & 0x000f_ffff) + 0x3fe6_a09e;
+    let x = f64::from_bits((hx as u64) << 32 | (bits & 0xffff_ffff));
+
+    let f = x - 1.0;
+    let hfsq = 0.5 * f * f;
+    let s = f / (2.0 + f);
+    let z = s * s;
+    let w = z * z;
+    let t1 = w * (LG2 + w * (LG4 + w * LG6));
+    let t2 = z * (LG1 + w * (LG3 + w * (LG5 + w * LG7)));
+    let r = t2 + t1;
+    let dk = k as f64;
+    s * (hfsq + r) + dk * LN2_LO - hfsq + f + dk * LN2_HI
+}
+
+/// Returns `ln(1 + x)`, accurate for small `x`.
+fn ln_1p(x: f64) -> f64 {
+    let u = 1.0 + x;
+    if u == 1.0 {
+        x
+    } else {
+        ln(u) * x / (u - 1.0)
+    }
+}
+
+/// Returns `x * 2^n` with a single rounding.
+fn scale_by_power_of_two(x: f64, n: i32) -> f64 {
+    let (mut y, mut n) = (x, n);
+    let two_1023 = f64::from_bits(0x7fe0_0000_0000_0000);
+    let two_m969 = f64::from_bits(0x0360_0000_0000_0000); // 2^-1022 * 2^53
+
+    if n > 1023 {
+        y *= two_1023;
+        n -= 1023;
+        if n > 1023 {
+            y *= two_1023;
+            n = (n - 1023).min(1023);
+        }
+    } else if n < -1022 {
+        y *= two_m969;
+        n += 1022 - 53;
+        if n < -1022 {
+            y *= two_m969;
+            n = (n + 1022 - 53).max(-1022);
+        }
+    }
+    y * f64::from_bits(((0x3ff + n) as u64) << 52)
+}
+
+/// Returns the exponential function of `x`.
+///
+/// A port of `exp` from FDLIBM, accurate to less than 1 ulp. It only uses basic arithmetic, so
+/// it rounds the same way on every platform.
+fn exp(x: f64) -> f64 {
+    const P1: f64 = 1.666_666_666_666_660_2e-1;
+    const P2: f64 = -2.777_777_777_701_559_3e-3;
+    const P3: f64 = 6.613_756_321_437_934e-5;
+    const P4: f64 = -1.653_390_220_546_525_2e-6;
+    const P5: f64 = 4.138_136_797_057_238_5e-8;
+
+    if x.is_nan() {
+        return x;
+    }
+    if x > 709.782_712_893_384 {
+        return f64::INFINITY;
+    }
+    if x < -745.133_219_101_941_1 {
+        return 0.0;
+    }
+
+    let hx = (x.to_bits() >> 32) as u32 & 0x7fff_ffff;
+    let (k, hi, lo) = if hx > 0x3fd6_2e42 {
+        // Reduce |x| > ln(2) / 2 to r = x - k ln(2) with |r| <= ln(2) / 2.
+        let k = if hx >= 0x3ff0_a2b2 {
+            (LOG2_E * x + 0.5f64.copysign(x)) as i32
+        } else if x < 0.0 {
+            -1
+        } else {
+            1
+        };
+        (k, x - k as f64 * LN2_HI, k as f64 * LN2_LO)
+    } else if hx > 0x3e30_0000 {
+        (0, x, 0.0)
+    } else {
+        return 1.0 + x;
+    };
+
+    let x = hi - lo;
+    let xx = x * x;
+    let c = x - xx * (P1 + xx * (P2 + xx * (P3 + xx * (P4 + xx * P5))));
+    let y = 1.0 + (x * c / (2.0 - c) - lo + hi);
+    if k == 0 {
+        y
+    } else {
+        scale_by_power_of_two(y, k)
+    }
+}
+
+/// Returns a sample from the uniform distribution on `[lower, upper)`.
+pub fn uniform(rng: &mut dyn Rng, lower: f64, upper: f64) -> f64 {
+    lower + (upper - lower) * rng.next_f64()
+}
+
+/// Returns a sample from the standard normal distribution.
+///
+/// Uses the polar method by Marsaglia.
+pub fn standard_normal(rng: &mut dyn Rng) -> f64 {
+    loop {
+        let u = 2.0 * rng.next_f64() - 1.0;
+        let v = 2.0 * rng.next_f64() - 1.0;
+        let s = u * u + v * v;
+        if s > 0.0 && s < 1.0 {
+            return u * (-2.0 * ln(s) / s).sqrt();
+        }
+    }
+}
+
+/// Returns a sample from the normal distribution with the given mean and standard deviation.
+pub fn normal(rng: &mut dyn Rng, mean: f64, std_dev: f64) -> f64 {
+    mean + std_dev * standard_normal(rng)
+}
+
+/// Returns a sample from the exponential distribution with the given rate.
+///
+/// # Panics
+///
+/// The rate must be positive, otherwise the code will panic.
+pub fn exponential(rng: &mut dyn Rng, rate: f64) -> f64 {
+    assert!(
+        rate > 0.0,
+        "ERROR - exponential: The rate must be positive."
+    );
+    -ln(rng.next_open_f64()) / rate
+}
+
+/// Returns a sample from the gamma distribution with the given shape and scale.
+///
+/// Uses the method by Marsaglia and Tsang, with a boost for shapes below 1.
+///
+/// # Panics
+///
+/// The shape and the scale must be positive, otherwise the code will panic.
+pub fn gamma(rng: &mut dyn Rng, shape: f64, scale: f64) -> f64 {
+    assert!(
+        shape > 0.0 && scale > 0.0,
+        "ERROR - gamma: The shape and scale must be positive."
+    );
+
+    if shape < 1.0 {
+        let boost = exp(ln(rng.next_open_f64()) / shape);
+        return gamma(rng, shape + 1.0, scale) * boost;
+    }
+
+    let d = shape - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        let x = standard_normal(rng);
+        let v = 1.0 + c * x;
+        if v <= 0.0 {
+            continue;
+        }
+
+        let v = v * v * v;
+        let u = rng.next_open_f64();
+        let x2 = x * x;
+        if u < 1.0 - 0.0331 * x2 * x2 || ln(u) < 0.5 * x2 + d * (1.0 - v + ln(v)) {
+            return d * v * scale;
+        }
+    }
+}
+
+/// Returns a sample from the beta distribution with the shape parameters `alpha` and `beta`.
+///
+/// # Panics
+///
+/// Both parameters must be positive, otherwise the code will panic.
+pub fn beta(rng: &mut dyn Rng, alpha: f64, beta: f64) -> f64 {
+    loop {
+        let x = gamma(rng, alpha, 1.0);
+        let y = gamma(rng, beta, 1.0);
+        // Both can underflow to zero for tiny shape parameters.
+        if x + y > 0.0 {
+            return x / (x + y);
+        }
+    }
+}
+
+/// Returns a sample from the chi-squared distribution with `df` degrees of freedom.
+pub fn chi_squared(rng: &mut dyn Rng, df: f64) -> f64 {
+    gamma(rng, 0.5 * df, 2.0)
+}
+
+/// Returns a sample from Student's t-distribution with `df` degrees of freedom.
+pub fn student_t(rng: &mut dyn Rng, df: f64) -> f64 {
+    let z = standard_normal(rng);
+    z / (chi_squared(rng, df) / df).sqrt()
+}
+
+/// Returns a sample from the F-distribution with `df1` and `df2` degrees of freedom.
+pub fn fisher_f(rng: &mut dyn Rng, df1: f64, df2: f64) -> f64 {
+    let x = chi_squared(rng, df1) / df1;
+    x / (chi_squared(rng, df2) / df2)
+}
+
+/// Returns a sample from the binomial distribution with `n` trials and success probability `p`.
+///
+/// Large `n` are split recursively with beta variates (Knuth, TAOCP 3.4.1), so that at most 64
+/// trials are simulated one by one.
+///
+/// # Panics
+///
+/// `p` must be in `[0, 1]`, otherwise the code will panic.
+pub fn binomial(rng: &mut dyn Rng, n: u64, p: f64) -> u64 {
+    assert!(
+        (0.0..=1.0).contains(&p),
+        "ERROR - binomial: The probability must be between 0 and 1."
+    );
+
+    let (mut n, mut p, mut k) = (n, p, 0);
+    while n > 64 {
+        // x is the a-th smallest of n uniform numbers.
+        let a = 1 + n / 2;
+        let b = n + 1 - a;
+        let x = beta(rng, a as f64, b as f64);
+        if x >= p {
+            n = a - 1;
+            p /= x;
+        } else {
+            k += a;
+            n = b - 1;
+            p = (p - x) / (1.0 - x);
+        }
+    }
+
+    k + (0..n).filter(|_| rng.next_f64() < p).count() as u64
+}
+
+/// Returns a sample from the Poisson distribution with the given mean.
+///
+/// Large means are reduced with gamma and binomial variates (Knuth, TAOCP 3.4.1) before the
+/// events are counted with the multiplication method.
+///
+/// # Panics
+///
+/// The rate must not be negative, otherwise the code will panic.
+pub fn poisson(rng: &mut dyn Rng, rate: f64) -> u64 {
+    assert!(
+        rate >= 0.0 && rate.is_finite(),
+        "ERROR - poisson: The rate must not be negative."
+    );
+
+    let (mut rate, mut k) = (rate, 0);
+    while rate > 16.0 {
+        // x is the time of the m-th event.
+        let m = (0.875 * rate) as u64;
+        let x = gamma(rng, m as f64, 1.0);
+        if x < rate {
+            k += m;
+            rate -= x;
+        } else {
+            return k + binomial(rng, m - 1, rate / x);
+        }
+    }
+
+    let limit = exp(-rate);
+    let mut product = rng.next_open_f64();
+    while product > limit {
+        k += 1;
+        product *= rng.next_open_f64();
+    }
+    k
+}
+
+/// Returns a sample from the geometric distribution, the number of trials up to and including
+/// the first success.
+///
+/// # Panics
+///
+/// `p` must be in `(0, 1]`, otherwise the code will panic.
+pub fn geometric(rng: &mut dyn Rng, p: f64) -> u64 {
+    assert!(
+        p > 0.0 && p <= 1.0,
+        "ERROR - geometric: The probability must be in (0, 1]."
+    );
+
+    if p == 1.0 {
+        return 1;
+    }
+    (ln(rng.next_open_f64()) / ln_1p(-p)).ceil().max(1.0) as u64
+}
+
+/// Returns a sample from the negative binomial distribution, the number of failures before the
+/// `r`th success.
+///
+/// Draws a Poisson variate with a gamma distributed mean.
+///
+/// # Panics
+///
+/// `r` must be positive and `p` must be in `(0, 1]`, otherwise the code will panic.
+pub fn negative_binomial(rng: &mut dyn Rng, r: f64, p: f64) -> u64 {
+    assert!(
+        r > 0.0 && p > 0.0 && p <= 1.0,
+        "ERROR - negative_binomial: r must be positive and the probability in (0, 1]."
+    );
+
+    if p == 1.0 {
+        return 0;
+    }
+    let rate = gamma(rng, r, (1.0 - p) / p);
+    poisson(rng, rate)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::random::pcg::Pcg64;
+    use crate::random::xoshiro::Xoshiro256PlusPlus;
+
+    /// Returns the mean and the variance of the samples.
+    fn moments(samples: &[f64]) -> (f64, f64) {
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let var = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (mean, var)
+    }
+
+    /// Asserts that the samples have the given mean and variance, within 5 standard errors.
+    fn assert_moments(samples: &[f64], mean: f64, var: f64) {
+        let n = samples.len() as f64;
+        let (sample_mean, sample_var) = moments(samples);
+
+        assert!(
+            (sample_mean - mean).abs() < 5.0 * (var / n).sqrt(),
+            "mean {} instead of {}",
+            sample_mean,
+            mean
+        );
+        assert!(
+            (sample_var - var).abs() < 0.1 * var,
+            "variance {} instead of {}",
+            sample_var,
+            var
+        );
+    }
+
+    fn draw(n: usize, mut sampler: impl FnMut(&mut dyn Rng) -> f64) -> Vec<f64> {
+        let mut rng = Pcg64::seed_from_u64(2022);
+        (0..n).map(|_| sampler(&mut rng)).collect()
+    }
+
+    #[test]
+    fn test_ln_and_exp() {
+        for &x in &[1e-310, 1e-20, 0.3, 0.999, 1.0, 1.5, 2.0, 1e10, 1e300] {
+            assert!((ln(x) - x.ln()).abs() <= 2.0 * f64::EPSILON * x.ln().abs());
+        }
+        for &x in &[-745.0, -20.5, -1.0, -0.2, 1e-10, 0.4, 1.0, 3.3, 700.0] {
+            assert!((exp(x) - x.exp()).abs() <= 2.0 * f64::EPSILON * x.exp());
+        }
+
+        assert_eq!(0.0, ln(1.0));
+        assert_eq!(f64::NEG_INFINITY, ln(0.0));
+        assert!(ln(-1.0).is_nan());
+        assert_eq!(1.0, exp(0.0));
+        assert_eq!(f64::INFINITY, exp(710.0));
+        assert_eq!(0.0, exp(-746.0));
+        assert!((ln_1p(-1e-20) + 1e-20).abs() < 1e-35);
+    }
+
+    #[test]
+    fn test_bit_identical_samples() {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(12345);
+        let samples = [
+            standard_normal(&mut rng),
+            gamma(&mut rng, 0.5, 1.0),
+            beta(&mut rng, 2.0, 3.0),
+            poisson(&mut rng, 100.0) as f64,
+        ];
+
+        assert_eq!(
+            [
+                0.25313732119355664,
+                0.003770068851827466,
+                0.18525646647433439,
+                108.0
+            ],
+            samples
+        );
+    }
+
+    #[test]
+    fn test_continuous_samplers() {
+        let n = 20_000;
+
+        assert_moments(&draw(n, |rng| uniform(rng, -1.0, 3.0)), 1.0, 16.0 / 12.0);
+        assert_moments(&draw(n, |rng| normal(rng, 2.0, 3.0)), 2.0, 9.0);
+        assert_moments(&draw(n, |rng| exponential(rng, 2.0)), 0.5, 0.25);
+        assert_moments(&draw(n, |rng| gamma(rng, 0.3, 2.0)), 0.6, 1.2);
+        assert_moments(&draw(n, |rng| gamma(rng, 5.0, 0.5)), 2.5, 1.25);
+        assert_moments(&draw(n, |rng| beta(rng, 2.0, 5.0)), 2.0 / 7.0, 10.0 / 392.0);
+        assert_moments(&draw(n, |rng| chi_squared(rng, 4.0)), 4.0, 8.0);
+        assert_moments(&draw(n, |rng| student_t(rng, 10.0)), 0.0, 1.25);
+        assert_moments(
+            &draw(n, |rng| fisher_f(rng, 5.0, 20.0)),
+            20.0 / 18.0,
+            0.70988,
+        );
+    }
+
+    #[test]
+    fn test_discrete_samplers() {
+        let n = 20_000;
+
+        assert_moments(&draw(n, |rng| binomial(rng, 20, 0.3) as f64), 6.0, 4.2);
+        assert_moments(
+            &draw(n, |rng| binomial(rng, 100_000, 0.4) as f64),
+            40_000.0,
+            24_000.0,
+        );
+        assert_moments(&draw(n, |rng| poisson(rng, 3.5) as f64), 3.5, 3.5);
+        assert_moments(&draw(n, |rng| poisson(rng, 1000.0) as f64), 1000.0, 1000.0);
+        assert_moments(&draw(n, |rng| geometric(rng, 0.25) as f64), 4.0, 12.0);
+        assert_moments(
+            &draw(n, |rng| negative_binomial(rng, 3.0, 0.4) as f64),
+            4.5,
+            11.25,
+        );
+
+        let mut rng = Pcg64::seed_from_u64(1);
+        assert_eq!(0, binomial(&mut rng, 1000, 0.0));
+        assert_eq!(1000, binomial(&mut rng, 1000, 1.0));
+        assert_eq!(0, poisson(&mut rng, 0.0));
+        assert_eq!(1, geometric(&mut rng, 1.0));
+    }
+}
@@ -0,0 +1,163 @@
+//! Xoshiro256++ - A xor/shift/rotate generator
+//!
+//! Xoshiro256++ by D. Blackman and S. Vigna has 256 bits of state and a period of 2^256 - 1. It
+//! is very fast and supports jumping ahead by 2^128 and 2^192 steps to split a sequence into
+//! non-overlapping streams. The outputs match the reference C implementation.
+
+use crate::random::{splitmix64, Rng};
+
+/// The polynomial for jumping ahead by 2^128 steps.
+const JUMP: [u64; 4] = [
+    0x180e_c6d3_3cfd_0aba,
+    0xd5a6_1266_f0c9_392c,
+    0xa958_2618_e03f_c9aa,
+    0x39ab_dc45_29b1_661c,
+];
+
+/// The polynomial for jumping ahead by 2^192 steps.
+const LONG_JUMP: [u64; 4] = [
+    0x76e1_5d3e_fefd_cbbf,
+    0xc500_4e44_1c52_2fb3,
+    0x7771_0069_854e_e241,
+    0x3910_9bb0_2acb_e635,
+];
+
+/// The xoshiro256++ random number generator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Xoshiro256PlusPlus {
+    state: [u64; 4],
+}
+
+impl Xoshiro256PlusPlus {
+    /// Returns a generator with the given state.
+    ///
+    /// # Panics
+    ///
+    /// The state must not be all zeros, otherwise the code will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moonalloy::random::{xoshiro::Xoshiro256PlusPlus, Rng};
+    /// let mut rng = Xoshiro256PlusPlus::new([1, 2, 3, 4]);
+    ///
+    /// assert_eq!(41_943_041, rng.next_u64());
+    /// ```
+    pub fn new(state: [u64; 4]) -> Xoshiro256PlusPlus {
+        assert!(
+            state.iter().any(|&word| word != 0),
+            "ERROR - Xoshiro256PlusPlus: The state must not be all zeros."
+        );
+        Xoshiro256PlusPlus { state }
+    }
+
+    /// Returns a generator seeded from a single 64-bit integer.
+    ///
+    /// The seed is expanded into the state with SplitMix64, as recommended by the authors.
+    pub fn seed_from_u64(seed: u64) -> Xoshiro256PlusPlus {
+        let mut sm = seed;
+        let state = [
+            splitmix64(&mut sm),
+            splitmix64(&mut sm),
+            splitmix64(&mut sm),
+            splitmix64(&mut sm),
+        ];
+        Xoshiro256PlusPlus::new(state)
+    }
+
+    /// Advances the generator by 2^128 steps.
+    ///
+    /// Calling `jump` repeatedly on clones of a generator gives up to 2^128 non-overlapping
+    /// streams of 2^128 numbers each, e.g. one for every thread of a simulation.
+    pub fn jump(&mut self) {
+        self.jump_with(&JUMP);
+    }
+
+    /// Advances the generator by 2^192 steps.
+    ///
+    /// This splits the sequence into 2^64 blocks, each of which can be divided further with
+    /// `jump`.
+    pub fn long_jump(&mut self) {
+        self.jump_with(&LONG_JUMP);
+    }
+
+    fn jump_with(&mut self, polynomial: &[u64; 4]) {
+        let mut acc = [0; 4];
+        for word in polynomial {
+            for bit in 0..64 {
+                if word & (1 << bit) != 0 {
+                    for (a, s) in acc.iter_mut().zip(self.state.iter()) {
+                        *a ^= s;
+                    }
+                }
+                self.next_u64();
+            }
+        }
+        self.state = acc;
+    }
+}
+
+impl Rng for Xoshiro256PlusPlus {
+    fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[0].wrapping_add(s[3]).rotate_left(23).wrapping_add(s[0]);
+        let t = s[1] << 17;
+
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_reference_outputs() {
+        let mut rng = Xoshiro256PlusPlus::new([1, 2, 3, 4]);
+        let outputs: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+
+        assert_eq!(
+            vec![41943041, 58720359, 3588806011781223, 3591011842654386],
+            outputs
+        );
+    }
+
+    #[test]
+    fn test_seed_from_u64() {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(42);
+        let outputs: Vec<u64> = (0..3).map(|_| rng.next_u64()).collect();
+
+        assert_eq!(
+            vec![
+                15021278609987233951,
+                5881210131331364753,
+                18149643915985481100
+            ],
+            outputs
+        );
+    }
+
+    #[test]
+    fn test_jumps() {
+        let mut rng = Xoshiro256PlusPlus::new([1, 2, 3, 4]);
+        rng.jump();
+        assert_eq!(17043750140134683703, rng.next_u64());
+
+        let mut rng = Xoshiro256PlusPlus::new([1, 2, 3, 4]);
+        rng.long_jump();
+        assert_eq!(13097851138432240629, rng.next_u64());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_state() {
+        Xoshiro256PlusPlus::new([0; 4]);
+    }
+}
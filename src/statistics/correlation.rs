@@ -0,0 +1,377 @@
+//! Correlation - Covariance and correlation of several variables
+//!
+//! This module computes covariance and correlation matrices of data where the rows of a Matrix
+//! are observations and the columns are variables, and the same measures for a pair of Arrays.
+//! Besides Pearson's linear correlation it supports the rank correlations by Spearman and
+//! Kendall, both of which handle tied observations.
+
+use crate::linalg::array::Array;
+use crate::linalg::matrix::Matrix;
+use crate::linalg::summation;
+
+/// A measure of correlation between two variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CorrelationMethod {
+    /// Pearson's product-moment correlation, which measures linear dependence.
+    #[default]
+    Pearson,
+    /// Spearman's rank correlation: Pearson's correlation of the ranks.
+    Spearman,
+    /// Kendall's tau-b, based on concordant and discordant pairs and corrected for ties.
+    Kendall,
+}
+
+/// Returns the ranks of the elements, starting at 1. Tied elements get the average of the ranks
+/// they span.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::statistics::correlation::rank;
+///
+/// let ranks = rank(&Array::from(&[10.0, 30.0, 20.0, 20.0]));
+///
+/// assert_eq!(Array::from(&[1.0, 4.0, 2.5, 2.5]), ranks);
+/// ```
+pub fn rank(xs: &Array) -> Array {
+    let mut order: Vec<usize> = (0..xs.len()).collect();
+    order.sort_by(|&i, &j| xs[i].total_cmp(&xs[j]));
+
+    let mut ranks = vec![0.0; xs.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && xs[order[end]] == xs[order[start]] {
+            end += 1;
+        }
+
+        // The elements at start..end share the ranks start + 1 to end.
+        let average = (start + end + 1) as f64 / 2.0;
+        for &i in &order[start..end] {
+            ranks[i] = average;
+        }
+        start = end;
+    }
+
+    Array::from_vec(ranks)
+}
+
+/// Returns the sample covariance of two Arrays, with `n - 1` in the denominator.
+///
+/// # Panics
+///
+/// The Arrays must have the same length, otherwise the code will panic.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::statistics::correlation::cov;
+///
+/// let xs = Array::from(&[1.0, 2.0, 3.0]);
+/// let ys = Array::from(&[2.0, 4.0, 6.0]);
+///
+/// assert_eq!(2.0, cov(&xs, &ys));
+/// ```
+pub fn cov(xs: &Array, ys: &Array) -> f64 {
+    assert_eq!(
+        xs.len(),
+        ys.len(),
+        "ERROR - cov: The Arrays must have the same length."
+    );
+    let (mx, my) = (xs.average(), ys.average());
+    let sum = summation::neumaier(xs.iter().zip(ys.iter()).map(|(x, y)| (x - mx) * (y - my)));
+    sum / (xs.len() as f64 - 1.0)
+}
+
+/// Returns the correlation coefficient of two Arrays.
+///
+/// The result is NaN if one of the Arrays is constant.
+///
+/// # Arguments
+///
+/// * `xs` - the observations of the first variable.
+/// * `ys` - the observations of the second variable, in the same order.
+/// * `method` - the measure of correlation.
+///
+/// # Panics
+///
+/// The Arrays must have the same length, otherwise the code will panic.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::statistics::correlation::{corr, CorrelationMethod};
+///
+/// let xs = Array::from(&[1.0, 2.0, 3.0, 4.0]);
+/// let ys = Array::from(&[1.0, 4.0, 9.0, 16.0]);
+///
+/// assert!(corr(&xs, &ys, CorrelationMethod::Pearson) < 1.0);
+/// assert_eq!(1.0, corr(&xs, &ys, CorrelationMethod::Spearman));
+/// assert_eq!(1.0, corr(&xs, &ys, CorrelationMethod::Kendall));
+/// ```
+pub fn corr(xs: &Array, ys: &Array, method: CorrelationMethod) -> f64 {
+    assert_eq!(
+        xs.len(),
+        ys.len(),
+        "ERROR - corr: The Arrays must have the same length."
+    );
+    match method {
+        CorrelationMethod::Pearson => pearson(xs, ys),
+        CorrelationMethod::Spearman => pearson(&rank(xs), &rank(ys)),
+        CorrelationMethod::Kendall => kendall(xs, ys),
+    }
+}
+
+/// Returns Pearson's correlation coefficient, clamped to [-1, 1] against rounding errors.
+fn pearson(xs: &Array, ys: &Array) -> f64 {
+    let (mx, my) = (xs.average(), ys.average());
+    let dx: Vec<f64> = xs.iter().map(|x| x - mx).collect();
+    let dy: Vec<f64> = ys.iter().map(|y| y - my).collect();
+    let sxy = summation::dot(&dx, &dy);
+    let sxx = summation::dot(&dx, &dx);
+    let syy = summation::dot(&dy, &dy);
+    (sxy / (sxx * syy).sqrt()).clamp(-1.0, 1.0)
+}
+
+/// Returns the number of pairs of equal elements in a sorted sequence.
+fn tied_pairs<T: PartialEq>(sorted: &[T]) -> u64 {
+    let mut pairs = 0;
+    let mut run = 1;
+    for i in 1..=sorted.len() {
+        if i < sorted.len() && sorted[i] == sorted[i - 1] {
+            run += 1;
+        } else {
+            pairs += run * (run - 1) / 2;
+            run = 1;
+        }
+    }
+    pairs
+}
+
+/// Sorts `ys` with a merge sort and returns the number of swaps of adjacent elements that an
+/// insertion sort would have needed, i.e. the number of strictly decreasing pairs.
+fn count_inversions(ys: &mut [f64], buffer: &mut [f64]) -> u64 {
+    let n = ys.len();
+    if n < 2 {
+        return 0;
+    }
+
+    let mid = n / 2;
+    let mut swaps =
+        count_inversions(&mut ys[..mid], buffer) + count_inversions(&mut ys[mid..], buffer);
+
+    let (mut i, mut j) = (0, mid);
+    for slot in buffer[..n].iter_mut() {
+        if j == n || (i < mid && ys[i] <= ys[j]) {
+            *slot = ys[i];
+            i += 1;
+        } else {
+            *slot = ys[j];
+            // ys[j] jumps over the remaining elements of the left half.
+            swaps += (mid - i) as u64;
+            j += 1;
+        }
+    }
+    ys.copy_from_slice(&buffer[..n]);
+    swaps
+}
+
+/// Returns Kendall's tau-b in O(n log n) time with the algorithm by Knight.
+fn kendall(xs: &Array, ys: &Array) -> f64 {
+    let mut pairs: Vec<(f64, f64)> = xs.iter().copied().zip(ys.iter().copied()).collect();
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+
+    let n = pairs.len() as u64;
+    let total = n * n.saturating_sub(1) / 2;
+    let x_ties = tied_pairs(&pairs.iter().map(|p| p.0).collect::<Vec<f64>>());
+    let joint_ties = tied_pairs(&pairs);
+
+    let mut sorted_ys: Vec<f64> = pairs.iter().map(|p| p.1).collect();
+    let mut buffer = vec![0.0; sorted_ys.len()];
+    let discordant = count_inversions(&mut sorted_ys, &mut buffer);
+    let y_ties = tied_pairs(&sorted_ys);
+
+    // Pairs tied in x are not discordant, as they are sorted by y.
+    let concordant_minus_discordant =
+        (total + joint_ties) as f64 - (x_ties + y_ties) as f64 - 2.0 * discordant as f64;
+    let denominator = (((total - x_ties) as f64) * ((total - y_ties) as f64)).sqrt();
+    (concordant_minus_discordant / denominator).clamp(-1.0, 1.0)
+}
+
+/// Returns the columns of a matrix.
+fn columns(mat: &Matrix) -> Vec<Array> {
+    let (_, cols) = mat.dimensions();
+    (0..cols).map(|j| mat.column(j)).collect()
+}
+
+/// Returns a symmetric matrix with the elements `f(columns[i], columns[j])`.
+fn pairwise(columns: &[Array], f: impl Fn(&Array, &Array) -> f64) -> Matrix {
+    let k = columns.len();
+    let mut result = vec![vec![0.0; k]; k];
+    for i in 0..k {
+        for j in i..k {
+            let val = f(&columns[i], &columns[j]);
+            result[i][j] = val;
+            result[j][i] = val;
+        }
+    }
+    Matrix::from_rows(result.into_iter().map(Array::from_vec).collect())
+}
+
+/// Returns the sample covariance matrix of the columns of a matrix.
+///
+/// The rows of `mat` are observations and the columns are variables, so the element (i, j) of
+/// the result is the covariance of the columns i and j.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::statistics::correlation::covariance;
+///
+/// let mat = Matrix::new(&[
+///     Array::from(&[1.0, 2.0]),
+///     Array::from(&[2.0, 1.0]),
+///     Array::from(&[3.0, 0.0]),
+/// ]);
+///
+/// assert_eq!(
+///     Matrix::new(&[Array::from(&[1.0, -1.0]), Array::from(&[-1.0, 1.0])]),
+///     covariance(&mat)
+/// );
+/// ```
+pub fn covariance(mat: &Matrix) -> Matrix {
+    pairwise(&columns(mat), cov)
+}
+
+/// Returns the correlation matrix of the columns of a matrix.
+///
+/// The rows of `mat` are observations and the columns are variables, so the element (i, j) of
+/// the result is the correlation of the columns i and j. The diagonal is 1, except for constant
+/// columns where the correlation is NaN.
+pub fn correlation(mat: &Matrix, method: CorrelationMethod) -> Matrix {
+    let columns = match method {
+        CorrelationMethod::Spearman => columns(mat).iter().map(rank).collect(),
+        _ => columns(mat),
+    };
+
+    pairwise(&columns, |xs, ys| match method {
+        CorrelationMethod::Kendall => kendall(xs, ys),
+        _ => pearson(xs, ys),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_close(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < 1e-14,
+            "expected {}, found {}",
+            expected,
+            actual
+        );
+    }
+
+    fn data() -> (Array, Array) {
+        (
+            Array::from(&[1.0, 2.0, 2.0, 3.0, 5.0, 5.0, 5.0, 8.0]),
+            Array::from(&[2.0, 1.0, 4.0, 4.0, 3.0, 6.0, 6.0, 7.0]),
+        )
+    }
+
+    #[test]
+    fn test_rank() {
+        let (xs, _) = data();
+
+        assert_eq!(
+            Array::from(&[1.0, 2.5, 2.5, 4.0, 6.0, 6.0, 6.0, 8.0]),
+            rank(&xs)
+        );
+        assert_eq!(Array::new(), rank(&Array::new()));
+    }
+
+    #[test]
+    fn test_pairwise() {
+        let (xs, ys) = data();
+
+        assert_close(3.875, cov(&xs, &ys));
+        assert_close(
+            0.803_896_706_191_122_9,
+            corr(&xs, &ys, CorrelationMethod::Pearson),
+        );
+        assert_close(
+            0.788_956_854_059_301_6,
+            corr(&xs, &ys, CorrelationMethod::Spearman),
+        );
+        assert_close(
+            0.680_544_653_671_620_3,
+            corr(&xs, &ys, CorrelationMethod::Kendall),
+        );
+    }
+
+    #[test]
+    fn test_kendall_matches_definition() {
+        let xs = Array::from(&[3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0, 5.0, 3.0, 5.0]);
+        let ys = Array::from(&[2.0, 7.0, 1.0, 8.0, 2.0, 8.0, 1.0, 8.0, 2.0, 8.0, 4.0]);
+
+        // Count the pairs directly.
+        let (mut cd, mut tx, mut ty, mut n0) = (0.0, 0.0, 0.0, 0.0);
+        for i in 0..xs.len() {
+            for j in 0..i {
+                let (dx, dy) = (xs[i] - xs[j], ys[i] - ys[j]);
+                cd += (dx * dy).signum() * (dx != 0.0 && dy != 0.0) as i32 as f64;
+                tx += (dx == 0.0) as i32 as f64;
+                ty += (dy == 0.0) as i32 as f64;
+                n0 += 1.0;
+            }
+        }
+        let expected = cd / ((n0 - tx) * (n0 - ty)).sqrt();
+
+        assert_close(expected, corr(&xs, &ys, CorrelationMethod::Kendall));
+        assert_close(-1.0, corr(&xs, &-xs.clone(), CorrelationMethod::Kendall));
+    }
+
+    #[test]
+    fn test_constant_variable() {
+        let xs = Array::from(&[1.0, 2.0, 3.0]);
+        let ys = Array::from(&[4.0, 4.0, 4.0]);
+
+        assert!(corr(&xs, &ys, CorrelationMethod::Pearson).is_nan());
+        assert!(corr(&xs, &ys, CorrelationMethod::Kendall).is_nan());
+    }
+
+    #[test]
+    fn test_matrices() {
+        let (xs, ys) = data();
+        let mat = Matrix::from_rows(
+            xs.iter()
+                .zip(ys.iter())
+                .map(|(&x, &y)| Array::from(&[x, y, -x]))
+                .collect(),
+        );
+
+        let c = covariance(&mat);
+        assert_eq!((3, 3), c.dimensions());
+        assert_close(xs.var(1), c[0][0]);
+        assert_close(cov(&xs, &ys), c[1][0]);
+        assert_close(-xs.var(1), c[2][0]);
+
+        for &method in &[
+            CorrelationMethod::Pearson,
+            CorrelationMethod::Spearman,
+            CorrelationMethod::Kendall,
+        ] {
+            let r = correlation(&mat, method);
+            assert_close(1.0, r[1][1]);
+            assert_close(corr(&xs, &ys, method), r[0][1]);
+            assert_eq!(r[0][1], r[1][0]);
+            assert_close(-1.0, r[0][2]);
+        }
+    }
+}
@@ -0,0 +1,544 @@
+//! Descriptive - Summary statistics of samples
+//!
+//! This module adds the usual descriptive statistics to Array: the variance and standard
+//! deviation, quantiles and the median, the mode, the skewness and kurtosis, and the extreme
+//! values and their positions. The functions of the same names compute the statistics for every
+//! column of a Matrix, where the rows are observations and the columns are variables.
+//!
+//! Like `Array::average`, the statistics are NaN when they are undefined, e.g. for an empty
+//! Array, and when the Array contains NaN.
+
+use crate::linalg::array::Array;
+use crate::linalg::matrix::Matrix;
+use crate::linalg::summation;
+
+/// The method used to interpolate between the observations when computing a quantile.
+///
+/// The continuous methods correspond to the sample quantile definitions 5 to 9 by Hyndman and
+/// Fan, and all methods match those of the same name in NumPy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuantileMethod {
+    /// Linear interpolation between the closest observations (type 7). This is the default.
+    #[default]
+    Linear,
+    /// The closest observation below the quantile.
+    Lower,
+    /// The closest observation above the quantile.
+    Higher,
+    /// The closest observation, with ties going to the observation with an even index.
+    Nearest,
+    /// The average of `Lower` and `Higher`.
+    Midpoint,
+    /// Piecewise linear with the observations at the midpoints of their steps (type 5).
+    Hazen,
+    /// Linear interpolation with `p(k) = k / (n + 1)` (type 6).
+    Weibull,
+    /// Approximately median-unbiased regardless of the distribution (type 8).
+    MedianUnbiased,
+    /// Approximately unbiased for normally distributed data (type 9).
+    NormalUnbiased,
+}
+
+/// A summary of a sample, as returned by `describe`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    /// The number of observations.
+    pub count: usize,
+    /// The mean.
+    pub mean: f64,
+    /// The sample standard deviation, with `ddof = 1`.
+    pub std: f64,
+    /// The smallest observation.
+    pub min: f64,
+    /// The first quartile.
+    pub q1: f64,
+    /// The median.
+    pub median: f64,
+    /// The third quartile.
+    pub q3: f64,
+    /// The largest observation.
+    pub max: f64,
+}
+
+/// Returns the interpolated quantile `q` of the sorted values.
+fn sorted_quantile(sorted: &[f64], q: f64, method: QuantileMethod) -> f64 {
+    let n = sorted.len();
+    if n == 0 {
+        return f64::NAN;
+    }
+
+    let last = (n - 1) as f64;
+    let (alpha, beta) = match method {
+        QuantileMethod::Linear => (1.0, 1.0),
+        QuantileMethod::Hazen => (0.5, 0.5),
+        QuantileMethod::Weibull => (0.0, 0.0),
+        QuantileMethod::MedianUnbiased => (1.0 / 3.0, 1.0 / 3.0),
+        QuantileMethod::NormalUnbiased => (3.0 / 8.0, 3.0 / 8.0),
+        QuantileMethod::Lower => return sorted[(last * q).floor() as usize],
+        QuantileMethod::Higher => return sorted[(last * q).ceil() as usize],
+        QuantileMethod::Nearest => return sorted[(last * q).round_ties_even() as usize],
+        QuantileMethod::Midpoint => {
+            let pos = last * q;
+            return 0.5 * (sorted[pos.floor() as usize] + sorted[pos.ceil() as usize]);
+        }
+    };
+
+    let pos = (n as f64 * q + alpha + q * (1.0 - alpha - beta) - 1.0).clamp(0.0, last);
+    let lower = pos.floor() as usize;
+    let upper = pos.ceil() as usize;
+    let frac = pos - lower as f64;
+    if frac == 0.0 {
+        sorted[lower]
+    } else {
+        sorted[lower] + frac * (sorted[upper] - sorted[lower])
+    }
+}
+
+impl Array {
+    /// Returns the elements in increasing order, or `None` if any of them is NaN.
+    fn sorted(&self) -> Option<Vec<f64>> {
+        if self.iter().any(|x| x.is_nan()) {
+            return None;
+        }
+        let mut sorted = self.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        Some(sorted)
+    }
+
+    /// Returns the `k`th central moment of the elements.
+    fn central_moment(&self, k: i32) -> f64 {
+        let mean = self.average();
+        summation::neumaier(self.iter().map(|x| (x - mean).powi(k))) / self.len() as f64
+    }
+
+    /// Returns the variance of the elements.
+    ///
+    /// # Arguments
+    ///
+    /// * `ddof` - the delta degrees of freedom: the sum of squared deviations is divided by
+    ///   `len - ddof`. Use 0 for the population variance and 1 for the unbiased sample variance.
+    ///
+    /// Returns NaN if the Array has no more than `ddof` elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moonalloy::linalg::array::Array;
+    /// let array = Array::from(&[1.0, 2.0, 3.0, 4.0]);
+    ///
+    /// assert_eq!(1.25, array.var(0));
+    /// assert_eq!(5.0 / 3.0, array.var(1));
+    /// ```
+    pub fn var(&self, ddof: usize) -> f64 {
+        if self.len() <= ddof {
+            return f64::NAN;
+        }
+        let mean = self.average();
+        let ss = summation::neumaier(self.iter().map(|x| (x - mean) * (x - mean)));
+        ss / (self.len() - ddof) as f64
+    }
+
+    /// Returns the standard deviation of the elements, the square root of `var(ddof)`.
+    pub fn std(&self, ddof: usize) -> f64 {
+        self.var(ddof).sqrt()
+    }
+
+    /// Returns the median of the elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moonalloy::linalg::array::Array;
+    /// let array = Array::from(&[3.0, 1.0, 4.0, 1.0]);
+    ///
+    /// assert_eq!(2.0, array.median());
+    /// ```
+    pub fn median(&self) -> f64 {
+        self.quantile(0.5, QuantileMethod::Linear)
+    }
+
+    /// Returns the quantile `q` of the elements.
+    ///
+    /// # Arguments
+    ///
+    /// * `q` - the probability of the quantile, between 0 and 1.
+    /// * `method` - how to interpolate between the observations.
+    ///
+    /// # Panics
+    ///
+    /// `q` must be between 0 and 1, otherwise the code will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moonalloy::linalg::array::Array;
+    /// use moonalloy::statistics::descriptive::QuantileMethod;
+    /// let array = Array::from(&[1.0, 2.0, 3.0, 4.0]);
+    ///
+    /// assert_eq!(1.75, array.quantile(0.25, QuantileMethod::Linear));
+    /// assert_eq!(1.0, array.quantile(0.25, QuantileMethod::Lower));
+    /// ```
+    pub fn quantile(&self, q: f64, method: QuantileMethod) -> f64 {
+        assert!(
+            (0.0..=1.0).contains(&q),
+            "ERROR - quantile: q must be between 0 and 1."
+        );
+        match self.sorted() {
+            Some(sorted) => sorted_quantile(&sorted, q, method),
+            None => f64::NAN,
+        }
+    }
+
+    /// Returns the interquartile range, the difference between the third and first quartile.
+    pub fn iqr(&self) -> f64 {
+        match self.sorted() {
+            Some(sorted) => {
+                sorted_quantile(&sorted, 0.75, QuantileMethod::Linear)
+                    - sorted_quantile(&sorted, 0.25, QuantileMethod::Linear)
+            }
+            None => f64::NAN,
+        }
+    }
+
+    /// Returns the most frequent element. If several elements are equally frequent, the
+    /// smallest of them is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moonalloy::linalg::array::Array;
+    /// let array = Array::from(&[3.0, 1.0, 3.0, 1.0, 2.0]);
+    ///
+    /// assert_eq!(1.0, array.mode());
+    /// ```
+    pub fn mode(&self) -> f64 {
+        let sorted = match self.sorted() {
+            Some(sorted) if !sorted.is_empty() => sorted,
+            _ => return f64::NAN,
+        };
+
+        let (mut mode, mut best) = (sorted[0], 0);
+        let mut start = 0;
+        for i in 1..=sorted.len() {
+            if i == sorted.len() || sorted[i] != sorted[start] {
+                if i - start > best {
+                    mode = sorted[start];
+                    best = i - start;
+                }
+                start = i;
+            }
+        }
+        mode
+    }
+
+    /// Returns the skewness of the elements, `m3 / m2^(3/2)` where `mk` is the `k`th central
+    /// moment.
+    ///
+    /// This is the biased (population) estimator, which is 0 for symmetric samples.
+    pub fn skewness(&self) -> f64 {
+        self.central_moment(3) / self.central_moment(2).powf(1.5)
+    }
+
+    /// Returns the excess kurtosis of the elements, `m4 / m2^2 - 3` where `mk` is the `k`th
+    /// central moment.
+    ///
+    /// This is the biased (population) estimator. It is 0 for a normal distribution.
+    pub fn kurtosis(&self) -> f64 {
+        let m2 = self.central_moment(2);
+        self.central_moment(4) / (m2 * m2) - 3.0
+    }
+
+    /// Returns the smallest element.
+    pub fn min(&self) -> f64 {
+        self.argmin().map_or(f64::NAN, |i| self[i])
+    }
+
+    /// Returns the largest element.
+    pub fn max(&self) -> f64 {
+        self.argmax().map_or(f64::NAN, |i| self[i])
+    }
+
+    /// Returns the index of the smallest element, or `None` if the Array is empty.
+    ///
+    /// The index of the first NaN is returned if the Array contains NaN, and the first of
+    /// several equal elements otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moonalloy::linalg::array::Array;
+    /// let array = Array::from(&[3.0, 1.0, 4.0, 1.0]);
+    ///
+    /// assert_eq!(Some(1), array.argmin());
+    /// assert_eq!(1.0, array.min());
+    /// ```
+    pub fn argmin(&self) -> Option<usize> {
+        self.position_of(|x, best| x < best)
+    }
+
+    /// Returns the index of the largest element, or `None` if the Array is empty.
+    ///
+    /// The index of the first NaN is returned if the Array contains NaN, and the first of
+    /// several equal elements otherwise.
+    pub fn argmax(&self) -> Option<usize> {
+        self.position_of(|x, best| x > best)
+    }
+
+    /// Returns the index of the element that is better than all before it, or of the first NaN.
+    fn position_of(&self, better: impl Fn(f64, f64) -> bool) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+        if let Some(i) = self.iter().position(|x| x.is_nan()) {
+            return Some(i);
+        }
+
+        let mut best = 0;
+        for i in 1..self.len() {
+            if better(self[i], self[best]) {
+                best = i;
+            }
+        }
+        Some(best)
+    }
+
+    /// Returns a summary of the elements: the count, mean, standard deviation, minimum,
+    /// quartiles and maximum.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moonalloy::linalg::array::Array;
+    /// let summary = Array::from(&[1.0, 2.0, 3.0, 4.0, 5.0]).describe();
+    ///
+    /// assert_eq!(5, summary.count);
+    /// assert_eq!(3.0, summary.median);
+    /// assert_eq!(4.0, summary.q3);
+    /// ```
+    pub fn describe(&self) -> Summary {
+        let sorted = self.sorted();
+        let quantile = |q| {
+            sorted.as_deref().map_or(f64::NAN, |sorted| {
+                sorted_quantile(sorted, q, QuantileMethod::Linear)
+            })
+        };
+
+        Summary {
+            count: self.len(),
+            mean: self.average(),
+            std: self.std(1),
+            min: self.min(),
+            q1: quantile(0.25),
+            median: quantile(0.5),
+            q3: quantile(0.75),
+            max: self.max(),
+        }
+    }
+}
+
+/// Applies `f` to every column of a matrix.
+fn per_column<T>(mat: &Matrix, f: impl Fn(&Array) -> T) -> Vec<T> {
+    let (_, cols) = mat.dimensions();
+    (0..cols).map(|j| f(&mat.column(j))).collect()
+}
+
+/// Applies `f` to every column of a matrix and collects the results in an Array.
+fn per_column_array(mat: &Matrix, f: impl Fn(&Array) -> f64) -> Array {
+    Array::from_vec(per_column(mat, f))
+}
+
+/// Returns the mean of every column of a matrix.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::linalg::matrix::Matrix;
+/// use moonalloy::statistics::descriptive;
+///
+/// let mat = Matrix::new(&[Array::from(&[1.0, 10.0]), Array::from(&[3.0, 20.0])]);
+///
+/// assert_eq!(Array::from(&[2.0, 15.0]), descriptive::mean(&mat));
+/// ```
+pub fn mean(mat: &Matrix) -> Array {
+    per_column_array(mat, Array::average)
+}
+
+/// Returns the variance of every column of a matrix. See `Array::var`.
+pub fn var(mat: &Matrix, ddof: usize) -> Array {
+    per_column_array(mat, |column| column.var(ddof))
+}
+
+/// Returns the standard deviation of every column of a matrix. See `Array::std`.
+pub fn std(mat: &Matrix, ddof: usize) -> Array {
+    per_column_array(mat, |column| column.std(ddof))
+}
+
+/// Returns the median of every column of a matrix.
+pub fn median(mat: &Matrix) -> Array {
+    per_column_array(mat, Array::median)
+}
+
+/// Returns the quantile `q` of every column of a matrix. See `Array::quantile`.
+///
+/// # Panics
+///
+/// `q` must be between 0 and 1, otherwise the code will panic.
+pub fn quantile(mat: &Matrix, q: f64, method: QuantileMethod) -> Array {
+    per_column_array(mat, |column| column.quantile(q, method))
+}
+
+/// Returns the interquartile range of every column of a matrix.
+pub fn iqr(mat: &Matrix) -> Array {
+    per_column_array(mat, Array::iqr)
+}
+
+/// Returns the mode of every column of a matrix. See `Array::mode`.
+pub fn mode(mat: &Matrix) -> Array {
+    per_column_array(mat, Array::mode)
+}
+
+/// Returns the skewness of every column of a matrix. See `Array::skewness`.
+pub fn skewness(mat: &Matrix) -> Array {
+    per_column_array(mat, Array::skewness)
+}
+
+/// Returns the excess kurtosis of every column of a matrix. See `Array::kurtosis`.
+pub fn kurtosis(mat: &Matrix) -> Array {
+    per_column_array(mat, Array::kurtosis)
+}
+
+/// Returns the smallest element of every column of a matrix.
+pub fn min(mat: &Matrix) -> Array {
+    per_column_array(mat, Array::min)
+}
+
+/// Returns the largest element of every column of a matrix.
+pub fn max(mat: &Matrix) -> Array {
+    per_column_array(mat, Array::max)
+}
+
+/// Returns the row index of the smallest element of every column of a matrix.
+pub fn argmin(mat: &Matrix) -> Vec<Option<usize>> {
+    per_column(mat, Array::argmin)
+}
+
+/// Returns the row index of the largest element of every column of a matrix.
+pub fn argmax(mat: &Matrix) -> Vec<Option<usize>> {
+    per_column(mat, Array::argmax)
+}
+
+/// Returns a summary of every column of a matrix. See `Array::describe`.
+pub fn describe(mat: &Matrix) -> Vec<Summary> {
+    per_column(mat, Array::describe)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> Array {
+        Array::from(&[2.0, 8.0, 0.0, 4.0, 1.0, 9.0, 9.0, 0.0])
+    }
+
+    #[test]
+    fn test_var_and_std() {
+        let a = sample();
+
+        assert_eq!(13.859_375, a.var(0));
+        assert_eq!(15.839_285_714_285_714, a.var(1));
+        assert_eq!(a.var(1).sqrt(), a.std(1));
+        assert!(Array::from(&[1.0]).var(1).is_nan());
+    }
+
+    #[test]
+    fn test_quantile_methods() {
+        let a = sample();
+        let expected = [
+            (QuantileMethod::Linear, 0.75),
+            (QuantileMethod::Lower, 0.0),
+            (QuantileMethod::Higher, 1.0),
+            (QuantileMethod::Nearest, 1.0),
+            (QuantileMethod::Midpoint, 0.5),
+            (QuantileMethod::Hazen, 0.5),
+            (QuantileMethod::Weibull, 0.25),
+            (QuantileMethod::MedianUnbiased, 0.416_666_666_666_666_96),
+            (QuantileMethod::NormalUnbiased, 0.437_5),
+        ];
+
+        for &(method, q1) in &expected {
+            assert!(
+                (a.quantile(0.25, method) - q1).abs() < 1e-15,
+                "{:?}: {} instead of {}",
+                method,
+                a.quantile(0.25, method),
+                q1
+            );
+        }
+
+        assert_eq!(0.0, a.quantile(0.0, QuantileMethod::Weibull));
+        assert_eq!(9.0, a.quantile(1.0, QuantileMethod::Hazen));
+        assert_eq!(3.0, a.median());
+        assert_eq!(7.5, a.iqr());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_quantile_out_of_range() {
+        sample().quantile(1.5, QuantileMethod::Linear);
+    }
+
+    #[test]
+    fn test_mode() {
+        assert_eq!(0.0, sample().mode());
+        assert_eq!(7.0, Array::from(&[7.0]).mode());
+        assert!(Array::new().mode().is_nan());
+    }
+
+    #[test]
+    fn test_skewness_and_kurtosis() {
+        let a = sample();
+
+        assert!((a.skewness() - 0.265_055_412_269_857_3).abs() < 1e-14);
+        assert!((a.kurtosis() + 1.666_001_075_283_850_8).abs() < 1e-14);
+        assert_eq!(0.0, Array::from(&[1.0, 2.0, 3.0]).skewness());
+    }
+
+    #[test]
+    fn test_extremes() {
+        let a = sample();
+
+        assert_eq!((0.0, 9.0), (a.min(), a.max()));
+        assert_eq!((Some(2), Some(5)), (a.argmin(), a.argmax()));
+        assert_eq!(None, Array::new().argmin());
+        assert!(Array::from(&[1.0, f64::NAN]).max().is_nan());
+        assert!(Array::from(&[1.0, f64::NAN]).median().is_nan());
+    }
+
+    #[test]
+    fn test_describe() {
+        let summary = sample().describe();
+
+        assert_eq!(8, summary.count);
+        assert_eq!(4.125, summary.mean);
+        assert_eq!((0.75, 3.0, 8.25), (summary.q1, summary.median, summary.q3));
+        assert_eq!((0.0, 9.0), (summary.min, summary.max));
+    }
+
+    #[test]
+    fn test_per_column() {
+        let mat = Matrix::new(&[
+            Array::from(&[1.0, 5.0]),
+            Array::from(&[2.0, 5.0]),
+            Array::from(&[6.0, 2.0]),
+        ]);
+
+        assert_eq!(Array::from(&[3.0, 4.0]), mean(&mat));
+        assert_eq!(Array::from(&[7.0, 3.0]), var(&mat, 1));
+        assert_eq!(Array::from(&[2.0, 5.0]), median(&mat));
+        assert_eq!(Array::from(&[1.0, 5.0]), mode(&mat));
+        assert_eq!(vec![Some(2), Some(0)], argmax(&mat));
+        assert_eq!(Array::from(&[1.0, 2.0]), min(&mat));
+        assert_eq!(2, describe(&mat).len());
+    }
+}
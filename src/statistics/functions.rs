@@ -1,9 +1,64 @@
-///
+//! Functions - Special functions used in probability and statistics
+//!
+//! Besides the combinatorial functions on integers, this module contains real-valued special
+//! functions: the gamma function and its relatives, the beta function, the error function and
+//! the regularized incomplete gamma and beta functions. They are accurate to around 1e-14
+//! relative to the result, or absolute for results close to zero.
+
+use std::f64::consts::PI;
+use std::convert::TryFrom;
+
+/// The relative precision the series and continued fractions are evaluated to.
+const EPSILON: f64 = 1e-16;
+
+/// A number close to the smallest normal float, used to avoid divisions by zero.
+const TINY: f64 = 1e-300;
+
+/// The upper limit on the number of terms of a series or continued fraction.
+const MAX_ITERATIONS: usize = 10_000;
+
+/// The `g` parameter of the Lanczos approximation.
+const LANCZOS_G: f64 = 7.0;
 
+/// The coefficients of the Lanczos approximation for `g = 7` and `n = 9`.
+const LANCZOS: [f64; 9] = [
+    0.999_999_999_999_809_9,
+    676.520_368_121_885_1,
+    -1_259.139_216_722_402_8,
+    771.323_428_777_653_1,
+    -176.615_029_162_140_6,
+    12.507_343_278_686_905,
+    -0.138_571_095_265_720_12,
+    9.984_369_578_019_572e-6,
+    1.505_632_735_149_311_6e-7,
+];
+
+/// Returns the binomial coefficient "`n` choose `k`", the number of ways to choose `k` elements
+/// out of `n`.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::statistics::functions::binomial_coefficient;
+///
+/// assert_eq!(10, binomial_coefficient(5, 2));
+/// assert_eq!(0, binomial_coefficient(2, 5));
+/// ```
 pub fn binomial_coefficient(n: usize, k: usize) -> usize {
-    factorial(n) / (factorial(n) * factorial(n - k))
+    if k > n {
+        return 0;
+    }
+
+    // Multiplying before dividing keeps every intermediate result an integer. The product is
+    // formed in 128 bits since it can exceed the final result by a factor of up to `n`.
+    let k = k.min(n - k);
+    (1..=k).fold(1, |acc, i| {
+        let next = acc as u128 * (n - k + i) as u128 / i as u128;
+        usize::try_from(next).expect("ERROR - binomial_coefficient: result does not fit in usize")
+    })
 }
 
+/// Returns the factorial `n!`.
 pub fn factorial(n: usize) -> usize {
     if n == 0 {
 	1
@@ -12,8 +67,449 @@ pub fn factorial(n: usize) -> usize {
     }
 }
 
-pub fn gamma(n: usize) -> usize {
-    factorial(n - 1)
+/// Returns `sin(pi * x)`, which is exactly zero for integers.
+fn sin_pi(x: f64) -> f64 {
+    if x == x.floor() {
+        return 0.0;
+    }
+    (PI * (x % 2.0)).sin()
+}
+
+/// Returns the Lanczos sum and the shifted argument `t` for `x >= 0.5`.
+fn lanczos(x: f64) -> (f64, f64) {
+    let x = x - 1.0;
+    let sum = LANCZOS
+        .iter()
+        .enumerate()
+        .skip(1)
+        .fold(LANCZOS[0], |sum, (i, c)| sum + c / (x + i as f64));
+
+    (sum, x + LANCZOS_G + 0.5)
+}
+
+/// Returns the gamma function `Γ(x)`, which extends the factorial with `Γ(n) = (n - 1)!`.
+///
+/// Uses the Lanczos approximation and the reflection formula for `x < 0.5`. The result is
+/// infinite at zero and beyond `x ≈ 171.6`, and NaN at the negative integers.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::statistics::functions::gamma;
+///
+/// assert_eq!(24.0, gamma(5.0));
+/// assert!((gamma(0.5) - std::f64::consts::PI.sqrt()).abs() < 1e-14);
+/// ```
+pub fn gamma(x: f64) -> f64 {
+    if x == 0.0 {
+        return 1.0 / x;
+    }
+    if x.is_nan() || (x < 0.0 && x == x.floor()) {
+        return f64::NAN;
+    }
+    if x > 171.7 {
+        return f64::INFINITY;
+    }
+
+    if x == x.floor() && x > 0.0 {
+        return (1..x as usize).map(|i| i as f64).product();
+    }
+
+    if x < 0.5 {
+        return PI / (sin_pi(x) * gamma(1.0 - x));
+    }
+
+    let (sum, t) = lanczos(x);
+    // t^(x - 0.5) is split in two halves so it does not overflow before it is multiplied by e^-t.
+    let half = t.powf(0.5 * (x - 0.5));
+    (2.0 * PI).sqrt() * half * (half * (-t).exp()) * sum
+}
+
+/// Returns the natural logarithm of the absolute value of the gamma function, `ln |Γ(x)|`.
+///
+/// Unlike `gamma` it does not overflow for large `x`. The result is infinite at zero and the
+/// negative integers.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::statistics::functions::lgamma;
+///
+/// assert!((lgamma(100.0) - 359.134_205_369_575_4).abs() < 1e-10);
+/// ```
+pub fn lgamma(x: f64) -> f64 {
+    if x.is_nan() {
+        return f64::NAN;
+    }
+    if x <= 0.0 && x == x.floor() {
+        return f64::INFINITY;
+    }
+    if x.is_infinite() {
+        return f64::INFINITY;
+    }
+
+    if x < 0.5 {
+        return (PI / sin_pi(x).abs()).ln() - lgamma(1.0 - x);
+    }
+
+    let (sum, t) = lanczos(x);
+    0.5 * (2.0 * PI).ln() + (x - 0.5) * t.ln() - t + sum.ln()
+}
+
+/// Returns the digamma function `ψ(x)`, the derivative of `ln Γ(x)`.
+///
+/// The result is NaN at zero and the negative integers.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::statistics::functions::digamma;
+///
+/// // ψ(1) is minus the Euler-Mascheroni constant
+/// assert!((digamma(1.0) + 0.577_215_664_901_532_9).abs() < 1e-14);
+/// ```
+pub fn digamma(x: f64) -> f64 {
+    if x.is_nan() || (x <= 0.0 && x == x.floor()) {
+        return f64::NAN;
+    }
+
+    if x < 0.0 {
+        return digamma(1.0 - x) - PI / (PI * (x % 2.0)).tan();
+    }
+
+    // Shift the argument up with ψ(x) = ψ(x + 1) - 1/x until the asymptotic series is accurate.
+    let mut x = x;
+    let mut result = 0.0;
+    while x < 10.0 {
+        result -= 1.0 / x;
+        x += 1.0;
+    }
+
+    let f = 1.0 / (x * x);
+    let series = f
+        * (1.0 / 12.0
+            - f * (1.0 / 120.0
+                - f * (1.0 / 252.0
+                    - f * (1.0 / 240.0
+                        - f * (1.0 / 132.0 - f * (691.0 / 32_760.0 - f / 12.0))))));
+
+    result + x.ln() - 0.5 / x - series
+}
+
+/// Returns the beta function `B(a, b) = Γ(a)Γ(b) / Γ(a + b)` for positive `a` and `b`.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::statistics::functions::beta;
+///
+/// assert!((beta(2.0, 3.0) - 1.0 / 12.0).abs() < 1e-15);
+/// ```
+pub fn beta(a: f64, b: f64) -> f64 {
+    lbeta(a, b).exp()
+}
+
+/// Returns the natural logarithm of the beta function, `ln B(a, b)`, for positive `a` and `b`.
+pub fn lbeta(a: f64, b: f64) -> f64 {
+    if !(a > 0.0 && b > 0.0) {
+        return f64::NAN;
+    }
+
+    lgamma(a) + lgamma(b) - lgamma(a + b)
+}
+
+/// Returns the error function `erf(x) = 2/√π ∫₀ˣ e^(-t²) dt`.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::statistics::functions::erf;
+///
+/// assert!((erf(0.5) - 0.520_499_877_813_046_5).abs() < 1e-15);
+/// ```
+pub fn erf(x: f64) -> f64 {
+    if x.is_nan() {
+        return f64::NAN;
+    }
+
+    gamma_p(0.5, x * x).copysign(x)
+}
+
+/// Returns the complementary error function `erfc(x) = 1 - erf(x)`.
+///
+/// It stays accurate for large `x`, where `1 - erf(x)` would round to zero.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::statistics::functions::erfc;
+///
+/// assert!((erfc(5.0) - 1.537_459_794_428_035e-12).abs() < 1e-25);
+/// ```
+pub fn erfc(x: f64) -> f64 {
+    if x.is_nan() {
+        return f64::NAN;
+    }
+
+    if x < 0.0 {
+        1.0 + gamma_p(0.5, x * x)
+    } else {
+        gamma_q(0.5, x * x)
+    }
+}
+
+/// Returns the inverse error function, the `x` for which `erf(x) = y`.
+///
+/// The result is infinite for `y = ±1` and NaN outside of `[-1, 1]`.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::statistics::functions::{erf, erfinv};
+///
+/// assert!((erf(erfinv(0.3)) - 0.3).abs() < 1e-15);
+/// ```
+pub fn erfinv(y: f64) -> f64 {
+    if y.is_nan() || y.abs() > 1.0 {
+        return f64::NAN;
+    }
+    if y.abs() == 1.0 {
+        return f64::INFINITY.copysign(y);
+    }
+    if y == 0.0 {
+        return y;
+    }
+
+    let (sign, y) = (y.signum(), y.abs());
+
+    // Initial guess with single precision by M. Giles, "Approximating the erfinv function".
+    let mut w = -((1.0 - y) * (1.0 + y)).ln();
+    let p = if w < 5.0 {
+        w -= 2.5;
+        [
+            2.810_226_36e-8,
+            3.432_739_39e-7,
+            -3.523_387_7e-6,
+            -4.391_506_54e-6,
+            0.000_218_580_87,
+            -0.001_253_725_03,
+            -0.004_177_681_64,
+            0.246_640_727,
+            1.501_409_41,
+        ]
+        .iter()
+        .fold(0.0, |p, c| c + p * w)
+    } else {
+        w = w.sqrt() - 3.0;
+        [
+            -0.000_200_214_257,
+            0.000_100_950_558,
+            0.001_349_343_22,
+            -0.003_673_428_44,
+            0.005_739_507_73,
+            -0.007_622_461_3,
+            0.009_438_870_47,
+            1.001_674_06,
+            2.832_976_82,
+        ]
+        .iter()
+        .fold(0.0, |p, c| c + p * w)
+    };
+    let mut x = p * y;
+
+    // Polish with Halley's method. Close to 1 the residual is computed with erfc, since
+    // erf(x) - y would cancel.
+    for _ in 0..8 {
+        let f = if y > 0.5 {
+            (1.0 - y) - erfc(x)
+        } else {
+            erf(x) - y
+        };
+        let df = 2.0 / PI.sqrt() * (-x * x).exp();
+        let step = f / (df + x * f);
+        x -= step;
+        if step.abs() <= EPSILON * x {
+            break;
+        }
+    }
+
+    sign * x
+}
+
+/// Returns `x^a e^(-x) / Γ(a)`, the common factor of the incomplete gamma functions.
+fn gamma_factor(a: f64, x: f64) -> f64 {
+    (a * x.ln() - x - lgamma(a)).exp()
+}
+
+/// Returns the lower regularized incomplete gamma function `P(a, x) = γ(a, x) / Γ(a)` for
+/// `a > 0` and `x >= 0`.
+///
+/// This is the CDF of a gamma distribution with shape `a` and scale 1.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::statistics::functions::gamma_p;
+///
+/// // For a = 1 this is the CDF of the standard exponential distribution
+/// assert!((gamma_p(1.0, 2.0) - (1.0 - (-2.0_f64).exp())).abs() < 1e-15);
+/// ```
+pub fn gamma_p(a: f64, x: f64) -> f64 {
+    if !(a > 0.0 && x >= 0.0) {
+        return f64::NAN;
+    }
+    if x == 0.0 {
+        return 0.0;
+    }
+    if x.is_infinite() {
+        return 1.0;
+    }
+
+    if x < a + 1.0 {
+        gamma_series(a, x)
+    } else {
+        1.0 - gamma_fraction(a, x)
+    }
+}
+
+/// Returns the upper regularized incomplete gamma function `Q(a, x) = 1 - P(a, x)` for
+/// `a > 0` and `x >= 0`.
+///
+/// It stays accurate when `P(a, x)` is close to 1.
+pub fn gamma_q(a: f64, x: f64) -> f64 {
+    if !(a > 0.0 && x >= 0.0) {
+        return f64::NAN;
+    }
+    if x == 0.0 {
+        return 1.0;
+    }
+    if x.is_infinite() {
+        return 0.0;
+    }
+
+    if x < a + 1.0 {
+        1.0 - gamma_series(a, x)
+    } else {
+        gamma_fraction(a, x)
+    }
+}
+
+/// Evaluates `P(a, x)` with its power series, which converges quickly for `x < a + 1`.
+fn gamma_series(a: f64, x: f64) -> f64 {
+    let mut term = 1.0 / a;
+    let mut sum = term;
+
+    for n in 1..MAX_ITERATIONS {
+        term *= x / (a + n as f64);
+        sum += term;
+        if term.abs() < sum.abs() * EPSILON {
+            break;
+        }
+    }
+
+    sum * gamma_factor(a, x)
+}
+
+/// Evaluates `Q(a, x)` with its continued fraction, which converges quickly for `x >= a + 1`.
+///
+/// Uses the modified Lentz algorithm.
+fn gamma_fraction(a: f64, x: f64) -> f64 {
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / TINY;
+    let mut d = 1.0 / b;
+    let mut h = d;
+
+    for n in 1..MAX_ITERATIONS {
+        let an = -(n as f64) * (n as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = b + an / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    h * gamma_factor(a, x)
+}
+
+/// Returns the regularized incomplete beta function `I_x(a, b) = B(x; a, b) / B(a, b)` for
+/// `a > 0`, `b > 0` and `0 <= x <= 1`.
+///
+/// This is the CDF of a beta distribution with shape parameters `a` and `b`.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::statistics::functions::beta_inc;
+///
+/// // For a = b = 1 this is the CDF of the standard uniform distribution
+/// assert!((beta_inc(1.0, 1.0, 0.3) - 0.3).abs() < 1e-15);
+/// ```
+pub fn beta_inc(a: f64, b: f64, x: f64) -> f64 {
+    if !(a > 0.0 && b > 0.0 && (0.0..=1.0).contains(&x)) {
+        return f64::NAN;
+    }
+    if x == 0.0 || x == 1.0 {
+        return x;
+    }
+
+    let factor = (a * x.ln() + b * (-x).ln_1p() - lbeta(a, b)).exp();
+
+    // The continued fraction converges quickly below the mean, so use the symmetry
+    // I_x(a, b) = 1 - I_(1-x)(b, a) above it.
+    if x < (a + 1.0) / (a + b + 2.0) {
+        factor * beta_fraction(a, b, x) / a
+    } else {
+        1.0 - factor * beta_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Evaluates the continued fraction of the incomplete beta function with the modified Lentz
+/// algorithm.
+fn beta_fraction(a: f64, b: f64, x: f64) -> f64 {
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..MAX_ITERATIONS {
+        let m = m as f64;
+
+        // The even and odd steps of the fraction.
+        for an in [
+            m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m)),
+            -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0)),
+        ] {
+            d = 1.0 + an * d;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = 1.0 + an / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            h *= d * c;
+        }
+
+        if (d * c - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    h
 }
 
 pub fn dirac_delta(x: usize) -> usize {
@@ -38,7 +534,7 @@ mod test {
 
     #[test]
     fn test_binomial() {
-	let expected = 1;
+	let expected = 3;
 	let actual = binomial_coefficient(3, 2);
 
 	assert_eq!(expected, actual);
@@ -75,4 +571,118 @@ mod test {
 
 	assert_eq!(expected, actual);
     }
+    /// Asserts that `actual` is within `tol` of `expected`, relative to `expected` if it is
+    /// larger than 1.
+    fn assert_close(expected: f64, actual: f64, tol: f64) {
+        let err = (expected - actual).abs() / expected.abs().max(1.0);
+        assert!(
+            err <= tol,
+            "expected {}, found {} (error {:e})",
+            expected,
+            actual,
+            err
+        );
+    }
+
+    #[test]
+    fn test_binomial_large() {
+        assert_eq!(252, binomial_coefficient(10, 5));
+        assert_eq!(4_845, binomial_coefficient(20, 16));
+        assert_eq!(118_264_581_564_861_424, binomial_coefficient(60, 30));
+        assert_eq!(14_226_520_737_620_288_370, binomial_coefficient(67, 33));
+    }
+
+    #[test]
+    fn test_gamma() {
+        assert_eq!(1.0, gamma(1.0));
+        assert_eq!(3_628_800.0, gamma(11.0));
+        assert_close(1.772_453_850_905_516, gamma(0.5), 1e-14);
+        assert_close(-3.544_907_701_811_032, gamma(-0.5), 1e-14);
+        assert_close(-0.945_308_720_482_941_9, gamma(-2.5), 1e-14);
+        assert_close(454_760.751_441_585_6, gamma(10.1), 1e-14);
+        assert_close(9.999_999_999_422_784e9, gamma(1e-10), 1e-14);
+        assert_close(9.483_367_566_824_8e307, gamma(171.5), 1e-12);
+    }
+
+    #[test]
+    fn test_gamma_poles() {
+        assert_eq!(f64::INFINITY, gamma(0.0));
+        assert!(gamma(-3.0).is_nan());
+        assert_eq!(f64::INFINITY, gamma(172.0));
+    }
+
+    #[test]
+    fn test_lgamma() {
+        assert_close(0.0, lgamma(1.0), 1e-15);
+        assert_close(0.0, lgamma(2.0), 1e-15);
+        assert_close(0.572_364_942_924_700_1, lgamma(0.5), 1e-14);
+        assert_close(359.134_205_369_575_4, lgamma(100.0), 1e-14);
+        assert_close(-0.056_243_716_497_674_05, lgamma(-2.5), 1e-13);
+        assert_close(11.512_919_692_895_826, lgamma(1e-5), 1e-14);
+        assert_close(220_258_509_288.810_58, lgamma(1e10), 1e-14);
+        assert_eq!(f64::INFINITY, lgamma(-2.0));
+    }
+
+    #[test]
+    fn test_digamma() {
+        assert_close(-0.577_215_664_901_532_9, digamma(1.0), 1e-14);
+        assert_close(-1.963_510_026_021_423_5, digamma(0.5), 1e-14);
+        assert_close(2.251_752_589_066_721, digamma(10.0), 1e-14);
+        assert_close(0.703_156_640_645_243_2, digamma(2.5), 1e-14);
+        assert_close(0.036_489_973_978_576_52, digamma(-0.5), 1e-13);
+        assert_close(-1_000.575_571_931_810_3, digamma(1e-3), 1e-14);
+        assert!(digamma(0.0).is_nan());
+    }
+
+    #[test]
+    fn test_beta() {
+        assert_close(1.0 / 12.0, beta(2.0, 3.0), 1e-14);
+        assert_close(std::f64::consts::PI, beta(0.5, 0.5), 1e-14);
+        assert_close(-192.134_192_274_978_95, lbeta(100.0, 200.0), 1e-13);
+        assert!(beta(-1.0, 2.0).is_nan());
+    }
+
+    #[test]
+    fn test_erf_and_erfc() {
+        assert_close(0.520_499_877_813_046_5, erf(0.5), 1e-15);
+        assert_close(-0.966_105_146_475_310_7, erf(-1.5), 1e-15);
+        assert_close(1.128_379_167_095_512_6e-10, erf(1e-10), 1e-25);
+        assert_eq!(1.0, erf(f64::INFINITY));
+        assert_close(0.479_500_122_186_953_5, erfc(0.5), 1e-15);
+        assert_close(1.842_700_792_949_715, erfc(-1.0), 1e-15);
+        assert!((erfc(5.0) / 1.537_459_794_428_035e-12 - 1.0).abs() < 1e-13);
+        assert!((erfc(20.0) / 5.395_865_611_607_901e-176 - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_erfinv() {
+        assert_close(0.476_936_276_204_469_9, erfinv(0.5), 1e-15);
+        assert_close(-1.163_087_153_676_674_2, erfinv(-0.9), 1e-14);
+        assert_close(3.458_910_737_275_499, erfinv(0.999_999), 1e-12);
+        assert_close(8.862_269_254_527_58e-11, erfinv(1e-10), 1e-24);
+        assert_close(5.051_254_085_249_39, erfinv(1.0 - 2f64.powi(-40)), 1e-14);
+        assert_eq!(f64::NEG_INFINITY, erfinv(-1.0));
+        assert!(erfinv(1.5).is_nan());
+    }
+
+    #[test]
+    fn test_incomplete_gamma() {
+        assert_close(0.300_014_164_121_372_5, gamma_p(2.5, 1.5), 1e-14);
+        assert_close(0.699_985_835_878_627_5, gamma_q(2.5, 1.5), 1e-14);
+        assert_close(0.158_220_989_186_430_17, gamma_p(100.0, 90.0), 1e-12);
+        assert_close(0.999_999_445_201_428_2, gamma_p(0.1, 10.0), 1e-14);
+        assert_close(1.654_216_528_074_877e-7, gamma_p(3.0, 0.01), 1e-20);
+        assert_eq!(0.0, gamma_p(2.0, 0.0));
+        assert!(gamma_p(-1.0, 1.0).is_nan());
+    }
+
+    #[test]
+    fn test_incomplete_beta() {
+        assert_close(0.5248, beta_inc(2.0, 3.0, 0.4), 1e-14);
+        assert_close(0.204_832_764_699_133_46, beta_inc(0.5, 0.5, 0.1), 1e-14);
+        assert_close(0.830_907_293_901_669_4, beta_inc(50.0, 60.0, 0.5), 1e-12);
+        assert_close(0.757_158_109_101_562_4, beta_inc(5.0, 0.5, 0.99), 1e-13);
+        assert_eq!(1.0, beta_inc(2.0, 3.0, 1.0));
+        assert!(beta_inc(2.0, 3.0, 1.5).is_nan());
+    }
 }
@@ -0,0 +1,368 @@
+//! Histogram - One- and two-dimensional histograms
+//!
+//! The bins of a histogram are given by their edges, either explicitly or by a rule that picks
+//! the number of equal-width bins from the data. Like in NumPy, every bin includes its left
+//! edge, and the last bin also includes its right edge. NaN and infinite values and values
+//! outside the edges are not counted.
+
+use crate::linalg::array::Array;
+use crate::linalg::matrix::Matrix;
+
+/// How to choose the bins of a histogram.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Bins {
+    /// The given number of equal-width bins between the smallest and the largest value.
+    Count(usize),
+    /// Sturges' rule: `log2(n) + 1` bins, which assumes roughly normal data.
+    Sturges,
+    /// Scott's rule: a bin width of `(24 sqrt(pi) / n)^(1/3)` standard deviations, which is
+    /// optimal for normal data.
+    Scott,
+    /// The Freedman-Diaconis rule: a bin width of `2 IQR / n^(1/3)`, which is robust to
+    /// outliers.
+    FreedmanDiaconis,
+    /// The given edges, which must be strictly increasing.
+    Edges(Array),
+}
+
+/// A histogram of one variable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    /// The number of values in every bin.
+    pub counts: Array,
+    /// The edges of the bins, one more than the number of bins.
+    pub edges: Array,
+}
+
+/// A histogram of two variables.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram2d {
+    /// The number of pairs in every bin, with a row for every bin of the first variable and a
+    /// column for every bin of the second.
+    pub counts: Matrix,
+    /// The edges of the bins of the first variable.
+    pub x_edges: Array,
+    /// The edges of the bins of the second variable.
+    pub y_edges: Array,
+}
+
+/// Returns the widths of the bins with the given edges.
+fn widths(edges: &Array) -> Array {
+    Array::from_vec(edges.windows(2).map(|w| w[1] - w[0]).collect())
+}
+
+/// Returns the edges for the finite values.
+fn edges(values: &Array, bins: &Bins) -> Array {
+    if let Bins::Edges(edges) = bins {
+        assert!(
+            edges.len() >= 2 && edges.windows(2).all(|w| w[0] < w[1]),
+            "ERROR - histogram: The edges must be strictly increasing."
+        );
+        return edges.clone();
+    }
+
+    let (mut lo, mut hi) = if values.is_empty() {
+        (0.0, 1.0)
+    } else {
+        (values.min(), values.max())
+    };
+    if lo == hi {
+        lo -= 0.5;
+        hi += 0.5;
+    }
+
+    let n = values.len() as f64;
+    let from_width = |width: f64| {
+        if width > 0.0 {
+            (((hi - lo) / width).ceil() as usize).max(1)
+        } else {
+            1
+        }
+    };
+    let count = match bins {
+        Bins::Count(count) => {
+            assert!(
+                *count > 0,
+                "ERROR - histogram: The number of bins must be positive."
+            );
+            *count
+        }
+        Bins::Sturges => (n.max(1.0).log2().ceil() as usize) + 1,
+        Bins::Scott => from_width((24.0 * std::f64::consts::PI.sqrt() / n).cbrt() * values.std(0)),
+        Bins::FreedmanDiaconis => from_width(2.0 * values.iqr() / n.cbrt()),
+        Bins::Edges(_) => unreachable!(),
+    };
+
+    let mut edges: Vec<f64> = (0..=count)
+        .map(|i| lo + (hi - lo) * i as f64 / count as f64)
+        .collect();
+    edges[count] = hi;
+    Array::from_vec(edges)
+}
+
+/// Returns the index of the bin containing `x`, if any.
+fn bin(edges: &Array, x: f64) -> Option<usize> {
+    let last = edges.len() - 1;
+    if x.is_nan() || x < edges[0] || x > edges[last] {
+        return None;
+    }
+    Some((edges.partition_point(|&edge| edge <= x) - 1).min(last - 1))
+}
+
+/// Returns the values of an Array without NaN and infinite values.
+fn finite(xs: &Array) -> Array {
+    Array::from_vec(xs.iter().copied().filter(|x| x.is_finite()).collect())
+}
+
+/// Returns the histogram of the values of an Array.
+///
+/// NaN and infinite values are not counted, and the bins are chosen from the remaining values.
+///
+/// # Arguments
+///
+/// * `xs` - the values.
+/// * `bins` - the number of bins, a rule to choose it, or explicit edges.
+///
+/// # Panics
+///
+/// The number of bins must be positive and explicit edges must be strictly increasing,
+/// otherwise the code will panic.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::statistics::histogram::{histogram, Bins};
+///
+/// let xs = Array::from(&[1.0, 2.0, 2.0, 3.0, 4.0]);
+/// let hist = histogram(&xs, Bins::Count(3));
+///
+/// assert_eq!(Array::from(&[1.0, 2.0, 2.0]), hist.counts);
+/// assert_eq!(Array::from(&[1.0, 2.0, 3.0, 4.0]), hist.edges);
+/// ```
+pub fn histogram(xs: &Array, bins: Bins) -> Histogram {
+    let values = finite(xs);
+    let edges = edges(&values, &bins);
+
+    let mut counts = Array::zeros(edges.len() - 1);
+    for &x in values.iter() {
+        if let Some(i) = bin(&edges, x) {
+            counts[i] += 1.0;
+        }
+    }
+
+    Histogram { counts, edges }
+}
+
+impl Histogram {
+    /// Returns the widths of the bins.
+    pub fn widths(&self) -> Array {
+        widths(&self.edges)
+    }
+
+    /// Returns the centers of the bins.
+    pub fn centers(&self) -> Array {
+        Array::from_vec(self.edges.windows(2).map(|w| 0.5 * (w[0] + w[1])).collect())
+    }
+
+    /// Returns the density of every bin, such that the histogram integrates to 1.
+    ///
+    /// The density is NaN if no values were counted.
+    pub fn density(&self) -> Array {
+        let total = self.counts.sum();
+        Array::from_vec(
+            self.counts
+                .iter()
+                .zip(self.widths().iter())
+                .map(|(count, width)| count / (total * width))
+                .collect(),
+        )
+    }
+}
+
+/// Returns the two-dimensional histogram of pairs of values.
+///
+/// Pairs in which either value is NaN or infinite are not counted, and the bins of each variable are
+/// chosen from its values in the remaining pairs.
+///
+/// # Arguments
+///
+/// * `xs` - the first values of the pairs.
+/// * `ys` - the second values of the pairs.
+/// * `x_bins` - the bins of the first variable.
+/// * `y_bins` - the bins of the second variable.
+///
+/// # Panics
+///
+/// The Arrays must have the same length, the number of bins must be positive and explicit
+/// edges must be strictly increasing, otherwise the code will panic.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::statistics::histogram::{histogram2d, Bins};
+///
+/// let xs = Array::from(&[0.0, 0.0, 1.0, 1.0]);
+/// let ys = Array::from(&[0.0, 1.0, 1.0, 1.0]);
+/// let hist = histogram2d(&xs, &ys, Bins::Count(2), Bins::Count(2));
+///
+/// assert_eq!(1.0, hist.counts[0][0]);
+/// assert_eq!(2.0, hist.counts[1][1]);
+/// ```
+pub fn histogram2d(xs: &Array, ys: &Array, x_bins: Bins, y_bins: Bins) -> Histogram2d {
+    assert_eq!(
+        xs.len(),
+        ys.len(),
+        "ERROR - histogram2d: The Arrays must have the same length."
+    );
+
+    let (xs, ys): (Vec<f64>, Vec<f64>) = xs
+        .iter()
+        .zip(ys.iter())
+        .filter(|(x, y)| x.is_finite() && y.is_finite())
+        .unzip();
+    let (xs, ys) = (Array::from_vec(xs), Array::from_vec(ys));
+    let x_edges = edges(&xs, &x_bins);
+    let y_edges = edges(&ys, &y_bins);
+
+    let mut counts = Matrix::zeros(x_edges.len() - 1, y_edges.len() - 1);
+    for (&x, &y) in xs.iter().zip(ys.iter()) {
+        if let (Some(i), Some(j)) = (bin(&x_edges, x), bin(&y_edges, y)) {
+            counts[(i, j)] += 1.0;
+        }
+    }
+
+    Histogram2d {
+        counts,
+        x_edges,
+        y_edges,
+    }
+}
+
+impl Histogram2d {
+    /// Returns the density of every bin, such that the histogram integrates to 1.
+    ///
+    /// The density is NaN if no pairs were counted.
+    pub fn density(&self) -> Matrix {
+        let total: f64 = self.counts.iter().map(|row| row.sum()).sum();
+        let (x_widths, y_widths) = (widths(&self.x_edges), widths(&self.y_edges));
+
+        let rows = self
+            .counts
+            .iter()
+            .zip(x_widths.iter())
+            .map(|(row, dx)| {
+                Array::from_vec(
+                    row.iter()
+                        .zip(y_widths.iter())
+                        .map(|(count, dy)| count / (total * dx * dy))
+                        .collect(),
+                )
+            })
+            .collect();
+        Matrix::from_rows(rows)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_histogram_counts() {
+        let xs = Array::from(&[0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0, f64::NAN]);
+        let hist = histogram(&xs, Bins::Count(3));
+
+        assert_eq!(Array::from(&[2.0, 2.0, 3.0]), hist.counts);
+        assert_eq!(Array::from(&[0.0, 1.0, 2.0, 3.0]), hist.edges);
+        assert_eq!(Array::from(&[0.5, 1.5, 2.5]), hist.centers());
+    }
+
+    #[test]
+    fn test_infinite_values() {
+        let xs = Array::from(&[1.0, f64::INFINITY, 2.0, f64::NEG_INFINITY, 3.0, 4.0]);
+
+        let hist = histogram(&xs, Bins::Count(2));
+        assert_eq!(Array::from(&[2.0, 2.0]), hist.counts);
+        assert_eq!(Array::from(&[1.0, 2.5, 4.0]), hist.edges);
+        assert_eq!(4.0, histogram(&xs, Bins::Sturges).counts.sum());
+
+        let ys = Array::from(&[0.0, 0.0, f64::INFINITY, 1.0, 1.0, 1.0]);
+        let hist2d = histogram2d(&xs, &ys, Bins::Count(2), Bins::Count(2));
+        assert_eq!(3.0, hist2d.counts.iter().map(|row| row.sum()).sum::<f64>());
+        assert_eq!(Array::from(&[1.0, 2.5, 4.0]), hist2d.x_edges);
+    }
+
+    #[test]
+    fn test_explicit_edges() {
+        let xs = Array::from(&[-1.0, 0.0, 0.2, 0.5, 0.7, 1.0, 4.0, 5.0]);
+        let edges = Array::from(&[0.0, 0.5, 1.0, 4.0]);
+        let hist = histogram(&xs, Bins::Edges(edges.clone()));
+
+        assert_eq!(Array::from(&[2.0, 2.0, 2.0]), hist.counts);
+        assert_eq!(edges, hist.edges);
+
+        let density = hist.density();
+        assert_eq!(Array::from(&[2.0 / 3.0, 2.0 / 3.0, 1.0 / 9.0]), density);
+        assert!((density.dotp(&hist.widths()) - 1.0).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_bin_rules() {
+        let xs = Array::from_vec((0..100).map(|i| (i as f64 * 0.37).sin()).collect());
+
+        assert_eq!(8, histogram(&xs, Bins::Sturges).counts.len());
+
+        let width = (24.0 * std::f64::consts::PI.sqrt() / 100.0).cbrt() * xs.std(0);
+        let scott = histogram(&xs, Bins::Scott);
+        assert_eq!(
+            ((xs.max() - xs.min()) / width).ceil() as usize,
+            scott.counts.len()
+        );
+
+        let width = 2.0 * xs.iqr() / 100f64.cbrt();
+        let fd = histogram(&xs, Bins::FreedmanDiaconis);
+        assert_eq!(
+            ((xs.max() - xs.min()) / width).ceil() as usize,
+            fd.counts.len()
+        );
+        assert_eq!(100.0, fd.counts.sum());
+    }
+
+    #[test]
+    fn test_constant_values() {
+        let hist = histogram(&Array::of(2.0, 4), Bins::FreedmanDiaconis);
+
+        assert_eq!(Array::from(&[4.0]), hist.counts);
+        assert_eq!(Array::from(&[1.5, 2.5]), hist.edges);
+    }
+
+    #[test]
+    fn test_histogram2d() {
+        let xs = Array::from(&[0.1, 0.2, 0.6, 0.9, 0.4, f64::NAN]);
+        let ys = Array::from(&[1.0, 3.0, 3.0, 2.0, 1.0, 2.0]);
+        let hist = histogram2d(
+            &xs,
+            &ys,
+            Bins::Edges(Array::from(&[0.0, 0.5, 1.0])),
+            Bins::Count(2),
+        );
+
+        assert_eq!(
+            Matrix::new(&[Array::from(&[2.0, 1.0]), Array::from(&[0.0, 2.0])]),
+            hist.counts
+        );
+        assert_eq!(Array::from(&[1.0, 2.0, 3.0]), hist.y_edges);
+
+        let density = hist.density();
+        assert_eq!(0.8, density[0][0]);
+        assert_eq!(0.0, density[1][0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_decreasing_edges() {
+        histogram(&Array::from(&[1.0]), Bins::Edges(Array::from(&[1.0, 0.0])));
+    }
+}
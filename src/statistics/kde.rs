@@ -0,0 +1,228 @@
+//! KDE - Kernel density estimation
+//!
+//! A kernel density estimate smooths a sample into a continuous density by placing a kernel at
+//! every observation. The bandwidth is the standard deviation of the kernel, so the bandwidth
+//! rules give comparable amounts of smoothing for every kernel.
+
+use crate::linalg::array::Array;
+
+use std::f64::consts::PI;
+
+/// The shape of the kernel placed at every observation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Kernel {
+    /// The normal density.
+    #[default]
+    Gaussian,
+    /// The parabolic kernel `3 / (4 sqrt(5)) (1 - u^2 / 5)` on `|u| <= sqrt(5)`, which has the
+    /// smallest asymptotic error of all kernels.
+    Epanechnikov,
+}
+
+/// How to choose the bandwidth of a kernel density estimate.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Bandwidth {
+    /// Silverman's rule of thumb: `0.9 min(std, IQR / 1.34) n^(-1/5)`.
+    #[default]
+    Silverman,
+    /// Scott's rule of thumb: `1.06 std n^(-1/5)`.
+    Scott,
+    /// The given bandwidth, which must be positive.
+    Fixed(f64),
+}
+
+/// A kernel density estimate of a sample.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kde {
+    data: Array,
+    kernel: Kernel,
+    bandwidth: f64,
+}
+
+impl Kernel {
+    /// Returns the density of the kernel with unit variance.
+    fn density(self, u: f64) -> f64 {
+        match self {
+            Kernel::Gaussian => (-0.5 * u * u).exp() / (2.0 * PI).sqrt(),
+            Kernel::Epanechnikov => {
+                if u * u < 5.0 {
+                    0.75 / 5f64.sqrt() * (1.0 - u * u / 5.0)
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+impl Kde {
+    /// Returns the kernel density estimate of a sample.
+    ///
+    /// # Arguments
+    ///
+    /// * `xs` - the sample, NaN values are left out.
+    /// * `kernel` - the shape of the kernel.
+    /// * `bandwidth` - the bandwidth, or a rule to choose it.
+    ///
+    /// # Panics
+    ///
+    /// The sample must have at least 2 values that are not NaN, and the bandwidth must be
+    /// positive, otherwise the code will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moonalloy::linalg::array::Array;
+    /// use moonalloy::statistics::kde::{Bandwidth, Kde, Kernel};
+    ///
+    /// let xs = Array::from(&[1.2, 1.9, 2.1, 2.4, 3.3, 3.5, 4.1, 5.0]);
+    /// let kde = Kde::new(&xs, Kernel::Gaussian, Bandwidth::Silverman);
+    /// let (grid, density) = kde.grid(200);
+    ///
+    /// assert_eq!(200, grid.len());
+    /// assert!(density.iter().all(|&d| d >= 0.0));
+    /// ```
+    pub fn new(xs: &Array, kernel: Kernel, bandwidth: Bandwidth) -> Kde {
+        let data = Array::from_vec(xs.iter().copied().filter(|x| !x.is_nan()).collect());
+        assert!(
+            data.len() >= 2,
+            "ERROR - Kde: The sample must have at least 2 values."
+        );
+
+        let n = data.len() as f64;
+        let bandwidth = match bandwidth {
+            Bandwidth::Silverman => {
+                let std = data.std(1);
+                let iqr = data.iqr() / 1.34;
+                let spread = if iqr > 0.0 { std.min(iqr) } else { std };
+                0.9 * spread * n.powf(-0.2)
+            }
+            Bandwidth::Scott => 1.06 * data.std(1) * n.powf(-0.2),
+            Bandwidth::Fixed(h) => h,
+        };
+        assert!(
+            bandwidth > 0.0 && bandwidth.is_finite(),
+            "ERROR - Kde: The bandwidth must be positive."
+        );
+
+        Kde {
+            data,
+            kernel,
+            bandwidth,
+        }
+    }
+
+    /// Returns the bandwidth.
+    pub fn bandwidth(&self) -> f64 {
+        self.bandwidth
+    }
+
+    /// Returns the kernel.
+    pub fn kernel(&self) -> Kernel {
+        self.kernel
+    }
+
+    /// Returns the estimated density at `x`.
+    pub fn evaluate(&self, x: f64) -> f64 {
+        let h = self.bandwidth;
+        let sum: f64 = self
+            .data
+            .iter()
+            .map(|xi| self.kernel.density((x - xi) / h))
+            .sum();
+        sum / (self.data.len() as f64 * h)
+    }
+
+    /// Returns the estimated density at every value of an Array.
+    pub fn evaluate_array(&self, xs: &Array) -> Array {
+        Array::from_vec(xs.iter().map(|&x| self.evaluate(x)).collect())
+    }
+
+    /// Returns `points` equally spaced values and the estimated density at them.
+    ///
+    /// The values range from three bandwidths below the smallest observation to three
+    /// bandwidths above the largest, which covers nearly all of the density.
+    ///
+    /// # Panics
+    ///
+    /// There must be at least 2 points, otherwise the code will panic.
+    pub fn grid(&self, points: usize) -> (Array, Array) {
+        assert!(
+            points >= 2,
+            "ERROR - Kde grid: There must be at least 2 points."
+        );
+
+        let lo = self.data.min() - 3.0 * self.bandwidth;
+        let hi = self.data.max() + 3.0 * self.bandwidth;
+        let step = (hi - lo) / (points - 1) as f64;
+        let grid = Array::from_vec((0..points).map(|i| lo + step * i as f64).collect());
+        let density = self.evaluate_array(&grid);
+
+        (grid, density)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> Array {
+        Array::from(&[1.2, 1.9, 2.1, 2.4, 3.3, 3.5, 4.1, 5.0])
+    }
+
+    #[test]
+    fn test_bandwidth_rules() {
+        let xs = sample();
+        let std = xs.std(1);
+        let iqr = xs.iqr();
+
+        let silverman = Kde::new(&xs, Kernel::Gaussian, Bandwidth::Silverman);
+        let expected = 0.9 * std.min(iqr / 1.34) * 8f64.powf(-0.2);
+        assert!((silverman.bandwidth() - expected).abs() < 1e-15);
+
+        let scott = Kde::new(&xs, Kernel::Gaussian, Bandwidth::Scott);
+        assert!((scott.bandwidth() - 1.06 * std * 8f64.powf(-0.2)).abs() < 1e-15);
+
+        let fixed = Kde::new(&xs, Kernel::Epanechnikov, Bandwidth::Fixed(0.5));
+        assert_eq!(0.5, fixed.bandwidth());
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let kde = Kde::new(
+            &Array::from(&[0.0, 2.0]),
+            Kernel::Gaussian,
+            Bandwidth::Fixed(1.0),
+        );
+        let normal = |x: f64| (-0.5 * x * x).exp() / (2.0 * PI).sqrt();
+
+        assert!((kde.evaluate(1.0) - normal(1.0)).abs() < 1e-15);
+        assert!((kde.evaluate(0.0) - 0.5 * (normal(0.0) + normal(2.0))).abs() < 1e-15);
+
+        let kde = Kde::new(
+            &Array::from(&[0.0, 2.0]),
+            Kernel::Epanechnikov,
+            Bandwidth::Fixed(0.5),
+        );
+        assert_eq!(0.0, kde.evaluate(3.5));
+        assert!((kde.evaluate(0.0) - 0.75 / 5f64.sqrt()).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_density_integrates_to_one() {
+        for &kernel in &[Kernel::Gaussian, Kernel::Epanechnikov] {
+            let kde = Kde::new(&sample(), kernel, Bandwidth::Silverman);
+            let (grid, density) = kde.grid(2001);
+            let step = grid[1] - grid[0];
+            let integral = step * (density.sum() - 0.5 * (density[0] + density[2000]));
+
+            assert!((integral - 1.0).abs() < 1e-3, "{:?}: {}", kernel, integral);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_constant_sample() {
+        Kde::new(&Array::of(1.0, 5), Kernel::Gaussian, Bandwidth::Scott);
+    }
+}
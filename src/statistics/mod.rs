@@ -1,2 +1,9 @@
 pub mod probability;
 pub mod functions;
+pub mod descriptive;
+pub mod correlation;
+pub mod tests;
+pub mod resampling;
+pub mod histogram;
+pub mod kde;
+pub mod online;
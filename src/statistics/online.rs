@@ -0,0 +1,809 @@
+//! Online - Streaming statistics accumulators
+//!
+//! The accumulators in this module compute statistics of a stream of values one value at a
+//! time, in constant or bounded memory, so the data never has to fit into one Array. Every
+//! accumulator can be merged with another one of the same kind, e.g. to combine the statistics
+//! of several sensors or of chunks processed on different threads.
+//!
+//! Missing values encoded as NaN are skipped, so the statistics are those of the remaining
+//! values.
+
+use crate::linalg::array::Array;
+
+/// A statistics accumulator that receives one value at a time.
+pub trait Accumulator {
+    /// Adds a value to the accumulator.
+    fn push(&mut self, x: f64);
+
+    /// Merges the statistics of another accumulator into this one.
+    fn merge(&mut self, other: &Self);
+
+    /// Adds every value of an Array to the accumulator.
+    fn push_array(&mut self, xs: &Array) {
+        for &x in xs.iter() {
+            self.push(x);
+        }
+    }
+}
+
+/// The count, mean, variance and extreme values of a stream, using Welford's algorithm.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::statistics::online::{Accumulator, RunningStats};
+///
+/// let mut first = RunningStats::new();
+/// first.push_array(&Array::from(&[1.0, 2.0, 3.0]));
+/// let mut second = RunningStats::new();
+/// second.push_array(&Array::from(&[4.0, 5.0]));
+/// first.merge(&second);
+///
+/// assert_eq!(5, first.count());
+/// assert_eq!(3.0, first.mean());
+/// assert_eq!(2.5, first.var(1));
+/// assert_eq!(5.0, first.max());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunningStats {
+    count: usize,
+    mean: f64,
+    m2: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for RunningStats {
+    fn default() -> RunningStats {
+        RunningStats::new()
+    }
+}
+
+impl RunningStats {
+    /// Returns an empty accumulator.
+    pub fn new() -> RunningStats {
+        RunningStats {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Returns the number of values.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the mean, or NaN if there are no values.
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            f64::NAN
+        } else {
+            self.mean
+        }
+    }
+
+    /// Returns the variance with `count - ddof` in the denominator, or NaN if there are at most
+    /// `ddof` values.
+    pub fn var(&self, ddof: usize) -> f64 {
+        if self.count <= ddof {
+            f64::NAN
+        } else {
+            self.m2 / (self.count - ddof) as f64
+        }
+    }
+
+    /// Returns the standard deviation with `count - ddof` in the denominator.
+    pub fn std(&self, ddof: usize) -> f64 {
+        self.var(ddof).sqrt()
+    }
+
+    /// Returns the smallest value, or NaN if there are no values.
+    pub fn min(&self) -> f64 {
+        if self.count == 0 {
+            f64::NAN
+        } else {
+            self.min
+        }
+    }
+
+    /// Returns the largest value, or NaN if there are no values.
+    pub fn max(&self) -> f64 {
+        if self.count == 0 {
+            f64::NAN
+        } else {
+            self.max
+        }
+    }
+}
+
+impl Accumulator for RunningStats {
+    fn push(&mut self, x: f64) {
+        if x.is_nan() {
+            return;
+        }
+
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+    }
+
+    /// Merges another accumulator with the pairwise update by Chan, Golub and LeVeque.
+    fn merge(&mut self, other: &RunningStats) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+
+        let (n_a, n_b) = (self.count as f64, other.count as f64);
+        let n = n_a + n_b;
+        let delta = other.mean - self.mean;
+        self.mean += delta * n_b / n;
+        self.m2 += other.m2 + delta * delta * n_a * n_b / n;
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+}
+
+/// The means, variances and covariance of a stream of pairs of values.
+///
+/// Pairs in which either value is NaN are skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RunningCovariance {
+    count: usize,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x: f64,
+    m2_y: f64,
+    c_xy: f64,
+}
+
+impl RunningCovariance {
+    /// Returns an empty accumulator.
+    pub fn new() -> RunningCovariance {
+        RunningCovariance::default()
+    }
+
+    /// Adds a pair of values to the accumulator.
+    pub fn push(&mut self, x: f64, y: f64) {
+        if x.is_nan() || y.is_nan() {
+            return;
+        }
+
+        self.count += 1;
+        let n = self.count as f64;
+        let dx = x - self.mean_x;
+        let dy = y - self.mean_y;
+        self.mean_x += dx / n;
+        self.mean_y += dy / n;
+        self.m2_x += dx * (x - self.mean_x);
+        self.m2_y += dy * (y - self.mean_y);
+        self.c_xy += dx * (y - self.mean_y);
+    }
+
+    /// Adds the pairs of values of two Arrays to the accumulator.
+    ///
+    /// # Panics
+    ///
+    /// The Arrays must have the same length, otherwise the code will panic.
+    pub fn push_arrays(&mut self, xs: &Array, ys: &Array) {
+        assert_eq!(
+            xs.len(),
+            ys.len(),
+            "ERROR - RunningCovariance push_arrays: The Arrays must have the same length."
+        );
+        for (&x, &y) in xs.iter().zip(ys.iter()) {
+            self.push(x, y);
+        }
+    }
+
+    /// Merges the statistics of another accumulator into this one.
+    pub fn merge(&mut self, other: &RunningCovariance) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+
+        let (n_a, n_b) = (self.count as f64, other.count as f64);
+        let n = n_a + n_b;
+        let dx = other.mean_x - self.mean_x;
+        let dy = other.mean_y - self.mean_y;
+        self.mean_x += dx * n_b / n;
+        self.mean_y += dy * n_b / n;
+        self.m2_x += other.m2_x + dx * dx * n_a * n_b / n;
+        self.m2_y += other.m2_y + dy * dy * n_a * n_b / n;
+        self.c_xy += other.c_xy + dx * dy * n_a * n_b / n;
+        self.count += other.count;
+    }
+
+    /// Returns the number of pairs.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the means of both variables, or NaN if there are no pairs.
+    pub fn means(&self) -> (f64, f64) {
+        if self.count == 0 {
+            (f64::NAN, f64::NAN)
+        } else {
+            (self.mean_x, self.mean_y)
+        }
+    }
+
+    /// Returns the covariance with `count - ddof` in the denominator, or NaN if there are at
+    /// most `ddof` pairs.
+    pub fn cov(&self, ddof: usize) -> f64 {
+        if self.count <= ddof {
+            f64::NAN
+        } else {
+            self.c_xy / (self.count - ddof) as f64
+        }
+    }
+
+    /// Returns the Pearson correlation coefficient, or NaN if either variable is constant.
+    pub fn corr(&self) -> f64 {
+        let den = (self.m2_x * self.m2_y).sqrt();
+        if self.count < 2 || den == 0.0 {
+            f64::NAN
+        } else {
+            (self.c_xy / den).clamp(-1.0, 1.0)
+        }
+    }
+}
+
+/// Approximate quantiles of a stream, using a merging t-digest by Dunning and Ertl.
+///
+/// The t-digest summarizes the values in clusters, called centroids, that are small near the
+/// extreme quantiles and larger near the median. Its memory is bounded by the compression,
+/// and its quantiles are most accurate in the tails.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::statistics::online::{Accumulator, TDigest};
+///
+/// let mut digest = TDigest::new(100.0);
+/// for i in 0..10_000 {
+///     digest.push(i as f64);
+/// }
+///
+/// assert!((digest.quantile(0.99) - 9899.5).abs() < 10.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TDigest {
+    compression: f64,
+    /// The means and weights of the centroids, sorted by mean.
+    centroids: Vec<(f64, f64)>,
+    buffer: Vec<f64>,
+    count: usize,
+    min: f64,
+    max: f64,
+}
+
+impl Default for TDigest {
+    /// Returns an empty t-digest with the compression 100.
+    fn default() -> TDigest {
+        TDigest::new(100.0)
+    }
+}
+
+impl TDigest {
+    /// Returns an empty t-digest.
+    ///
+    /// # Arguments
+    ///
+    /// * `compression` - bounds the number of centroids to about the compression. Larger
+    ///   values are more accurate and use more memory.
+    ///
+    /// # Panics
+    ///
+    /// The compression must be at least 10, otherwise the code will panic.
+    pub fn new(compression: f64) -> TDigest {
+        assert!(
+            compression >= 10.0,
+            "ERROR - TDigest: The compression must be at least 10."
+        );
+        TDigest {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Returns the number of values.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the smallest value, or NaN if there are no values.
+    pub fn min(&self) -> f64 {
+        if self.count == 0 {
+            f64::NAN
+        } else {
+            self.min
+        }
+    }
+
+    /// Returns the largest value, or NaN if there are no values.
+    pub fn max(&self) -> f64 {
+        if self.count == 0 {
+            f64::NAN
+        } else {
+            self.max
+        }
+    }
+
+    /// Merges the buffered values into the centroids.
+    fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+
+        let mut items: Vec<(f64, f64)> = self.centroids.drain(..).collect();
+        items.extend(self.buffer.drain(..).map(|x| (x, 1.0)));
+        self.centroids = merge_centroids(items, self.compression);
+    }
+
+    /// Returns the approximate `q` quantile, or NaN if there are no values.
+    ///
+    /// The quantile interpolates linearly between the centroids, and between the extreme
+    /// centroids and the smallest and largest values.
+    ///
+    /// # Panics
+    ///
+    /// `q` must be between 0 and 1, otherwise the code will panic.
+    pub fn quantile(&self, q: f64) -> f64 {
+        assert!(
+            (0.0..=1.0).contains(&q),
+            "ERROR - TDigest quantile: q must be between 0 and 1."
+        );
+        if self.count == 0 {
+            return f64::NAN;
+        }
+
+        let mut digest = self.clone();
+        digest.compress();
+        let centroids = &digest.centroids;
+        let total = self.count as f64;
+        let index = q * total;
+
+        let (first_mean, first_weight) = centroids[0];
+        if index < first_weight / 2.0 {
+            if first_weight == 1.0 {
+                return self.min;
+            }
+            return self.min + (first_mean - self.min) * index / (first_weight / 2.0);
+        }
+
+        let (last_mean, last_weight) = centroids[centroids.len() - 1];
+        if index > total - last_weight / 2.0 {
+            if last_weight == 1.0 {
+                return self.max;
+            }
+            let remaining = total - index;
+            return self.max - (self.max - last_mean) * remaining / (last_weight / 2.0);
+        }
+
+        let mut cumulative = first_weight / 2.0;
+        for pair in centroids.windows(2) {
+            let ((left, left_weight), (right, right_weight)) = (pair[0], pair[1]);
+            let step = (left_weight + right_weight) / 2.0;
+            if index <= cumulative + step {
+                return left + (right - left) * (index - cumulative) / step;
+            }
+            cumulative += step;
+        }
+        last_mean
+    }
+
+    /// Returns the approximate median, or NaN if there are no values.
+    pub fn median(&self) -> f64 {
+        self.quantile(0.5)
+    }
+}
+
+/// Returns the centroids after merging neighbouring items as long as every centroid stays
+/// within one unit of the `k1` scale function `compression / (2 pi) asin(2q - 1)`.
+fn merge_centroids(mut items: Vec<(f64, f64)>, compression: f64) -> Vec<(f64, f64)> {
+    items.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let total: f64 = items.iter().map(|(_, w)| w).sum();
+
+    let scale = |q: f64| compression / (2.0 * std::f64::consts::PI) * (2.0 * q - 1.0).asin();
+    let limit = |q: f64| {
+        let k = scale(q) + 1.0;
+        let angle = (k * 2.0 * std::f64::consts::PI / compression).min(std::f64::consts::FRAC_PI_2);
+        (angle.sin() + 1.0) / 2.0
+    };
+
+    let mut merged = Vec::new();
+    let mut items = items.into_iter();
+    let mut current = match items.next() {
+        Some(item) => item,
+        None => return merged,
+    };
+    let mut so_far = 0.0;
+    let mut q_limit = limit(0.0);
+
+    for (mean, weight) in items {
+        if (so_far + current.1 + weight) / total <= q_limit {
+            current.1 += weight;
+            current.0 += (mean - current.0) * weight / current.1;
+        } else {
+            so_far += current.1;
+            merged.push(current);
+            q_limit = limit(so_far / total);
+            current = (mean, weight);
+        }
+    }
+    merged.push(current);
+    merged
+}
+
+impl Accumulator for TDigest {
+    fn push(&mut self, x: f64) {
+        if x.is_nan() {
+            return;
+        }
+
+        self.count += 1;
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        self.buffer.push(x);
+        if self.buffer.len() as f64 >= 5.0 * self.compression {
+            self.compress();
+        }
+    }
+
+    /// Merges another t-digest, keeping the compression of this one.
+    fn merge(&mut self, other: &TDigest) {
+        if other.count == 0 {
+            return;
+        }
+
+        let mut items: Vec<(f64, f64)> = self.centroids.drain(..).collect();
+        items.extend(self.buffer.drain(..).map(|x| (x, 1.0)));
+        items.extend(other.centroids.iter().copied());
+        items.extend(other.buffer.iter().map(|&x| (x, 1.0)));
+        self.centroids = merge_centroids(items, self.compression);
+
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+}
+
+/// Exponentially weighted mean and variance of a stream.
+///
+/// The weight of a value decays by the factor `1 - alpha` with every newer value. The
+/// statistics are normalized by the total weight, so the first values are not biased towards
+/// the initial state.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::statistics::online::{Accumulator, ExponentialStats};
+///
+/// let mut ewm = ExponentialStats::new(0.5);
+/// ewm.push(1.0);
+/// ewm.push(4.0);
+///
+/// // The weights are 0.5 and 1.
+/// assert_eq!(3.0, ewm.mean());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExponentialStats {
+    alpha: f64,
+    count: usize,
+    weight: f64,
+    mean: f64,
+    m2: f64,
+}
+
+impl ExponentialStats {
+    /// Returns an empty accumulator.
+    ///
+    /// # Arguments
+    ///
+    /// * `alpha` - the smoothing factor. Larger values forget older values faster.
+    ///
+    /// # Panics
+    ///
+    /// `alpha` must be in (0, 1], otherwise the code will panic.
+    pub fn new(alpha: f64) -> ExponentialStats {
+        assert!(
+            alpha > 0.0 && alpha <= 1.0,
+            "ERROR - ExponentialStats: alpha must be in (0, 1]."
+        );
+        ExponentialStats {
+            alpha,
+            count: 0,
+            weight: 0.0,
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    /// Returns an empty accumulator with the smoothing factor for a span, `2 / (span + 1)`.
+    ///
+    /// # Panics
+    ///
+    /// The span must be at least 1, otherwise the code will panic.
+    pub fn with_span(span: f64) -> ExponentialStats {
+        assert!(
+            span >= 1.0,
+            "ERROR - ExponentialStats: The span must be at least 1."
+        );
+        ExponentialStats::new(2.0 / (span + 1.0))
+    }
+
+    /// Returns the smoothing factor.
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Returns the number of values.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the weighted mean, or NaN if there are no values.
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            f64::NAN
+        } else {
+            self.mean
+        }
+    }
+
+    /// Returns the weighted variance, normalized by the total weight, or NaN if there are no
+    /// values.
+    pub fn var(&self) -> f64 {
+        if self.count == 0 {
+            f64::NAN
+        } else {
+            self.m2 / self.weight
+        }
+    }
+
+    /// Returns the weighted standard deviation.
+    pub fn std(&self) -> f64 {
+        self.var().sqrt()
+    }
+
+    /// Adds the weighted statistics `(weight, mean, m2)` of newer values to these.
+    fn combine(&mut self, weight: f64, mean: f64, m2: f64) {
+        let total = self.weight + weight;
+        let delta = mean - self.mean;
+        self.mean += delta * weight / total;
+        self.m2 += m2 + delta * delta * self.weight * weight / total;
+        self.weight = total;
+    }
+}
+
+impl Accumulator for ExponentialStats {
+    fn push(&mut self, x: f64) {
+        if x.is_nan() {
+            return;
+        }
+
+        let decay = 1.0 - self.alpha;
+        self.weight *= decay;
+        self.m2 *= decay;
+        self.count += 1;
+        self.combine(1.0, x, 0.0);
+    }
+
+    /// Merges another accumulator whose values are all newer than the values of this one.
+    ///
+    /// The values of this accumulator are decayed by the number of values of the other one,
+    /// so the result is the same as pushing the values of both in order.
+    ///
+    /// # Panics
+    ///
+    /// Both accumulators must have the same smoothing factor, otherwise the code will panic.
+    fn merge(&mut self, other: &ExponentialStats) {
+        assert!(
+            self.alpha == other.alpha,
+            "ERROR - ExponentialStats: Only accumulators with the same alpha can be merged."
+        );
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            self.count = other.count;
+            self.weight = other.weight;
+            self.mean = other.mean;
+            self.m2 = other.m2;
+            return;
+        }
+
+        let decay = (1.0 - self.alpha).powf(other.count as f64);
+        self.weight *= decay;
+        self.m2 *= decay;
+        self.count += other.count;
+        self.combine(other.weight, other.mean, other.m2);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> Array {
+        Array::from_vec(
+            (0..1000)
+                .map(|i| 1e6 + (i as f64 * 0.7).sin() * 3.0)
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_running_stats() {
+        let xs = sample();
+        let mut stats = RunningStats::new();
+        stats.push_array(&xs);
+        stats.push(f64::NAN);
+
+        assert_eq!(1000, stats.count());
+        assert!((stats.mean() - xs.average()).abs() < 1e-6);
+        assert!((stats.var(1) - xs.var(1)).abs() < 1e-9);
+        assert_eq!(xs.min(), stats.min());
+        assert_eq!(xs.max(), stats.max());
+
+        let empty = RunningStats::new();
+        assert!(empty.mean().is_nan() && empty.min().is_nan());
+        assert!(stats.var(1000).is_nan());
+    }
+
+    #[test]
+    fn test_running_stats_merge() {
+        let xs = sample();
+        let mut whole = RunningStats::new();
+        whole.push_array(&xs);
+
+        let mut parts: Vec<RunningStats> = xs
+            .chunks(300)
+            .map(|chunk| {
+                let mut stats = RunningStats::new();
+                stats.push_array(&Array::from(chunk));
+                stats
+            })
+            .collect();
+        let mut merged = RunningStats::new();
+        for part in parts.iter_mut() {
+            merged.merge(part);
+        }
+
+        assert_eq!(whole.count(), merged.count());
+        assert!((whole.mean() - merged.mean()).abs() < 1e-9);
+        assert!((whole.var(0) - merged.var(0)).abs() < 1e-9);
+        assert_eq!(whole.min(), merged.min());
+    }
+
+    #[test]
+    fn test_running_covariance() {
+        let xs = Array::from(&[1.0, 2.0, 3.0, 4.0, 5.0, f64::NAN]);
+        let ys = Array::from(&[2.0, 4.1, 5.9, 8.2, 9.8, 1.0]);
+        let mut all = RunningCovariance::new();
+        all.push_arrays(&xs, &ys);
+
+        let mut first = RunningCovariance::new();
+        first.push_arrays(&xs.splice(0, 2), &ys.splice(0, 2));
+        let mut second = RunningCovariance::new();
+        second.push_arrays(&xs.splice(2, 6), &ys.splice(2, 6));
+        first.merge(&second);
+
+        // The covariance of the first five pairs is 4.925.
+        assert_eq!(5, all.count());
+        assert!((all.cov(1) - 4.925).abs() < 1e-12);
+        assert!((first.cov(1) - all.cov(1)).abs() < 1e-12);
+        assert!((first.corr() - all.corr()).abs() < 1e-12);
+        assert!(all.corr() > 0.99);
+    }
+
+    #[test]
+    fn test_tdigest_quantiles() {
+        let mut digest = TDigest::default();
+        // A permutation of 0..100000.
+        for i in 0..100_000u64 {
+            digest.push(((i * 7919) % 100_000) as f64);
+        }
+
+        assert_eq!(100_000, digest.count());
+        assert_eq!(0.0, digest.quantile(0.0));
+        assert_eq!(99_999.0, digest.quantile(1.0));
+        for &q in &[0.001, 0.01, 0.1, 0.5, 0.9, 0.99, 0.999] {
+            let exact = q * 99_999.0;
+            let error = (digest.quantile(q) - exact).abs() / 100_000.0;
+            assert!(error < 0.002, "q = {}: {}", q, digest.quantile(q));
+        }
+        assert!(digest.centroids.len() < 200);
+    }
+
+    #[test]
+    fn test_tdigest_small_and_merged() {
+        let mut digest = TDigest::default();
+        digest.push_array(&Array::from(&[3.0, 1.0, 2.0, 4.0]));
+        assert_eq!(2.5, digest.median());
+        assert!(TDigest::default().median().is_nan());
+
+        let mut first = TDigest::new(50.0);
+        let mut second = TDigest::new(50.0);
+        for i in 0..20_000 {
+            if i % 3 == 0 {
+                first.push(i as f64);
+            } else {
+                second.push(i as f64);
+            }
+        }
+        first.merge(&second);
+
+        assert_eq!(20_000, first.count());
+        assert!((first.median() - 9999.5).abs() < 100.0);
+        assert!((first.quantile(0.99) - 19_799.0).abs() < 20.0);
+    }
+
+    #[test]
+    fn test_exponential_stats() {
+        let xs = Array::from(&[1.0, 3.0, 2.0, 5.0, 4.0, 6.0]);
+        let alpha = 0.3;
+        let mut ewm = ExponentialStats::new(alpha);
+        ewm.push_array(&xs);
+
+        let weights: Vec<f64> = (0..6).map(|i| (1.0 - alpha).powi(5 - i)).collect();
+        let total: f64 = weights.iter().sum();
+        let mean = weights
+            .iter()
+            .zip(xs.iter())
+            .map(|(w, x)| w * x)
+            .sum::<f64>()
+            / total;
+        let var = weights
+            .iter()
+            .zip(xs.iter())
+            .map(|(w, x)| w * (x - mean) * (x - mean))
+            .sum::<f64>()
+            / total;
+
+        assert!((ewm.mean() - mean).abs() < 1e-14);
+        assert!((ewm.var() - var).abs() < 1e-14);
+
+        let mut first = ExponentialStats::new(alpha);
+        first.push_array(&xs.splice(0, 4));
+        let mut second = ExponentialStats::new(alpha);
+        second.push_array(&xs.splice(4, 6));
+        first.merge(&second);
+
+        assert_eq!(6, first.count());
+        assert!((first.mean() - mean).abs() < 1e-14);
+        assert!((first.var() - var).abs() < 1e-14);
+        assert_eq!(2.0 / 3.0, ExponentialStats::with_span(2.0).alpha());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_exponential_stats_merge_alpha() {
+        let mut first = ExponentialStats::new(0.3);
+        first.push(1.0);
+        let mut second = ExponentialStats::new(0.5);
+        second.push(2.0);
+        first.merge(&second);
+    }
+}
@@ -0,0 +1,1516 @@
+//! Probability - Probability distributions
+//!
+//! This module contains the `Distribution` trait and implementations of the common continuous
+//! and discrete probability distributions. Every distribution can evaluate its density or mass
+//! function, CDF, survival function and quantile function, either for a single value or
+//! element-wise for an Array, and can draw samples with a random number generator from the
+//! `random` module.
+//!
+//! The discrete distributions are defined on the integers. Their mass function is zero for any
+//! other value, and their CDF is a step function of `x`.
+
+use crate::linalg::array::Array;
+use crate::random::{samplers, Rng};
+use crate::statistics::functions::{beta_inc, erfc, gamma_p, gamma_q, lbeta, lgamma};
+
+use std::f64::consts::{PI, SQRT_2};
+
+/// The upper limit on the number of steps when inverting a CDF numerically.
+const MAX_ITERATIONS: usize = 200;
+
+/// A probability distribution over the real numbers.
+pub trait Distribution {
+    /// Returns the probability density function at `x`.
+    ///
+    /// For discrete distributions this is the probability mass function.
+    fn pdf(&self, x: f64) -> f64;
+
+    /// Returns the probability mass function at `x`, the same as `pdf`.
+    fn pmf(&self, x: f64) -> f64 {
+        self.pdf(x)
+    }
+
+    /// Returns the natural logarithm of the density or mass function at `x`.
+    fn logpdf(&self, x: f64) -> f64 {
+        self.pdf(x).ln()
+    }
+
+    /// Returns the cumulative distribution function `P(X <= x)`.
+    fn cdf(&self, x: f64) -> f64;
+
+    /// Returns the survival function `P(X > x) = 1 - cdf(x)`.
+    fn sf(&self, x: f64) -> f64 {
+        1.0 - self.cdf(x)
+    }
+
+    /// Returns the quantile function (percent point function), the inverse of the CDF.
+    ///
+    /// For discrete distributions this is the smallest `x` for which `cdf(x) >= p`. The result
+    /// is NaN when `p` is outside of `[0, 1]`.
+    fn quantile(&self, p: f64) -> f64;
+
+    /// Returns the mean of the distribution.
+    fn mean(&self) -> f64;
+
+    /// Returns the variance of the distribution.
+    fn variance(&self) -> f64;
+
+    /// Returns the standard deviation of the distribution.
+    fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// Draws a sample from the distribution.
+    ///
+    /// The distributions in this module use the portable samplers in `random::samplers`, so a
+    /// seeded generator gives the same samples on every platform. The default implementation
+    /// inverts the CDF at a uniform random number.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - the random number generator.
+    fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        self.quantile(rng.next_open_f64())
+    }
+
+    /// Draws `n` independent samples from the distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - the number of samples.
+    /// * `rng` - the random number generator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moonalloy::random::pcg::Pcg64;
+    /// use moonalloy::statistics::probability::{Distribution, Poisson};
+    ///
+    /// let mut rng = Pcg64::seed_from_u64(7);
+    /// let samples = Poisson::new(4.0).sample_n(100, &mut rng);
+    ///
+    /// assert_eq!(100, samples.len());
+    /// ```
+    fn sample_n(&self, n: usize, rng: &mut dyn Rng) -> Array {
+        Array::from_vec((0..n).map(|_| self.sample(rng)).collect())
+    }
+
+    /// Returns the density or mass function of every element of `xs`.
+    fn pdf_array(&self, xs: &Array) -> Array {
+        map(xs, |x| self.pdf(x))
+    }
+
+    /// Returns the logarithm of the density or mass function of every element of `xs`.
+    fn logpdf_array(&self, xs: &Array) -> Array {
+        map(xs, |x| self.logpdf(x))
+    }
+
+    /// Returns the CDF of every element of `xs`.
+    fn cdf_array(&self, xs: &Array) -> Array {
+        map(xs, |x| self.cdf(x))
+    }
+
+    /// Returns the survival function of every element of `xs`.
+    fn sf_array(&self, xs: &Array) -> Array {
+        map(xs, |x| self.sf(x))
+    }
+
+    /// Returns the quantile function of every element of `ps`.
+    fn quantile_array(&self, ps: &Array) -> Array {
+        map(ps, |p| self.quantile(p))
+    }
+}
+
+/// Applies `f` to every element of an Array.
+fn map(xs: &Array, f: impl Fn(f64) -> f64) -> Array {
+    Array::from_vec(xs.iter().map(|&x| f(x)).collect())
+}
+
+/// Evaluates a polynomial with the coefficients in increasing order of degree.
+fn polynomial(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().rev().fold(0.0, |acc, c| acc * x + c)
+}
+
+/// Returns the quantile function of the standard normal distribution.
+///
+/// Uses algorithm AS 241 by M. J. Wichura, which is accurate to about 1e-16.
+fn standard_normal_quantile(p: f64) -> f64 {
+    if !(0.0..=1.0).contains(&p) {
+        return f64::NAN;
+    }
+
+    let q = p - 0.5;
+    if q.abs() <= 0.425 {
+        let r = 0.180_625 - q * q;
+        let num = [
+            3.387_132_872_796_366_5,
+            133.141_667_891_784_38,
+            1_971.590_950_306_551_3,
+            13_731.693_765_509_46,
+            45_921.953_931_549_87,
+            67_265.770_927_008_7,
+            33_430.575_583_588_13,
+            2_509.080_928_730_122_7,
+        ];
+        let den = [
+            1.0,
+            42.313_330_701_600_91,
+            687.187_007_492_057_9,
+            5_394.196_021_424_751,
+            21_213.794_301_586_597,
+            39_307.895_800_092_71,
+            28_729.085_735_721_943,
+            5_226.495_278_852_546,
+        ];
+        return q * polynomial(&num, r) / polynomial(&den, r);
+    }
+
+    let r = if q < 0.0 { p } else { 1.0 - p };
+    if r == 0.0 {
+        return f64::INFINITY.copysign(q);
+    }
+
+    let r = (-r.ln()).sqrt();
+    let val = if r <= 5.0 {
+        let r = r - 1.6;
+        let num = [
+            1.423_437_110_749_683_5,
+            4.630_337_846_156_545,
+            5.769_497_221_460_691,
+            3.647_848_324_763_204_5,
+            1.270_458_252_452_368_4,
+            0.241_780_725_177_450_6,
+            0.022_723_844_989_269_184,
+            7.745_450_142_783_414e-4,
+        ];
+        let den = [
+            1.0,
+            2.053_191_626_637_759,
+            1.676_384_830_183_803_8,
+            0.689_767_334_985_1,
+            0.148_103_976_427_480_08,
+            0.015_198_666_563_616_457,
+            5.475_938_084_995_345e-4,
+            1.050_750_071_644_416_8e-9,
+        ];
+        polynomial(&num, r) / polynomial(&den, r)
+    } else {
+        let r = r - 5.0;
+        let num = [
+            6.657_904_643_501_103,
+            5.463_784_911_164_114,
+            1.784_826_539_917_291_3,
+            0.296_560_571_828_504_9,
+            0.026_532_189_526_576_124,
+            0.001_242_660_947_388_078_4,
+            2.711_555_568_743_487_6e-5,
+            2.010_334_399_292_288_1e-7,
+        ];
+        let den = [
+            1.0,
+            0.599_832_206_555_888,
+            0.136_929_880_922_735_8,
+            0.014_875_361_290_850_615,
+            7.868_691_311_456_133e-4,
+            1.846_318_317_510_054_8e-5,
+            1.421_511_758_316_446e-7,
+            2.044_263_103_389_939_7e-15,
+        ];
+        polynomial(&num, r) / polynomial(&den, r)
+    };
+
+    val.copysign(q)
+}
+
+/// Inverts the CDF of a continuous distribution with support `[lower, upper]`.
+///
+/// Newton steps are taken from `guess` while they stay inside a bracket around the root, and
+/// the bracket is bisected otherwise. The upper half is solved with the survival function to
+/// avoid cancellation.
+fn continuous_quantile(dist: &dyn Distribution, p: f64, lower: f64, upper: f64, guess: f64) -> f64 {
+    if !(0.0..=1.0).contains(&p) {
+        return f64::NAN;
+    }
+    if p == 0.0 {
+        return lower;
+    }
+    if p == 1.0 {
+        return upper;
+    }
+
+    // The residual increases with x in both halves.
+    let residual = |x: f64| {
+        if p > 0.5 {
+            (1.0 - p) - dist.sf(x)
+        } else {
+            dist.cdf(x) - p
+        }
+    };
+
+    // Expand infinite bounds until they bracket the root.
+    let (mut lo, mut hi) = (lower, upper);
+    let mut step = guess.abs().max(1.0);
+    if lo.is_infinite() {
+        lo = guess - step;
+        while residual(lo) > 0.0 {
+            step *= 2.0;
+            lo = guess - step;
+        }
+    }
+    step = guess.abs().max(1.0);
+    if hi.is_infinite() {
+        hi = guess + step;
+        while residual(hi) < 0.0 {
+            step *= 2.0;
+            hi = guess + step;
+        }
+    }
+
+    let mut x = if guess > lo && guess < hi {
+        guess
+    } else {
+        0.5 * (lo + hi)
+    };
+
+    for _ in 0..MAX_ITERATIONS {
+        let r = residual(x);
+        if r == 0.0 {
+            break;
+        }
+        if r < 0.0 {
+            lo = x;
+        } else {
+            hi = x;
+        }
+
+        let mut next = x - r / dist.pdf(x);
+        if !(next > lo && next < hi) {
+            next = 0.5 * (lo + hi);
+        }
+
+        let done = (next - x).abs() <= 4.0 * f64::EPSILON * x.abs() || next == lo || next == hi;
+        x = next;
+        if done {
+            break;
+        }
+    }
+
+    x
+}
+
+/// Returns the smallest integer `k >= lower` with `dist.cdf(k) >= p`, searching from `guess`.
+fn discrete_quantile(dist: &dyn Distribution, p: f64, lower: f64, guess: f64) -> f64 {
+    if !(0.0..=1.0).contains(&p) {
+        return f64::NAN;
+    }
+    if p == 0.0 {
+        return lower;
+    }
+
+    // A little slack keeps rounding errors in the CDF from skipping to the next integer.
+    let target = p * (1.0 - 64.0 * f64::EPSILON);
+
+    let mut hi = guess.floor().max(lower);
+    let mut step = 1.0;
+    while dist.cdf(hi) < target {
+        hi += step;
+        step *= 2.0;
+        if hi.is_infinite() {
+            return hi;
+        }
+    }
+
+    let mut lo = hi - 1.0;
+    step = 1.0;
+    while lo >= lower && dist.cdf(lo) >= target {
+        hi = lo;
+        lo -= step;
+        step *= 2.0;
+    }
+    let mut lo = lo.max(lower - 1.0);
+
+    // Invariant: cdf(lo) < target <= cdf(hi)
+    while hi - lo > 1.0 {
+        let mid = (0.5 * (lo + hi)).floor();
+        if dist.cdf(mid) >= target {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+
+    hi
+}
+
+/// Returns whether `x` is a whole number.
+fn is_integer(x: f64) -> bool {
+    x == x.floor()
+}
+
+/// The normal (Gaussian) distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normal {
+    mean: f64,
+    std_dev: f64,
+}
+
+impl Normal {
+    /// Returns a normal distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `mean` - the mean of the distribution.
+    /// * `std_dev` - the standard deviation of the distribution.
+    ///
+    /// # Panics
+    ///
+    /// The standard deviation must be positive, otherwise the code will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moonalloy::statistics::probability::{Distribution, Normal};
+    /// let normal = Normal::new(0.0, 1.0);
+    ///
+    /// assert_eq!(0.5, normal.cdf(0.0));
+    /// assert!((normal.quantile(0.975) - 1.959_963_984_540_054).abs() < 1e-15);
+    /// ```
+    pub fn new(mean: f64, std_dev: f64) -> Normal {
+        assert!(
+            std_dev > 0.0 && mean.is_finite(),
+            "ERROR - Normal: The standard deviation must be positive."
+        );
+        Normal { mean, std_dev }
+    }
+
+    /// Returns the standard normal distribution with mean 0 and standard deviation 1.
+    pub fn standard() -> Normal {
+        Normal::new(0.0, 1.0)
+    }
+}
+
+impl Distribution for Normal {
+    fn pdf(&self, x: f64) -> f64 {
+        self.logpdf(x).exp()
+    }
+
+    fn logpdf(&self, x: f64) -> f64 {
+        let z = (x - self.mean) / self.std_dev;
+        -0.5 * z * z - self.std_dev.ln() - 0.5 * (2.0 * PI).ln()
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        0.5 * erfc(-(x - self.mean) / (self.std_dev * SQRT_2))
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        0.5 * erfc((x - self.mean) / (self.std_dev * SQRT_2))
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        self.mean + self.std_dev * standard_normal_quantile(p)
+    }
+
+    fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    fn variance(&self) -> f64 {
+        self.std_dev * self.std_dev
+    }
+
+    fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        samplers::normal(rng, self.mean, self.std_dev)
+    }
+}
+
+/// The continuous uniform distribution on an interval `[lower, upper]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Uniform {
+    lower: f64,
+    upper: f64,
+}
+
+impl Uniform {
+    /// Returns a uniform distribution on `[lower, upper]`.
+    ///
+    /// # Panics
+    ///
+    /// `lower` must be strictly smaller than `upper`, otherwise the code will panic.
+    pub fn new(lower: f64, upper: f64) -> Uniform {
+        assert!(
+            lower < upper && lower.is_finite() && upper.is_finite(),
+            "ERROR - Uniform: The lower bound must be smaller than the upper bound."
+        );
+        Uniform { lower, upper }
+    }
+}
+
+impl Distribution for Uniform {
+    fn pdf(&self, x: f64) -> f64 {
+        if x >= self.lower && x <= self.upper {
+            1.0 / (self.upper - self.lower)
+        } else {
+            0.0
+        }
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        ((x - self.lower) / (self.upper - self.lower)).clamp(0.0, 1.0)
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        ((self.upper - x) / (self.upper - self.lower)).clamp(0.0, 1.0)
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        if !(0.0..=1.0).contains(&p) {
+            return f64::NAN;
+        }
+        self.lower + p * (self.upper - self.lower)
+    }
+
+    fn mean(&self) -> f64 {
+        0.5 * (self.lower + self.upper)
+    }
+
+    fn variance(&self) -> f64 {
+        (self.upper - self.lower).powi(2) / 12.0
+    }
+
+    fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        samplers::uniform(rng, self.lower, self.upper)
+    }
+}
+
+/// The exponential distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exponential {
+    rate: f64,
+}
+
+impl Exponential {
+    /// Returns an exponential distribution with the rate parameter `rate` (1 / mean).
+    ///
+    /// # Panics
+    ///
+    /// The rate must be positive, otherwise the code will panic.
+    pub fn new(rate: f64) -> Exponential {
+        assert!(
+            rate > 0.0 && rate.is_finite(),
+            "ERROR - Exponential: The rate must be positive."
+        );
+        Exponential { rate }
+    }
+}
+
+impl Distribution for Exponential {
+    fn pdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            0.0
+        } else {
+            self.rate * (-self.rate * x).exp()
+        }
+    }
+
+    fn logpdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            f64::NEG_INFINITY
+        } else {
+            self.rate.ln() - self.rate * x
+        }
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            0.0
+        } else {
+            -(-self.rate * x).exp_m1()
+        }
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            1.0
+        } else {
+            (-self.rate * x).exp()
+        }
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        if !(0.0..=1.0).contains(&p) {
+            return f64::NAN;
+        }
+        -(-p).ln_1p() / self.rate
+    }
+
+    fn mean(&self) -> f64 {
+        1.0 / self.rate
+    }
+
+    fn variance(&self) -> f64 {
+        1.0 / (self.rate * self.rate)
+    }
+
+    fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        samplers::exponential(rng, self.rate)
+    }
+}
+
+/// The gamma distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gamma {
+    shape: f64,
+    scale: f64,
+}
+
+impl Gamma {
+    /// Returns a gamma distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `shape` - the shape parameter `k`.
+    /// * `scale` - the scale parameter `θ`, the inverse of the rate.
+    ///
+    /// # Panics
+    ///
+    /// Both parameters must be positive, otherwise the code will panic.
+    pub fn new(shape: f64, scale: f64) -> Gamma {
+        assert!(
+            shape > 0.0 && scale > 0.0 && shape.is_finite() && scale.is_finite(),
+            "ERROR - Gamma: The shape and scale must be positive."
+        );
+        Gamma { shape, scale }
+    }
+}
+
+impl Distribution for Gamma {
+    fn pdf(&self, x: f64) -> f64 {
+        self.logpdf(x).exp()
+    }
+
+    fn logpdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        if x == 0.0 {
+            return if self.shape < 1.0 {
+                f64::INFINITY
+            } else if self.shape == 1.0 {
+                -self.scale.ln()
+            } else {
+                f64::NEG_INFINITY
+            };
+        }
+
+        (self.shape - 1.0) * x.ln()
+            - x / self.scale
+            - lgamma(self.shape)
+            - self.shape * self.scale.ln()
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            0.0
+        } else {
+            gamma_p(self.shape, x / self.scale)
+        }
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            1.0
+        } else {
+            gamma_q(self.shape, x / self.scale)
+        }
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        // The Wilson-Hilferty approximation as the starting point.
+        let c = 1.0 / (9.0 * self.shape);
+        let z = standard_normal_quantile(p.clamp(1e-300, 1.0 - 1e-16));
+        let guess = (self.shape * self.scale * (1.0 - c + z * c.sqrt()).powi(3)).max(1e-300);
+
+        continuous_quantile(self, p, 0.0, f64::INFINITY, guess)
+    }
+
+    fn mean(&self) -> f64 {
+        self.shape * self.scale
+    }
+
+    fn variance(&self) -> f64 {
+        self.shape * self.scale * self.scale
+    }
+
+    fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        samplers::gamma(rng, self.shape, self.scale)
+    }
+}
+
+/// The beta distribution on `[0, 1]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Beta {
+    alpha: f64,
+    beta: f64,
+}
+
+impl Beta {
+    /// Returns a beta distribution with the shape parameters `alpha` and `beta`.
+    ///
+    /// # Panics
+    ///
+    /// Both parameters must be positive, otherwise the code will panic.
+    pub fn new(alpha: f64, beta: f64) -> Beta {
+        assert!(
+            alpha > 0.0 && beta > 0.0 && alpha.is_finite() && beta.is_finite(),
+            "ERROR - Beta: The shape parameters must be positive."
+        );
+        Beta { alpha, beta }
+    }
+}
+
+impl Distribution for Beta {
+    fn pdf(&self, x: f64) -> f64 {
+        self.logpdf(x).exp()
+    }
+
+    fn logpdf(&self, x: f64) -> f64 {
+        if !(0.0..=1.0).contains(&x) {
+            return f64::NEG_INFINITY;
+        }
+
+        let log_x = if self.alpha == 1.0 {
+            0.0
+        } else {
+            (self.alpha - 1.0) * x.ln()
+        };
+        let log_1mx = if self.beta == 1.0 {
+            0.0
+        } else {
+            (self.beta - 1.0) * (-x).ln_1p()
+        };
+        log_x + log_1mx - lbeta(self.alpha, self.beta)
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        beta_inc(self.alpha, self.beta, x.clamp(0.0, 1.0))
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        beta_inc(self.beta, self.alpha, 1.0 - x.clamp(0.0, 1.0))
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        continuous_quantile(self, p, 0.0, 1.0, self.mean())
+    }
+
+    fn mean(&self) -> f64 {
+        self.alpha / (self.alpha + self.beta)
+    }
+
+    fn variance(&self) -> f64 {
+        let sum = self.alpha + self.beta;
+        self.alpha * self.beta / (sum * sum * (sum + 1.0))
+    }
+
+    fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        samplers::beta(rng, self.alpha, self.beta)
+    }
+}
+
+/// The chi-squared distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChiSquared {
+    df: f64,
+    gamma: Gamma,
+}
+
+impl ChiSquared {
+    /// Returns a chi-squared distribution with `df` degrees of freedom.
+    ///
+    /// # Panics
+    ///
+    /// The degrees of freedom must be positive, otherwise the code will panic.
+    pub fn new(df: f64) -> ChiSquared {
+        assert!(
+            df > 0.0 && df.is_finite(),
+            "ERROR - ChiSquared: The degrees of freedom must be positive."
+        );
+        ChiSquared {
+            df,
+            gamma: Gamma::new(0.5 * df, 2.0),
+        }
+    }
+}
+
+impl Distribution for ChiSquared {
+    fn pdf(&self, x: f64) -> f64 {
+        self.gamma.pdf(x)
+    }
+
+    fn logpdf(&self, x: f64) -> f64 {
+        self.gamma.logpdf(x)
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        self.gamma.cdf(x)
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        self.gamma.sf(x)
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        self.gamma.quantile(p)
+    }
+
+    fn mean(&self) -> f64 {
+        self.df
+    }
+
+    fn variance(&self) -> f64 {
+        2.0 * self.df
+    }
+
+    fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        samplers::chi_squared(rng, self.df)
+    }
+}
+
+/// Student's t-distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StudentT {
+    df: f64,
+}
+
+impl StudentT {
+    /// Returns a t-distribution with `df` degrees of freedom.
+    ///
+    /// # Panics
+    ///
+    /// The degrees of freedom must be positive and finite, otherwise the code will panic.
+    pub fn new(df: f64) -> StudentT {
+        assert!(
+            df > 0.0 && df.is_finite(),
+            "ERROR - StudentT: The degrees of freedom must be positive and finite."
+        );
+        StudentT { df }
+    }
+
+    /// Returns `P(|T| > |t|) / 2`, the probability of the tail beyond `t`.
+    fn tail(&self, t: f64) -> f64 {
+        if t.is_infinite() {
+            return 0.0;
+        }
+        0.5 * beta_inc(0.5 * self.df, 0.5, self.df / (self.df + t * t))
+    }
+}
+
+impl Distribution for StudentT {
+    fn pdf(&self, x: f64) -> f64 {
+        self.logpdf(x).exp()
+    }
+
+    fn logpdf(&self, x: f64) -> f64 {
+        let v = self.df;
+        -0.5 * (v + 1.0) * (x * x / v).ln_1p() - 0.5 * v.ln() - lbeta(0.5 * v, 0.5)
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            self.tail(x)
+        } else {
+            1.0 - self.tail(x)
+        }
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        self.cdf(-x)
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        let guess = standard_normal_quantile(p.clamp(1e-300, 1.0 - 1e-16));
+        continuous_quantile(self, p, f64::NEG_INFINITY, f64::INFINITY, guess)
+    }
+
+    /// Returns 0 for more than 1 degree of freedom, and NaN otherwise.
+    fn mean(&self) -> f64 {
+        if self.df > 1.0 {
+            0.0
+        } else {
+            f64::NAN
+        }
+    }
+
+    /// Returns `df / (df - 2)` for more than 2 degrees of freedom, infinity for more than 1,
+    /// and NaN otherwise.
+    fn variance(&self) -> f64 {
+        match self.df {
+            v if v > 2.0 => v / (v - 2.0),
+            v if v > 1.0 => f64::INFINITY,
+            _ => f64::NAN,
+        }
+    }
+
+    fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        samplers::student_t(rng, self.df)
+    }
+}
+
+/// The F-distribution (Fisher-Snedecor distribution).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FisherF {
+    df1: f64,
+    df2: f64,
+}
+
+impl FisherF {
+    /// Returns an F-distribution with `df1` and `df2` degrees of freedom.
+    ///
+    /// # Panics
+    ///
+    /// Both degrees of freedom must be positive, otherwise the code will panic.
+    pub fn new(df1: f64, df2: f64) -> FisherF {
+        assert!(
+            df1 > 0.0 && df2 > 0.0 && df1.is_finite() && df2.is_finite(),
+            "ERROR - FisherF: The degrees of freedom must be positive."
+        );
+        FisherF { df1, df2 }
+    }
+}
+
+impl Distribution for FisherF {
+    fn pdf(&self, x: f64) -> f64 {
+        self.logpdf(x).exp()
+    }
+
+    fn logpdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        let (d1, d2) = (self.df1, self.df2);
+        0.5 * (d1 * (d1 * x).ln() + d2 * d2.ln() - (d1 + d2) * (d1 * x + d2).ln())
+            - x.ln()
+            - lbeta(0.5 * d1, 0.5 * d2)
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            return 0.0;
+        }
+        let (d1, d2) = (self.df1, self.df2);
+        beta_inc(0.5 * d1, 0.5 * d2, d1 * x / (d1 * x + d2))
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            return 1.0;
+        }
+        let (d1, d2) = (self.df1, self.df2);
+        beta_inc(0.5 * d2, 0.5 * d1, d2 / (d1 * x + d2))
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        let guess = if self.df2 > 2.0 { self.mean() } else { 1.0 };
+        continuous_quantile(self, p, 0.0, f64::INFINITY, guess)
+    }
+
+    /// Returns `df2 / (df2 - 2)` for `df2 > 2`, and NaN otherwise.
+    fn mean(&self) -> f64 {
+        if self.df2 > 2.0 {
+            self.df2 / (self.df2 - 2.0)
+        } else {
+            f64::NAN
+        }
+    }
+
+    /// Returns the variance for `df2 > 4`, and NaN otherwise.
+    fn variance(&self) -> f64 {
+        let (d1, d2) = (self.df1, self.df2);
+        if d2 > 4.0 {
+            2.0 * d2 * d2 * (d1 + d2 - 2.0) / (d1 * (d2 - 2.0).powi(2) * (d2 - 4.0))
+        } else {
+            f64::NAN
+        }
+    }
+
+    fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        samplers::fisher_f(rng, self.df1, self.df2)
+    }
+}
+
+/// The binomial distribution, the number of successes in `n` independent trials.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Binomial {
+    n: usize,
+    p: f64,
+}
+
+impl Binomial {
+    /// Returns a binomial distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - the number of trials.
+    /// * `p` - the probability of success in each trial.
+    ///
+    /// # Panics
+    ///
+    /// `p` must be in `[0, 1]`, otherwise the code will panic.
+    pub fn new(n: usize, p: f64) -> Binomial {
+        assert!(
+            (0.0..=1.0).contains(&p),
+            "ERROR - Binomial: The probability must be between 0 and 1."
+        );
+        Binomial { n, p }
+    }
+}
+
+impl Distribution for Binomial {
+    fn pdf(&self, x: f64) -> f64 {
+        self.logpdf(x).exp()
+    }
+
+    fn logpdf(&self, x: f64) -> f64 {
+        let n = self.n as f64;
+        if !is_integer(x) || x < 0.0 || x > n {
+            return f64::NEG_INFINITY;
+        }
+        if self.p == 0.0 || self.p == 1.0 {
+            let certain = if self.p == 0.0 { 0.0 } else { n };
+            return if x == certain { 0.0 } else { f64::NEG_INFINITY };
+        }
+
+        lgamma(n + 1.0) - lgamma(x + 1.0) - lgamma(n - x + 1.0)
+            + x * self.p.ln()
+            + (n - x) * (-self.p).ln_1p()
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        let (n, k) = (self.n as f64, x.floor());
+        if k < 0.0 {
+            0.0
+        } else if k >= n {
+            1.0
+        } else {
+            beta_inc(n - k, k + 1.0, 1.0 - self.p)
+        }
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        let (n, k) = (self.n as f64, x.floor());
+        if k < 0.0 {
+            1.0
+        } else if k >= n {
+            0.0
+        } else {
+            beta_inc(k + 1.0, n - k, self.p)
+        }
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        let guess = self.mean() + self.std_dev() * standard_normal_quantile(p.clamp(0.0, 1.0));
+        discrete_quantile(self, p, 0.0, guess.clamp(0.0, self.n as f64))
+    }
+
+    fn mean(&self) -> f64 {
+        self.n as f64 * self.p
+    }
+
+    fn variance(&self) -> f64 {
+        self.n as f64 * self.p * (1.0 - self.p)
+    }
+
+    fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        samplers::binomial(rng, self.n as u64, self.p) as f64
+    }
+}
+
+/// The Poisson distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Poisson {
+    rate: f64,
+}
+
+impl Poisson {
+    /// Returns a Poisson distribution with the mean `rate`.
+    ///
+    /// # Panics
+    ///
+    /// The rate must be positive, otherwise the code will panic.
+    pub fn new(rate: f64) -> Poisson {
+        assert!(
+            rate > 0.0 && rate.is_finite(),
+            "ERROR - Poisson: The rate must be positive."
+        );
+        Poisson { rate }
+    }
+}
+
+impl Distribution for Poisson {
+    fn pdf(&self, x: f64) -> f64 {
+        self.logpdf(x).exp()
+    }
+
+    fn logpdf(&self, x: f64) -> f64 {
+        if !is_integer(x) || x < 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        x * self.rate.ln() - self.rate - lgamma(x + 1.0)
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        let k = x.floor();
+        if k < 0.0 {
+            0.0
+        } else {
+            gamma_q(k + 1.0, self.rate)
+        }
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        let k = x.floor();
+        if k < 0.0 {
+            1.0
+        } else {
+            gamma_p(k + 1.0, self.rate)
+        }
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        let guess = self.rate + self.rate.sqrt() * standard_normal_quantile(p.clamp(0.0, 1.0));
+        discrete_quantile(self, p, 0.0, guess.max(0.0))
+    }
+
+    fn mean(&self) -> f64 {
+        self.rate
+    }
+
+    fn variance(&self) -> f64 {
+        self.rate
+    }
+
+    fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        samplers::poisson(rng, self.rate) as f64
+    }
+}
+
+/// The geometric distribution, the number of trials up to and including the first success.
+///
+/// The support is `1, 2, 3, ...`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geometric {
+    p: f64,
+}
+
+impl Geometric {
+    /// Returns a geometric distribution with the probability of success `p` in each trial.
+    ///
+    /// # Panics
+    ///
+    /// `p` must be in `(0, 1]`, otherwise the code will panic.
+    pub fn new(p: f64) -> Geometric {
+        assert!(
+            p > 0.0 && p <= 1.0,
+            "ERROR - Geometric: The probability must be in (0, 1]."
+        );
+        Geometric { p }
+    }
+}
+
+impl Distribution for Geometric {
+    fn pdf(&self, x: f64) -> f64 {
+        self.logpdf(x).exp()
+    }
+
+    fn logpdf(&self, x: f64) -> f64 {
+        if !is_integer(x) || x < 1.0 {
+            return f64::NEG_INFINITY;
+        }
+        if self.p == 1.0 {
+            return if x == 1.0 { 0.0 } else { f64::NEG_INFINITY };
+        }
+        (x - 1.0) * (-self.p).ln_1p() + self.p.ln()
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        let k = x.floor();
+        if k < 1.0 {
+            0.0
+        } else {
+            -(k * (-self.p).ln_1p()).exp_m1()
+        }
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        let k = x.floor();
+        if k < 1.0 {
+            1.0
+        } else {
+            (k * (-self.p).ln_1p()).exp()
+        }
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        let guess = ((-p).ln_1p() / (-self.p).ln_1p()).ceil();
+        discrete_quantile(self, p, 1.0, if guess.is_finite() { guess } else { 1.0 })
+    }
+
+    fn mean(&self) -> f64 {
+        1.0 / self.p
+    }
+
+    fn variance(&self) -> f64 {
+        (1.0 - self.p) / (self.p * self.p)
+    }
+
+    fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        samplers::geometric(rng, self.p) as f64
+    }
+}
+
+/// The negative binomial distribution, the number of failures before the `r`th success.
+///
+/// The support is `0, 1, 2, ...`. The number of successes `r` can be any positive number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NegativeBinomial {
+    r: f64,
+    p: f64,
+}
+
+impl NegativeBinomial {
+    /// Returns a negative binomial distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `r` - the number of successes.
+    /// * `p` - the probability of success in each trial.
+    ///
+    /// # Panics
+    ///
+    /// `r` must be positive and `p` must be in `(0, 1]`, otherwise the code will panic.
+    pub fn new(r: f64, p: f64) -> NegativeBinomial {
+        assert!(
+            r > 0.0 && r.is_finite() && p > 0.0 && p <= 1.0,
+            "ERROR - NegativeBinomial: r must be positive and the probability in (0, 1]."
+        );
+        NegativeBinomial { r, p }
+    }
+}
+
+impl Distribution for NegativeBinomial {
+    fn pdf(&self, x: f64) -> f64 {
+        self.logpdf(x).exp()
+    }
+
+    fn logpdf(&self, x: f64) -> f64 {
+        if !is_integer(x) || x < 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        if self.p == 1.0 {
+            return if x == 0.0 { 0.0 } else { f64::NEG_INFINITY };
+        }
+        lgamma(x + self.r) - lgamma(x + 1.0) - lgamma(self.r)
+            + self.r * self.p.ln()
+            + x * (-self.p).ln_1p()
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        let k = x.floor();
+        if k < 0.0 {
+            0.0
+        } else {
+            beta_inc(self.r, k + 1.0, self.p)
+        }
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        let k = x.floor();
+        if k < 0.0 {
+            1.0
+        } else {
+            beta_inc(k + 1.0, self.r, 1.0 - self.p)
+        }
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        let guess = self.mean() + self.std_dev() * standard_normal_quantile(p.clamp(0.0, 1.0));
+        discrete_quantile(self, p, 0.0, guess.max(0.0))
+    }
+
+    fn mean(&self) -> f64 {
+        self.r * (1.0 - self.p) / self.p
+    }
+
+    fn variance(&self) -> f64 {
+        self.r * (1.0 - self.p) / (self.p * self.p)
+    }
+
+    fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        samplers::negative_binomial(rng, self.r, self.p) as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::random::pcg::Pcg64;
+
+    /// Asserts that `actual` is within `tol` of `expected`, relative to `expected` if it is
+    /// larger than 1.
+    fn assert_close(expected: f64, actual: f64, tol: f64) {
+        let err = (expected - actual).abs() / expected.abs().max(1.0);
+        assert!(
+            err <= tol,
+            "expected {}, found {} (error {:e})",
+            expected,
+            actual,
+            err
+        );
+    }
+
+    #[test]
+    fn test_normal() {
+        let normal = Normal::new(1.0, 2.0);
+
+        assert_close(0.176_032_663_382_149_74, normal.pdf(2.0), 1e-15);
+        assert_close(0.691_462_461_274_013_1, normal.cdf(2.0), 1e-15);
+        assert_close(0.308_537_538_725_986_9, normal.sf(2.0), 1e-15);
+        assert_eq!(1.0, normal.mean());
+        assert_eq!(4.0, normal.variance());
+    }
+
+    #[test]
+    fn test_normal_quantile() {
+        let normal = Normal::standard();
+
+        assert_eq!(0.0, normal.quantile(0.5));
+        assert_close(1.959_963_984_540_054_2, normal.quantile(0.975), 1e-15);
+        assert_close(-0.524_400_512_708_040_8, normal.quantile(0.3), 1e-15);
+        assert_close(-6.361_340_902_404_056, normal.quantile(1e-10), 1e-15);
+        assert!((normal.cdf(normal.quantile(1e-300)) / 1e-300 - 1.0).abs() < 1e-12);
+        assert_eq!(f64::NEG_INFINITY, normal.quantile(0.0));
+        assert!(normal.quantile(1.5).is_nan());
+    }
+
+    #[test]
+    fn test_uniform_and_exponential() {
+        let uniform = Uniform::new(-1.0, 3.0);
+        assert_eq!(0.25, uniform.pdf(0.0));
+        assert_eq!(0.0, uniform.pdf(4.0));
+        assert_eq!(0.75, uniform.cdf(2.0));
+        assert_eq!(2.0, uniform.quantile(0.75));
+        assert_close(4.0 / 3.0, uniform.variance(), 1e-15);
+
+        let exponential = Exponential::new(2.0);
+        assert_close(2.0 * (-2.0_f64).exp(), exponential.pdf(1.0), 1e-15);
+        assert_close(1.0 - (-2.0_f64).exp(), exponential.cdf(1.0), 1e-15);
+        assert_close(1.0, exponential.quantile(exponential.cdf(1.0)), 1e-15);
+        assert_eq!(0.25, exponential.variance());
+    }
+
+    #[test]
+    fn test_gamma() {
+        let gamma = Gamma::new(2.5, 2.0);
+
+        assert_close(0.154_180_329_803_769_28, gamma.pdf(3.0), 1e-14);
+        assert_close(0.300_014_164_121_372_5, gamma.cdf(3.0), 1e-14);
+        assert_close(0.699_985_835_878_627_5, gamma.sf(3.0), 1e-14);
+        assert_close(9.236_356_899_781_118, gamma.quantile(0.9), 1e-14);
+        assert_close(0.554_298_076_728_277_1, gamma.quantile(0.01), 1e-14);
+        assert_eq!(5.0, gamma.mean());
+        assert_eq!(10.0, gamma.variance());
+    }
+
+    #[test]
+    fn test_beta() {
+        let beta = Beta::new(2.0, 5.0);
+
+        assert_close(2.1609, beta.pdf(0.3), 1e-14);
+        assert_close(0.579_825, beta.cdf(0.3), 1e-14);
+        assert_close(0.420_175, beta.sf(0.3), 1e-14);
+        assert_close(0.264_449_983_295_659_96, beta.quantile(0.5), 1e-14);
+        assert_eq!(0.0, beta.pdf(1.5));
+    }
+
+    #[test]
+    fn test_chi_squared() {
+        let chi2 = ChiSquared::new(3.0);
+
+        assert_close(0.207_553_748_710_297_35, chi2.pdf(2.0), 1e-14);
+        assert_close(0.427_593_295_529_120_2, chi2.cdf(2.0), 1e-14);
+        assert_close(7.814_727_903_251_18, chi2.quantile(0.95), 1e-14);
+        assert_eq!(6.0, chi2.variance());
+    }
+
+    #[test]
+    fn test_student_t() {
+        let t = StudentT::new(5.0);
+
+        assert_close(0.065_090_310_326_216_47, t.pdf(2.0), 1e-14);
+        assert_close(0.949_030_260_585_070_8, t.cdf(2.0), 1e-14);
+        assert_close(0.050_969_739_414_929_18, t.sf(2.0), 1e-14);
+        assert_close(2.570_581_835_636_315_5, t.quantile(0.975), 1e-14);
+        assert_close(-1.475_884_048_824_481, t.quantile(0.1), 1e-14);
+        assert_close(5.0 / 3.0, t.variance(), 1e-15);
+        assert!(StudentT::new(1.0).mean().is_nan());
+    }
+
+    #[test]
+    fn test_fisher_f() {
+        let f = FisherF::new(3.0, 10.0);
+
+        assert_close(0.148_210_941_559_117_85, f.pdf(2.0), 1e-14);
+        assert_close(0.821_992_592_624_824_6, f.cdf(2.0), 1e-14);
+        assert_close(0.178_007_407_375_175_4, f.sf(2.0), 1e-14);
+        assert_close(3.708_264_819_046_844_5, f.quantile(0.95), 1e-14);
+        assert_eq!(1.25, f.mean());
+    }
+
+    #[test]
+    fn test_binomial() {
+        let binomial = Binomial::new(10, 0.3);
+
+        assert_close(0.266_827_932, binomial.pmf(3.0), 1e-14);
+        assert_eq!(0.0, binomial.pmf(3.5));
+        assert_close(0.649_610_718_4, binomial.cdf(3.0), 1e-14);
+        assert_close(0.649_610_718_4, binomial.cdf(3.7), 1e-14);
+        assert_close(0.350_389_281_6, binomial.sf(3.0), 1e-14);
+        assert_eq!(3.0, binomial.quantile(0.5));
+        assert_eq!(3.0, binomial.quantile(binomial.cdf(3.0)));
+        assert_eq!(10.0, binomial.quantile(1.0));
+        assert_close(2.1, binomial.variance(), 1e-15);
+    }
+
+    #[test]
+    fn test_poisson() {
+        let poisson = Poisson::new(4.5);
+
+        assert_close(0.168_717_884_924_555_03, poisson.pmf(3.0), 1e-14);
+        assert_close(0.342_295_955_834_591_07, poisson.cdf(3.0), 1e-14);
+        assert_close(0.657_704_044_165_409, poisson.sf(3.0), 1e-14);
+        assert_eq!(3.0, poisson.quantile(0.3));
+        assert_eq!(4.0, poisson.quantile(0.35));
+        assert_eq!(0.0, poisson.cdf(-1.0));
+    }
+
+    #[test]
+    fn test_geometric() {
+        let geometric = Geometric::new(0.25);
+
+        assert_eq!(0.0, geometric.pmf(0.0));
+        assert_close(0.75 * 0.75 * 0.25, geometric.pmf(3.0), 1e-15);
+        assert_close(1.0 - 0.75f64.powi(3), geometric.cdf(3.0), 1e-15);
+        assert_eq!(3.0, geometric.quantile(1.0 - 0.75f64.powi(3)));
+        assert_eq!(1.0, geometric.quantile(0.1));
+        assert_eq!(4.0, geometric.mean());
+    }
+
+    #[test]
+    fn test_negative_binomial() {
+        let nb = NegativeBinomial::new(3.0, 0.4);
+
+        assert_close(0.138_24, nb.pmf(2.0), 1e-14);
+        assert_close(0.317_44, nb.cdf(2.0), 1e-14);
+        assert_close(1.0 - 0.317_44, nb.sf(2.0), 1e-14);
+        assert_eq!(2.0, nb.quantile(0.3));
+        assert_close(4.5, nb.mean(), 1e-15);
+    }
+
+    #[test]
+    fn test_quantile_inverts_cdf() {
+        let distributions: Vec<Box<dyn Distribution>> = vec![
+            Box::new(Normal::new(-2.0, 0.5)),
+            Box::new(Gamma::new(0.3, 1.0)),
+            Box::new(Gamma::new(50.0, 0.1)),
+            Box::new(Beta::new(0.5, 2.0)),
+            Box::new(Beta::new(30.0, 2.0)),
+            Box::new(ChiSquared::new(1.0)),
+            Box::new(StudentT::new(1.0)),
+            Box::new(StudentT::new(30.0)),
+            Box::new(FisherF::new(1.0, 1.0)),
+        ];
+
+        for dist in &distributions {
+            for &p in &[1e-12, 1e-3, 0.2, 0.5, 0.8, 0.999, 1.0 - 1e-12] {
+                let x = dist.quantile(p);
+                let back = if p > 0.5 {
+                    1.0 - dist.sf(x)
+                } else {
+                    dist.cdf(x)
+                };
+                assert!(
+                    (back - p).abs() <= 1e-9 * p.min(1.0 - p),
+                    "quantile({}) = {} has cdf {}",
+                    p,
+                    x,
+                    back
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_array_evaluation() {
+        let normal = Normal::standard();
+        let xs = Array::from(&[-1.0, 0.0, 1.0]);
+
+        let cdf = normal.cdf_array(&xs);
+        assert_eq!(0.5, cdf[1]);
+        assert_close(1.0, cdf[0] + cdf[2], 1e-15);
+        assert_eq!(normal.pdf(1.0), normal.pdf_array(&xs)[2]);
+        assert_eq!(normal.logpdf(-1.0), normal.logpdf_array(&xs)[0]);
+        assert_eq!(normal.sf(1.0), normal.sf_array(&xs)[2]);
+        let back = normal.quantile_array(&cdf);
+        for i in 0..xs.len() {
+            assert_close(xs[i], back[i], 1e-15);
+        }
+    }
+
+    #[test]
+    fn test_sampling() {
+        let n = 10_000;
+        let mut rng = Pcg64::seed_from_u64(32);
+
+        let gamma = Gamma::new(2.0, 3.0);
+        let samples = gamma.sample_n(n, &mut rng);
+        assert_eq!(n, samples.len());
+        assert!((gamma.mean() - samples.average()).abs() < 5.0 * gamma.std_dev() / 100.0);
+
+        let poisson = Poisson::new(3.0);
+        let samples = poisson.sample_n(n, &mut rng);
+        assert!(samples.iter().all(|&k| is_integer(k) && k >= 0.0));
+        assert!((poisson.mean() - samples.average()).abs() < 5.0 * poisson.std_dev() / 100.0);
+    }
+
+    #[test]
+    fn test_default_sampling_inverts_the_cdf() {
+        /// A distribution that only implements the required methods.
+        struct Shifted(Exponential);
+
+        impl Distribution for Shifted {
+            fn pdf(&self, x: f64) -> f64 {
+                self.0.pdf(x - 1.0)
+            }
+            fn cdf(&self, x: f64) -> f64 {
+                self.0.cdf(x - 1.0)
+            }
+            fn quantile(&self, p: f64) -> f64 {
+                self.0.quantile(p) + 1.0
+            }
+            fn mean(&self) -> f64 {
+                self.0.mean() + 1.0
+            }
+            fn variance(&self) -> f64 {
+                self.0.variance()
+            }
+        }
+
+        let shifted = Shifted(Exponential::new(1.0));
+        let mut rng = Pcg64::seed_from_u64(32);
+        let samples = shifted.sample_n(10_000, &mut rng);
+
+        assert!(samples.iter().all(|&x| x > 1.0));
+        assert!((samples.average() - 2.0).abs() < 0.05);
+    }
+}
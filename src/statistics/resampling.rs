@@ -0,0 +1,566 @@
+//! Resampling - Bootstrap confidence intervals and permutation tests
+//!
+//! Resampling methods approximate the sampling distribution of any statistic by recomputing it
+//! on many resamples of the data, which is useful when there is no closed form for it. The
+//! statistics are passed as closures over `Array`.
+//!
+//! # Reproducibility
+//!
+//! The resamples are drawn with a `Pcg64` generator seeded from `ResampleOptions::seed`. The
+//! `i`-th resample always uses the `i`-th block of 2^64 numbers of that generator, so the
+//! results only depend on the seed and the number of resamples, not on the number of threads.
+
+use crate::linalg::array::Array;
+use crate::random::pcg::Pcg64;
+use crate::random::{shuffle, Rng};
+use crate::statistics::descriptive::QuantileMethod;
+use crate::statistics::probability::{Distribution, Normal};
+use crate::statistics::tests::{Alternative, TestResult};
+
+use std::thread;
+
+/// The relative tolerance for resampled statistics to count as equal to the observed one.
+const TOLERANCE: f64 = 1e-12;
+
+/// The method to compute a bootstrap confidence interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BootstrapMethod {
+    /// The quantiles of the bootstrap distribution.
+    #[default]
+    Percentile,
+    /// The quantiles of the bootstrap distribution reflected around the estimate.
+    Basic,
+    /// The bias-corrected and accelerated percentile interval by Efron, with the acceleration
+    /// estimated by the jackknife.
+    Bca,
+}
+
+/// The options of a resampling method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResampleOptions {
+    /// The number of resamples.
+    pub resamples: usize,
+    /// The seed of the random number generator.
+    pub seed: u64,
+    /// The number of threads to compute the resamples on.
+    pub threads: usize,
+}
+
+impl Default for ResampleOptions {
+    /// Returns 9999 resamples with the seed 0 on a single thread.
+    fn default() -> ResampleOptions {
+        ResampleOptions {
+            resamples: 9999,
+            seed: 0,
+            threads: 1,
+        }
+    }
+}
+
+/// The outcome of a bootstrap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bootstrap {
+    /// The statistic of the original sample.
+    pub estimate: f64,
+    /// The mean of the bootstrap distribution minus the estimate.
+    pub bias: f64,
+    /// The standard deviation of the bootstrap distribution.
+    pub standard_error: f64,
+    /// The confidence interval for the statistic.
+    pub confidence_interval: (f64, f64),
+    /// The statistic of every resample.
+    pub replicates: Array,
+}
+
+/// Returns `statistic` evaluated on `options.resamples` resamples, where `resample` draws the
+/// resample from the given generator.
+fn replicate<F>(options: &ResampleOptions, resample: F) -> Vec<f64>
+where
+    F: Fn(&mut dyn Rng) -> f64 + Sync,
+{
+    assert!(
+        options.resamples > 0 && options.threads > 0,
+        "ERROR - resampling: The number of resamples and threads must be positive."
+    );
+
+    let base = Pcg64::seed_from_u64(options.seed);
+    let run = |range: std::ops::Range<usize>| -> Vec<f64> {
+        range
+            .map(|i| {
+                let mut rng = base.clone();
+                rng.advance((i as u128) << 64);
+                resample(&mut rng)
+            })
+            .collect()
+    };
+
+    let n = options.resamples;
+    let threads = options.threads.min(n);
+    if threads == 1 {
+        return run(0..n);
+    }
+
+    let chunk = n.div_ceil(threads);
+    thread::scope(|scope| {
+        let run = &run;
+        let handles: Vec<_> = (0..n)
+            .step_by(chunk)
+            .map(|start| scope.spawn(move || run(start..(start + chunk).min(n))))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+/// Returns the bootstrap distribution and a confidence interval of a statistic.
+///
+/// # Arguments
+///
+/// * `xs` - the sample.
+/// * `statistic` - the statistic to estimate, e.g. `|xs| xs.median()`.
+/// * `method` - the method to compute the confidence interval with.
+/// * `confidence` - the confidence level of the interval, e.g. 0.95.
+/// * `options` - the number of resamples, the seed and the number of threads.
+///
+/// # Panics
+///
+/// The sample must have at least 2 observations, the confidence level must be between 0 and 1,
+/// and the number of resamples and threads must be positive, otherwise the code will panic.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::statistics::resampling::{bootstrap, BootstrapMethod, ResampleOptions};
+///
+/// let xs = Array::from(&[2.1, 3.4, 1.9, 5.6, 4.2, 3.3, 2.8, 4.9, 3.7, 3.1]);
+/// let result = bootstrap(
+///     &xs,
+///     |sample| sample.median(),
+///     BootstrapMethod::Bca,
+///     0.95,
+///     &ResampleOptions::default(),
+/// );
+///
+/// let (lower, upper) = result.confidence_interval;
+/// assert!(lower < result.estimate && result.estimate < upper);
+/// ```
+pub fn bootstrap<F>(
+    xs: &Array,
+    statistic: F,
+    method: BootstrapMethod,
+    confidence: f64,
+    options: &ResampleOptions,
+) -> Bootstrap
+where
+    F: Fn(&Array) -> f64 + Sync,
+{
+    let n = xs.len();
+    assert!(
+        n >= 2,
+        "ERROR - bootstrap: The sample must have at least 2 observations."
+    );
+    assert!(
+        confidence > 0.0 && confidence < 1.0,
+        "ERROR - bootstrap: The confidence level must be between 0 and 1."
+    );
+
+    let estimate = statistic(xs);
+    let replicates = Array::from_vec(replicate(options, |rng| {
+        let resample = (0..n).map(|_| xs[rng.below(n as u64) as usize]).collect();
+        statistic(&Array::from_vec(resample))
+    }));
+
+    let alpha = (1.0 - confidence) / 2.0;
+    let quantile = |q: f64| replicates.quantile(q, QuantileMethod::Linear);
+    let confidence_interval = match method {
+        BootstrapMethod::Percentile => (quantile(alpha), quantile(1.0 - alpha)),
+        BootstrapMethod::Basic => (
+            2.0 * estimate - quantile(1.0 - alpha),
+            2.0 * estimate - quantile(alpha),
+        ),
+        BootstrapMethod::Bca => {
+            let normal = Normal::standard();
+            // Ties count as half below the estimate, and the proportion is kept away from 0
+            // and 1, so the bias correction stays finite for e.g. a minimum or a discrete sample.
+            let resamples = replicates.len() as f64;
+            let below: f64 = replicates
+                .iter()
+                .map(|&r| {
+                    if r < estimate {
+                        1.0
+                    } else if r == estimate {
+                        0.5
+                    } else {
+                        0.0
+                    }
+                })
+                .sum();
+            let proportion =
+                (below / resamples).clamp(1.0 / (resamples + 1.0), resamples / (resamples + 1.0));
+            let bias_correction = normal.quantile(proportion);
+
+            let jackknife: Vec<f64> = (0..n)
+                .map(|i| {
+                    let rest = (0..n).filter(|&j| j != i).map(|j| xs[j]).collect();
+                    statistic(&Array::from_vec(rest))
+                })
+                .collect();
+            let mean = jackknife.iter().sum::<f64>() / n as f64;
+            let num: f64 = jackknife.iter().map(|t| (mean - t).powi(3)).sum();
+            let den: f64 = jackknife.iter().map(|t| (mean - t).powi(2)).sum();
+            let acceleration = if den > 0.0 {
+                num / (6.0 * den.powf(1.5))
+            } else {
+                0.0
+            };
+
+            let adjusted = |q: f64| {
+                let z = bias_correction + normal.quantile(q);
+                let p = normal.cdf(bias_correction + z / (1.0 - acceleration * z));
+                if p.is_nan() {
+                    q
+                } else {
+                    p.clamp(0.0, 1.0)
+                }
+            };
+            (quantile(adjusted(alpha)), quantile(adjusted(1.0 - alpha)))
+        }
+    };
+
+    let mean = replicates.average();
+    Bootstrap {
+        estimate,
+        bias: mean - estimate,
+        standard_error: replicates.std(1),
+        confidence_interval,
+        replicates,
+    }
+}
+
+/// Returns the Monte Carlo p-value of an observed statistic from the resampled ones.
+///
+/// The observed statistic is counted as one of the resamples, so the p-value is never 0.
+fn permutation_p_value(observed: f64, replicates: &[f64], alternative: Alternative) -> f64 {
+    let tolerance = TOLERANCE * observed.abs().max(1.0);
+    let extreme = replicates
+        .iter()
+        .filter(|&&r| match alternative {
+            Alternative::TwoSided => r.abs() >= observed.abs() - tolerance,
+            Alternative::Less => r <= observed + tolerance,
+            Alternative::Greater => r >= observed - tolerance,
+        })
+        .count();
+    (extreme + 1) as f64 / (replicates.len() + 1) as f64
+}
+
+/// Tests whether two independent samples come from the same distribution with a permutation
+/// test.
+///
+/// The samples are pooled and randomly split into groups of the original sizes, and the
+/// statistic of every split is compared to the observed one. For a two-sided alternative the
+/// statistic should be 0 under the null hypothesis, e.g. a difference of means, and the
+/// absolute values are compared.
+///
+/// # Arguments
+///
+/// * `xs` - the first sample.
+/// * `ys` - the second sample.
+/// * `statistic` - the statistic of both samples, e.g. `|xs, ys| xs.average() - ys.average()`.
+/// * `alternative` - the alternative hypothesis about the statistic.
+/// * `options` - the number of resamples, the seed and the number of threads.
+///
+/// # Panics
+///
+/// The samples must not be empty, and the number of resamples and threads must be positive,
+/// otherwise the code will panic.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::statistics::resampling::{permutation_test, ResampleOptions};
+/// use moonalloy::statistics::tests::Alternative;
+///
+/// let xs = Array::from(&[5.1, 4.9, 5.6, 5.8, 6.0, 5.3, 5.7, 6.1]);
+/// let ys = Array::from(&[4.8, 5.0, 4.7, 5.2, 5.1, 4.6]);
+/// let result = permutation_test(
+///     &xs,
+///     &ys,
+///     |xs, ys| xs.average() - ys.average(),
+///     Alternative::TwoSided,
+///     &ResampleOptions::default(),
+/// );
+///
+/// assert!(result.p_value < 0.05);
+/// ```
+pub fn permutation_test<F>(
+    xs: &Array,
+    ys: &Array,
+    statistic: F,
+    alternative: Alternative,
+    options: &ResampleOptions,
+) -> TestResult
+where
+    F: Fn(&Array, &Array) -> f64 + Sync,
+{
+    assert!(
+        !xs.is_empty() && !ys.is_empty(),
+        "ERROR - permutation_test: The samples must not be empty."
+    );
+
+    let observed = statistic(xs, ys);
+    let pooled = xs.concat(ys);
+    let replicates = replicate(options, |rng| {
+        let mut permuted = pooled.to_vec();
+        shuffle(&mut permuted, rng);
+        let (first, second) = permuted.split_at(xs.len());
+        statistic(&Array::from(first), &Array::from(second))
+    });
+
+    TestResult {
+        statistic: observed,
+        p_value: permutation_p_value(observed, &replicates, alternative),
+        df: None,
+        confidence_interval: None,
+    }
+}
+
+/// Tests whether the paired differences `xs - ys` are symmetric around 0 with a permutation
+/// test.
+///
+/// Under the null hypothesis the two observations of a pair are exchangeable, so the sign of
+/// every difference is flipped at random, and the statistic of the flipped differences is
+/// compared to the observed one. For a two-sided alternative the statistic should be 0 under
+/// the null hypothesis, e.g. the mean, and the absolute values are compared.
+///
+/// # Arguments
+///
+/// * `xs` - the first observations of the pairs.
+/// * `ys` - the second observations of the pairs.
+/// * `statistic` - the statistic of the differences, e.g. `|diffs| diffs.average()`.
+/// * `alternative` - the alternative hypothesis about the statistic.
+/// * `options` - the number of resamples, the seed and the number of threads.
+///
+/// # Panics
+///
+/// The samples must have the same positive length, and the number of resamples and threads
+/// must be positive, otherwise the code will panic.
+pub fn paired_permutation_test<F>(
+    xs: &Array,
+    ys: &Array,
+    statistic: F,
+    alternative: Alternative,
+    options: &ResampleOptions,
+) -> TestResult
+where
+    F: Fn(&Array) -> f64 + Sync,
+{
+    assert!(
+        !xs.is_empty() && xs.len() == ys.len(),
+        "ERROR - paired_permutation_test: The samples must have the same positive length."
+    );
+
+    let diffs = xs.minus(ys);
+    let observed = statistic(&diffs);
+    let replicates = replicate(options, |rng| {
+        let mut flipped = diffs.clone();
+        for d in flipped.iter_mut() {
+            if rng.next_u64() >> 63 == 1 {
+                *d = -*d;
+            }
+        }
+        statistic(&flipped)
+    });
+
+    TestResult {
+        statistic: observed,
+        p_value: permutation_p_value(observed, &replicates, alternative),
+        df: None,
+        confidence_interval: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> Array {
+        Array::from(&[2.1, 3.4, 1.9, 5.6, 4.2, 3.3, 2.8, 4.9, 3.7, 3.1, 6.3, 2.2])
+    }
+
+    fn options(threads: usize) -> ResampleOptions {
+        ResampleOptions {
+            resamples: 2000,
+            seed: 37,
+            threads,
+        }
+    }
+
+    #[test]
+    fn test_bootstrap_is_reproducible() {
+        let single = bootstrap(
+            &sample(),
+            |xs| xs.average(),
+            BootstrapMethod::Bca,
+            0.9,
+            &options(1),
+        );
+        let parallel = bootstrap(
+            &sample(),
+            |xs| xs.average(),
+            BootstrapMethod::Bca,
+            0.9,
+            &options(3),
+        );
+        assert_eq!(single, parallel);
+
+        let other = ResampleOptions {
+            seed: 38,
+            ..options(1)
+        };
+        let other = bootstrap(
+            &sample(),
+            |xs| xs.average(),
+            BootstrapMethod::Bca,
+            0.9,
+            &other,
+        );
+        assert_ne!(single.replicates, other.replicates);
+    }
+
+    #[test]
+    fn test_bootstrap_of_mean() {
+        let xs = sample();
+        let result = bootstrap(
+            &xs,
+            |xs| xs.average(),
+            BootstrapMethod::Percentile,
+            0.95,
+            &options(2),
+        );
+
+        // The bootstrap standard error of the mean is close to the plug-in estimate.
+        let plug_in = xs.std(0) / (xs.len() as f64).sqrt();
+        assert_eq!(xs.average(), result.estimate);
+        assert!((result.standard_error - plug_in).abs() < 0.05 * plug_in);
+        assert!(result.bias.abs() < 0.05);
+        assert_eq!(2000, result.replicates.len());
+
+        let (lower, upper) = result.confidence_interval;
+        assert!(lower < result.estimate && result.estimate < upper);
+    }
+
+    #[test]
+    fn test_bootstrap_methods() {
+        let xs = sample();
+        let percentile = bootstrap(
+            &xs,
+            |xs| xs.var(1),
+            BootstrapMethod::Percentile,
+            0.9,
+            &options(1),
+        );
+        let basic = bootstrap(
+            &xs,
+            |xs| xs.var(1),
+            BootstrapMethod::Basic,
+            0.9,
+            &options(1),
+        );
+        let bca = bootstrap(&xs, |xs| xs.var(1), BootstrapMethod::Bca, 0.9, &options(1));
+
+        // The basic interval reflects the percentile interval around the estimate.
+        let estimate = percentile.estimate;
+        let (p_lower, p_upper) = percentile.confidence_interval;
+        let (b_lower, b_upper) = basic.confidence_interval;
+        assert!((b_lower - (2.0 * estimate - p_upper)).abs() < 1e-12);
+        assert!((b_upper - (2.0 * estimate - p_lower)).abs() < 1e-12);
+
+        // The variance is biased down in resamples, so BCa shifts the interval up.
+        let (bca_lower, bca_upper) = bca.confidence_interval;
+        assert!(bca_lower > p_lower && bca_upper > p_upper);
+    }
+
+    #[test]
+    fn test_bca_of_minimum() {
+        // No resample has a smaller minimum than the sample.
+        let xs = sample();
+        let result = bootstrap(&xs, |xs| xs.min(), BootstrapMethod::Bca, 0.9, &options(1));
+
+        let (lower, upper) = result.confidence_interval;
+        assert!(lower.is_finite() && upper.is_finite());
+        assert!(xs.min() <= lower && lower <= upper);
+    }
+
+    #[test]
+    fn test_bca_with_ties() {
+        // Most resamples have the same median as the sample.
+        let xs = Array::from(&[1.0, 1.0, 1.0, 1.0, 2.0, 1.0, 1.0, 1.0]);
+        let result = bootstrap(
+            &xs,
+            |xs| xs.median(),
+            BootstrapMethod::Bca,
+            0.95,
+            &options(1),
+        );
+
+        let (lower, upper) = result.confidence_interval;
+        assert_eq!(1.0, lower);
+        assert!((1.0..=2.0).contains(&upper));
+    }
+
+    #[test]
+    fn test_permutation_test() {
+        let xs = Array::from(&[5.1, 4.9, 5.6, 5.8, 6.0, 5.3, 5.7, 6.1]);
+        let ys = Array::from(&[4.8, 5.0, 4.7, 5.2, 5.1, 4.6]);
+        let diff = |xs: &Array, ys: &Array| xs.average() - ys.average();
+
+        let two_sided = permutation_test(&xs, &ys, diff, Alternative::TwoSided, &options(1));
+        assert_eq!(diff(&xs, &ys), two_sided.statistic);
+        assert!(two_sided.p_value < 0.02);
+        assert_eq!(
+            two_sided,
+            permutation_test(&xs, &ys, diff, Alternative::TwoSided, &options(4))
+        );
+
+        let less = permutation_test(&xs, &ys, diff, Alternative::Less, &options(1));
+        assert!(less.p_value > 0.98);
+
+        let same = permutation_test(&xs, &xs, diff, Alternative::TwoSided, &options(1));
+        assert_eq!(1.0, same.p_value);
+    }
+
+    #[test]
+    fn test_paired_permutation_test() {
+        let xs = Array::from(&[2.4, 1.9, 3.1, 2.8, 2.2, 3.5, 2.7, 3.0, 1.8]);
+        let ys = Array::from(&[1.6, 2.1, 2.4, 2.5, 1.3, 2.4, 3.1, 2.4, 1.3]);
+        let options = ResampleOptions {
+            resamples: 20_000,
+            ..options(2)
+        };
+        let result =
+            paired_permutation_test(&xs, &ys, |d| d.average(), Alternative::Greater, &options);
+
+        // There are 512 equally likely sign patterns, of which 8 are at least as extreme.
+        assert!((result.p_value - 8.0 / 512.0).abs() < 0.004);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_no_resamples() {
+        let options = ResampleOptions {
+            resamples: 0,
+            ..ResampleOptions::default()
+        };
+        bootstrap(
+            &sample(),
+            |xs| xs.average(),
+            BootstrapMethod::Basic,
+            0.9,
+            &options,
+        );
+    }
+}
@@ -0,0 +1,1237 @@
+//! Tests - Statistical hypothesis tests
+//!
+//! This module contains the common parametric and non-parametric hypothesis tests: t-tests and
+//! z-tests for means, chi-square tests for counts, Kolmogorov-Smirnov tests for distributions,
+//! the rank tests by Mann-Whitney and Wilcoxon, and the Shapiro-Wilk test for normality.
+//!
+//! Every test returns a `TestResult` with the test statistic and its p-value. Tests based on a
+//! distribution with degrees of freedom also return those, and the tests for means return a
+//! confidence interval for the mean or the difference of means.
+
+use crate::linalg::array::Array;
+use crate::linalg::matrix::Matrix;
+use crate::statistics::correlation::rank;
+use crate::statistics::probability::{ChiSquared, Distribution, Normal, StudentT};
+
+use std::f64::consts::PI;
+
+/// The alternative hypothesis of a test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alternative {
+    /// The parameter differs from its value under the null hypothesis.
+    #[default]
+    TwoSided,
+    /// The parameter is less than its value under the null hypothesis.
+    Less,
+    /// The parameter is greater than its value under the null hypothesis.
+    Greater,
+}
+
+/// The outcome of a hypothesis test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestResult {
+    /// The test statistic.
+    pub statistic: f64,
+    /// The probability of a statistic at least as extreme under the null hypothesis.
+    pub p_value: f64,
+    /// The degrees of freedom of the distribution of the statistic, if it has any.
+    pub df: Option<f64>,
+    /// The confidence interval for the tested parameter, if the test provides one.
+    ///
+    /// One-sided alternatives give intervals with an infinite bound.
+    pub confidence_interval: Option<(f64, f64)>,
+}
+
+/// Returns the p-value of a statistic with a distribution that is symmetric around 0.
+fn symmetric_p_value(dist: &dyn Distribution, statistic: f64, alternative: Alternative) -> f64 {
+    match alternative {
+        Alternative::TwoSided => (2.0 * dist.sf(statistic.abs())).min(1.0),
+        Alternative::Less => dist.cdf(statistic),
+        Alternative::Greater => dist.sf(statistic),
+    }
+}
+
+/// Returns the confidence interval `estimate ± q * se`, where `q` is a quantile of a
+/// distribution that is symmetric around 0.
+fn interval(
+    dist: &dyn Distribution,
+    estimate: f64,
+    se: f64,
+    alternative: Alternative,
+    confidence: f64,
+) -> (f64, f64) {
+    assert!(
+        confidence > 0.0 && confidence < 1.0,
+        "ERROR - confidence interval: The confidence level must be between 0 and 1."
+    );
+
+    match alternative {
+        Alternative::TwoSided => {
+            let q = dist.quantile(0.5 + 0.5 * confidence);
+            (estimate - q * se, estimate + q * se)
+        }
+        Alternative::Less => (f64::NEG_INFINITY, estimate + dist.quantile(confidence) * se),
+        Alternative::Greater => (estimate - dist.quantile(confidence) * se, f64::INFINITY),
+    }
+}
+
+/// Returns the result of a t-test of `estimate - hypothesis` with the standard error `se`.
+fn t_result(
+    estimate: f64,
+    hypothesis: f64,
+    se: f64,
+    df: f64,
+    alternative: Alternative,
+    confidence: f64,
+) -> TestResult {
+    let dist = StudentT::new(df);
+    let statistic = (estimate - hypothesis) / se;
+
+    TestResult {
+        statistic,
+        p_value: symmetric_p_value(&dist, statistic, alternative),
+        df: Some(df),
+        confidence_interval: Some(interval(&dist, estimate, se, alternative, confidence)),
+    }
+}
+
+/// Tests whether the mean of a sample is `mu` with Student's one-sample t-test.
+///
+/// The confidence interval is for the mean.
+///
+/// # Arguments
+///
+/// * `xs` - the sample.
+/// * `mu` - the mean under the null hypothesis.
+/// * `alternative` - the alternative hypothesis about the mean.
+/// * `confidence` - the confidence level of the interval, e.g. 0.95.
+///
+/// # Panics
+///
+/// The sample must have at least 2 observations, otherwise the code will panic.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::statistics::tests::{one_sample_t_test, Alternative};
+///
+/// let xs = Array::from(&[5.1, 4.9, 5.6, 5.8, 6.0, 5.3, 5.7, 6.1]);
+/// let result = one_sample_t_test(&xs, 5.0, Alternative::TwoSided, 0.95);
+///
+/// assert!(result.p_value < 0.01);
+/// assert_eq!(Some(7.0), result.df);
+/// ```
+pub fn one_sample_t_test(
+    xs: &Array,
+    mu: f64,
+    alternative: Alternative,
+    confidence: f64,
+) -> TestResult {
+    assert!(
+        xs.len() >= 2,
+        "ERROR - one_sample_t_test: The sample must have at least 2 observations."
+    );
+    let n = xs.len() as f64;
+    let se = (xs.var(1) / n).sqrt();
+    t_result(xs.average(), mu, se, n - 1.0, alternative, confidence)
+}
+
+/// Tests whether two independent samples have the same mean with a two-sample t-test.
+///
+/// The confidence interval is for the difference of the means `mean(xs) - mean(ys)`.
+///
+/// # Arguments
+///
+/// * `xs` - the first sample.
+/// * `ys` - the second sample.
+/// * `equal_var` - whether to assume equal variances (Student's t-test with a pooled variance)
+///   or not (Welch's t-test with the Welch-Satterthwaite degrees of freedom).
+/// * `alternative` - the alternative hypothesis about `mean(xs) - mean(ys)`.
+/// * `confidence` - the confidence level of the interval, e.g. 0.95.
+///
+/// # Panics
+///
+/// Both samples must have at least 2 observations, otherwise the code will panic.
+pub fn two_sample_t_test(
+    xs: &Array,
+    ys: &Array,
+    equal_var: bool,
+    alternative: Alternative,
+    confidence: f64,
+) -> TestResult {
+    assert!(
+        xs.len() >= 2 && ys.len() >= 2,
+        "ERROR - two_sample_t_test: The samples must have at least 2 observations."
+    );
+    let (nx, ny) = (xs.len() as f64, ys.len() as f64);
+    let (vx, vy) = (xs.var(1), ys.var(1));
+
+    let (se, df) = if equal_var {
+        let df = nx + ny - 2.0;
+        let pooled = ((nx - 1.0) * vx + (ny - 1.0) * vy) / df;
+        ((pooled * (1.0 / nx + 1.0 / ny)).sqrt(), df)
+    } else {
+        let (wx, wy) = (vx / nx, vy / ny);
+        let df = (wx + wy).powi(2) / (wx * wx / (nx - 1.0) + wy * wy / (ny - 1.0));
+        ((wx + wy).sqrt(), df)
+    };
+
+    t_result(
+        xs.average() - ys.average(),
+        0.0,
+        se,
+        df,
+        alternative,
+        confidence,
+    )
+}
+
+/// Tests whether the mean difference of paired observations is 0 with a paired t-test.
+///
+/// This is the one-sample t-test of the differences `xs - ys`, and the confidence interval is
+/// for their mean.
+///
+/// # Panics
+///
+/// The samples must have the same length of at least 2, otherwise the code will panic.
+pub fn paired_t_test(
+    xs: &Array,
+    ys: &Array,
+    alternative: Alternative,
+    confidence: f64,
+) -> TestResult {
+    assert_eq!(
+        xs.len(),
+        ys.len(),
+        "ERROR - paired_t_test: The samples must have the same length."
+    );
+    one_sample_t_test(&xs.minus(ys), 0.0, alternative, confidence)
+}
+
+/// Returns the result of a z-test of `estimate - hypothesis` with the standard error `se`.
+fn z_result(
+    estimate: f64,
+    hypothesis: f64,
+    se: f64,
+    alternative: Alternative,
+    confidence: f64,
+) -> TestResult {
+    let dist = Normal::standard();
+    let statistic = (estimate - hypothesis) / se;
+
+    TestResult {
+        statistic,
+        p_value: symmetric_p_value(&dist, statistic, alternative),
+        df: None,
+        confidence_interval: Some(interval(&dist, estimate, se, alternative, confidence)),
+    }
+}
+
+/// Tests whether the mean of a sample is `mu` with a z-test, for a known standard deviation.
+///
+/// The confidence interval is for the mean.
+///
+/// # Arguments
+///
+/// * `xs` - the sample.
+/// * `mu` - the mean under the null hypothesis.
+/// * `sigma` - the known standard deviation of the population.
+/// * `alternative` - the alternative hypothesis about the mean.
+/// * `confidence` - the confidence level of the interval, e.g. 0.95.
+///
+/// # Panics
+///
+/// The sample must not be empty and `sigma` must be positive, otherwise the code will panic.
+pub fn one_sample_z_test(
+    xs: &Array,
+    mu: f64,
+    sigma: f64,
+    alternative: Alternative,
+    confidence: f64,
+) -> TestResult {
+    assert!(
+        !xs.is_empty() && sigma > 0.0,
+        "ERROR - one_sample_z_test: The sample must not be empty and sigma must be positive."
+    );
+    let se = sigma / (xs.len() as f64).sqrt();
+    z_result(xs.average(), mu, se, alternative, confidence)
+}
+
+/// Tests whether two independent samples have the same mean with a z-test, for known standard
+/// deviations.
+///
+/// The confidence interval is for the difference of the means `mean(xs) - mean(ys)`.
+///
+/// # Panics
+///
+/// The samples must not be empty and the standard deviations must be positive, otherwise the
+/// code will panic.
+pub fn two_sample_z_test(
+    xs: &Array,
+    ys: &Array,
+    sigma_x: f64,
+    sigma_y: f64,
+    alternative: Alternative,
+    confidence: f64,
+) -> TestResult {
+    assert!(
+        !xs.is_empty() && !ys.is_empty() && sigma_x > 0.0 && sigma_y > 0.0,
+        "ERROR - two_sample_z_test: The samples must not be empty and sigma must be positive."
+    );
+    let se = (sigma_x * sigma_x / xs.len() as f64 + sigma_y * sigma_y / ys.len() as f64).sqrt();
+    z_result(
+        xs.average() - ys.average(),
+        0.0,
+        se,
+        alternative,
+        confidence,
+    )
+}
+
+/// Tests whether observed counts follow the expected counts with Pearson's chi-square test.
+///
+/// # Arguments
+///
+/// * `observed` - the observed count of every category.
+/// * `expected` - the expected count of every category, or `None` for equally likely
+///   categories. The expected counts must have the same total as the observed counts.
+///
+/// # Panics
+///
+/// There must be at least 2 categories, the expected counts must be positive and have the
+/// same total as the observed counts, otherwise the code will panic.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::statistics::tests::chi_square_goodness_of_fit;
+///
+/// // 60 rolls of a die.
+/// let observed = Array::from(&[5.0, 8.0, 9.0, 8.0, 10.0, 20.0]);
+/// let result = chi_square_goodness_of_fit(&observed, None);
+///
+/// assert_eq!(13.4, result.statistic);
+/// assert!(result.p_value < 0.05);
+/// ```
+pub fn chi_square_goodness_of_fit(observed: &Array, expected: Option<&Array>) -> TestResult {
+    let k = observed.len();
+    assert!(
+        k >= 2,
+        "ERROR - chi_square_goodness_of_fit: There must be at least 2 categories."
+    );
+
+    let total = observed.sum();
+    let expected = match expected {
+        Some(expected) => {
+            assert!(
+                expected.len() == k
+                    && expected.iter().all(|&e| e > 0.0)
+                    && (expected.sum() - total).abs() <= 1e-8 * total.abs(),
+                "ERROR - chi_square_goodness_of_fit: The expected counts must be positive and sum to the observed total."
+            );
+            expected.clone()
+        }
+        None => Array::of(total / k as f64, k),
+    };
+
+    let statistic = observed
+        .iter()
+        .zip(expected.iter())
+        .map(|(o, e)| (o - e) * (o - e) / e)
+        .sum::<f64>();
+    let df = (k - 1) as f64;
+
+    TestResult {
+        statistic,
+        p_value: ChiSquared::new(df).sf(statistic),
+        df: Some(df),
+        confidence_interval: None,
+    }
+}
+
+/// Tests whether the rows and columns of a contingency table are independent with Pearson's
+/// chi-square test, without continuity correction.
+///
+/// # Arguments
+///
+/// * `table` - the observed counts, where the rows are the categories of one variable and the
+///   columns the categories of the other.
+///
+/// # Panics
+///
+/// The table must have at least 2 rows and 2 columns, and no row or column may sum to 0,
+/// otherwise the code will panic.
+pub fn chi_square_independence(table: &Matrix) -> TestResult {
+    let (rows, cols) = table.dimensions();
+    assert!(
+        rows >= 2 && cols >= 2,
+        "ERROR - chi_square_independence: The table must have at least 2 rows and 2 columns."
+    );
+
+    let row_sums: Vec<f64> = table.iter().map(|row| row.sum()).collect();
+    let col_sums: Vec<f64> = (0..cols).map(|j| table.column(j).sum()).collect();
+    let total: f64 = row_sums.iter().sum();
+    assert!(
+        row_sums.iter().chain(&col_sums).all(|&sum| sum > 0.0),
+        "ERROR - chi_square_independence: Every row and column must have a positive sum."
+    );
+
+    let mut statistic = 0.0;
+    for i in 0..rows {
+        for j in 0..cols {
+            let expected = row_sums[i] * col_sums[j] / total;
+            statistic += (table[i][j] - expected).powi(2) / expected;
+        }
+    }
+    let df = ((rows - 1) * (cols - 1)) as f64;
+
+    TestResult {
+        statistic,
+        p_value: ChiSquared::new(df).sf(statistic),
+        df: Some(df),
+        confidence_interval: None,
+    }
+}
+
+/// Returns the survival function of the Kolmogorov distribution, the limiting distribution of
+/// `sqrt(n) D` for the Kolmogorov-Smirnov statistic `D`.
+fn kolmogorov_sf(lambda: f64) -> f64 {
+    if lambda <= 0.0 {
+        return 1.0;
+    }
+
+    if lambda < 1.18 {
+        let c = -PI * PI / (8.0 * lambda * lambda);
+        let cdf = (2.0 * PI).sqrt() / lambda
+            * (1..=5)
+                .map(|j| (c * ((2 * j - 1) * (2 * j - 1)) as f64).exp())
+                .sum::<f64>();
+        return (1.0 - cdf).clamp(0.0, 1.0);
+    }
+
+    let c = -2.0 * lambda * lambda;
+    let sf: f64 = (1..=10)
+        .map(|j| {
+            let sign = if j % 2 == 1 { 2.0 } else { -2.0 };
+            sign * (c * (j * j) as f64).exp()
+        })
+        .sum();
+    sf.clamp(0.0, 1.0)
+}
+
+/// Returns the p-value of the Kolmogorov-Smirnov statistic `d` for the effective sample size
+/// `n` from the limiting distribution, with the small-sample correction by Stephens.
+fn kolmogorov_asymptotic(d: f64, n: f64) -> f64 {
+    let sqrt_n = n.sqrt();
+    kolmogorov_sf((sqrt_n + 0.12 + 0.11 / sqrt_n) * d)
+}
+
+/// Multiplies two `m` x `m` matrices stored by rows.
+fn multiply_square(a: &[f64], b: &[f64], m: usize) -> Vec<f64> {
+    let mut c = vec![0.0; m * m];
+    for i in 0..m {
+        for k in 0..m {
+            let aik = a[i * m + k];
+            if aik != 0.0 {
+                for j in 0..m {
+                    c[i * m + j] += aik * b[k * m + j];
+                }
+            }
+        }
+    }
+    c
+}
+
+/// Returns `h^n` and a decimal exponent `e`, such that the actual power is `h^n * 10^e`.
+fn power_square(h: &[f64], m: usize, n: usize) -> (Vec<f64>, i32) {
+    if n == 1 {
+        return (h.to_vec(), 0);
+    }
+
+    let (half, e) = power_square(h, m, n / 2);
+    let mut result = multiply_square(&half, &half, m);
+    let e = 2 * e;
+    if n % 2 == 1 {
+        result = multiply_square(h, &result, m);
+    }
+
+    if result[(m / 2) * m + m / 2] > 1e140 {
+        result.iter_mut().for_each(|v| *v *= 1e-140);
+        (result, e + 140)
+    } else {
+        (result, e)
+    }
+}
+
+/// Returns the p-value `P(D >= d)` of the one-sample Kolmogorov-Smirnov statistic for a sample
+/// of size `n`.
+///
+/// Uses the exact method by Marsaglia, Tsang and Wang, and their approximation far in the tail.
+fn kolmogorov_exact(d: f64, n: usize) -> f64 {
+    let nf = n as f64;
+    let s = d * d * nf;
+    if s > 7.24 || (s > 3.76 && n > 99) {
+        return (2.0 * (-(2.000_071 + 0.331 / nf.sqrt() + 1.409 / nf) * s).exp()).min(1.0);
+    }
+    if d >= 1.0 {
+        return 0.0;
+    }
+
+    let k = (nf * d) as usize + 1;
+    let m = 2 * k - 1;
+    let h = k as f64 - nf * d;
+
+    let mut mat = vec![0.0; m * m];
+    for i in 0..m {
+        for j in 0..m {
+            if i + 1 >= j {
+                mat[i * m + j] = 1.0;
+            }
+        }
+    }
+    for i in 0..m {
+        mat[i * m] -= h.powi(i as i32 + 1);
+        mat[(m - 1) * m + i] -= h.powi((m - i) as i32);
+    }
+    if 2.0 * h - 1.0 > 0.0 {
+        mat[(m - 1) * m] += (2.0 * h - 1.0).powi(m as i32);
+    }
+    for i in 0..m {
+        for j in 0..m {
+            if i + 1 > j {
+                for g in 1..=(i + 1 - j) {
+                    mat[i * m + j] /= g as f64;
+                }
+            }
+        }
+    }
+
+    let (power, mut e) = power_square(&mat, m, n);
+    let mut cdf = power[(k - 1) * m + k - 1];
+    for i in 1..=n {
+        cdf = cdf * i as f64 / nf;
+        if cdf < 1e-140 {
+            cdf *= 1e140;
+            e -= 140;
+        }
+    }
+    cdf *= 10f64.powi(e);
+
+    (1.0 - cdf).clamp(0.0, 1.0)
+}
+
+/// Tests whether a sample comes from a continuous distribution with the one-sample
+/// Kolmogorov-Smirnov test.
+///
+/// The statistic is the largest distance between the empirical CDF of the sample and the CDF of
+/// the distribution. The two-sided p-value is exact for samples of up to 1000 observations.
+///
+/// # Panics
+///
+/// The sample must not be empty, otherwise the code will panic.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::statistics::probability::Uniform;
+/// use moonalloy::statistics::tests::kolmogorov_smirnov;
+///
+/// let xs = Array::from(&[0.05, 0.2, 0.35, 0.5, 0.65, 0.8, 0.95]);
+/// let result = kolmogorov_smirnov(&xs, &Uniform::new(0.0, 1.0));
+///
+/// assert!(result.p_value > 0.99);
+/// ```
+pub fn kolmogorov_smirnov(xs: &Array, dist: &dyn Distribution) -> TestResult {
+    assert!(
+        !xs.is_empty(),
+        "ERROR - kolmogorov_smirnov: The sample must not be empty."
+    );
+
+    let mut sorted = xs.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len() as f64;
+
+    let statistic = sorted
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            let cdf = dist.cdf(x);
+            ((i + 1) as f64 / n - cdf).max(cdf - i as f64 / n)
+        })
+        .fold(0.0, f64::max);
+
+    let p_value = if sorted.len() <= 1000 {
+        kolmogorov_exact(statistic, sorted.len())
+    } else {
+        kolmogorov_asymptotic(statistic, n)
+    };
+
+    TestResult {
+        statistic,
+        p_value,
+        df: None,
+        confidence_interval: None,
+    }
+}
+
+/// Tests whether two samples come from the same continuous distribution with the two-sample
+/// Kolmogorov-Smirnov test.
+///
+/// The statistic is the largest distance between the empirical CDFs of the samples. The
+/// two-sided p-value is exact when the product of the sample sizes is at most 10000, and
+/// asymptotic otherwise.
+///
+/// # Panics
+///
+/// The samples must not be empty, otherwise the code will panic.
+pub fn kolmogorov_smirnov_two_sample(xs: &Array, ys: &Array) -> TestResult {
+    assert!(
+        !xs.is_empty() && !ys.is_empty(),
+        "ERROR - kolmogorov_smirnov_two_sample: The samples must not be empty."
+    );
+
+    let mut xs = xs.to_vec();
+    let mut ys = ys.to_vec();
+    xs.sort_by(f64::total_cmp);
+    ys.sort_by(f64::total_cmp);
+    let (n, m) = (xs.len(), ys.len());
+
+    // The largest |i m - j n| over the steps of both CDFs, in integers to compare exactly.
+    let (mut i, mut j) = (0, 0);
+    let mut largest = 0;
+    while i < n && j < m {
+        let value = xs[i].min(ys[j]);
+        while i < n && xs[i] == value {
+            i += 1;
+        }
+        while j < m && ys[j] == value {
+            j += 1;
+        }
+        largest = largest.max((i * m).abs_diff(j * n));
+    }
+    let statistic = largest as f64 / (n * m) as f64;
+
+    let p_value = if n * m <= 10_000 {
+        // Count the lattice paths from (0, 0) to (n, m) that stay strictly inside the band.
+        let total = (1..=n).fold(1.0, |acc, k| acc * (m + k) as f64 / k as f64);
+        let mut paths = vec![0.0; m + 1];
+        for i in 0..=n {
+            for j in 0..=m {
+                if (i * m).abs_diff(j * n) >= largest && (i, j) != (0, 0) {
+                    paths[j] = 0.0;
+                } else if i == 0 && j == 0 {
+                    paths[j] = 1.0;
+                } else if j > 0 {
+                    paths[j] += paths[j - 1];
+                }
+            }
+        }
+        (1.0 - paths[m] / total).clamp(0.0, 1.0)
+    } else {
+        kolmogorov_asymptotic(statistic, (n * m) as f64 / (n + m) as f64)
+    };
+
+    TestResult {
+        statistic,
+        p_value,
+        df: None,
+        confidence_interval: None,
+    }
+}
+
+/// Returns the sum of `t^3 - t` over the groups of `t` tied elements.
+fn tie_sum(xs: &[f64]) -> f64 {
+    let mut sorted = xs.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    let mut sum = 0.0;
+    let mut start = 0;
+    for i in 1..=sorted.len() {
+        if i == sorted.len() || sorted[i] != sorted[start] {
+            let t = (i - start) as f64;
+            sum += t * t * t - t;
+            start = i;
+        }
+    }
+    sum
+}
+
+/// Returns the p-value of a statistic with an approximately normal distribution, with a
+/// continuity correction of 0.5.
+fn normal_p_value(statistic: f64, mean: f64, sd: f64, alternative: Alternative) -> f64 {
+    let normal = Normal::standard();
+    let diff = statistic - mean;
+    match alternative {
+        Alternative::TwoSided => {
+            let z = (diff - 0.5 * diff.signum()) / sd;
+            (2.0 * normal.cdf(z).min(normal.sf(z))).min(1.0)
+        }
+        Alternative::Less => normal.cdf((diff + 0.5) / sd),
+        Alternative::Greater => normal.sf((diff - 0.5) / sd),
+    }
+}
+
+/// Returns the p-value of an integer statistic from the counts of its values.
+///
+/// `counts[s]` is the number of equally likely outcomes with the statistic `s`, and the
+/// distribution of the statistic must be symmetric.
+fn exact_p_value(counts: &[f64], statistic: f64, alternative: Alternative) -> f64 {
+    let total: f64 = counts.iter().sum();
+    let s = statistic.round() as usize;
+    let at_most = |s: usize| counts[..=s.min(counts.len() - 1)].iter().sum::<f64>() / total;
+    let at_least = |s: usize| counts[s.min(counts.len())..].iter().sum::<f64>() / total;
+
+    match alternative {
+        Alternative::Less => at_most(s),
+        Alternative::Greater => at_least(s),
+        Alternative::TwoSided => {
+            let mirrored = counts.len() - 1 - s;
+            (2.0 * at_most(s.min(mirrored))).min(1.0)
+        }
+    }
+}
+
+/// Tests whether two independent samples come from the same distribution, against a shift in
+/// location, with the Mann-Whitney U test (Wilcoxon rank-sum test).
+///
+/// The statistic is `U` of the first sample: the number of pairs in which the observation from
+/// `xs` is larger than the one from `ys`, with ties counting as a half. The p-value is exact
+/// when both samples have fewer than 50 observations and there are no ties, and uses the
+/// normal approximation with tie and continuity corrections otherwise.
+///
+/// # Panics
+///
+/// The samples must not be empty, otherwise the code will panic.
+pub fn mann_whitney_u(xs: &Array, ys: &Array, alternative: Alternative) -> TestResult {
+    assert!(
+        !xs.is_empty() && !ys.is_empty(),
+        "ERROR - mann_whitney_u: The samples must not be empty."
+    );
+
+    let (n1, n2) = (xs.len(), ys.len());
+    let ranks = rank(&xs.concat(ys));
+    let rank_sum: f64 = ranks.iter().take(n1).sum();
+    let statistic = rank_sum - (n1 * (n1 + 1)) as f64 / 2.0;
+
+    let ties = tie_sum(&xs.concat(ys));
+    let p_value = if n1 < 50 && n2 < 50 && ties == 0.0 {
+        // counts[j][u]: arrangements of i elements of xs and j of ys with the statistic u.
+        let mut counts = vec![vec![0.0; n1 * n2 + 1]; n1 + 1];
+        counts[0][0] = 1.0;
+        for j in 0..=n2 {
+            for i in 1..=n1 {
+                if j == 0 {
+                    counts[i][0] = 1.0;
+                    continue;
+                }
+                // The largest element is from xs, above all j elements of ys, or from ys.
+                let (lower, upper) = counts.split_at_mut(i);
+                for u in (j..=n1 * n2).rev() {
+                    upper[0][u] += lower[i - 1][u - j];
+                }
+            }
+        }
+        exact_p_value(&counts[n1], statistic, alternative)
+    } else {
+        let (n1f, n2f) = (n1 as f64, n2 as f64);
+        let n = n1f + n2f;
+        let var = n1f * n2f / 12.0 * ((n + 1.0) - ties / (n * (n - 1.0)));
+        normal_p_value(statistic, n1f * n2f / 2.0, var.sqrt(), alternative)
+    };
+
+    TestResult {
+        statistic,
+        p_value,
+        df: None,
+        confidence_interval: None,
+    }
+}
+
+/// Tests whether a distribution is symmetric around 0 with the Wilcoxon signed-rank test.
+///
+/// With `ys` the test is for the paired differences `xs - ys`. Zero differences are left out.
+/// The statistic is the sum of the ranks of the positive differences. The p-value is exact
+/// when there are fewer than 50 differences and no ties or zeros, and uses the normal
+/// approximation with tie and continuity corrections otherwise.
+///
+/// # Panics
+///
+/// The samples must have the same length, and there must be at least one non-zero difference,
+/// otherwise the code will panic.
+pub fn wilcoxon_signed_rank(
+    xs: &Array,
+    ys: Option<&Array>,
+    alternative: Alternative,
+) -> TestResult {
+    let diffs = match ys {
+        Some(ys) => {
+            assert_eq!(
+                xs.len(),
+                ys.len(),
+                "ERROR - wilcoxon_signed_rank: The samples must have the same length."
+            );
+            xs.minus(ys)
+        }
+        None => xs.clone(),
+    };
+
+    let nonzero: Vec<f64> = diffs.iter().copied().filter(|&d| d != 0.0).collect();
+    let zeros = diffs.len() - nonzero.len();
+    assert!(
+        !nonzero.is_empty(),
+        "ERROR - wilcoxon_signed_rank: All differences are zero."
+    );
+
+    let magnitudes = Array::from_vec(nonzero.iter().map(|d| d.abs()).collect());
+    let ranks = rank(&magnitudes);
+    let statistic: f64 = nonzero
+        .iter()
+        .zip(ranks.iter())
+        .filter(|(&d, _)| d > 0.0)
+        .map(|(_, r)| r)
+        .sum();
+
+    let n = nonzero.len();
+    let ties = tie_sum(&magnitudes);
+    let p_value = if n < 50 && ties == 0.0 && zeros == 0 {
+        // counts[w]: subsets of the ranks 1..=n with the sum w.
+        let max = n * (n + 1) / 2;
+        let mut counts = vec![0.0; max + 1];
+        counts[0] = 1.0;
+        for r in 1..=n {
+            for w in (r..=max).rev() {
+                counts[w] += counts[w - r];
+            }
+        }
+        exact_p_value(&counts, statistic, alternative)
+    } else {
+        let nf = n as f64;
+        let var = nf * (nf + 1.0) * (2.0 * nf + 1.0) / 24.0 - ties / 48.0;
+        normal_p_value(statistic, nf * (nf + 1.0) / 4.0, var.sqrt(), alternative)
+    };
+
+    TestResult {
+        statistic,
+        p_value,
+        df: None,
+        confidence_interval: None,
+    }
+}
+
+/// Evaluates a polynomial with the coefficients in increasing order of degree.
+fn polynomial(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().rev().fold(0.0, |acc, c| acc * x + c)
+}
+
+/// Tests whether a sample comes from a normal distribution with the Shapiro-Wilk test.
+///
+/// The statistic `W` is between 0 and 1, where small values indicate departure from normality.
+/// The coefficients and the p-value are computed with the approximations by Royston (algorithm
+/// AS R94).
+///
+/// # Panics
+///
+/// The sample must have between 3 and 5000 observations and must not be constant, otherwise
+/// the code will panic.
+///
+/// # Examples
+///
+/// ```
+/// use moonalloy::linalg::array::Array;
+/// use moonalloy::statistics::tests::shapiro_wilk;
+///
+/// let weights = Array::from(&[
+///     148.0, 154.0, 158.0, 160.0, 161.0, 162.0, 166.0, 170.0, 182.0, 195.0, 236.0,
+/// ]);
+/// let result = shapiro_wilk(&weights);
+///
+/// assert!(result.p_value < 0.01);
+/// ```
+pub fn shapiro_wilk(xs: &Array) -> TestResult {
+    let n = xs.len();
+    assert!(
+        (3..=5000).contains(&n),
+        "ERROR - shapiro_wilk: The sample must have between 3 and 5000 observations."
+    );
+
+    let mut sorted = xs.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    assert!(
+        sorted[0] < sorted[n - 1],
+        "ERROR - shapiro_wilk: The sample must not be constant."
+    );
+
+    let nf = n as f64;
+    let half = n / 2;
+
+    // The coefficients for the lower half of the order statistics, with the sign flipped.
+    let mut a = vec![0.0; half];
+    if n == 3 {
+        a[0] = 0.5f64.sqrt();
+    } else {
+        let normal = Normal::standard();
+        let m: Vec<f64> = (1..=half)
+            .map(|i| normal.quantile((i as f64 - 0.375) / (nf + 0.25)))
+            .collect();
+        let summ2 = 2.0 * m.iter().map(|v| v * v).sum::<f64>();
+        let ssumm2 = summ2.sqrt();
+        let rsn = 1.0 / nf.sqrt();
+
+        let c1 = [0.0, 0.221_157, -0.147_981, -2.071_19, 4.434_685, -2.706_056];
+        let c2 = [
+            0.0, 0.042_981, -0.293_762, -1.752_461, 5.682_633, -3.582_633,
+        ];
+        let a1 = polynomial(&c1, rsn) - m[0] / ssumm2;
+
+        let (first, fac) = if n > 5 {
+            let a2 = -m[1] / ssumm2 + polynomial(&c2, rsn);
+            a[1] = a2;
+            let fac = ((summ2 - 2.0 * m[0] * m[0] - 2.0 * m[1] * m[1])
+                / (1.0 - 2.0 * a1 * a1 - 2.0 * a2 * a2))
+                .sqrt();
+            (2, fac)
+        } else {
+            (
+                1,
+                ((summ2 - 2.0 * m[0] * m[0]) / (1.0 - 2.0 * a1 * a1)).sqrt(),
+            )
+        };
+
+        a[0] = a1;
+        for i in first..half {
+            a[i] = -m[i] / fac;
+        }
+    }
+
+    let mean = sorted.iter().sum::<f64>() / nf;
+    let ssq: f64 = sorted.iter().map(|x| (x - mean) * (x - mean)).sum();
+    let w1: f64 = (0..half)
+        .map(|i| a[i] * (sorted[n - 1 - i] - sorted[i]))
+        .sum();
+    let statistic = (w1 * w1 / ssq).min(1.0);
+
+    let p_value = if n == 3 {
+        (6.0 / PI * (statistic.sqrt().asin() - PI / 3.0)).max(0.0)
+    } else {
+        let y = (1.0 - statistic).ln();
+        let (z, m, s) = if n <= 11 {
+            let gamma = polynomial(&[-2.273, 0.459], nf);
+            if y >= gamma {
+                (f64::INFINITY, 0.0, 1.0)
+            } else {
+                let m = polynomial(&[0.544, -0.399_78, 0.025_054, -6.714e-4], nf);
+                let s = polynomial(&[1.382_2, -0.778_57, 0.062_767, -0.002_032_2], nf).exp();
+                (-(gamma - y).ln(), m, s)
+            }
+        } else {
+            let ln_n = nf.ln();
+            let m = polynomial(&[-1.586_1, -0.310_82, -0.083_751, 0.003_891_5], ln_n);
+            let s = polynomial(&[-0.480_3, -0.082_676, 0.003_030_2], ln_n).exp();
+            (y, m, s)
+        };
+        Normal::standard().sf((z - m) / s)
+    };
+
+    TestResult {
+        statistic,
+        p_value,
+        df: None,
+        confidence_interval: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Reference values computed with mpmath.
+    const T_ONE: f64 = 3.722_405_806_106_75;
+    const P_ONE: f64 = 0.007_432_530_122_421_496;
+    const CI_ONE: (f64, f64) = (5.205_177_018_357_628, 5.919_822_981_642_372);
+    const T_STUDENT: f64 = 3.403_649_830_495_683;
+    const P_STUDENT: f64 = 0.005_234_292_944_833_819;
+    const T_WELCH: f64 = 3.693_795_159_618_778;
+    const DF_WELCH: f64 = 11.258_477_404_411_334;
+    const P_WELCH: f64 = 0.003_406_744_636_331_590_4;
+    const CI_WELCH: (f64, f64) = (0.268_845_616_377_354_8, 1.056_154_383_622_645);
+    const P_GOF: f64 = 0.019_905_220_334_774_38;
+    const CHI_IND: f64 = 0.271_574_651_504_035_2;
+    const P_IND: f64 = 0.873_028_283_380_072_9;
+    const P_MWU_TIES: f64 = 0.014_107_907_274_540_632;
+    const P_WILCOXON_TIES: f64 = 0.043_455_304_047_793_68;
+
+    fn assert_close(expected: f64, actual: f64, tol: f64) {
+        assert!(
+            (expected - actual).abs() <= tol * expected.abs().max(1e-300),
+            "expected {}, found {}",
+            expected,
+            actual
+        );
+    }
+
+    fn sample_x() -> Array {
+        Array::from(&[5.1, 4.9, 5.6, 5.8, 6.0, 5.3, 5.7, 6.1])
+    }
+
+    fn sample_y() -> Array {
+        Array::from(&[4.8, 5.0, 4.7, 5.2, 5.1, 4.6])
+    }
+
+    #[test]
+    fn test_one_sample_t_test() {
+        let result = one_sample_t_test(&sample_x(), 5.0, Alternative::TwoSided, 0.95);
+
+        assert_close(T_ONE, result.statistic, 1e-13);
+        assert_close(P_ONE, result.p_value, 1e-10);
+        assert_eq!(Some(7.0), result.df);
+        let (lo, hi) = result.confidence_interval.unwrap();
+        assert_close(CI_ONE.0, lo, 1e-12);
+        assert_close(CI_ONE.1, hi, 1e-12);
+
+        let less = one_sample_t_test(&sample_x(), 5.0, Alternative::Less, 0.95);
+        let greater = one_sample_t_test(&sample_x(), 5.0, Alternative::Greater, 0.95);
+        assert_close(1.0, less.p_value + greater.p_value, 1e-14);
+        assert_close(result.p_value / 2.0, greater.p_value, 1e-12);
+        assert_eq!(f64::NEG_INFINITY, less.confidence_interval.unwrap().0);
+    }
+
+    #[test]
+    fn test_two_sample_t_tests() {
+        let student =
+            two_sample_t_test(&sample_x(), &sample_y(), true, Alternative::TwoSided, 0.95);
+        assert_close(T_STUDENT, student.statistic, 1e-13);
+        assert_close(P_STUDENT, student.p_value, 1e-10);
+        assert_eq!(Some(12.0), student.df);
+
+        let welch = two_sample_t_test(&sample_x(), &sample_y(), false, Alternative::TwoSided, 0.95);
+        assert_close(T_WELCH, welch.statistic, 1e-13);
+        assert_close(DF_WELCH, welch.df.unwrap(), 1e-13);
+        assert_close(P_WELCH, welch.p_value, 1e-10);
+        let (lo, hi) = welch.confidence_interval.unwrap();
+        assert_close(CI_WELCH.0, lo, 1e-11);
+        assert_close(CI_WELCH.1, hi, 1e-11);
+    }
+
+    #[test]
+    fn test_paired_t_test() {
+        let xs = Array::from(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        let ys = Array::from(&[1.5, 2.0, 3.5, 5.0, 5.5]);
+        let paired = paired_t_test(&xs, &ys, Alternative::TwoSided, 0.9);
+        let diffs = one_sample_t_test(&xs.minus(&ys), 0.0, Alternative::TwoSided, 0.9);
+
+        assert_eq!(diffs, paired);
+        assert!(paired.statistic < 0.0);
+    }
+
+    #[test]
+    fn test_z_tests() {
+        let result = one_sample_z_test(&sample_x(), 5.0, 0.5, Alternative::TwoSided, 0.95);
+        let z = (sample_x().average() - 5.0) / (0.5 / 8f64.sqrt());
+        assert_close(z, result.statistic, 1e-14);
+        assert_close(Normal::standard().sf(z) * 2.0, result.p_value, 1e-14);
+        assert_eq!(None, result.df);
+
+        let (lo, hi) = result.confidence_interval.unwrap();
+        let half_width = 1.959_963_984_540_054 * 0.5 / 8f64.sqrt();
+        assert_close(sample_x().average() - half_width, lo, 1e-14);
+        assert_close(sample_x().average() + half_width, hi, 1e-14);
+
+        let two = two_sample_z_test(
+            &sample_x(),
+            &sample_y(),
+            0.5,
+            0.3,
+            Alternative::Greater,
+            0.95,
+        );
+        let se = (0.25 / 8.0 + 0.09 / 6.0f64).sqrt();
+        assert_close(
+            (sample_x().average() - sample_y().average()) / se,
+            two.statistic,
+            1e-14,
+        );
+        assert_eq!(f64::INFINITY, two.confidence_interval.unwrap().1);
+    }
+
+    #[test]
+    fn test_chi_square() {
+        let observed = Array::from(&[5.0, 8.0, 9.0, 8.0, 10.0, 20.0]);
+        let result = chi_square_goodness_of_fit(&observed, None);
+        assert_close(13.4, result.statistic, 1e-14);
+        assert_close(P_GOF, result.p_value, 1e-12);
+        assert_eq!(Some(5.0), result.df);
+
+        let expected = Array::from(&[10.0, 10.0, 10.0, 10.0, 10.0, 10.0]);
+        assert_eq!(
+            result,
+            chi_square_goodness_of_fit(&observed, Some(&expected))
+        );
+
+        let table = Matrix::new(&[
+            Array::from(&[10.0, 20.0, 30.0]),
+            Array::from(&[6.0, 9.0, 17.0]),
+        ]);
+        let result = chi_square_independence(&table);
+        assert_close(CHI_IND, result.statistic, 1e-13);
+        assert_close(P_IND, result.p_value, 1e-12);
+        assert_eq!(Some(2.0), result.df);
+    }
+
+    #[test]
+    fn test_kolmogorov_exact() {
+        // The example from Marsaglia, Tsang and Wang (2003).
+        assert_close(
+            1.0 - 0.628_479_615_456_504_3,
+            kolmogorov_exact(0.274, 10),
+            1e-12,
+        );
+        assert_eq!(0.0, kolmogorov_exact(1.0, 5));
+        assert!(kolmogorov_exact(0.9, 100) < 1e-60);
+    }
+
+    #[test]
+    fn test_kolmogorov_smirnov() {
+        let xs = Array::from(&[
+            0.61, 0.29, 0.06, 0.59, -1.73, -0.74, 0.51, -0.56, 0.39, 1.64,
+        ]);
+        let result = kolmogorov_smirnov(&xs, &Normal::standard());
+
+        let normal = Normal::standard();
+        let mut sorted = xs.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let d = (0..10)
+            .map(|i| {
+                let f = normal.cdf(sorted[i]);
+                ((i + 1) as f64 / 10.0 - f).max(f - i as f64 / 10.0)
+            })
+            .fold(0.0, f64::max);
+
+        assert_eq!(d, result.statistic);
+        assert!(result.p_value > 0.5);
+
+        let shifted = kolmogorov_smirnov(&xs.scalar_add(2.0), &normal);
+        assert!(shifted.p_value < 1e-4);
+    }
+
+    #[test]
+    fn test_kolmogorov_smirnov_two_sample_is_exact() {
+        let xs = Array::from(&[0.1, 0.7, 1.4, 2.2]);
+        let ys = Array::from(&[0.5, 1.9, 2.5, 3.1, 3.6]);
+        let result = kolmogorov_smirnov_two_sample(&xs, &ys);
+        assert_close(0.6, result.statistic, 1e-15);
+
+        // Enumerate all 126 ways to split the pooled sample.
+        let pooled = xs.concat(&ys);
+        let (mut extreme, mut total) = (0, 0);
+        for mask in 0u32..(1 << 9) {
+            if mask.count_ones() != 4 {
+                continue;
+            }
+            let (a, b): (Vec<usize>, Vec<usize>) = (0..9).partition(|&i| mask & (1 << i) != 0);
+            let a = Array::from_vec(a.iter().map(|&i| pooled[i]).collect());
+            let b = Array::from_vec(b.iter().map(|&i| pooled[i]).collect());
+            if kolmogorov_smirnov_two_sample(&a, &b).statistic >= result.statistic - 1e-12 {
+                extreme += 1;
+            }
+            total += 1;
+        }
+
+        assert_eq!(126, total);
+        assert_close(extreme as f64 / 126.0, result.p_value, 1e-14);
+    }
+
+    #[test]
+    fn test_mann_whitney_u_is_exact() {
+        let xs = Array::from(&[1.83, 0.50, 1.62, 2.48, 1.68]);
+        let ys = Array::from(&[0.88, 0.65, 0.59, 2.05, 1.06, 1.29]);
+        let result = mann_whitney_u(&xs, &ys, Alternative::Greater);
+        assert_eq!(21.0, result.statistic);
+
+        // Enumerate all 462 ways to split the pooled sample.
+        let pooled = xs.concat(&ys);
+        let (mut greater, mut two_sided) = (0, 0);
+        for mask in 0u32..(1 << 11) {
+            if mask.count_ones() != 5 {
+                continue;
+            }
+            let (a, b): (Vec<usize>, Vec<usize>) = (0..11).partition(|&i| mask & (1 << i) != 0);
+            let u = a
+                .iter()
+                .map(|&i| b.iter().filter(|&&j| pooled[j] < pooled[i]).count())
+                .sum::<usize>() as f64;
+            greater += (u >= 21.0) as usize;
+            two_sided += ((u - 15.0).abs() >= 6.0) as usize;
+        }
+
+        assert_close(greater as f64 / 462.0, result.p_value, 1e-14);
+        let result = mann_whitney_u(&xs, &ys, Alternative::TwoSided);
+        assert_close(two_sided as f64 / 462.0, result.p_value, 1e-14);
+    }
+
+    #[test]
+    fn test_mann_whitney_u_with_ties() {
+        let xs = Array::from(&[1.0, 2.0, 2.0, 3.0, 4.0, 4.0, 5.0]);
+        let ys = Array::from(&[3.0, 4.0, 5.0, 5.0, 6.0, 6.0, 7.0, 8.0]);
+        let result = mann_whitney_u(&xs, &ys, Alternative::TwoSided);
+
+        assert_eq!(6.5, result.statistic);
+        assert_close(P_MWU_TIES, result.p_value, 1e-12);
+    }
+
+    #[test]
+    fn test_wilcoxon_signed_rank() {
+        let xs = Array::from(&[1.83, 0.50, 1.62, 2.48, 1.68, 1.88, 1.55, 3.06, 1.30]);
+        let ys = Array::from(&[0.878, 0.647, 0.598, 2.05, 1.06, 1.29, 1.06, 3.15, 1.29]);
+        let result = wilcoxon_signed_rank(&xs, Some(&ys), Alternative::Greater);
+
+        // The classic example from Hollander and Wolfe (3.14 nudged to 3.15, which keeps the
+        // ranks), with V = 40 and p = 0.01953.
+        assert_eq!(40.0, result.statistic);
+        assert_close(10.0 / 512.0, result.p_value, 1e-14);
+
+        let two_sided = wilcoxon_signed_rank(&xs.minus(&ys), None, Alternative::TwoSided);
+        assert_close(20.0 / 512.0, two_sided.p_value, 1e-14);
+    }
+
+    #[test]
+    fn test_wilcoxon_signed_rank_with_ties() {
+        let diffs = Array::from(&[1.0, -2.0, 2.0, 3.0, 0.0, 4.0, 4.0, 5.0, -1.0, 6.0]);
+        let result = wilcoxon_signed_rank(&diffs, None, Alternative::TwoSided);
+
+        assert_eq!(40.0, result.statistic);
+        assert_close(P_WILCOXON_TIES, result.p_value, 1e-12);
+    }
+
+    #[test]
+    fn test_shapiro_wilk() {
+        let weights = Array::from(&[
+            148.0, 154.0, 158.0, 160.0, 161.0, 162.0, 166.0, 170.0, 182.0, 195.0, 236.0,
+        ]);
+        let result = shapiro_wilk(&weights);
+
+        assert_close(0.788_1, result.statistic, 1e-3);
+        assert_close(0.006_7, result.p_value, 2e-2);
+
+        let normal = Normal::standard();
+        let quantiles = Array::from_vec(
+            (1..=50)
+                .map(|i| normal.quantile((i as f64 - 0.375) / 50.25))
+                .collect(),
+        );
+        let result = shapiro_wilk(&quantiles);
+        assert!(result.statistic > 0.99);
+        assert!(result.p_value > 0.9);
+
+        let skewed = Array::from_vec((1..=50).map(|i| (i as f64 / 10.0).exp()).collect());
+        assert!(shapiro_wilk(&skewed).p_value < 1e-6);
+
+        // For n = 3 the distribution of W is known exactly.
+        let three = shapiro_wilk(&Array::from(&[1.0, 2.0, 4.0]));
+        assert_close(27.0 / 28.0, three.statistic, 1e-14);
+        let p = 6.0 / PI * ((27.0f64 / 28.0).sqrt().asin() - PI / 3.0);
+        assert_close(p, three.p_value, 1e-14);
+    }
+}